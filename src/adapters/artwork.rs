@@ -0,0 +1,237 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::adapters::audio_format::AudioFormat;
+use crate::adapters::flac_tagger::read_front_picture;
+use crate::application::ports::{ArtworkSource, TrackArtwork};
+
+const ARTWORK_STEMS: [&str; 4] = ["cover", "folder", "front", "albumart"];
+const ARTWORK_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+const FOLDER_ARTWORK_STEM: &str = "folder";
+
+pub fn find_artwork(cue_path: &Path, audio_inputs: &[PathBuf]) -> Result<Option<TrackArtwork>> {
+    let directory = cue_path.parent().unwrap_or_else(|| Path::new("."));
+    for path in artwork_files(directory)? {
+        let data =
+            fs::read(&path).map_err(|err| anyhow!("failed to read {}: {err}", path.display()))?;
+        if let Some(mime_type) = image_mime_type(&data) {
+            return Ok(Some(TrackArtwork {
+                source: ArtworkSource::File(path),
+                mime_type: mime_type.to_owned(),
+                data,
+            }));
+        }
+    }
+
+    for path in audio_inputs {
+        if AudioFormat::detect(path) != Some(AudioFormat::Flac) {
+            continue;
+        }
+        if let Some(artwork) = read_front_picture(path)? {
+            return Ok(Some(artwork));
+        }
+    }
+    Ok(None)
+}
+
+pub fn write_folder_artwork(directory: &Path, artwork: &TrackArtwork) -> Result<Option<PathBuf>> {
+    let existing = fs::read_dir(directory)
+        .map_err(|err| anyhow!("failed to read {}: {err}", directory.display()))?
+        .filter_map(|entry| entry.ok())
+        .any(|entry| artwork_rank(&entry.path(), &[FOLDER_ARTWORK_STEM]).is_some());
+    if existing {
+        return Ok(None);
+    }
+
+    let path = directory.join(format!("{FOLDER_ARTWORK_STEM}.{}", artwork.extension()));
+    fs::write(&path, &artwork.data)
+        .map_err(|err| anyhow!("failed to write {}: {err}", path.display()))?;
+    Ok(Some(path))
+}
+
+pub fn image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else {
+        None
+    }
+}
+
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32, u32)> {
+    match image_mime_type(data)? {
+        "image/png" => png_dimensions(data),
+        _ => jpeg_dimensions(data),
+    }
+}
+
+fn artwork_files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut candidates = fs::read_dir(directory)
+        .map_err(|err| anyhow!("failed to read {}: {err}", directory.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| artwork_rank(&path, &ARTWORK_STEMS).map(|rank| (rank, path)))
+        .collect::<Vec<_>>();
+    candidates.sort();
+    Ok(candidates.into_iter().map(|(_, path)| path).collect())
+}
+
+fn artwork_rank(path: &Path, stems: &[&str]) -> Option<(usize, usize)> {
+    let stem = path.file_stem()?.to_str()?.to_ascii_lowercase();
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some((
+        stems.iter().position(|candidate| *candidate == stem)?,
+        ARTWORK_EXTENSIONS
+            .iter()
+            .position(|candidate| *candidate == extension)?,
+    ))
+}
+
+fn png_dimensions(data: &[u8]) -> Option<(u32, u32, u32)> {
+    if data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+    let bit_depth = u32::from(*data.get(24)?);
+    let channels = match data.get(25)? {
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => 1,
+    };
+    Some((width, height, bit_depth * channels))
+}
+
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32, u32)> {
+    let mut offset = 2;
+    loop {
+        if *data.get(offset)? != 0xff {
+            return None;
+        }
+        let marker = *data.get(offset + 1)?;
+        if marker == 0xff {
+            offset += 1;
+            continue;
+        }
+        let length = usize::from(u16::from_be_bytes(
+            data.get(offset + 2..offset + 4)?.try_into().ok()?,
+        ));
+        if (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker) {
+            let segment = data.get(offset + 4..offset + 2 + length)?;
+            let precision = u32::from(*segment.first()?);
+            let height = u32::from(u16::from_be_bytes(segment.get(1..3)?.try_into().ok()?));
+            let width = u32::from(u16::from_be_bytes(segment.get(3..5)?.try_into().ok()?));
+            let components = u32::from(*segment.get(5)?);
+            return Some((width, height, precision * components));
+        }
+        offset += 2 + length;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::{find_artwork, image_dimensions, write_folder_artwork};
+    use crate::adapters::flac_tagger::write_picture;
+    use crate::adapters::flac_writer::{FlacStreamFormat, FlacTrackWriter};
+    use crate::application::ports::{ArtworkSource, TrackArtwork};
+
+    #[test]
+    fn prefers_cover_files_next_to_the_cue() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(tmp.path().join("Folder.png"), png(10, 20)).unwrap();
+        fs::write(tmp.path().join("Cover.JPG"), jpeg(300, 200)).unwrap();
+        fs::write(tmp.path().join("cover.txt"), b"not an image").unwrap();
+
+        let artwork = find_artwork(&cue_path, &[]).unwrap().unwrap();
+
+        assert_eq!(
+            artwork.source,
+            ArtworkSource::File(tmp.path().join("Cover.JPG"))
+        );
+        assert_eq!(artwork.mime_type, "image/jpeg");
+        assert_eq!(image_dimensions(&artwork.data), Some((300, 200, 24)));
+    }
+
+    #[test]
+    fn falls_back_to_the_source_flac_picture() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        let source = tmp.path().join("album.flac");
+        write_flac(&source);
+        write_picture(
+            &source,
+            &TrackArtwork {
+                source: ArtworkSource::File(tmp.path().join("scan.png")),
+                mime_type: "image/png".into(),
+                data: png(10, 20),
+            },
+        )
+        .unwrap();
+
+        let artwork = find_artwork(&cue_path, std::slice::from_ref(&source))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(artwork.source, ArtworkSource::Embedded(source));
+        assert_eq!(artwork.mime_type, "image/png");
+        assert_eq!(image_dimensions(&artwork.data), Some((10, 20, 32)));
+    }
+
+    #[test]
+    fn folder_artwork_is_not_overwritten() {
+        let tmp = tempdir().unwrap();
+        let artwork = TrackArtwork {
+            source: ArtworkSource::File(tmp.path().join("cover.jpg")),
+            mime_type: "image/jpeg".into(),
+            data: jpeg(1, 1),
+        };
+
+        let written = write_folder_artwork(tmp.path(), &artwork).unwrap();
+        assert_eq!(written, Some(tmp.path().join("folder.jpg")));
+        assert_eq!(write_folder_artwork(tmp.path(), &artwork).unwrap(), None);
+        assert_eq!(fs::read(tmp.path().join("folder.jpg")).unwrap(), jpeg(1, 1));
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[8, 6, 0, 0, 0]);
+        data
+    }
+
+    fn jpeg(width: u16, height: u16) -> Vec<u8> {
+        let mut data = vec![0xff, 0xd8, 0xff, 0xe0, 0, 4, 0, 0, 0xff, 0xc0, 0, 17, 8];
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&width.to_be_bytes());
+        data.push(3);
+        data.extend_from_slice(&[0; 9]);
+        data.extend_from_slice(&[0xff, 0xd9]);
+        data
+    }
+
+    fn write_flac(path: &Path) {
+        let mut writer = FlacTrackWriter::create(
+            path,
+            FlacStreamFormat {
+                sample_rate: 44_100,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        )
+        .unwrap();
+        writer.write_samples(&[&[0, 1, 2, 3]]).unwrap();
+        writer.finish().unwrap();
+    }
+}
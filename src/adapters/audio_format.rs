@@ -0,0 +1,615 @@
+use std::env;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+const WAVPACK_SAMPLE_RATES: [u64; 15] = [
+    6_000, 8_000, 9_600, 11_025, 12_000, 16_000, 22_050, 24_000, 32_000, 44_100, 48_000, 64_000,
+    88_200, 96_000, 192_000,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AudioFormat {
+    Flac,
+    Wav,
+    Aiff,
+    Ape,
+    WavPack,
+    Tta,
+}
+
+impl AudioFormat {
+    pub const ALL: [Self; 6] = [
+        Self::Flac,
+        Self::Wav,
+        Self::Aiff,
+        Self::Ape,
+        Self::WavPack,
+        Self::Tta,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Flac => "FLAC",
+            Self::Wav => "WAV",
+            Self::Aiff => "AIFF",
+            Self::Ape => "APE",
+            Self::WavPack => "WavPack",
+            Self::Tta => "TTA",
+        }
+    }
+
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "flac" => Some(Self::Flac),
+            "wav" | "wave" => Some(Self::Wav),
+            "aif" | "aiff" | "aifc" => Some(Self::Aiff),
+            "ape" => Some(Self::Ape),
+            "wv" => Some(Self::WavPack),
+            "tta" => Some(Self::Tta),
+            _ => None,
+        }
+    }
+
+    pub fn detect(path: &Path) -> Option<Self> {
+        sniff(path).or_else(|| Self::from_extension(path))
+    }
+
+    pub fn shntool_name(self) -> &'static str {
+        match self {
+            Self::Flac => "flac",
+            Self::Wav => "wav",
+            Self::Aiff => "aiff",
+            Self::Ape => "ape",
+            Self::WavPack => "wv",
+            Self::Tta => "tta",
+        }
+    }
+
+    pub fn external_decoder(self) -> Option<ExternalDecoder> {
+        let (program, args): (&str, &[&str]) = match self {
+            Self::Flac => ("flac", &["-cd", "-s", "%f"]),
+            Self::Wav => return None,
+            Self::Aiff => ("sox", &["%f", "-t", "wav", "-"]),
+            Self::Ape => ("mac", &["%f", "-", "-d"]),
+            Self::WavPack => ("wvunpack", &["-q", "%f", "-o", "-"]),
+            Self::Tta => ("ttaenc", &["-d", "-o", "-", "%f"]),
+        };
+        Some(ExternalDecoder { program, args })
+    }
+
+    fn native_decoding(self) -> bool {
+        matches!(self, Self::Flac | Self::Wav)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalDecoder {
+    pub program: &'static str,
+    pub args: &'static [&'static str],
+}
+
+impl ExternalDecoder {
+    pub fn shntool_spec(&self, format: AudioFormat) -> String {
+        std::iter::once(format.shntool_name())
+            .chain(std::iter::once(self.program))
+            .chain(self.args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+pub fn command_for(program: &Path, args: &[&str], path: &Path) -> Command {
+    let mut command = Command::new(program);
+    for arg in args {
+        if *arg == "%f" {
+            command.arg(path);
+        } else {
+            command.arg(arg);
+        }
+    }
+    command
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioStreamInfo {
+    pub samples: u64,
+    pub sample_rate: u64,
+}
+
+pub fn read_stream_info(path: &Path, format: AudioFormat) -> Result<AudioStreamInfo> {
+    let mut file =
+        File::open(path).map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?;
+    skip_id3v2(&mut file).map_err(|err| anyhow!("failed to read {}: {err}", path.display()))?;
+    match format {
+        AudioFormat::Ape => read_ape_stream_info(&mut file, path),
+        AudioFormat::WavPack => read_wavpack_stream_info(&mut file, path),
+        AudioFormat::Tta => read_tta_stream_info(&mut file, path),
+        AudioFormat::Aiff => read_aiff_stream_info(&mut file, path),
+        AudioFormat::Flac | AudioFormat::Wav => Err(anyhow!(
+            "{} headers are not parsed here: {}",
+            format.label(),
+            path.display()
+        )),
+    }
+}
+
+fn read_ape_stream_info(file: &mut File, path: &Path) -> Result<AudioStreamInfo> {
+    let mut header = [0_u8; 6];
+    file.read_exact(&mut header)
+        .map_err(|err| anyhow!("failed to read APE header {}: {err}", path.display()))?;
+    if &header[0..4] != b"MAC " {
+        return Err(anyhow!("not an APE file: {}", path.display()));
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+
+    let (blocks_per_frame, final_frame_blocks, total_frames, sample_rate) = if version >= 3980 {
+        let mut descriptor = [0_u8; 46];
+        file.read_exact(&mut descriptor)
+            .map_err(|err| anyhow!("failed to read APE descriptor {}: {err}", path.display()))?;
+        let descriptor_bytes = u64::from(le_u32(&descriptor[2..6]));
+        let descriptor_start = file
+            .stream_position()
+            .map_err(|err| anyhow!("failed to inspect APE {}: {err}", path.display()))?
+            - 52;
+        file.seek(SeekFrom::Start(descriptor_start + descriptor_bytes))
+            .map_err(|err| anyhow!("failed to seek APE header {}: {err}", path.display()))?;
+        let mut fields = [0_u8; 24];
+        file.read_exact(&mut fields)
+            .map_err(|err| anyhow!("failed to read APE header {}: {err}", path.display()))?;
+        (
+            u64::from(le_u32(&fields[4..8])),
+            u64::from(le_u32(&fields[8..12])),
+            u64::from(le_u32(&fields[12..16])),
+            u64::from(le_u32(&fields[20..24])),
+        )
+    } else {
+        let mut fields = [0_u8; 26];
+        file.read_exact(&mut fields)
+            .map_err(|err| anyhow!("failed to read APE header {}: {err}", path.display()))?;
+        let compression_level = u16::from_le_bytes([fields[0], fields[1]]);
+        let blocks_per_frame = if version >= 3950 {
+            73_728 * 4
+        } else if version >= 3900 || (version >= 3800 && compression_level == 4000) {
+            73_728
+        } else {
+            9_216
+        };
+        (
+            blocks_per_frame,
+            u64::from(le_u32(&fields[22..26])),
+            u64::from(le_u32(&fields[18..22])),
+            u64::from(le_u32(&fields[6..10])),
+        )
+    };
+
+    let samples = match total_frames {
+        0 => 0,
+        frames => (frames - 1) * blocks_per_frame + final_frame_blocks,
+    };
+    Ok(AudioStreamInfo {
+        samples,
+        sample_rate,
+    })
+}
+
+fn read_wavpack_stream_info(file: &mut File, path: &Path) -> Result<AudioStreamInfo> {
+    let mut header = [0_u8; 32];
+    file.read_exact(&mut header)
+        .map_err(|err| anyhow!("failed to read WavPack header {}: {err}", path.display()))?;
+    if &header[0..4] != b"wvpk" {
+        return Err(anyhow!("not a WavPack file: {}", path.display()));
+    }
+    let total_samples = le_u32(&header[12..16]);
+    if total_samples == u32::MAX {
+        return Err(anyhow!(
+            "WavPack file does not record its length: {}",
+            path.display()
+        ));
+    }
+    let total_samples_high = u64::from(header[11]);
+    let samples = u64::from(total_samples) + (total_samples_high << 32) - total_samples_high;
+
+    let flags = le_u32(&header[24..28]);
+    let sample_rate = WAVPACK_SAMPLE_RATES
+        .get(((flags >> 23) & 0xf) as usize)
+        .copied()
+        .ok_or_else(|| {
+            anyhow!(
+                "WavPack file uses a non-standard sample rate: {}",
+                path.display()
+            )
+        })?;
+    Ok(AudioStreamInfo {
+        samples,
+        sample_rate,
+    })
+}
+
+fn read_tta_stream_info(file: &mut File, path: &Path) -> Result<AudioStreamInfo> {
+    let mut header = [0_u8; 18];
+    file.read_exact(&mut header)
+        .map_err(|err| anyhow!("failed to read TTA header {}: {err}", path.display()))?;
+    if &header[0..4] != b"TTA1" {
+        return Err(anyhow!("not a TTA file: {}", path.display()));
+    }
+    Ok(AudioStreamInfo {
+        samples: u64::from(le_u32(&header[14..18])),
+        sample_rate: u64::from(le_u32(&header[10..14])),
+    })
+}
+
+fn read_aiff_stream_info(file: &mut File, path: &Path) -> Result<AudioStreamInfo> {
+    let mut form = [0_u8; 12];
+    file.read_exact(&mut form)
+        .map_err(|err| anyhow!("failed to read AIFF header {}: {err}", path.display()))?;
+    if &form[0..4] != b"FORM" || !matches!(&form[8..12], b"AIFF" | b"AIFC") {
+        return Err(anyhow!("not an AIFF file: {}", path.display()));
+    }
+
+    loop {
+        let mut header = [0_u8; 8];
+        file.read_exact(&mut header)
+            .map_err(|err| anyhow!("AIFF COMM chunk is missing for {}: {err}", path.display()))?;
+        let chunk_size = u64::from(u32::from_be_bytes(
+            header[4..8].try_into().expect("slice length"),
+        ));
+        if &header[0..4] == b"COMM" {
+            let mut comm = [0_u8; 18];
+            file.read_exact(&mut comm).map_err(|err| {
+                anyhow!("failed to read AIFF COMM chunk {}: {err}", path.display())
+            })?;
+            let samples = u64::from(u32::from_be_bytes(
+                comm[2..6].try_into().expect("slice length"),
+            ));
+            let sample_rate = extended_to_u64(&comm[8..18])
+                .ok_or_else(|| anyhow!("unsupported AIFF sample rate for {}", path.display()))?;
+            return Ok(AudioStreamInfo {
+                samples,
+                sample_rate,
+            });
+        }
+        let offset = i64::try_from(chunk_size + chunk_size % 2)
+            .map_err(|_| anyhow!("AIFF chunk is too large to skip for {}", path.display()))?;
+        file.seek(SeekFrom::Current(offset))
+            .map_err(|err| anyhow!("failed to skip AIFF chunk {}: {err}", path.display()))?;
+    }
+}
+
+fn extended_to_u64(bytes: &[u8]) -> Option<u64> {
+    let exponent = i32::from(u16::from_be_bytes([bytes[0], bytes[1]]) & 0x7fff);
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().ok()?);
+    if bytes[0] & 0x80 != 0 || mantissa == 0 {
+        return None;
+    }
+    let shift = 16_383 + 63 - exponent;
+    if !(0..64).contains(&shift) {
+        return None;
+    }
+    Some(mantissa >> shift)
+}
+
+fn sniff(path: &Path) -> Option<AudioFormat> {
+    let mut file = File::open(path).ok()?;
+    skip_id3v2(&mut file).ok()?;
+    let mut magic = [0_u8; 12];
+    file.read_exact(&mut magic).ok()?;
+    match (&magic[0..4], &magic[8..12]) {
+        (b"fLaC", _) => Some(AudioFormat::Flac),
+        (b"RIFF", b"WAVE") => Some(AudioFormat::Wav),
+        (b"FORM", b"AIFF" | b"AIFC") => Some(AudioFormat::Aiff),
+        (b"MAC ", _) => Some(AudioFormat::Ape),
+        (b"wvpk", _) => Some(AudioFormat::WavPack),
+        (b"TTA1", _) => Some(AudioFormat::Tta),
+        _ => None,
+    }
+}
+
+fn skip_id3v2(file: &mut File) -> std::io::Result<()> {
+    let mut header = [0_u8; 10];
+    let read = file.read(&mut header)?;
+    if read == header.len() && &header[0..3] == b"ID3" {
+        let size = header[6..10]
+            .iter()
+            .fold(0_u64, |size, byte| size << 7 | u64::from(byte & 0x7f));
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        file.seek(SeekFrom::Start(10 + size + footer))?;
+    } else {
+        file.seek(SeekFrom::Start(0))?;
+    }
+    Ok(())
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().expect("slice length"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatSupport {
+    Builtin,
+    External {
+        program: &'static str,
+        available: bool,
+    },
+}
+
+impl FormatSupport {
+    pub fn missing_program(&self) -> Option<&'static str> {
+        match self {
+            Self::External {
+                program,
+                available: false,
+            } => Some(program),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioFormatCapability {
+    pub format: AudioFormat,
+    pub shnsplit: FormatSupport,
+    pub native: FormatSupport,
+    pub disc_id_length: FormatSupport,
+}
+
+pub fn capability_report() -> Vec<AudioFormatCapability> {
+    capability_report_with(|program| find_executable(program).is_some())
+}
+
+fn capability_report_with(
+    mut available: impl FnMut(&'static str) -> bool,
+) -> Vec<AudioFormatCapability> {
+    let mut external = |program| FormatSupport::External {
+        program,
+        available: available(program),
+    };
+    AudioFormat::ALL
+        .into_iter()
+        .map(|format| {
+            let decoder = format.external_decoder();
+            let shnsplit =
+                decoder.map_or(FormatSupport::Builtin, |decoder| external(decoder.program));
+            let native = if format.native_decoding() {
+                FormatSupport::Builtin
+            } else {
+                shnsplit.clone()
+            };
+            let disc_id_length = if format == AudioFormat::Flac {
+                external("metaflac")
+            } else {
+                FormatSupport::Builtin
+            };
+            AudioFormatCapability {
+                format,
+                shnsplit,
+                native,
+                disc_id_length,
+            }
+        })
+        .collect()
+}
+
+pub fn find_executable(program: &str) -> Option<PathBuf> {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::{
+        capability_report_with, read_stream_info, AudioFormat, AudioStreamInfo, FormatSupport,
+    };
+
+    #[test]
+    fn detects_format_from_contents_before_extension() {
+        let tmp = tempdir().unwrap();
+        let mislabelled = tmp.path().join("album.wav");
+        fs::write(&mislabelled, tta_header(1_000, 44_100)).unwrap();
+        let unknown = tmp.path().join("album.ape");
+        fs::write(&unknown, b"garbage").unwrap();
+
+        assert_eq!(AudioFormat::detect(&mislabelled), Some(AudioFormat::Tta));
+        assert_eq!(AudioFormat::detect(&unknown), Some(AudioFormat::Ape));
+        assert_eq!(AudioFormat::detect(Path::new("missing.mp3")), None);
+    }
+
+    #[test]
+    fn reads_ape_length_from_descriptor_header() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.ape");
+        let mut bytes = b"MAC ".to_vec();
+        bytes.extend_from_slice(&3990_u16.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        bytes.extend_from_slice(&52_u32.to_le_bytes());
+        bytes.resize(52, 0);
+        bytes.extend_from_slice(&2000_u16.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        bytes.extend_from_slice(&294_912_u32.to_le_bytes());
+        bytes.extend_from_slice(&1_000_u32.to_le_bytes());
+        bytes.extend_from_slice(&3_u32.to_le_bytes());
+        bytes.extend_from_slice(&16_u16.to_le_bytes());
+        bytes.extend_from_slice(&2_u16.to_le_bytes());
+        bytes.extend_from_slice(&44_100_u32.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        let info = read_stream_info(&path, AudioFormat::Ape).unwrap();
+
+        assert_eq!(
+            info,
+            AudioStreamInfo {
+                samples: 2 * 294_912 + 1_000,
+                sample_rate: 44_100,
+            }
+        );
+    }
+
+    #[test]
+    fn reads_legacy_ape_length() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.ape");
+        let mut bytes = b"MAC ".to_vec();
+        bytes.extend_from_slice(&3970_u16.to_le_bytes());
+        bytes.extend_from_slice(&2000_u16.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        bytes.extend_from_slice(&2_u16.to_le_bytes());
+        bytes.extend_from_slice(&44_100_u32.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&2_u32.to_le_bytes());
+        bytes.extend_from_slice(&500_u32.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        let info = read_stream_info(&path, AudioFormat::Ape).unwrap();
+
+        assert_eq!(info.samples, 294_912 + 500);
+        assert_eq!(info.sample_rate, 44_100);
+    }
+
+    #[test]
+    fn reads_wavpack_length_and_sample_rate() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.wv");
+        let mut bytes = b"wvpk".to_vec();
+        bytes.extend_from_slice(&24_u32.to_le_bytes());
+        bytes.extend_from_slice(&0x410_u16.to_le_bytes());
+        bytes.push(0);
+        bytes.push(0);
+        bytes.extend_from_slice(&123_456_u32.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&(9_u32 << 23).to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        let info = read_stream_info(&path, AudioFormat::WavPack).unwrap();
+
+        assert_eq!(
+            info,
+            AudioStreamInfo {
+                samples: 123_456,
+                sample_rate: 44_100,
+            }
+        );
+    }
+
+    #[test]
+    fn reads_tta_length_after_id3_tag() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.tta");
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x00\x05".to_vec();
+        bytes.extend_from_slice(&[0; 5]);
+        bytes.extend_from_slice(&tta_header(88_200, 44_100));
+        fs::write(&path, bytes).unwrap();
+
+        let info = read_stream_info(&path, AudioFormat::Tta).unwrap();
+
+        assert_eq!(
+            info,
+            AudioStreamInfo {
+                samples: 88_200,
+                sample_rate: 44_100,
+            }
+        );
+        assert_eq!(AudioFormat::detect(&path), Some(AudioFormat::Tta));
+    }
+
+    #[test]
+    fn reads_aiff_length_and_extended_sample_rate() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.aiff");
+        let mut bytes = b"FORM\x00\x00\x00\x00AIFF".to_vec();
+        bytes.extend_from_slice(b"NAME\x00\x00\x00\x01x\x00");
+        bytes.extend_from_slice(b"COMM\x00\x00\x00\x12");
+        bytes.extend_from_slice(&2_i16.to_be_bytes());
+        bytes.extend_from_slice(&4_410_u32.to_be_bytes());
+        bytes.extend_from_slice(&16_i16.to_be_bytes());
+        bytes.extend_from_slice(&[0x40, 0x0e, 0xac, 0x44, 0, 0, 0, 0, 0, 0]);
+        fs::write(&path, bytes).unwrap();
+
+        let info = read_stream_info(&path, AudioFormat::Aiff).unwrap();
+
+        assert_eq!(
+            info,
+            AudioStreamInfo {
+                samples: 4_410,
+                sample_rate: 44_100,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_magic() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.wv");
+        fs::write(&path, [0_u8; 64]).unwrap();
+
+        let err = read_stream_info(&path, AudioFormat::WavPack).unwrap_err();
+
+        assert!(err.to_string().contains("not a WavPack file"));
+    }
+
+    #[test]
+    fn capability_report_reflects_available_tools() {
+        let report = capability_report_with(|program| program == "mac");
+
+        let ape = report
+            .iter()
+            .find(|capability| capability.format == AudioFormat::Ape)
+            .unwrap();
+        assert_eq!(
+            ape.shnsplit,
+            FormatSupport::External {
+                program: "mac",
+                available: true
+            }
+        );
+        assert_eq!(ape.native, ape.shnsplit);
+        assert_eq!(ape.disc_id_length, FormatSupport::Builtin);
+
+        let flac = report
+            .iter()
+            .find(|capability| capability.format == AudioFormat::Flac)
+            .unwrap();
+        assert_eq!(flac.native, FormatSupport::Builtin);
+        assert_eq!(flac.shnsplit.missing_program(), Some("flac"));
+        assert_eq!(flac.disc_id_length.missing_program(), Some("metaflac"));
+        assert_eq!(report.len(), AudioFormat::ALL.len());
+    }
+
+    #[test]
+    fn builds_shntool_decoder_specs() {
+        let spec = AudioFormat::Ape
+            .external_decoder()
+            .unwrap()
+            .shntool_spec(AudioFormat::Ape);
+
+        assert_eq!(spec, "ape mac %f - -d");
+        assert_eq!(AudioFormat::Wav.external_decoder(), None);
+    }
+
+    fn tta_header(samples: u32, sample_rate: u32) -> Vec<u8> {
+        let mut bytes = b"TTA1".to_vec();
+        bytes.extend_from_slice(&1_u16.to_le_bytes());
+        bytes.extend_from_slice(&2_u16.to_le_bytes());
+        bytes.extend_from_slice(&16_u16.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&samples.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes
+    }
+}
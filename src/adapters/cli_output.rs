@@ -0,0 +1,309 @@
+use std::fmt::Write;
+
+use crate::domain::{
+    CueFindingSeverity, CueSheetStatus, DownloadActionStatus, DownloadLifecycleState,
+    TrackCleanupStatus, TrackedDownload,
+};
+
+pub fn render_download_list(downloads: &[TrackedDownload]) -> String {
+    if downloads.is_empty() {
+        return "No downloads registered in Splittarr\n".to_owned();
+    }
+
+    let id_width = downloads
+        .iter()
+        .map(|download| download.download_id.len())
+        .chain(["ID".len()])
+        .max()
+        .unwrap_or_default();
+    let mut output = format!(
+        "{:<id_width$}  {:<15}  {:>6}  TITLE\n",
+        "ID", "STATE", "TRACKS"
+    );
+    for download in downloads {
+        let _ = writeln!(
+            output,
+            "{:<id_width$}  {:<15}  {:>6}  {}",
+            download.download_id,
+            lifecycle_label(&download.lifecycle_state),
+            download.generated_track_count(),
+            download.title
+        );
+    }
+    output
+}
+
+pub fn render_download_details(download: &TrackedDownload) -> String {
+    let mut output = String::new();
+    let _ = writeln!(output, "{}", download.title);
+    let _ = writeln!(output, "ID: {}", download.download_id);
+    let _ = writeln!(
+        output,
+        "State: {}",
+        lifecycle_label(&download.lifecycle_state)
+    );
+    let _ = writeln!(
+        output,
+        "Lidarr status: {} ({})",
+        download.status, download.tracked_download_state
+    );
+    let _ = writeln!(output, "Output path: {}", download.output_path);
+    for (label, value) in [
+        ("First seen", &download.first_seen_at),
+        ("Processing started", &download.processing_started_at),
+        ("Processing finished", &download.processing_finished_at),
+        ("Cleanup started", &download.cleanup_started_at),
+        ("Cleanup finished", &download.cleanup_finished_at),
+        ("Completed", &download.completed_at),
+    ] {
+        if let Some(value) = value {
+            let _ = writeln!(output, "{label}: {value}");
+        }
+    }
+    if download.attempt_count > 0 {
+        let _ = writeln!(output, "Failed attempts: {}", download.attempt_count);
+    }
+    if let Some(next_retry_at) = &download.next_retry_at {
+        let _ = writeln!(output, "Next retry: {next_retry_at}");
+    }
+    if let Some(last_error) = &download.last_error {
+        let _ = writeln!(output, "Last error: {last_error}");
+    }
+
+    for cue_sheet in &download.cue_sheets {
+        let _ = writeln!(output);
+        let _ = writeln!(
+            output,
+            "CUE {} [{}]",
+            cue_sheet.path,
+            cue_status_label(cue_sheet.status)
+        );
+        if let Some(message) = &cue_sheet.message {
+            let _ = writeln!(output, "  {message}");
+        }
+        if let Some(encoding) = &cue_sheet.encoding {
+            let _ = writeln!(output, "  Encoding: {encoding}");
+        }
+        if let Some(pregap_policy) = cue_sheet.pregap_policy {
+            let _ = writeln!(output, "  Pregap: {}", pregap_policy.as_str());
+        }
+        for finding in &cue_sheet.findings {
+            let _ = match &finding.track {
+                Some(track) => writeln!(
+                    output,
+                    "  {}: track {track} {}",
+                    finding_label(finding.severity),
+                    finding.message
+                ),
+                None => writeln!(
+                    output,
+                    "  {}: {}",
+                    finding_label(finding.severity),
+                    finding.message
+                ),
+            };
+        }
+        for track in &cue_sheet.tracks {
+            let _ = writeln!(
+                output,
+                "  {} [{}]",
+                track.path,
+                cleanup_label(track.cleanup_status)
+            );
+        }
+    }
+    if let Some(import_plan) = &download.import_plan {
+        let _ = writeln!(output);
+        let _ = writeln!(output, "Import plan (dry run):");
+        let _ = writeln!(output, "{import_plan}");
+    }
+    if !download.audit_events.is_empty() {
+        let _ = writeln!(output);
+        let _ = writeln!(output, "Actions:");
+        for event in &download.audit_events {
+            let _ = write!(
+                output,
+                "  {} {} [{}]",
+                event.requested_at,
+                event.action.as_str(),
+                action_status_label(event.status)
+            );
+            let _ = match event
+                .message
+                .as_deref()
+                .and_then(|message| message.lines().next())
+            {
+                Some(summary) => writeln!(output, " {summary}"),
+                None => writeln!(output),
+            };
+        }
+    }
+    if !download.notification_deliveries.is_empty() {
+        let _ = writeln!(output);
+        let _ = writeln!(output, "Notifications:");
+        for delivery in &download.notification_deliveries {
+            let _ = write!(
+                output,
+                "  {} {} {} [{}]",
+                delivery.sent_at,
+                delivery.notifier,
+                delivery.event.as_str(),
+                delivery.status.as_str()
+            );
+            let _ = match &delivery.message {
+                Some(message) => writeln!(output, " {message}"),
+                None => writeln!(output),
+            };
+        }
+    }
+    output
+}
+
+fn lifecycle_label(state: &DownloadLifecycleState) -> &'static str {
+    match state {
+        DownloadLifecycleState::Detected => "detected",
+        DownloadLifecycleState::Processing => "processing",
+        DownloadLifecycleState::AwaitingImport => "awaiting_import",
+        DownloadLifecycleState::CleaningUp => "cleaning_up",
+        DownloadLifecycleState::Completed => "completed",
+        DownloadLifecycleState::Failed => "failed",
+        DownloadLifecycleState::Planned => "planned",
+        DownloadLifecycleState::Ignored => "ignored",
+        DownloadLifecycleState::GaveUp => "gave_up",
+    }
+}
+
+fn cue_status_label(status: CueSheetStatus) -> &'static str {
+    match status {
+        CueSheetStatus::Pending => "pending",
+        CueSheetStatus::Split => "split",
+        CueSheetStatus::Skipped => "skipped",
+        CueSheetStatus::Failed => "failed",
+        CueSheetStatus::Planned => "planned",
+    }
+}
+
+fn action_status_label(status: DownloadActionStatus) -> &'static str {
+    match status {
+        DownloadActionStatus::Pending => "pending",
+        DownloadActionStatus::Succeeded => "succeeded",
+        DownloadActionStatus::Failed => "failed",
+    }
+}
+
+fn finding_label(severity: CueFindingSeverity) -> &'static str {
+    match severity {
+        CueFindingSeverity::Error => "error",
+        CueFindingSeverity::Warning => "warning",
+    }
+}
+
+fn cleanup_label(status: TrackCleanupStatus) -> &'static str {
+    match status {
+        TrackCleanupStatus::Pending => "pending",
+        TrackCleanupStatus::Deleted => "deleted",
+        TrackCleanupStatus::DeleteFailed => "delete_failed",
+        TrackCleanupStatus::Missing => "missing",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_download_details, render_download_list};
+    use crate::domain::{
+        CueSheet, CueSheetStatus, DownloadAction, DownloadActionStatus, DownloadAuditEvent,
+        DownloadLifecycleState, GeneratedTrack, NotificationDelivery, NotificationDeliveryStatus,
+        NotificationEvent, OutputCodec, PregapPolicy, TrackCleanupStatus, TrackedDownload,
+    };
+
+    #[test]
+    fn renders_downloads_as_an_aligned_table() {
+        let mut failed = download("download-12");
+        failed.lifecycle_state = DownloadLifecycleState::Failed;
+
+        assert_eq!(
+            render_download_list(&[download("d1"), failed]),
+            "ID           STATE            TRACKS  TITLE\n\
+             d1           awaiting_import       1  Artist - Album\n\
+             download-12  failed                1  Artist - Album\n"
+        );
+        assert_eq!(
+            render_download_list(&[]),
+            "No downloads registered in Splittarr\n"
+        );
+    }
+
+    #[test]
+    fn renders_download_details_with_cue_sheets_and_tracks() {
+        let mut download = download("d1");
+        download.last_error = Some("manual import failed".to_owned());
+        download.attempt_count = 2;
+        download.next_retry_at = Some("2026-06-12 12:10:00".to_owned());
+        download.audit_events = vec![DownloadAuditEvent {
+            id: 1,
+            download_id: "d1".to_owned(),
+            action: DownloadAction::Reimport,
+            status: DownloadActionStatus::Succeeded,
+            message: Some("Manual import: started for 1 track(s)\nLidarr command id=7".to_owned()),
+            requested_at: "2026-06-12 12:00:00".to_owned(),
+            finished_at: Some("2026-06-12 12:01:00".to_owned()),
+        }];
+        download.notification_deliveries = vec![NotificationDelivery {
+            notifier: "discord".to_owned(),
+            event: NotificationEvent::Failed,
+            status: NotificationDeliveryStatus::Failed,
+            message: Some("webhook returned HTTP 404 Not Found: ".to_owned()),
+            sent_at: "2026-06-12 12:02:00".to_owned(),
+        }];
+
+        let output = render_download_details(&download);
+
+        assert!(output.starts_with("Artist - Album\nID: d1\nState: awaiting_import\n"));
+        assert!(output.contains(
+            "Failed attempts: 2\nNext retry: 2026-06-12 12:10:00\nLast error: manual import failed\n"
+        ));
+        assert!(output.contains("CUE /downloads/album/album.cue [split]\n"));
+        assert!(output.contains("  Encoding: utf-8\n  Pregap: append_previous\n"));
+        assert!(output.contains("  /downloads/album/01.flac [pending]\n"));
+        assert!(output.ends_with(
+            "Actions:\n  2026-06-12 12:00:00 reimport [succeeded] Manual import: started for 1 track(s)\n\n\
+             Notifications:\n  2026-06-12 12:02:00 discord failed [failed] webhook returned HTTP 404 Not Found: \n"
+        ));
+    }
+
+    fn download(download_id: &str) -> TrackedDownload {
+        let mut download = TrackedDownload::pending(
+            download_id.to_owned(),
+            "Artist - Album".to_owned(),
+            "completed".to_owned(),
+            "/downloads/album".to_owned(),
+            "importFailed".to_owned(),
+        );
+        download.lifecycle_state = DownloadLifecycleState::AwaitingImport;
+        download.cue_sheets = vec![CueSheet {
+            id: "cue-1".to_owned(),
+            download_id: download_id.to_owned(),
+            path: "/downloads/album/album.cue".to_owned(),
+            status: CueSheetStatus::Split,
+            message: None,
+            updated_at: String::new(),
+            encoding: Some("utf-8".to_owned()),
+            pregap_policy: Some(PregapPolicy::AppendPrevious),
+            tracks: vec![GeneratedTrack {
+                id: "track-1".to_owned(),
+                cue_sheet_id: "cue-1".to_owned(),
+                download_id: download_id.to_owned(),
+                path: "/downloads/album/01.flac".to_owned(),
+                size_bytes: Some(1),
+                codec: OutputCodec::Flac,
+                cleanup_status: TrackCleanupStatus::Pending,
+                cleanup_message: None,
+                deleted_at: None,
+                tags: Vec::new(),
+            }],
+            findings: Vec::new(),
+            repairs: Vec::new(),
+        }];
+        download
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use rcue::cue::{Cue, Track};
+
+pub const PER_TRACK_FILES_MESSAGE: &str =
+    "cue sheet already has one FILE per track; nothing to split";
+
+pub fn index_offset(track: &Track, number: u32) -> Option<Duration> {
+    track
+        .indices
+        .iter()
+        .find(|(index, _)| index.parse::<u32>().ok() == Some(number))
+        .map(|(_, offset)| *offset)
+}
+
+pub fn has_one_file_per_track(cue: &Cue) -> bool {
+    let track_count = cue
+        .files
+        .iter()
+        .map(|file| file.tracks.len())
+        .sum::<usize>();
+    cue.files.len() > 1
+        && track_count == cue.files.len()
+        && cue
+            .files
+            .iter()
+            .flat_map(|file| &file.tracks)
+            .all(|track| index_offset(track, 1) == Some(Duration::ZERO))
+}
+
+pub fn has_hidden_track_audio(cue: &Cue) -> bool {
+    cue.files
+        .first()
+        .and_then(|file| file.tracks.first())
+        .and_then(|track| index_offset(track, 1))
+        .is_some_and(|offset| offset > Duration::ZERO)
+}
+
+pub fn prepend_gaps_to_next_track(text: &str) -> String {
+    let mut rewritten = String::with_capacity(text.len());
+    let mut next_track_opens_file = false;
+    let mut track_opens_file = false;
+    let mut gap_start: Option<String> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        let mut words = trimmed.split_whitespace();
+        match words.next().map(str::to_ascii_uppercase).as_deref() {
+            Some("FILE") => {
+                next_track_opens_file = true;
+                gap_start = None;
+            }
+            Some("TRACK") => {
+                track_opens_file = next_track_opens_file;
+                next_track_opens_file = false;
+                gap_start = None;
+            }
+            Some("INDEX") => {
+                let number = words.next().and_then(|number| number.parse::<u32>().ok());
+                let offset = words.next();
+                match (number, offset) {
+                    (Some(0), Some(offset)) => {
+                        gap_start = Some(offset.to_owned());
+                        continue;
+                    }
+                    (Some(1), Some(_)) => {
+                        let start = if track_opens_file {
+                            Some("00:00:00".to_owned())
+                        } else {
+                            gap_start.take()
+                        };
+                        if let Some(start) = start {
+                            rewritten.push_str(&format!("{indent}INDEX 01 {start}\n"));
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        rewritten.push_str(line);
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_hidden_track_audio, has_one_file_per_track, prepend_gaps_to_next_track};
+    use crate::adapters::cue_text::{decode_cue_bytes, parse_cue_text};
+
+    #[test]
+    fn detects_cue_sheets_that_are_already_split_per_track() {
+        let per_track = "FILE \"01.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\nFILE \"02.flac\" WAVE\n  TRACK 02 AUDIO\n    INDEX 01 00:00:00\n";
+        let gaps_prepended = "FILE \"01.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\nFILE \"02.flac\" WAVE\n  TRACK 02 AUDIO\n    INDEX 00 00:00:00\n    INDEX 01 00:02:00\n";
+        let image = "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    INDEX 01 04:00:00\n";
+
+        assert!(has_one_file_per_track(&parse(per_track)));
+        assert!(!has_one_file_per_track(&parse(gaps_prepended)));
+        assert!(!has_one_file_per_track(&parse(image)));
+        assert!(!has_hidden_track_audio(&parse(image)));
+    }
+
+    #[test]
+    fn moves_track_starts_to_the_beginning_of_their_pregap() {
+        let cue = "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 00 00:00:00\n    INDEX 01 00:32:10\n  TRACK 02 AUDIO\n    TITLE \"Two\"\n    INDEX 00 04:10:20\n    INDEX 01 04:12:00\n  TRACK 03 AUDIO\n    INDEX 01 08:00:00\n";
+        assert!(has_hidden_track_audio(&parse(cue)));
+
+        assert_eq!(
+            prepend_gaps_to_next_track(cue),
+            "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    TITLE \"Two\"\n    INDEX 01 04:10:20\n  TRACK 03 AUDIO\n    INDEX 01 08:00:00\n"
+        );
+    }
+
+    fn parse(text: &str) -> rcue::cue::Cue {
+        parse_cue_text(&decode_cue_bytes(text.as_bytes()), false).unwrap()
+    }
+}
@@ -0,0 +1,302 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use tracing::warn;
+
+use crate::adapters::audio_format::AudioFormat;
+use crate::adapters::cue_text::{decode_cue_bytes, read_cue_text};
+use crate::domain::{CueRepair, CueRepairKind};
+
+pub fn repair_cue(cue_path: &Path, output_path: &Path) -> Result<Option<Vec<CueRepair>>> {
+    let cue_text = read_cue_text(cue_path)?;
+    let text = cue_text.text.as_str();
+    let cue_dir = cue_path.parent().unwrap_or_else(|| Path::new("."));
+    let only_reference = file_references(text).len() == 1;
+
+    let mut repairs = Vec::new();
+    if !cue_text.is_utf8() {
+        repairs.push(CueRepair {
+            kind: CueRepairKind::Encoding,
+            original: cue_text.encoding.to_owned(),
+            replacement: "UTF-8".to_owned(),
+        });
+    }
+    let mut repaired = String::with_capacity(text.len());
+    let mut unquotable = None;
+    for line in text.lines() {
+        match parse_file_line(line) {
+            Some(reference) => {
+                let resolved = resolve_cue_file(cue_dir, &reference.name, only_reference);
+                let path = match resolved {
+                    Some((path, Some(kind))) => {
+                        repairs.push(CueRepair {
+                            kind,
+                            original: reference.name.clone(),
+                            replacement: file_name(&path),
+                        });
+                        path
+                    }
+                    Some((path, None)) => path,
+                    None => cue_dir.join(&reference.name),
+                };
+                match reference.rewrite(&path) {
+                    Some(rewritten) => repaired.push_str(&rewritten),
+                    None => unquotable = Some(path),
+                }
+            }
+            None => repaired.push_str(line),
+        }
+        repaired.push('\n');
+    }
+
+    if repairs.is_empty() {
+        return Ok(None);
+    }
+    if let Some(path) = unquotable {
+        // CUE sheets have no way to escape a quote inside a quoted FILE name.
+        warn!(
+            "Not repairing {}: {} contains a double quote",
+            cue_path.display(),
+            path.display()
+        );
+        return Ok(None);
+    }
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| anyhow!("failed to create {}: {err}", parent.display()))?;
+    }
+    fs::write(output_path, repaired)
+        .map_err(|err| anyhow!("failed to write {}: {err}", output_path.display()))?;
+    Ok(Some(repairs))
+}
+
+pub fn references_audio_file(cue_path: &Path, audio_path: &Path) -> bool {
+    let bytes = match fs::read(cue_path) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!(
+                "Unable to read cue file while matching audio {}: {err}",
+                cue_path.display()
+            );
+            return false;
+        }
+    };
+    let cue_text = decode_cue_bytes(&bytes);
+    let cue_dir = cue_path.parent().unwrap_or_else(|| Path::new("."));
+    let references = file_references(&cue_text.text);
+    let only_reference = references.len() == 1;
+
+    references.iter().any(|reference| {
+        resolve_cue_file(cue_dir, &reference.name, only_reference)
+            .is_some_and(|(path, _)| path == audio_path)
+    })
+}
+
+struct FileReference<'a> {
+    indent: &'a str,
+    name: String,
+    file_type: &'a str,
+}
+
+impl FileReference<'_> {
+    fn rewrite(&self, path: &Path) -> Option<String> {
+        let path = path.to_string_lossy();
+        if path.contains('"') {
+            return None;
+        }
+        Some(format!("{}FILE \"{path}\" {}", self.indent, self.file_type))
+    }
+}
+
+fn file_references(text: &str) -> Vec<FileReference<'_>> {
+    text.lines().filter_map(parse_file_line).collect()
+}
+
+fn parse_file_line(line: &str) -> Option<FileReference<'_>> {
+    let rest = line.trim_start();
+    let indent = &line[..line.len() - rest.len()];
+    let keyword = rest.get(..4)?;
+    if !keyword.eq_ignore_ascii_case("FILE") {
+        return None;
+    }
+    let rest = rest[4..].strip_prefix(char::is_whitespace)?.trim();
+    let (name, file_type) = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let (name, file_type) = quoted.split_once('"')?;
+            (name, file_type.trim())
+        }
+        None => rest.rsplit_once(char::is_whitespace)?,
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some(FileReference {
+        indent,
+        name: name.to_owned(),
+        file_type,
+    })
+}
+
+fn resolve_cue_file(
+    cue_dir: &Path,
+    name: &str,
+    only_reference: bool,
+) -> Option<(PathBuf, Option<CueRepairKind>)> {
+    let referenced = cue_dir.join(name);
+    if referenced.is_file() {
+        return Some((referenced, None));
+    }
+    let dir = referenced.parent()?;
+    let wanted = referenced.file_name()?.to_string_lossy().into_owned();
+    let files = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+
+    if let Some(path) = files
+        .iter()
+        .find(|path| file_name(path).eq_ignore_ascii_case(&wanted))
+    {
+        return Some((path.clone(), Some(CueRepairKind::FileCase)));
+    }
+
+    let audio_files = files
+        .into_iter()
+        .filter(|path| AudioFormat::from_extension(path).is_some())
+        .collect::<Vec<_>>();
+    let wanted_stem = Path::new(&wanted)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase());
+    let stem_matches = audio_files
+        .iter()
+        .filter(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_lowercase())
+                == wanted_stem
+        })
+        .collect::<Vec<_>>();
+    if let [path] = stem_matches.as_slice() {
+        return Some(((*path).clone(), Some(CueRepairKind::FileExtension)));
+    }
+
+    match audio_files.as_slice() {
+        [path] if only_reference => Some((path.clone(), Some(CueRepairKind::OnlyAudioFile))),
+        _ => None,
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{references_audio_file, repair_cue};
+    use crate::domain::{CueRepair, CueRepairKind};
+
+    #[test]
+    fn rewrites_mismatched_file_references_to_absolute_paths() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("album.flac"), b"audio").unwrap();
+        fs::write(tmp.path().join("Bonus.wav"), b"audio").unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            "FILE \"album.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\nFILE bonus.wav WAVE\n  TRACK 02 AUDIO\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        let output_path = tmp.path().join("repaired").join("cue-1.cue");
+
+        let repairs = repair_cue(&cue_path, &output_path).unwrap().unwrap();
+
+        assert_eq!(
+            repairs,
+            vec![
+                CueRepair {
+                    kind: CueRepairKind::FileExtension,
+                    original: "album.wav".into(),
+                    replacement: "album.flac".into(),
+                },
+                CueRepair {
+                    kind: CueRepairKind::FileCase,
+                    original: "bonus.wav".into(),
+                    replacement: "Bonus.wav".into(),
+                },
+            ]
+        );
+        let repaired = fs::read_to_string(&output_path).unwrap();
+        assert!(repaired.contains(&format!(
+            "FILE \"{}\" WAVE",
+            tmp.path().join("album.flac").display()
+        )));
+        assert!(repaired.contains(&format!(
+            "FILE \"{}\" WAVE",
+            tmp.path().join("Bonus.wav").display()
+        )));
+        assert!(repaired.contains("  TRACK 02 AUDIO"));
+        assert!(fs::read_to_string(&cue_path)
+            .unwrap()
+            .starts_with("FILE \"album.wav\""));
+    }
+
+    #[test]
+    fn falls_back_to_the_only_audio_file_and_transcodes_to_utf8() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("CD.ape"), b"audio").unwrap();
+        fs::write(tmp.path().join("cover.jpg"), b"image").unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        let mut cue = b"TITLE \"Caf\xe9\"\nFILE \"image.wav\" WAVE\n".to_vec();
+        cue.extend_from_slice(b"  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n");
+        fs::write(&cue_path, cue).unwrap();
+        let output_path = tmp.path().join("repaired.cue");
+
+        let repairs = repair_cue(&cue_path, &output_path).unwrap().unwrap();
+
+        assert_eq!(repairs[0].kind, CueRepairKind::Encoding);
+        assert_eq!(repairs[0].original, "windows-1252");
+        assert_eq!(repairs[1].kind, CueRepairKind::OnlyAudioFile);
+        assert_eq!(
+            repairs[1].describe(),
+            "image.wav -> CD.ape (only audio file in the folder)"
+        );
+        assert!(fs::read_to_string(&output_path)
+            .unwrap()
+            .starts_with("TITLE \"Café\"\n"));
+        assert!(references_audio_file(&cue_path, &tmp.path().join("CD.ape")));
+    }
+
+    #[test]
+    fn leaves_well_formed_cues_alone() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("album.flac"), b"audio").unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&cue_path, "FILE \"album.flac\" WAVE\n").unwrap();
+        let output_path = tmp.path().join("repaired.cue");
+
+        assert_eq!(repair_cue(&cue_path, &output_path).unwrap(), None);
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn refuses_to_repair_paths_a_file_line_cannot_quote() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("12\" Single");
+        fs::create_dir(&album_dir).unwrap();
+        fs::write(album_dir.join("single.flac"), b"audio").unwrap();
+        let cue_path = album_dir.join("single.cue");
+        fs::write(&cue_path, "FILE \"single.wav\" WAVE\n").unwrap();
+        let output_path = tmp.path().join("repaired.cue");
+
+        assert_eq!(repair_cue(&cue_path, &output_path).unwrap(), None);
+        assert!(!output_path.exists());
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+use rcue::cue::Cue;
+use rcue::parser::parse;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueText {
+    pub text: String,
+    pub encoding: &'static str,
+}
+
+impl CueText {
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8.name()
+    }
+}
+
+pub fn read_cue_text(cue_path: &Path) -> Result<CueText> {
+    let bytes = fs::read(cue_path)
+        .map_err(|err| anyhow!("failed to read {}: {err}", cue_path.display()))?;
+    Ok(decode_cue_bytes(&bytes))
+}
+
+pub fn decode_cue_bytes(bytes: &[u8]) -> CueText {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        return CueText {
+            text: text.into_owned(),
+            encoding: encoding.name(),
+        };
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return CueText {
+            text: text.to_owned(),
+            encoding: encoding_rs::UTF_8.name(),
+        };
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    CueText {
+        text: text.into_owned(),
+        encoding: encoding.name(),
+    }
+}
+
+pub fn parse_cue_text(cue_text: &CueText, strict: bool) -> Result<Cue> {
+    parse(&mut cue_text.text.as_bytes(), strict).map_err(|err| anyhow!("{err}"))
+}
+
+pub fn parse_cue_file(cue_path: &Path, strict: bool) -> Result<Cue> {
+    parse_cue_text(&read_cue_text(cue_path)?, strict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_cue_bytes, parse_cue_text};
+
+    #[test]
+    fn decodes_byte_order_marks() {
+        let utf8 = decode_cue_bytes(b"\xef\xbb\xbfTITLE \"x\"");
+        assert_eq!(utf8.text, "TITLE \"x\"");
+        assert_eq!(utf8.encoding, "UTF-8");
+        assert!(utf8.is_utf8());
+
+        let utf16 = decode_cue_bytes(b"\xff\xfeT\0I\0T\0L\0E\0");
+        assert_eq!(utf16.text, "TITLE");
+        assert_eq!(utf16.encoding, "UTF-16LE");
+    }
+
+    #[test]
+    fn detects_legacy_encodings() {
+        let cases: [(&[u8], &str, &str); 4] = [
+            (
+                b"TITLE \"Caf\xe9 del Mar \x96 D\xe9j\xe0 vu\"",
+                "windows-1252",
+                "Café del Mar – Déjà vu",
+            ),
+            (
+                b"TITLE \"\x83}\x83W\x83R\x83J\x83^\x83X\x83g\x83\x8d\x83t\x83B\"",
+                "Shift_JIS",
+                "マジコカタストロフィ",
+            ),
+            (
+                b"TITLE \"\xc8\xd5\xb1\xbe\xb5\xc4\xd2\xf4\xc0\xd6\xba\xdc\xba\xc3\xcc\xfd\"",
+                "GBK",
+                "日本的音乐很好听",
+            ),
+            (
+                b"PERFORMER \"\xca\xe8\xed\xee\"\nTITLE \"\xc3\xf0\xf3\xef\xef\xe0 \xea\xf0\xee\xe2\xe8\"\n",
+                "windows-1251",
+                "Группа крови",
+            ),
+        ];
+
+        for (bytes, encoding, title) in cases {
+            let cue_text = decode_cue_bytes(bytes);
+            assert_eq!(cue_text.encoding, encoding);
+            let cue = parse_cue_text(&cue_text, false).unwrap();
+            assert_eq!(cue.title.as_deref(), Some(title));
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use std::path::Path;
+
+use crate::adapters::cue_text::parse_cue_file;
+use rcue::cue::{CueFile, Track};
+
+use crate::adapters::musicbrainz_api::{
+    duration_to_cd_frames, read_audio_file_length, AudioFileLength,
+};
+use crate::domain::{CueFinding, CueFindingKind, CueFindingSeverity};
+
+const CD_FRAMES_PER_SECOND: u64 = 75;
+const CD_SAMPLE_RATE: u64 = 44_100;
+const MIN_TRACK_SECONDS: u64 = 4;
+
+pub fn validate_cue(cue_path: &Path) -> Vec<CueFinding> {
+    let mut findings = Vec::new();
+    let cue = match parse_cue_file(cue_path, false) {
+        Ok(cue) => cue,
+        Err(err) => {
+            findings.push(error(
+                CueFindingKind::Unparseable,
+                None,
+                format!("failed to parse CUE: {err}"),
+            ));
+            return findings;
+        }
+    };
+    if cue.files.is_empty() {
+        findings.push(error(
+            CueFindingKind::MissingFile,
+            None,
+            "CUE has no FILE entries".into(),
+        ));
+    }
+
+    let cue_dir = cue_path.parent().unwrap_or_else(|| Path::new("."));
+    for (position, file) in cue.files.iter().enumerate() {
+        if file.tracks.is_empty() {
+            findings.push(warning(
+                CueFindingKind::EmptyFile,
+                None,
+                format!("FILE \"{}\" has no TRACK entries", file.file),
+            ));
+        }
+        let audio_path = cue_dir.join(&file.file);
+        let audio = if !audio_path.is_file() {
+            findings.push(error(
+                CueFindingKind::MissingFile,
+                None,
+                format!("FILE \"{}\" does not exist", file.file),
+            ));
+            None
+        } else {
+            match read_audio_file_length(&audio_path) {
+                Ok(audio) => Some(audio),
+                Err(err) => {
+                    findings.push(warning(
+                        CueFindingKind::UnreadableAudio,
+                        None,
+                        format!("could not read the length of \"{}\": {err}", file.file),
+                    ));
+                    None
+                }
+            }
+        };
+        let continues_into_next_file = cue
+            .files
+            .get(position + 1)
+            .is_some_and(|next| next.tracks.is_empty());
+        check_tracks(
+            &mut findings,
+            file,
+            audio.as_ref(),
+            continues_into_next_file,
+        );
+    }
+    findings
+}
+
+fn check_tracks(
+    findings: &mut Vec<CueFinding>,
+    file: &CueFile,
+    audio: Option<&AudioFileLength>,
+    continues_into_next_file: bool,
+) {
+    let sample_rate = audio.map_or(CD_SAMPLE_RATE, |audio| audio.sample_rate);
+    let audio_tracks = file
+        .tracks
+        .iter()
+        .filter(|track| track.format.eq_ignore_ascii_case("AUDIO"))
+        .collect::<Vec<_>>();
+    let mut starts: Vec<(&Track, u64)> = Vec::new();
+
+    for (position, track) in audio_tracks.iter().enumerate() {
+        let is_last = position + 1 == audio_tracks.len();
+        let indices = track
+            .indices
+            .iter()
+            .map(|(number, offset)| (number.as_str(), duration_to_cd_frames(*offset)))
+            .collect::<Vec<_>>();
+        let Some(start) = index_frames(&indices, "01") else {
+            if !(is_last && continues_into_next_file) {
+                findings.push(error(
+                    CueFindingKind::MissingIndex,
+                    Some(track),
+                    "has no INDEX 01".into(),
+                ));
+            }
+            continue;
+        };
+
+        if let Some(pregap) = index_frames(&indices, "00") {
+            if pregap >= start {
+                findings.push(error(
+                    CueFindingKind::IndexOrder,
+                    Some(track),
+                    format!(
+                        "INDEX 00 at {} is not before INDEX 01 at {}",
+                        format_frames(pregap),
+                        format_frames(start)
+                    ),
+                ));
+            }
+        }
+        let mut previous: Option<(&str, u64)> = None;
+        for &(number, frames) in indices.iter().filter(|(number, _)| *number != "00") {
+            if let Some((previous_number, previous_frames)) = previous {
+                if frames <= previous_frames {
+                    findings.push(error(
+                        CueFindingKind::IndexOrder,
+                        Some(track),
+                        format!(
+                            "INDEX {number} at {} is not after INDEX {previous_number} at {}",
+                            format_frames(frames),
+                            format_frames(previous_frames)
+                        ),
+                    ));
+                }
+            }
+            previous = Some((number, frames));
+        }
+
+        if let Some((previous_track, previous_start)) = starts.last() {
+            if start <= *previous_start {
+                findings.push(error(
+                    CueFindingKind::IndexOrder,
+                    Some(track),
+                    format!(
+                        "INDEX 01 at {} is not after track {} INDEX 01 at {}",
+                        format_frames(start),
+                        previous_track.no,
+                        format_frames(*previous_start)
+                    ),
+                ));
+            }
+        }
+
+        if let Some(audio) = audio {
+            let last_index = indices
+                .iter()
+                .map(|(_, frames)| *frames)
+                .max()
+                .unwrap_or(start);
+            if frames_to_samples(last_index, sample_rate) >= audio.samples {
+                findings.push(error(
+                    CueFindingKind::PastEndOfAudio,
+                    Some(track),
+                    format!(
+                        "INDEX at {} is past the end of \"{}\" ({})",
+                        format_frames(last_index),
+                        file.file,
+                        format_samples(audio.samples, sample_rate)
+                    ),
+                ));
+            }
+        }
+        starts.push((track, start));
+    }
+
+    for (position, (track, start)) in starts.iter().enumerate() {
+        let start_samples = frames_to_samples(*start, sample_rate);
+        let end_samples = match starts.get(position + 1) {
+            Some((_, next_start)) => frames_to_samples(*next_start, sample_rate),
+            None if continues_into_next_file => continue,
+            None => match audio {
+                Some(audio) => audio.samples,
+                None => continue,
+            },
+        };
+        let Some(length) = end_samples.checked_sub(start_samples) else {
+            continue;
+        };
+        if length > 0 && length < MIN_TRACK_SECONDS * sample_rate {
+            findings.push(warning(
+                CueFindingKind::ShortTrack,
+                Some(track),
+                format!(
+                    "is only {:.3} seconds long ({length} samples), shorter than {MIN_TRACK_SECONDS} seconds",
+                    length as f64 / sample_rate as f64
+                ),
+            ));
+        }
+    }
+}
+
+fn index_frames(indices: &[(&str, u64)], number: &str) -> Option<u64> {
+    indices
+        .iter()
+        .find(|(candidate, _)| *candidate == number)
+        .map(|(_, frames)| *frames)
+}
+
+fn frames_to_samples(frames: u64, sample_rate: u64) -> u64 {
+    frames * sample_rate / CD_FRAMES_PER_SECOND
+}
+
+fn format_frames(frames: u64) -> String {
+    let seconds = frames / CD_FRAMES_PER_SECOND;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 60,
+        seconds % 60,
+        frames % CD_FRAMES_PER_SECOND
+    )
+}
+
+fn format_samples(samples: u64, sample_rate: u64) -> String {
+    format_frames(samples * CD_FRAMES_PER_SECOND / sample_rate)
+}
+
+fn error(kind: CueFindingKind, track: Option<&Track>, message: String) -> CueFinding {
+    finding(CueFindingSeverity::Error, kind, track, message)
+}
+
+fn warning(kind: CueFindingKind, track: Option<&Track>, message: String) -> CueFinding {
+    finding(CueFindingSeverity::Warning, kind, track, message)
+}
+
+fn finding(
+    severity: CueFindingSeverity,
+    kind: CueFindingKind,
+    track: Option<&Track>,
+    message: String,
+) -> CueFinding {
+    CueFinding {
+        severity,
+        kind,
+        track: track.map(|track| track.no.clone()),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::validate_cue;
+    use crate::domain::{CueFinding, CueFindingKind, CueFindingSeverity};
+
+    #[test]
+    fn well_formed_cue_has_no_findings() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("album.wav"), 44_100 * 20);
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            r#"FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    INDEX 00 00:09:00
+    INDEX 01 00:10:00
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(validate_cue(&cue_path), Vec::new());
+    }
+
+    #[test]
+    fn reports_ordering_length_and_end_of_audio_problems_per_track() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("album.wav"), 44_100 * 20);
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            r#"FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    INDEX 01 00:10:00
+  TRACK 03 AUDIO
+    INDEX 01 00:12:00
+  TRACK 04 AUDIO
+    INDEX 01 00:11:00
+  TRACK 05 AUDIO
+    INDEX 01 00:20:00
+"#,
+        )
+        .unwrap();
+
+        let findings = validate_cue(&cue_path);
+
+        assert_eq!(
+            findings,
+            vec![
+                CueFinding {
+                    severity: CueFindingSeverity::Error,
+                    kind: CueFindingKind::IndexOrder,
+                    track: Some("04".into()),
+                    message: "INDEX 01 at 00:11:00 is not after track 03 INDEX 01 at 00:12:00"
+                        .into(),
+                },
+                CueFinding {
+                    severity: CueFindingSeverity::Error,
+                    kind: CueFindingKind::PastEndOfAudio,
+                    track: Some("05".into()),
+                    message: "INDEX at 00:20:00 is past the end of \"album.wav\" (00:20:00)".into(),
+                },
+                CueFinding {
+                    severity: CueFindingSeverity::Warning,
+                    kind: CueFindingKind::ShortTrack,
+                    track: Some("02".into()),
+                    message: "is only 2.000 seconds long (88200 samples), shorter than 4 seconds"
+                        .into(),
+                },
+            ]
+        );
+        assert_eq!(
+            findings[0].describe(),
+            format!("track 04: {}", findings[0].message)
+        );
+    }
+
+    #[test]
+    fn reports_missing_files_and_indexes() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            r#"FILE "missing.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 00 00:00:00
+"#,
+        )
+        .unwrap();
+
+        let findings = validate_cue(&cue_path);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].kind, CueFindingKind::MissingFile);
+        assert_eq!(findings[0].message, "FILE \"missing.wav\" does not exist");
+        assert_eq!(findings[1].kind, CueFindingKind::MissingIndex);
+        assert_eq!(findings[1].track.as_deref(), Some("01"));
+        assert!(findings.iter().all(CueFinding::is_error));
+    }
+
+    #[test]
+    fn gap_appended_layout_only_warns_about_the_trackless_file() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("01.wav"), 44_100 * 10);
+        write_wav(&tmp.path().join("02.wav"), 44_100 * 10);
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            r#"FILE "01.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    INDEX 00 00:08:00
+FILE "02.wav" WAVE
+    INDEX 01 00:00:00
+"#,
+        )
+        .unwrap();
+
+        let findings = validate_cue(&cue_path);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, CueFindingSeverity::Warning);
+        assert_eq!(findings[0].kind, CueFindingKind::EmptyFile);
+    }
+
+    fn write_wav(path: &Path, samples: u32) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for _ in 0..samples {
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+}
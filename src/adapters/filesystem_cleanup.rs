@@ -1,19 +1,33 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
 
+use crate::application::metrics::Metrics;
 use crate::application::ports::TrackCleanup;
 use crate::domain::{
-    CueSheet, GeneratedTrack, TrackCleanupOutcome, TrackCleanupStatus, TrackedDownload,
+    CueSheet, GeneratedTrack, InProgressSplit, OutputCodec, SplitLayout, TrackCleanupOutcome,
+    TrackCleanupStatus, TrackedDownload,
 };
 
 #[derive(Debug, Clone, Default)]
-pub struct FilesystemTrackCleanup;
+pub struct FilesystemTrackCleanup {
+    split_layout: SplitLayout,
+    metrics: Metrics,
+}
 
 impl FilesystemTrackCleanup {
-    pub fn new() -> Self {
-        Self
+    pub fn new(split_layout: SplitLayout) -> Self {
+        Self {
+            split_layout,
+            metrics: Metrics::default(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
     }
 }
 
@@ -23,7 +37,30 @@ impl TrackCleanup for FilesystemTrackCleanup {
         download: &TrackedDownload,
     ) -> Result<Vec<TrackCleanupOutcome>> {
         let download = download.clone();
-        tokio::task::spawn_blocking(move || cleanup_download_tracks(&download))
+        let staging_root = self.split_layout.staging_root(&download.download_id);
+        let outcomes = tokio::task::spawn_blocking(move || {
+            let outcomes = cleanup_download_tracks(&download)?;
+            if let Some(staging_root) = staging_root {
+                if outcomes
+                    .iter()
+                    .all(|outcome| outcome.status != TrackCleanupStatus::DeleteFailed)
+                {
+                    remove_staging_root(&staging_root)?;
+                }
+            }
+            Ok::<_, anyhow::Error>(outcomes)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))??;
+        for outcome in &outcomes {
+            self.metrics.record_track_cleanup(outcome.status);
+        }
+        Ok(outcomes)
+    }
+
+    async fn remove_partial_outputs(&self, split: &InProgressSplit) -> Result<Vec<PathBuf>> {
+        let split = split.clone();
+        tokio::task::spawn_blocking(move || remove_partial_outputs(&split))
             .await
             .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
@@ -35,6 +72,19 @@ fn cleanup_download_tracks(download: &TrackedDownload) -> Result<Vec<TrackCleanu
     for cue_sheet in &download.cue_sheets {
         for track in &cue_sheet.tracks {
             let path = cleanup_track_path(cue_sheet, track);
+            if !has_codec_extension(&path, track.codec) {
+                outcomes.push(TrackCleanupOutcome {
+                    track_id: track.id.clone(),
+                    status: TrackCleanupStatus::DeleteFailed,
+                    message: Some(format!(
+                        "refusing to delete {}: expected a .{} file for {} output",
+                        path.display(),
+                        track.codec.extension(),
+                        track.codec.as_str()
+                    )),
+                });
+                continue;
+            }
             match fs::remove_file(&path) {
                 Ok(()) => outcomes.push(TrackCleanupOutcome {
                     track_id: track.id.clone(),
@@ -60,6 +110,59 @@ fn cleanup_download_tracks(download: &TrackedDownload) -> Result<Vec<TrackCleanu
     Ok(outcomes)
 }
 
+/// Only files with an output codec extension that were not in the output
+/// directory before the split started are removed.
+fn remove_partial_outputs(split: &InProgressSplit) -> Result<Vec<PathBuf>> {
+    let output_dir = Path::new(&split.output_dir);
+    let entries = match fs::read_dir(output_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(anyhow!("failed to list {}: {err}", output_dir.display())),
+    };
+    let existing_files = split
+        .existing_files
+        .iter()
+        .map(PathBuf::from)
+        .collect::<HashSet<_>>();
+    let codecs = [
+        OutputCodec::Flac,
+        OutputCodec::Wav,
+        OutputCodec::Alac,
+        OutputCodec::Opus,
+        OutputCodec::Mp3,
+    ];
+
+    let mut removed = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if !entry.file_type()?.is_file()
+            || existing_files.contains(&path)
+            || !codecs
+                .iter()
+                .any(|codec| has_codec_extension(&path, *codec))
+        {
+            continue;
+        }
+        fs::remove_file(&path)
+            .map_err(|err| anyhow!("failed to remove {}: {err}", path.display()))?;
+        removed.push(path);
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+fn remove_staging_root(staging_root: &Path) -> Result<()> {
+    match fs::remove_dir_all(staging_root) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(anyhow!(
+            "failed to remove staging directory {}: {err}",
+            staging_root.display()
+        )),
+    }
+}
+
 fn cleanup_track_path(cue_sheet: &CueSheet, track: &GeneratedTrack) -> PathBuf {
     let path = PathBuf::from(&track.path);
     if path.is_absolute() {
@@ -71,13 +174,66 @@ fn cleanup_track_path(cue_sheet: &CueSheet, track: &GeneratedTrack) -> PathBuf {
         .map_or(path.clone(), |cue_dir| cue_dir.join(path))
 }
 
+fn has_codec_extension(path: &Path, codec: OutputCodec) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(codec.extension()))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use std::path::PathBuf;
 
-    use crate::domain::{CueSheet, CueSheetStatus, GeneratedTrack, TrackCleanupStatus};
+    use tempfile::tempdir;
+
+    use crate::domain::{
+        CueSheet, CueSheetStatus, GeneratedTrack, InProgressSplit, OutputCodec, SplitLayout,
+        TrackCleanupStatus,
+    };
+
+    use super::{cleanup_download_tracks, cleanup_track_path, FilesystemTrackCleanup};
+    use crate::application::ports::TrackCleanup;
+
+    #[tokio::test]
+    async fn removes_only_audio_files_written_after_the_split_started() {
+        let tmp = tempdir().unwrap();
+        let source = tmp.path().join("image.flac");
+        let cue = tmp.path().join("image.cue");
+        let partial = tmp.path().join("01 - Track.flac");
+        let notes = tmp.path().join("notes.txt");
+        for path in [&source, &cue, &partial, &notes] {
+            fs::write(path, b"data").unwrap();
+        }
+        let split = InProgressSplit {
+            download_id: "download-1".into(),
+            output_dir: tmp.path().to_string_lossy().into_owned(),
+            existing_files: vec![
+                source.to_string_lossy().into_owned(),
+                cue.to_string_lossy().into_owned(),
+            ],
+            started_at: "2026-06-12 12:00:00".into(),
+        };
+
+        let removed = FilesystemTrackCleanup::default()
+            .remove_partial_outputs(&split)
+            .await
+            .unwrap();
+
+        assert_eq!(removed, vec![partial.clone()]);
+        assert!(source.exists() && cue.exists() && notes.exists());
+        assert!(!partial.exists());
 
-    use super::{cleanup_download_tracks, cleanup_track_path};
+        let missing = InProgressSplit {
+            output_dir: tmp.path().join("missing").to_string_lossy().into_owned(),
+            ..split
+        };
+        assert!(FilesystemTrackCleanup::default()
+            .remove_partial_outputs(&missing)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 
     #[test]
     fn cleanup_resolves_legacy_relative_track_paths_from_cue_directory() {
@@ -89,6 +245,10 @@ mod tests {
             message: None,
             updated_at: "2024-01-01 00:00:00".into(),
             tracks: Vec::new(),
+            findings: Vec::new(),
+            repairs: Vec::new(),
+            encoding: None,
+            pregap_policy: None,
         };
         let track = GeneratedTrack {
             id: "track-1".into(),
@@ -96,9 +256,11 @@ mod tests {
             download_id: "download-1".into(),
             path: "01 - Track.flac".into(),
             size_bytes: None,
+            codec: OutputCodec::Flac,
             cleanup_status: TrackCleanupStatus::Pending,
             cleanup_message: None,
             deleted_at: None,
+            tags: Vec::new(),
         };
 
         assert_eq!(
@@ -109,7 +271,78 @@ mod tests {
 
     #[test]
     fn cleanup_marks_missing_tracks_without_error() {
-        let download = crate::domain::TrackedDownload {
+        let download = download_with_track("/tmp/not-here.flac", OutputCodec::Flac);
+
+        let outcomes = cleanup_download_tracks(&download).unwrap();
+        assert_eq!(outcomes[0].status, TrackCleanupStatus::Missing);
+    }
+
+    #[test]
+    fn cleanup_refuses_to_delete_files_that_do_not_match_the_track_codec() {
+        let tmp = tempdir().unwrap();
+        let source = tmp.path().join("album.flac");
+        fs::write(&source, b"source image").unwrap();
+        let download = download_with_track(&source.to_string_lossy(), OutputCodec::Opus);
+
+        let outcomes = cleanup_download_tracks(&download).unwrap();
+
+        assert_eq!(outcomes[0].status, TrackCleanupStatus::DeleteFailed);
+        assert!(outcomes[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("expected a .opus file"));
+        assert!(source.exists());
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_the_staging_subtree_once_tracks_are_deleted() {
+        let tmp = tempdir().unwrap();
+        let staging_dir = tmp.path().join("staging");
+        let track_dir = staging_dir.join("download-1").join("album");
+        fs::create_dir_all(&track_dir).unwrap();
+        let track = track_dir.join("01.flac");
+        fs::write(&track, b"track").unwrap();
+        fs::write(track_dir.join("folder.jpg"), b"cover").unwrap();
+        let cleanup = FilesystemTrackCleanup::new(SplitLayout::new(Some(staging_dir.clone())));
+
+        let outcomes = cleanup
+            .cleanup_download_tracks(&download_with_track(
+                &track.to_string_lossy(),
+                OutputCodec::Flac,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes[0].status, TrackCleanupStatus::Deleted);
+        assert!(!staging_dir.join("download-1").exists());
+        assert!(staging_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn cleanup_keeps_the_staging_subtree_when_a_track_fails() {
+        let tmp = tempdir().unwrap();
+        let staging_dir = tmp.path().join("staging");
+        let track_dir = staging_dir.join("download-1").join("album");
+        fs::create_dir_all(&track_dir).unwrap();
+        let track = track_dir.join("01.flac");
+        fs::write(&track, b"track").unwrap();
+        let cleanup = FilesystemTrackCleanup::new(SplitLayout::new(Some(staging_dir)));
+
+        let outcomes = cleanup
+            .cleanup_download_tracks(&download_with_track(
+                &track.to_string_lossy(),
+                OutputCodec::Opus,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes[0].status, TrackCleanupStatus::DeleteFailed);
+        assert!(track.exists());
+    }
+
+    fn download_with_track(path: &str, codec: OutputCodec) -> crate::domain::TrackedDownload {
+        crate::domain::TrackedDownload {
             download_id: "download-1".into(),
             title: "Album".into(),
             status: "completed".into(),
@@ -131,12 +364,18 @@ mod tests {
                     id: "track-1".into(),
                     cue_sheet_id: "cue-1".into(),
                     download_id: "download-1".into(),
-                    path: "/tmp/not-here.flac".into(),
+                    path: path.into(),
                     size_bytes: None,
+                    codec,
                     cleanup_status: TrackCleanupStatus::Pending,
                     cleanup_message: None,
                     deleted_at: None,
+                    tags: Vec::new(),
                 }],
+                findings: Vec::new(),
+                repairs: Vec::new(),
+                encoding: None,
+                pregap_policy: None,
                 id: "cue-1".into(),
                 path: "/tmp/album.cue".into(),
                 download_id: "download-1".into(),
@@ -146,9 +385,11 @@ mod tests {
             }],
             generated_track_count: 1,
             last_error: None,
-        };
-
-        let outcomes = cleanup_download_tracks(&download).unwrap();
-        assert_eq!(outcomes[0].status, TrackCleanupStatus::Missing);
+            import_plan: None,
+            audit_events: Vec::new(),
+            notification_deliveries: Vec::new(),
+            attempt_count: 0,
+            next_retry_at: None,
+        }
     }
 }
@@ -1,17 +1,38 @@
-use std::fs;
+use std::fs::{self, File};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use anyhow::{anyhow, Result};
-use rcue::parser::parse_from_file;
-
-use crate::application::ports::{CueInputInspector, CueInputSnapshot, CueReferencedAudioInput};
-
-#[derive(Debug, Clone, Copy, Default)]
-pub struct FilesystemCueInputInspector;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::adapters::audio_format::AudioFormat;
+use crate::adapters::cue_repair::{references_audio_file, repair_cue};
+use crate::adapters::cue_text::{parse_cue_file, read_cue_text};
+use crate::adapters::cue_validation::validate_cue;
+use crate::application::ports::{
+    CueInputInspector, CueInputSnapshot, CueReferencedAudioInput, DecodedCue, RepairedCue,
+};
+use crate::domain::{CueFinding, FileFingerprint};
+
+#[derive(Debug, Clone, Default)]
+pub struct FilesystemCueInputInspector {
+    content_hash: bool,
+    repair_dir: Option<PathBuf>,
+}
 
 impl FilesystemCueInputInspector {
-    pub fn new() -> Self {
-        Self
+    pub fn new(content_hash: bool) -> Self {
+        Self {
+            content_hash,
+            repair_dir: None,
+        }
+    }
+
+    pub fn with_repair_dir(mut self, repair_dir: PathBuf) -> Self {
+        self.repair_dir = Some(repair_dir);
+        self
     }
 }
 
@@ -23,6 +44,14 @@ impl CueInputInspector for FilesystemCueInputInspector {
             .map_err(|err| anyhow!("blocking task failed to join: {err}"))
     }
 
+    async fn fingerprint_file(&self, path: &Path) -> Result<Option<FileFingerprint>> {
+        let path = path.to_path_buf();
+        let content_hash = self.content_hash;
+        tokio::task::spawn_blocking(move || fingerprint_file_sync(&path, content_hash))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
     async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot> {
         let cue_path = cue_path.to_path_buf();
         tokio::task::spawn_blocking(move || snapshot_inputs_sync(&cue_path))
@@ -30,12 +59,60 @@ impl CueInputInspector for FilesystemCueInputInspector {
             .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
+    async fn validate_cue(&self, cue_path: &Path) -> Result<Vec<CueFinding>> {
+        let cue_path = cue_path.to_path_buf();
+        tokio::task::spawn_blocking(move || validate_cue(&cue_path))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))
+    }
+
+    async fn decode_cue(&self, cue_path: &Path) -> Result<Option<DecodedCue>> {
+        let cue_path = cue_path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let cue_text = read_cue_text(&cue_path)?;
+            Ok(Some(DecodedCue {
+                text: cue_text.text,
+                encoding: cue_text.encoding.to_owned(),
+            }))
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn repair_cue(&self, cue_path: &Path, cue_sheet_id: &str) -> Result<Option<RepairedCue>> {
+        let Some(repair_dir) = &self.repair_dir else {
+            return Ok(None);
+        };
+        let cue_path = cue_path.to_path_buf();
+        let output_path = repair_dir.join(format!("{cue_sheet_id}.cue"));
+        tokio::task::spawn_blocking(move || {
+            Ok(
+                repair_cue(&cue_path, &output_path)?.map(|repairs| RepairedCue {
+                    path: output_path,
+                    repairs,
+                }),
+            )
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
     async fn cue_references_audio_file(&self, cue_path: &Path, audio_path: &Path) -> Result<bool> {
         let cue_path = cue_path.to_path_buf();
         let audio_path = audio_path.to_path_buf();
-        tokio::task::spawn_blocking(move || cue_references_audio_file_sync(&cue_path, &audio_path))
+        let repair = self.repair_dir.is_some();
+        tokio::task::spawn_blocking(move || {
+            cue_references_audio_file_sync(&cue_path, &audio_path, repair)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))
+    }
+
+    async fn list_output_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        let output_dir = output_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || list_files(&output_dir))
             .await
-            .map_err(|err| anyhow!("blocking task failed to join: {err}"))
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
     async fn filter_cue_files_for_audio(
@@ -44,18 +121,38 @@ impl CueInputInspector for FilesystemCueInputInspector {
         audio_path: &Path,
     ) -> Result<Vec<PathBuf>> {
         let audio_path = audio_path.to_path_buf();
-        tokio::task::spawn_blocking(move || filter_cue_files_for_audio_sync(cue_files, &audio_path))
-            .await
-            .map_err(|err| anyhow!("blocking task failed to join: {err}"))
+        let repair = self.repair_dir.is_some();
+        tokio::task::spawn_blocking(move || {
+            filter_cue_files_for_audio_sync(cue_files, &audio_path, repair)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))
+    }
+}
+
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(anyhow!("failed to list {}: {err}", dir.display())),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
     }
+    files.sort();
+    Ok(files)
 }
 
 fn snapshot_inputs_sync(cue_path: &Path) -> Result<CueInputSnapshot> {
     let cue_size_bytes = file_size(cue_path);
-    let cue = match parse_from_file(&cue_path.to_string_lossy(), false) {
+    let cue = match parse_cue_file(cue_path, false) {
         Ok(cue) => cue,
         Err(err) => {
-            eprintln!(
+            warn!(
                 "Unable to parse cue file for input snapshot {}: {err}",
                 cue_path.display()
             );
@@ -79,11 +176,14 @@ fn snapshot_inputs_sync(cue_path: &Path) -> Result<CueInputSnapshot> {
     })
 }
 
-fn cue_references_audio_file_sync(cue_path: &Path, audio_path: &Path) -> bool {
-    let cue = match parse_from_file(&cue_path.to_string_lossy(), false) {
+fn cue_references_audio_file_sync(cue_path: &Path, audio_path: &Path, repair: bool) -> bool {
+    if repair {
+        return references_audio_file(cue_path, audio_path);
+    }
+    let cue = match parse_cue_file(cue_path, false) {
         Ok(cue) => cue,
         Err(err) => {
-            eprintln!(
+            warn!(
                 "Unable to parse cue file while matching audio {}: {err}",
                 cue_path.display()
             );
@@ -98,16 +198,21 @@ fn cue_references_audio_file_sync(cue_path: &Path, audio_path: &Path) -> bool {
         .any(|candidate| candidate == audio_path)
 }
 
-fn filter_cue_files_for_audio_sync(cue_files: Vec<PathBuf>, audio_path: &Path) -> Vec<PathBuf> {
+fn filter_cue_files_for_audio_sync(
+    cue_files: Vec<PathBuf>,
+    audio_path: &Path,
+    repair: bool,
+) -> Vec<PathBuf> {
     cue_files
         .into_iter()
-        .filter(|cue_path| cue_references_audio_file_sync(cue_path, audio_path))
+        .filter(|cue_path| cue_references_audio_file_sync(cue_path, audio_path, repair))
         .collect()
 }
 
 fn existing_audio_input(path: PathBuf) -> Option<CueReferencedAudioInput> {
     let metadata = fs::metadata(&path).ok()?;
     Some(CueReferencedAudioInput {
+        audio_format: AudioFormat::detect(&path).map(|format| format.label().to_owned()),
         path,
         size_bytes: i64::try_from(metadata.len()).ok(),
     })
@@ -119,6 +224,48 @@ fn file_size(path: &Path) -> Option<i64> {
         .and_then(|metadata| i64::try_from(metadata.len()).ok())
 }
 
+fn fingerprint_file_sync(path: &Path, content_hash: bool) -> Result<Option<FileFingerprint>> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(None);
+    };
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .and_then(|duration| i64::try_from(duration.as_nanos()).ok());
+    let content_hash = if content_hash {
+        Some(sha256_hex(path)?)
+    } else {
+        None
+    };
+    Ok(Some(FileFingerprint {
+        size_bytes: i64::try_from(metadata.len())?,
+        modified_ns,
+        content_hash,
+    }))
+}
+
+fn sha256_hex(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|err| anyhow!("failed to read {}: {err}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -140,7 +287,7 @@ mod tests {
         .unwrap();
         fs::write(&audio_path, b"audio").unwrap();
 
-        let snapshot = FilesystemCueInputInspector::new()
+        let snapshot = FilesystemCueInputInspector::new(false)
             .snapshot_inputs(&cue_path)
             .await
             .unwrap();
@@ -151,6 +298,7 @@ mod tests {
             vec![CueReferencedAudioInput {
                 path: audio_path,
                 size_bytes: Some(5),
+                audio_format: Some("FLAC".into()),
             }]
         );
     }
@@ -165,7 +313,7 @@ mod tests {
         )
         .unwrap();
 
-        let snapshot = FilesystemCueInputInspector::new()
+        let snapshot = FilesystemCueInputInspector::new(false)
             .snapshot_inputs(&cue_path)
             .await
             .unwrap();
@@ -180,7 +328,7 @@ mod tests {
         let cue_path = tmp.path().join("broken.cue");
         fs::write(&cue_path, "not a cue").unwrap();
 
-        let snapshot = FilesystemCueInputInspector::new()
+        let snapshot = FilesystemCueInputInspector::new(false)
             .snapshot_inputs(&cue_path)
             .await
             .unwrap();
@@ -202,7 +350,7 @@ mod tests {
         )
         .unwrap();
 
-        let inspector = FilesystemCueInputInspector::new();
+        let inspector = FilesystemCueInputInspector::new(false);
         assert!(inspector
             .cue_references_audio_file(&cue_path, &target_audio)
             .await
@@ -213,6 +361,42 @@ mod tests {
             .unwrap());
     }
 
+    #[tokio::test]
+    async fn repair_writes_corrected_cue_into_repair_dir_when_enabled() {
+        let tmp = tempdir().unwrap();
+        let audio_path = tmp.path().join("album.flac");
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&audio_path, b"audio").unwrap();
+        fs::write(
+            &cue_path,
+            "FILE \"album.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let plain = FilesystemCueInputInspector::new(false);
+        assert_eq!(plain.repair_cue(&cue_path, "cue-1").await.unwrap(), None);
+        assert!(!plain
+            .cue_references_audio_file(&cue_path, &audio_path)
+            .await
+            .unwrap());
+
+        let repair_dir = tmp.path().join("repaired");
+        let inspector = FilesystemCueInputInspector::new(false).with_repair_dir(repair_dir.clone());
+        assert!(inspector
+            .cue_references_audio_file(&cue_path, &audio_path)
+            .await
+            .unwrap());
+        let repaired = inspector
+            .repair_cue(&cue_path, "cue-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repaired.path, repair_dir.join("cue-1.cue"));
+        assert_eq!(repaired.repairs.len(), 1);
+        let snapshot = inspector.snapshot_inputs(&repaired.path).await.unwrap();
+        assert_eq!(snapshot.audio_inputs[0].path, audio_path);
+    }
+
     #[tokio::test]
     async fn invalid_cue_matching_returns_false() {
         let tmp = tempdir().unwrap();
@@ -220,11 +404,44 @@ mod tests {
         let audio_path = tmp.path().join("target.flac");
         fs::write(&cue_path, "not a cue").unwrap();
 
-        let matches = FilesystemCueInputInspector::new()
+        let matches = FilesystemCueInputInspector::new(false)
             .cue_references_audio_file(&cue_path, &audio_path)
             .await
             .unwrap();
 
         assert!(!matches);
     }
+
+    #[tokio::test]
+    async fn fingerprint_includes_mtime_and_optional_content_hash() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("album.flac");
+        fs::write(&path, b"abc").unwrap();
+
+        let fingerprint = FilesystemCueInputInspector::new(false)
+            .fingerprint_file(&path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fingerprint.size_bytes, 3);
+        assert!(fingerprint.modified_ns.is_some());
+        assert_eq!(fingerprint.content_hash, None);
+
+        let hashed = FilesystemCueInputInspector::new(true)
+            .fingerprint_file(&path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            hashed.content_hash.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            FilesystemCueInputInspector::new(true)
+                .fingerprint_file(&tmp.path().join("missing.flac"))
+                .await
+                .unwrap(),
+            None
+        );
+    }
 }
@@ -0,0 +1,368 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::adapters::artwork;
+use crate::application::ports::{ArtworkSource, TrackArtwork, TrackTagger};
+use crate::domain::TrackTag;
+
+const FLAC_MARKER: &[u8; 4] = b"fLaC";
+const BLOCK_TYPE_STREAMINFO: u8 = 0;
+const BLOCK_TYPE_PADDING: u8 = 1;
+const BLOCK_TYPE_VORBIS_COMMENT: u8 = 4;
+const BLOCK_TYPE_PICTURE: u8 = 6;
+const PICTURE_TYPE_FRONT_COVER: u32 = 3;
+const MAX_BLOCK_LENGTH: usize = (1 << 24) - 1;
+const VENDOR: &str = concat!("splittarr ", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Default)]
+pub struct FlacTrackTagger;
+
+impl TrackTagger for FlacTrackTagger {
+    async fn write_track_tags(&self, track_path: &Path, tags: &[TrackTag]) -> Result<()> {
+        let track_path = track_path.to_path_buf();
+        let tags = tags.to_vec();
+        tokio::task::spawn_blocking(move || write_vorbis_comments(&track_path, &tags))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn find_artwork(
+        &self,
+        cue_path: &Path,
+        audio_inputs: &[PathBuf],
+    ) -> Result<Option<TrackArtwork>> {
+        let cue_path = cue_path.to_path_buf();
+        let audio_inputs = audio_inputs.to_vec();
+        tokio::task::spawn_blocking(move || artwork::find_artwork(&cue_path, &audio_inputs))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn write_track_artwork(&self, track_path: &Path, artwork: &TrackArtwork) -> Result<()> {
+        let track_path = track_path.to_path_buf();
+        let artwork = artwork.clone();
+        tokio::task::spawn_blocking(move || write_picture(&track_path, &artwork))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn write_folder_artwork(
+        &self,
+        directory: &Path,
+        artwork: &TrackArtwork,
+    ) -> Result<Option<PathBuf>> {
+        let directory = directory.to_path_buf();
+        let artwork = artwork.clone();
+        tokio::task::spawn_blocking(move || artwork::write_folder_artwork(&directory, &artwork))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+}
+
+pub fn write_vorbis_comments(path: &Path, tags: &[TrackTag]) -> Result<()> {
+    let comment_block = vorbis_comment_block(tags)?;
+    rewrite_metadata(path, "tags", |blocks| {
+        blocks.retain(|(block_type, _)| *block_type != BLOCK_TYPE_VORBIS_COMMENT);
+        blocks.insert(1, (BLOCK_TYPE_VORBIS_COMMENT, comment_block));
+    })
+}
+
+pub fn write_picture(path: &Path, artwork: &TrackArtwork) -> Result<()> {
+    let picture_block = picture_block(artwork)?;
+    rewrite_metadata(path, "artwork", |blocks| {
+        blocks.retain(|(block_type, _)| *block_type != BLOCK_TYPE_PICTURE);
+        blocks.push((BLOCK_TYPE_PICTURE, picture_block));
+    })
+}
+
+pub fn read_front_picture(path: &Path) -> Result<Option<TrackArtwork>> {
+    let mut reader = open_flac(path)?;
+    let pictures = read_metadata_blocks(&mut reader, path)?
+        .into_iter()
+        .filter(|(block_type, _)| *block_type == BLOCK_TYPE_PICTURE)
+        .filter_map(|(_, body)| parse_picture_block(&body))
+        .collect::<Vec<_>>();
+    let index = pictures
+        .iter()
+        .position(|(picture_type, _, _)| *picture_type == PICTURE_TYPE_FRONT_COVER)
+        .unwrap_or(0);
+    Ok(pictures
+        .into_iter()
+        .nth(index)
+        .map(|(_, mime_type, data)| TrackArtwork {
+            source: ArtworkSource::Embedded(path.to_path_buf()),
+            mime_type,
+            data,
+        }))
+}
+
+fn open_flac(path: &Path) -> Result<BufReader<File>> {
+    let mut reader = BufReader::new(
+        File::open(path).map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?,
+    );
+    let mut marker = [0_u8; 4];
+    reader
+        .read_exact(&mut marker)
+        .map_err(|err| anyhow!("failed to read {}: {err}", path.display()))?;
+    if &marker != FLAC_MARKER {
+        return Err(anyhow!("not a FLAC file: {}", path.display()));
+    }
+    Ok(reader)
+}
+
+fn read_metadata_blocks(reader: &mut impl Read, path: &Path) -> Result<Vec<(u8, Vec<u8>)>> {
+    let mut blocks = Vec::new();
+    loop {
+        let mut header = [0_u8; 4];
+        reader
+            .read_exact(&mut header)
+            .map_err(|err| anyhow!("failed to read FLAC metadata in {}: {err}", path.display()))?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7f;
+        let length =
+            usize::from(header[1]) << 16 | usize::from(header[2]) << 8 | usize::from(header[3]);
+        let mut body = vec![0_u8; length];
+        reader
+            .read_exact(&mut body)
+            .map_err(|err| anyhow!("failed to read FLAC metadata in {}: {err}", path.display()))?;
+        blocks.push((block_type, body));
+        if is_last {
+            return Ok(blocks);
+        }
+    }
+}
+
+fn rewrite_metadata(
+    path: &Path,
+    what: &str,
+    edit: impl FnOnce(&mut Vec<(u8, Vec<u8>)>),
+) -> Result<()> {
+    let mut reader = open_flac(path)?;
+    let mut blocks = read_metadata_blocks(&mut reader, path)?;
+    blocks.retain(|(block_type, _)| *block_type != BLOCK_TYPE_PADDING);
+    if blocks.first().map(|(block_type, _)| *block_type) != Some(BLOCK_TYPE_STREAMINFO) {
+        return Err(anyhow!(
+            "FLAC file has no STREAMINFO block: {}",
+            path.display()
+        ));
+    }
+    edit(&mut blocks);
+
+    let temp_path = temp_path_for(path)?;
+    let result = write_with_metadata(&temp_path, &blocks, &mut reader)
+        .and_then(|()| fs::rename(&temp_path, path))
+        .map_err(|err| anyhow!("failed to write {what} to {}: {err}", path.display()));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_with_metadata(
+    temp_path: &Path,
+    blocks: &[(u8, Vec<u8>)],
+    audio: &mut impl Read,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(temp_path)?);
+    writer.write_all(FLAC_MARKER)?;
+    for (index, (block_type, body)) in blocks.iter().enumerate() {
+        let last_flag = if index + 1 == blocks.len() { 0x80 } else { 0 };
+        let length = body.len();
+        writer.write_all(&[
+            last_flag | block_type,
+            (length >> 16) as u8,
+            (length >> 8) as u8,
+            length as u8,
+        ])?;
+        writer.write_all(body)?;
+    }
+    io::copy(audio, &mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|err| err.into_error())?
+        .sync_all()
+}
+
+fn vorbis_comment_block(tags: &[TrackTag]) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    push_vorbis_string(&mut body, VENDOR);
+    body.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+    for tag in tags {
+        if tag.name.is_empty() || tag.name.contains('=') {
+            return Err(anyhow!("invalid Vorbis comment field name: {:?}", tag.name));
+        }
+        push_vorbis_string(&mut body, &format!("{}={}", tag.name, tag.value));
+    }
+    if body.len() > MAX_BLOCK_LENGTH {
+        return Err(anyhow!("Vorbis comment block is too large"));
+    }
+    Ok(body)
+}
+
+fn picture_block(artwork: &TrackArtwork) -> Result<Vec<u8>> {
+    let (width, height, depth) = artwork::image_dimensions(&artwork.data).unwrap_or((0, 0, 0));
+    let mut body = Vec::new();
+    body.extend_from_slice(&PICTURE_TYPE_FRONT_COVER.to_be_bytes());
+    body.extend_from_slice(&(artwork.mime_type.len() as u32).to_be_bytes());
+    body.extend_from_slice(artwork.mime_type.as_bytes());
+    body.extend_from_slice(&0_u32.to_be_bytes());
+    for value in [width, height, depth, 0] {
+        body.extend_from_slice(&value.to_be_bytes());
+    }
+    body.extend_from_slice(&(artwork.data.len() as u32).to_be_bytes());
+    body.extend_from_slice(&artwork.data);
+    if body.len() > MAX_BLOCK_LENGTH {
+        return Err(anyhow!("artwork is too large to embed"));
+    }
+    Ok(body)
+}
+
+fn parse_picture_block(body: &[u8]) -> Option<(u32, String, Vec<u8>)> {
+    let mut offset = 0;
+    let next_u32 = |offset: &mut usize| {
+        let value = u32::from_be_bytes(body.get(*offset..*offset + 4)?.try_into().ok()?);
+        *offset += 4;
+        Some(value)
+    };
+    let picture_type = next_u32(&mut offset)?;
+    let mime_length = next_u32(&mut offset)? as usize;
+    let mime_type = String::from_utf8(body.get(offset..offset + mime_length)?.to_vec()).ok()?;
+    offset += mime_length;
+    let description_length = next_u32(&mut offset)? as usize;
+    offset += description_length + 16;
+    let data_length = next_u32(&mut offset)? as usize;
+    let data = body.get(offset..offset + data_length)?.to_vec();
+    Some((picture_type, mime_type, data))
+}
+
+fn push_vorbis_string(body: &mut Vec<u8>, value: &str) {
+    body.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    body.extend_from_slice(value.as_bytes());
+}
+
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("track path has no file name: {}", path.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".splittarr-tags");
+    Ok(path.with_file_name(temp_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::{read_front_picture, write_picture, write_vorbis_comments};
+    use crate::adapters::flac_writer::{FlacStreamFormat, FlacTrackWriter};
+    use crate::application::ports::{ArtworkSource, TrackArtwork};
+    use crate::domain::TrackTag;
+
+    #[test]
+    fn writes_and_replaces_vorbis_comments_without_touching_audio() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("01.flac");
+        let samples = (0..5_000)
+            .map(|index| (index % 300) - 150)
+            .collect::<Vec<_>>();
+        write_flac(&path, &samples);
+
+        write_vorbis_comments(&path, &[tag("TITLE", "Old")]).unwrap();
+        write_vorbis_comments(
+            &path,
+            &[tag("ARTIST", "Artist"), tag("TITLE", "Track = One")],
+        )
+        .unwrap();
+
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        let tags = reader
+            .tags()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![
+                ("ARTIST".to_owned(), "Artist".to_owned()),
+                ("TITLE".to_owned(), "Track = One".to_owned())
+            ]
+        );
+        let decoded = reader
+            .samples()
+            .map(|sample| sample.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, samples);
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn replaces_embedded_picture_and_keeps_tags() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("01.flac");
+        let samples = (0..2_000).map(|index| index % 64).collect::<Vec<_>>();
+        write_flac(&path, &samples);
+        write_vorbis_comments(&path, &[tag("TITLE", "One")]).unwrap();
+
+        write_picture(&path, &artwork(b"\xff\xd8\xffold")).unwrap();
+        write_picture(&path, &artwork(b"\xff\xd8\xffnew")).unwrap();
+
+        let picture = read_front_picture(&path).unwrap().unwrap();
+        assert_eq!(picture.source, ArtworkSource::Embedded(path.clone()));
+        assert_eq!(picture.mime_type, "image/jpeg");
+        assert_eq!(picture.data, b"\xff\xd8\xffnew");
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        assert_eq!(reader.get_tag("TITLE").collect::<Vec<_>>(), vec!["One"]);
+        let decoded = reader
+            .samples()
+            .map(|sample| sample.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn rejects_non_flac_files() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("01.flac");
+        fs::write(&path, b"RIFF....WAVE").unwrap();
+
+        let err = write_vorbis_comments(&path, &[tag("TITLE", "One")]).unwrap_err();
+
+        assert!(err.to_string().contains("not a FLAC file"));
+        assert_eq!(fs::read(&path).unwrap(), b"RIFF....WAVE");
+    }
+
+    fn write_flac(path: &Path, samples: &[i32]) {
+        let mut writer = FlacTrackWriter::create(
+            path,
+            FlacStreamFormat {
+                sample_rate: 44_100,
+                channels: 1,
+                bits_per_sample: 16,
+            },
+        )
+        .unwrap();
+        writer.write_samples(&[samples]).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn artwork(data: &[u8]) -> TrackArtwork {
+        TrackArtwork {
+            source: ArtworkSource::File("cover.jpg".into()),
+            mime_type: "image/jpeg".into(),
+            data: data.to_vec(),
+        }
+    }
+
+    fn tag(name: &str, value: &str) -> TrackTag {
+        TrackTag {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
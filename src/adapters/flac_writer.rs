@@ -0,0 +1,603 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+const BLOCK_SIZE: usize = 4096;
+const BLOCK_SIZE_CODE: u8 = 0b1100;
+const UNCOMMON_BLOCK_SIZE_CODE: u8 = 0b0111;
+const STREAMINFO_LENGTH: u32 = 34;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 8;
+const MAX_RICE_PARAMETER: u32 = 30;
+const RICE_ESCAPE_4BIT: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlacStreamFormat {
+    pub sample_rate: u32,
+    pub channels: u32,
+    pub bits_per_sample: u32,
+}
+
+impl FlacStreamFormat {
+    pub fn validate(&self) -> Result<()> {
+        if self.sample_rate == 0 || self.sample_rate >= 1 << 20 {
+            return Err(anyhow!("unsupported FLAC sample rate {}", self.sample_rate));
+        }
+        if !(1..=8).contains(&self.channels) {
+            return Err(anyhow!("unsupported FLAC channel count {}", self.channels));
+        }
+        if sample_size_code(self.bits_per_sample).is_none() {
+            return Err(anyhow!(
+                "unsupported FLAC bits per sample {}",
+                self.bits_per_sample
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Streaming FLAC encoder using fixed predictors and Rice-coded residuals.
+///
+/// Samples are buffered per channel and flushed as fixed-size frames. The
+/// STREAMINFO block is rewritten on `finish` once totals and frame sizes are known.
+pub struct FlacTrackWriter {
+    path: PathBuf,
+    output: BufWriter<File>,
+    format: FlacStreamFormat,
+    pending: Vec<Vec<i32>>,
+    frame_number: u64,
+    total_samples: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+}
+
+impl FlacTrackWriter {
+    pub fn create(path: &Path, format: FlacStreamFormat) -> Result<Self> {
+        format.validate()?;
+        let file = File::create(path)
+            .map_err(|err| anyhow!("failed to create {}: {err}", path.display()))?;
+        let mut writer = Self {
+            path: path.to_path_buf(),
+            output: BufWriter::new(file),
+            format,
+            pending: vec![Vec::with_capacity(BLOCK_SIZE); format.channels as usize],
+            frame_number: 0,
+            total_samples: 0,
+            min_frame_size: 0,
+            max_frame_size: 0,
+        };
+        writer.write_all(b"fLaC")?;
+        let streaminfo = writer.streaminfo_block();
+        writer.write_all(&streaminfo)?;
+        Ok(writer)
+    }
+
+    pub fn format(&self) -> FlacStreamFormat {
+        self.format
+    }
+
+    /// Appends one slice per channel; every slice must have the same length.
+    pub fn write_samples(&mut self, channels: &[&[i32]]) -> Result<()> {
+        if channels.len() != self.pending.len() {
+            return Err(anyhow!(
+                "expected {} channel(s) for {}, got {}",
+                self.pending.len(),
+                self.path.display(),
+                channels.len()
+            ));
+        }
+        let frames = channels.first().map_or(0, |channel| channel.len());
+        if channels.iter().any(|channel| channel.len() != frames) {
+            return Err(anyhow!(
+                "channel sample counts differ for {}",
+                self.path.display()
+            ));
+        }
+
+        let mut offset = 0;
+        while offset < frames {
+            let room = BLOCK_SIZE - self.pending[0].len();
+            let take = room.min(frames - offset);
+            for (pending, channel) in self.pending.iter_mut().zip(channels) {
+                pending.extend_from_slice(&channel[offset..offset + take]);
+            }
+            offset += take;
+            if self.pending[0].len() == BLOCK_SIZE {
+                self.flush_frame()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the final partial frame, rewrites STREAMINFO, and returns the
+    /// number of inter-channel samples written.
+    pub fn finish(mut self) -> Result<u64> {
+        if !self.pending[0].is_empty() {
+            self.flush_frame()?;
+        }
+        let streaminfo = self.streaminfo_block();
+        let path = self.path.clone();
+        let mut file = self
+            .output
+            .into_inner()
+            .map_err(|err| anyhow!("failed to flush {}: {}", path.display(), err.error()))?;
+        file.seek(SeekFrom::Start(4))
+            .and_then(|_| file.write_all(&streaminfo))
+            .and_then(|()| file.sync_all())
+            .map_err(|err| anyhow!("failed to finalize {}: {err}", path.display()))?;
+        Ok(self.total_samples)
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.output
+            .write_all(bytes)
+            .map_err(|err| anyhow!("failed to write {}: {err}", self.path.display()))
+    }
+
+    fn flush_frame(&mut self) -> Result<()> {
+        let block_len = self.pending[0].len();
+        let frame = encode_frame(&self.pending, self.format, self.frame_number);
+        self.write_all(&frame)?;
+        let frame_size = frame.len() as u32;
+        self.min_frame_size = if self.frame_number == 0 {
+            frame_size
+        } else {
+            self.min_frame_size.min(frame_size)
+        };
+        self.max_frame_size = self.max_frame_size.max(frame_size);
+        self.frame_number += 1;
+        self.total_samples += block_len as u64;
+        for pending in &mut self.pending {
+            pending.clear();
+        }
+        Ok(())
+    }
+
+    fn streaminfo_block(&self) -> Vec<u8> {
+        let mut bits = BitWriter::default();
+        bits.write(1, 1);
+        bits.write(0, 7);
+        bits.write(u64::from(STREAMINFO_LENGTH), 24);
+        bits.write(BLOCK_SIZE as u64, 16);
+        bits.write(BLOCK_SIZE as u64, 16);
+        bits.write(u64::from(self.min_frame_size), 24);
+        bits.write(u64::from(self.max_frame_size), 24);
+        bits.write(u64::from(self.format.sample_rate), 20);
+        bits.write(u64::from(self.format.channels - 1), 3);
+        bits.write(u64::from(self.format.bits_per_sample - 1), 5);
+        bits.write(self.total_samples, 36);
+        for _ in 0..16 {
+            bits.write(0, 8);
+        }
+        bits.into_bytes()
+    }
+}
+
+fn encode_frame(channels: &[Vec<i32>], format: FlacStreamFormat, frame_number: u64) -> Vec<u8> {
+    let block_len = channels[0].len();
+    let mut bits = BitWriter::default();
+    bits.write(0b11_1111_1111_1110, 14);
+    bits.write(0, 1);
+    bits.write(0, 1);
+    let block_size_code = if block_len == BLOCK_SIZE {
+        BLOCK_SIZE_CODE
+    } else {
+        UNCOMMON_BLOCK_SIZE_CODE
+    };
+    bits.write(u64::from(block_size_code), 4);
+    bits.write(u64::from(sample_rate_code(format.sample_rate)), 4);
+    bits.write(u64::from(format.channels - 1), 4);
+    bits.write(
+        u64::from(sample_size_code(format.bits_per_sample).unwrap_or(0)),
+        3,
+    );
+    bits.write(0, 1);
+    write_utf8_number(&mut bits, frame_number);
+    if block_size_code == UNCOMMON_BLOCK_SIZE_CODE {
+        bits.write((block_len - 1) as u64, 16);
+    }
+    let header_crc = crc8(bits.bytes());
+    bits.write(u64::from(header_crc), 8);
+
+    for channel in channels {
+        encode_subframe(&mut bits, channel, format.bits_per_sample);
+    }
+
+    bits.align();
+    let crc = crc16(bits.bytes());
+    bits.write(u64::from(crc), 16);
+    bits.into_bytes()
+}
+
+fn sample_rate_code(sample_rate: u32) -> u8 {
+    match sample_rate {
+        88_200 => 0b0001,
+        176_400 => 0b0010,
+        192_000 => 0b0011,
+        8_000 => 0b0100,
+        16_000 => 0b0101,
+        22_050 => 0b0110,
+        24_000 => 0b0111,
+        32_000 => 0b1000,
+        44_100 => 0b1001,
+        48_000 => 0b1010,
+        96_000 => 0b1011,
+        _ => 0b0000,
+    }
+}
+
+fn sample_size_code(bits_per_sample: u32) -> Option<u8> {
+    match bits_per_sample {
+        8 => Some(0b001),
+        12 => Some(0b010),
+        16 => Some(0b100),
+        20 => Some(0b101),
+        24 => Some(0b110),
+        _ => None,
+    }
+}
+
+fn encode_subframe(bits: &mut BitWriter, samples: &[i32], bits_per_sample: u32) {
+    if samples.iter().all(|sample| *sample == samples[0]) {
+        bits.write(0, 1);
+        bits.write(0b000000, 6);
+        bits.write(0, 1);
+        bits.write_signed(i64::from(samples[0]), bits_per_sample);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * u64::from(bits_per_sample);
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residuals = fixed_residuals(samples, order);
+            let coding = choose_residual_coding(&residuals, order, samples.len());
+            let cost = order as u64 * u64::from(bits_per_sample) + coding.bits;
+            (order, residuals, coding, cost)
+        })
+        .min_by_key(|(_, _, _, cost)| *cost);
+
+    match best {
+        Some((order, residuals, coding, cost)) if cost < verbatim_bits => {
+            bits.write(0, 1);
+            bits.write(0b001000 | order as u64, 6);
+            bits.write(0, 1);
+            for sample in &samples[..order] {
+                bits.write_signed(i64::from(*sample), bits_per_sample);
+            }
+            write_residuals(bits, &residuals, &coding, order, samples.len());
+        }
+        _ => {
+            bits.write(0, 1);
+            bits.write(0b000001, 6);
+            bits.write(0, 1);
+            for sample in samples {
+                bits.write_signed(i64::from(*sample), bits_per_sample);
+            }
+        }
+    }
+}
+
+fn fixed_residuals(samples: &[i32], order: usize) -> Vec<i64> {
+    samples
+        .iter()
+        .enumerate()
+        .skip(order)
+        .map(|(index, sample)| {
+            let s = |offset: usize| i64::from(samples[index - offset]);
+            let prediction = match order {
+                0 => 0,
+                1 => s(1),
+                2 => 2 * s(1) - s(2),
+                3 => 3 * s(1) - 3 * s(2) + s(3),
+                _ => 4 * s(1) - 6 * s(2) + 4 * s(3) - s(4),
+            };
+            i64::from(*sample) - prediction
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResidualCoding {
+    partition_order: u32,
+    parameters: Vec<u32>,
+    wide_parameters: bool,
+    bits: u64,
+}
+
+fn choose_residual_coding(residuals: &[i64], order: usize, block_len: usize) -> ResidualCoding {
+    let mut best: Option<ResidualCoding> = None;
+    for partition_order in 0..=MAX_PARTITION_ORDER {
+        let partitions = 1_usize << partition_order;
+        if !block_len.is_multiple_of(partitions) || block_len / partitions <= order {
+            break;
+        }
+        let partition_len = block_len / partitions;
+        let mut parameters = Vec::with_capacity(partitions);
+        let mut bits = 2 + 4;
+        let mut start = 0;
+        for partition in 0..partitions {
+            let len = if partition == 0 {
+                partition_len - order
+            } else {
+                partition_len
+            };
+            let (parameter, cost) = best_rice_parameter(&residuals[start..start + len]);
+            parameters.push(parameter);
+            bits += cost;
+            start += len;
+        }
+        let wide_parameters = parameters
+            .iter()
+            .any(|parameter| *parameter >= RICE_ESCAPE_4BIT);
+        bits += partitions as u64 * if wide_parameters { 5 } else { 4 };
+        if best.as_ref().is_none_or(|best| bits < best.bits) {
+            best = Some(ResidualCoding {
+                partition_order,
+                parameters,
+                wide_parameters,
+                bits,
+            });
+        }
+    }
+    best.expect("partition order zero is always valid")
+}
+
+fn best_rice_parameter(residuals: &[i64]) -> (u32, u64) {
+    let folded = residuals
+        .iter()
+        .map(|value| zigzag(*value))
+        .collect::<Vec<_>>();
+    let mean = folded.iter().sum::<u64>() / folded.len().max(1) as u64;
+    let estimate = (u64::BITS - mean.leading_zeros()).min(MAX_RICE_PARAMETER);
+    (estimate.saturating_sub(1)..=(estimate + 1).min(MAX_RICE_PARAMETER))
+        .map(|parameter| {
+            let cost = folded
+                .iter()
+                .map(|value| (value >> parameter) + 1 + u64::from(parameter))
+                .sum::<u64>();
+            (parameter, cost)
+        })
+        .min_by_key(|(_, cost)| *cost)
+        .unwrap_or((0, 0))
+}
+
+fn write_residuals(
+    bits: &mut BitWriter,
+    residuals: &[i64],
+    coding: &ResidualCoding,
+    order: usize,
+    block_len: usize,
+) {
+    let parameter_bits = if coding.wide_parameters { 5 } else { 4 };
+    bits.write(u64::from(coding.wide_parameters), 2);
+    bits.write(u64::from(coding.partition_order), 4);
+    let partition_len = block_len >> coding.partition_order;
+    let mut start = 0;
+    for (partition, parameter) in coding.parameters.iter().enumerate() {
+        let len = if partition == 0 {
+            partition_len - order
+        } else {
+            partition_len
+        };
+        bits.write(u64::from(*parameter), parameter_bits);
+        for residual in &residuals[start..start + len] {
+            let value = zigzag(*residual);
+            bits.write_unary(value >> parameter);
+            if *parameter > 0 {
+                bits.write(value & ((1 << parameter) - 1), *parameter);
+            }
+        }
+        start += len;
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn write_utf8_number(bits: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        bits.write(value, 8);
+        return;
+    }
+    let continuation_bytes = match value {
+        0..=0x7ff => 1,
+        0x800..=0xffff => 2,
+        0x1_0000..=0x1f_ffff => 3,
+        0x20_0000..=0x3ff_ffff => 4,
+        _ => 5,
+    };
+    let leading_ones = continuation_bytes + 1;
+    let prefix = (0xff_u64 << (8 - leading_ones)) & 0xff;
+    bits.write(prefix | (value >> (6 * continuation_bytes)), 8);
+    for index in (0..continuation_bytes).rev() {
+        bits.write(0x80 | ((value >> (6 * index)) & 0x3f), 8);
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0_u8;
+    for byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0_u16;
+    for byte in bytes {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, bits: u32) {
+        let mut remaining = bits;
+        while remaining > 0 {
+            let take = remaining.min(32);
+            remaining -= take;
+            let chunk = (value >> remaining) & ((1_u64 << take) - 1);
+            self.accumulator = (self.accumulator << take) | chunk;
+            self.pending_bits += take;
+            while self.pending_bits >= 8 {
+                self.pending_bits -= 8;
+                self.bytes
+                    .push((self.accumulator >> self.pending_bits) as u8);
+            }
+            self.accumulator &= (1_u64 << self.pending_bits) - 1;
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, bits: u32) {
+        self.write((value as u64) & ((1_u64 << bits) - 1), bits);
+    }
+
+    fn write_unary(&mut self, zeros: u64) {
+        let mut remaining = zeros;
+        while remaining >= 32 {
+            self.write(0, 32);
+            remaining -= 32;
+        }
+        self.write(1, remaining as u32 + 1);
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::{crc16, crc8, FlacStreamFormat, FlacTrackWriter, BLOCK_SIZE};
+
+    #[test]
+    fn crc_helpers_match_flac_reference_values() {
+        assert_eq!(crc8(b"123456789"), 0xf4);
+        assert_eq!(crc16(b"123456789"), 0xfee8);
+    }
+
+    #[test]
+    fn encoded_stereo_stream_decodes_to_identical_samples() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("track.flac");
+        let len = BLOCK_SIZE * 2 + 123;
+        let left = (0..len)
+            .map(|index| ((index as f64 * 0.05).sin() * 12_000.0) as i32)
+            .collect::<Vec<_>>();
+        let right = (0..len)
+            .map(|index| ((index * 7919) % 65_536) as i32 - 32_768)
+            .collect::<Vec<_>>();
+        let format = FlacStreamFormat {
+            sample_rate: 44_100,
+            channels: 2,
+            bits_per_sample: 16,
+        };
+
+        let mut writer = FlacTrackWriter::create(&path, format).unwrap();
+        writer
+            .write_samples(&[&left[..1000], &right[..1000]])
+            .unwrap();
+        writer
+            .write_samples(&[&left[1000..], &right[1000..]])
+            .unwrap();
+        assert_eq!(writer.finish().unwrap(), len as u64);
+
+        let (info, decoded) = decode(&path);
+        assert_eq!(info.sample_rate, 44_100);
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.samples, Some(len as u64));
+        assert_eq!(decoded, vec![left, right]);
+    }
+
+    #[test]
+    fn encoded_24_bit_silence_and_ramps_round_trip() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("track.flac");
+        let mut mono = vec![0_i32; 500];
+        mono.extend((0..BLOCK_SIZE as i32).map(|index| index * 2_000 - 4_000_000));
+        mono.extend([8_388_607, -8_388_608, 0, 8_388_607]);
+        let format = FlacStreamFormat {
+            sample_rate: 96_000,
+            channels: 1,
+            bits_per_sample: 24,
+        };
+
+        let mut writer = FlacTrackWriter::create(&path, format).unwrap();
+        writer.write_samples(&[&mono]).unwrap();
+        writer.finish().unwrap();
+
+        let (info, decoded) = decode(&path);
+        assert_eq!(info.bits_per_sample, 24);
+        assert_eq!(decoded, vec![mono]);
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        let tmp = tempdir().unwrap();
+        let err = FlacTrackWriter::create(
+            &tmp.path().join("track.flac"),
+            FlacStreamFormat {
+                sample_rate: 44_100,
+                channels: 2,
+                bits_per_sample: 32,
+            },
+        )
+        .err()
+        .unwrap();
+
+        assert!(err.to_string().contains("unsupported FLAC bits per sample"));
+    }
+
+    fn decode(path: &std::path::Path) -> (claxon::metadata::StreamInfo, Vec<Vec<i32>>) {
+        let mut reader = claxon::FlacReader::open(path).unwrap();
+        let info = reader.streaminfo();
+        let mut channels = vec![Vec::new(); info.channels as usize];
+        let mut blocks = reader.blocks();
+        let mut buffer = Vec::new();
+        while let Some(block) = blocks.read_next_or_eof(buffer).unwrap() {
+            for (channel, samples) in channels.iter_mut().enumerate() {
+                samples.extend_from_slice(block.channel(channel as u32));
+            }
+            buffer = block.into_buffer();
+        }
+        (info, channels)
+    }
+}
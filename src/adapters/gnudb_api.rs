@@ -2,6 +2,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 
+use crate::application::metrics::{LookupOutcome, LookupService, Metrics};
 use crate::application::ports::{
     DiscReleaseCandidate, DiscReleaseLookup, DiscReleaseLookupRequest, DiscReleaseLookupResult,
 };
@@ -13,6 +14,7 @@ pub struct GnudbDiscReleaseLookup {
     server: String,
     user_email: String,
     client: Client,
+    metrics: Metrics,
 }
 
 impl GnudbDiscReleaseLookup {
@@ -22,9 +24,20 @@ impl GnudbDiscReleaseLookup {
             server: settings.server.clone(),
             user_email: settings.user_email.clone(),
             client: Client::new(),
+            metrics: Metrics::default(),
         }
     }
 
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    fn record_lookup(&self, outcome: LookupOutcome) {
+        self.metrics
+            .record_disc_lookup(LookupService::Gnudb, outcome);
+    }
+
     async fn request(&self, command: String) -> Result<String> {
         let response = self
             .client
@@ -98,6 +111,7 @@ impl DiscReleaseLookup for GnudbDiscReleaseLookup {
             .filter(|value| !value.trim().is_empty())
         else {
             diagnostic.push_str("GnuDB lookup: skipped because artist hint is missing\n");
+            self.record_lookup(LookupOutcome::Skipped);
             return Ok(DiscReleaseLookupResult::NotFound { diagnostic });
         };
         let Some(album) = request
@@ -106,6 +120,7 @@ impl DiscReleaseLookup for GnudbDiscReleaseLookup {
             .filter(|value| !value.trim().is_empty())
         else {
             diagnostic.push_str("GnuDB lookup: skipped because album hint is missing\n");
+            self.record_lookup(LookupOutcome::Skipped);
             return Ok(DiscReleaseLookupResult::NotFound { diagnostic });
         };
 
@@ -117,6 +132,7 @@ impl DiscReleaseLookup for GnudbDiscReleaseLookup {
             Ok(body) => body,
             Err(err) => {
                 diagnostic.push_str(&format!("GnuDB lookup failed: {err}\n"));
+                self.record_lookup(LookupOutcome::Error);
                 return Ok(DiscReleaseLookupResult::NotFound { diagnostic });
             }
         };
@@ -128,6 +144,7 @@ impl DiscReleaseLookup for GnudbDiscReleaseLookup {
 
         if search_candidates.is_empty() {
             diagnostic.push_str("GnuDB lookup: no search candidates\n");
+            self.record_lookup(LookupOutcome::NotFound);
             return Ok(DiscReleaseLookupResult::NotFound { diagnostic });
         }
 
@@ -180,8 +197,10 @@ impl DiscReleaseLookup for GnudbDiscReleaseLookup {
 
         diagnostic.push_str(&format!("GnuDB accepted candidates: {}\n", accepted.len()));
         if accepted.is_empty() {
+            self.record_lookup(LookupOutcome::NotFound);
             Ok(DiscReleaseLookupResult::NotFound { diagnostic })
         } else {
+            self.record_lookup(LookupOutcome::Found);
             Ok(DiscReleaseLookupResult::Found {
                 candidates: accepted,
                 diagnostic,
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,6 +7,8 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::application::disc_grouping::album_title_without_disc;
+use crate::application::metrics::{LidarrOperation, ManualImportOutcome, Metrics};
 use crate::application::ports::{
     CueMetadataHint, DiscReleaseLookup, DiscReleaseLookupRequest, DiscReleaseLookupResult,
     ManualImportRequest, ManualImportResult, ManualImportTrigger, MusicBrainzDiscLookupRequest,
@@ -28,6 +30,7 @@ pub struct LidarrQueueSource {
     page_size: usize,
     max_pages: usize,
     manual_import_enabled: bool,
+    dry_run: bool,
     client: reqwest::Client,
     disc_release_lookup: Arc<dyn DiscReleaseLookup>,
     musicbrainz_disc_release_lookup: Arc<dyn MusicBrainzDiscReleaseLookup>,
@@ -37,6 +40,7 @@ pub struct LidarrQueueSource {
     musicbrainz_add_album_refetch_delay: Duration,
     lidarr_command_poll_attempts: usize,
     lidarr_command_poll_delay: Duration,
+    metrics: Metrics,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -71,6 +75,7 @@ impl LidarrQueueSource {
             page_size: settings.queue_page_size.max(1),
             max_pages: settings.queue_max_pages.max(1),
             manual_import_enabled: settings.manual_import_enabled,
+            dry_run: false,
             client: reqwest::Client::new(),
             disc_release_lookup: Arc::new(NoopDiscReleaseLookup),
             musicbrainz_disc_release_lookup: Arc::new(NoopMusicBrainzDiscReleaseLookup),
@@ -80,6 +85,7 @@ impl LidarrQueueSource {
             musicbrainz_add_album_refetch_delay: DEFAULT_MUSICBRAINZ_ADD_ALBUM_REFETCH_DELAY,
             lidarr_command_poll_attempts: DEFAULT_LIDARR_COMMAND_POLL_ATTEMPTS,
             lidarr_command_poll_delay: DEFAULT_LIDARR_COMMAND_POLL_DELAY,
+            metrics: Metrics::default(),
         }
     }
 
@@ -106,6 +112,16 @@ impl LidarrQueueSource {
         self
     }
 
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     #[cfg(test)]
     fn with_musicbrainz_add_album_refetch(mut self, attempts: usize, delay: Duration) -> Self {
         self.musicbrainz_add_album_refetch_attempts = attempts.max(1);
@@ -123,6 +139,43 @@ impl LidarrQueueSource {
 
 impl QueueSource for LidarrQueueSource {
     async fn queue_snapshot(&self) -> Result<QueueSnapshot> {
+        let snapshot = self.fetch_queue_snapshot().await;
+        if snapshot.is_err() {
+            self.metrics.record_lidarr_api_error(LidarrOperation::Queue);
+        }
+        snapshot
+    }
+}
+
+impl ManualImportTrigger for LidarrQueueSource {
+    async fn trigger_manual_import(
+        &self,
+        request: ManualImportRequest,
+    ) -> Result<ManualImportResult> {
+        let result = self.start_manual_import(request).await;
+        match &result {
+            Ok(ManualImportResult::Started { .. }) => {
+                self.metrics
+                    .record_manual_import(ManualImportOutcome::Started);
+            }
+            Ok(ManualImportResult::Skipped { .. }) => {
+                self.metrics
+                    .record_manual_import(ManualImportOutcome::Skipped);
+            }
+            Ok(ManualImportResult::Disabled | ManualImportResult::Planned { .. }) => {}
+            Err(_) => {
+                self.metrics
+                    .record_manual_import(ManualImportOutcome::Failed);
+                self.metrics
+                    .record_lidarr_api_error(LidarrOperation::ManualImport);
+            }
+        }
+        result
+    }
+}
+
+impl LidarrQueueSource {
+    async fn fetch_queue_snapshot(&self) -> Result<QueueSnapshot> {
         let mut page = 1_usize;
         let mut pages_fetched = 0_usize;
         let mut all_records = Vec::new();
@@ -198,10 +251,8 @@ impl QueueSource for LidarrQueueSource {
             failed_imports,
         })
     }
-}
 
-impl ManualImportTrigger for LidarrQueueSource {
-    async fn trigger_manual_import(
+    async fn start_manual_import(
         &self,
         request: ManualImportRequest,
     ) -> Result<ManualImportResult> {
@@ -209,11 +260,17 @@ impl ManualImportTrigger for LidarrQueueSource {
             return Ok(ManualImportResult::Disabled);
         }
 
+        // Planned tracks do not exist yet, so ask Lidarr about the source folder instead.
+        let folder = if self.dry_run {
+            request.download.output_path.as_str()
+        } else {
+            request.import_folder.as_str()
+        };
         let response = self
             .client
             .get(format!("{}/api/v1/manualimport", self.base_url))
             .query(&[
-                ("folder", request.download.output_path.as_str()),
+                ("folder", folder),
                 ("downloadId", request.download.download_id.as_str()),
                 ("filterExistingFiles", "true"),
                 ("replaceExistingFiles", "true"),
@@ -237,13 +294,29 @@ impl ManualImportTrigger for LidarrQueueSource {
         let candidates: Vec<ManualImportResource> = serde_json::from_str(&body).map_err(|err| {
             anyhow!("lidarr returned invalid manual import JSON: {err}; body: {body}")
         })?;
+        let mut dry_run_note = String::new();
+        let candidates = if self.dry_run {
+            dry_run_note = format!(
+                "Dry run: planning {} track(s) from {} Lidarr candidate(s) in {folder}\n",
+                request.generated_tracks.len(),
+                candidates.len()
+            );
+            planned_import_candidates(&request, &candidates)
+        } else {
+            candidates
+        };
         let selection = self
             .select_manual_import_files(&request, &candidates)
             .await?;
         let (files, diagnostic) = match selection {
-            ManualImportSelection::Selected { files, diagnostic } => (files, diagnostic),
+            ManualImportSelection::Selected { files, diagnostic } => {
+                (files, dry_run_note + &diagnostic)
+            }
             ManualImportSelection::Skipped { reason, diagnostic } => {
-                return Ok(ManualImportResult::Skipped { reason, diagnostic });
+                return Ok(ManualImportResult::Skipped {
+                    reason,
+                    diagnostic: dry_run_note + &diagnostic,
+                });
             }
         };
         let imported_track_count = files.len();
@@ -256,6 +329,16 @@ impl ManualImportTrigger for LidarrQueueSource {
         let command_body = serde_json::to_string(&command)
             .map_err(|err| anyhow!("failed serializing lidarr manual import command: {err}"))?;
         let mut diagnostic = diagnostic;
+        if self.dry_run {
+            diagnostic.push_str(&format!(
+                "Lidarr manual import command: dry run, not posting /api/v1/command files={imported_track_count}\n"
+            ));
+            append_lidarr_manual_import_command_files(&mut diagnostic, &command.files);
+            return Ok(ManualImportResult::Planned {
+                imported_track_count,
+                diagnostic,
+            });
+        }
         diagnostic.push_str(&format!(
             "Lidarr manual import command: posting /api/v1/command files={imported_track_count}\n"
         ));
@@ -299,9 +382,7 @@ impl ManualImportTrigger for LidarrQueueSource {
             diagnostic,
         })
     }
-}
 
-impl LidarrQueueSource {
     async fn await_lidarr_command(
         &self,
         mut command: LidarrCommandResource,
@@ -863,6 +944,12 @@ impl LidarrQueueSource {
             search_release_count
         ));
 
+        if self.dry_run {
+            diagnostic.push_str(&format!(
+                "MusicBrainz add missing release group decision: dry run, not adding release group {release_group_id} to Lidarr\n"
+            ));
+            return Ok(None);
+        }
         let Some(added_album) = self.add_lidarr_album(album, diagnostic).await else {
             return Ok(None);
         };
@@ -1260,12 +1347,21 @@ impl LidarrQueueSource {
             );
             return Ok(None);
         }
-
-        let result = self
-            .musicbrainz_disc_release_lookup
-            .lookup_musicbrainz_disc_releases(MusicBrainzDiscLookupRequest { cue_paths })
-            .await
-            .map_err(|err| format!("MusicBrainz lookup failed: {err}"))?;
+        let result = if let Some(result) = &hints.musicbrainz_lookup {
+            diagnostic.push_str(
+                "Fallback MusicBrainz release lookup: reusing the lookup made before splitting\n",
+            );
+            result.clone()
+        } else if hints.disc_groups.len() > 1 {
+            return self
+                .lookup_musicbrainz_releases_per_disc(hints, diagnostic)
+                .await;
+        } else {
+            self.musicbrainz_disc_release_lookup
+                .lookup_musicbrainz_disc_releases(MusicBrainzDiscLookupRequest { cue_paths })
+                .await
+                .map_err(|err| format!("MusicBrainz lookup failed: {err}"))?
+        };
 
         match result {
             MusicBrainzDiscLookupResult::Disabled {
@@ -1287,6 +1383,72 @@ impl LidarrQueueSource {
             }
         }
     }
+
+    async fn lookup_musicbrainz_releases_per_disc(
+        &self,
+        hints: &AlbumMatchHints,
+        diagnostic: &mut String,
+    ) -> std::result::Result<Option<Vec<MusicBrainzDiscRelease>>, String> {
+        let mut shared: Option<Vec<MusicBrainzDiscRelease>> = None;
+        for (disc_number, cue_paths) in &hints.disc_groups {
+            diagnostic.push_str(&format!(
+                "Fallback MusicBrainz lookup for disc {disc_number}\n"
+            ));
+            let result = self
+                .musicbrainz_disc_release_lookup
+                .lookup_musicbrainz_disc_releases(MusicBrainzDiscLookupRequest {
+                    cue_paths: cue_paths.clone(),
+                })
+                .await
+                .map_err(|err| format!("MusicBrainz lookup failed: {err}"))?;
+
+            let releases = match result {
+                MusicBrainzDiscLookupResult::Disabled {
+                    diagnostic: lookup_diagnostic,
+                }
+                | MusicBrainzDiscLookupResult::NotFound {
+                    diagnostic: lookup_diagnostic,
+                } => {
+                    diagnostic.push_str(&lookup_diagnostic);
+                    diagnostic.push_str("Fallback MusicBrainz decision: fell through to GnuDB\n");
+                    return Ok(None);
+                }
+                MusicBrainzDiscLookupResult::Found {
+                    releases,
+                    diagnostic: lookup_diagnostic,
+                } => {
+                    diagnostic.push_str(&lookup_diagnostic);
+                    releases
+                }
+            };
+            shared = Some(match shared {
+                None => releases,
+                Some(shared) => shared
+                    .into_iter()
+                    .filter(|release| {
+                        releases
+                            .iter()
+                            .any(|other| other.id.eq_ignore_ascii_case(&release.id))
+                    })
+                    .collect(),
+            });
+        }
+
+        let shared = shared.unwrap_or_default();
+        diagnostic.push_str(&format!(
+            "Fallback MusicBrainz releases shared by all discs: [{}]\n",
+            shared
+                .iter()
+                .map(|release| release.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        if shared.is_empty() {
+            diagnostic.push_str("Fallback MusicBrainz decision: fell through to GnuDB\n");
+            return Ok(None);
+        }
+        Ok(Some(shared))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -1875,6 +2037,8 @@ struct LidarrTrackResource {
     #[serde(default)]
     track_number: Option<String>,
     #[serde(default)]
+    medium_number: i64,
+    #[serde(default)]
     title: String,
 }
 
@@ -1997,6 +2161,31 @@ fn select_manual_import_files(
     }
 }
 
+fn planned_import_candidates(
+    request: &ManualImportRequest,
+    source_candidates: &[ManualImportResource],
+) -> Vec<ManualImportResource> {
+    let artist = source_candidates
+        .iter()
+        .find_map(|candidate| candidate.artist.clone());
+    let quality = source_candidates
+        .iter()
+        .find_map(|candidate| candidate.quality.clone());
+    request
+        .generated_tracks
+        .iter()
+        .map(|track| ManualImportResource {
+            path: track.to_string_lossy().to_string(),
+            artist: artist.clone(),
+            album: None,
+            album_release_id: 0,
+            tracks: Vec::new(),
+            quality: quality.clone(),
+            indexer_flags: 0,
+        })
+        .collect()
+}
+
 fn skipped_selection(reason: impl Into<String>, mut diagnostic: String) -> ManualImportSelection {
     let reason = reason.into();
     diagnostic.push_str(&format!("Decision: skipped manual import: {reason}\n"));
@@ -2377,12 +2566,14 @@ fn fallback_prerequisites<'a>(
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct AlbumMatchHints {
     cue_paths: Vec<PathBuf>,
+    disc_groups: BTreeMap<u32, Vec<PathBuf>>,
     album_title: Option<String>,
     artist: Option<String>,
     disc_id: Option<String>,
     year: Option<i32>,
     track_count: usize,
     track_titles_by_number: HashMap<i64, String>,
+    musicbrainz_lookup: Option<MusicBrainzDiscLookupResult>,
 }
 
 impl AlbumMatchHints {
@@ -2392,7 +2583,7 @@ impl AlbumMatchHints {
             .iter()
             .filter_map(|hint| hint.album_title.as_deref())
             .find(|title| !title.trim().is_empty())
-            .map(str::to_owned)
+            .map(album_title_without_disc)
             .or_else(|| album_title_from_download(&request.download.title))
             .or_else(|| album_title_from_download(&request.download.output_path));
         let artist = request
@@ -2436,18 +2627,30 @@ impl AlbumMatchHints {
             }
         }
 
+        let mut disc_groups = BTreeMap::<u32, Vec<PathBuf>>::new();
+        for hint in &request.cue_hints {
+            if let Some(disc_number) = hint.disc_number {
+                disc_groups
+                    .entry(disc_number)
+                    .or_default()
+                    .push(hint.path.clone());
+            }
+        }
+
         Self {
             cue_paths: request
                 .cue_hints
                 .iter()
                 .map(|hint| hint.path.clone())
                 .collect(),
+            disc_groups,
             album_title,
             artist,
             disc_id,
             year,
             track_count,
             track_titles_by_number,
+            musicbrainz_lookup: request.musicbrainz_lookup.clone(),
         }
     }
 }
@@ -2612,9 +2815,10 @@ fn map_generated_tracks_to_lidarr_tracks(
     diagnostic.push_str("Fallback Lidarr tracks:\n");
     for track in tracks {
         diagnostic.push_str(&format!(
-            "  - track_id={} album_id={} absolute={} track_number={} title={}\n",
+            "  - track_id={} album_id={} medium={} absolute={} track_number={} title={}\n",
             track.id,
             track.album_id,
+            track.medium_number,
             track.absolute_track_number,
             track.track_number.as_deref().unwrap_or("-"),
             track.title
@@ -2630,15 +2834,21 @@ fn map_generated_tracks_to_lidarr_tracks(
                 generated_track.display()
             ));
         };
-        let generated_title = cue_titles
-            .get(&number)
-            .cloned()
+        let disc = request.disc_for(generated_track);
+        let generated_title = disc
+            .and_then(|disc| disc_track_title(request, &disc.cue_path, number))
+            .or_else(|| cue_titles.get(&number).cloned())
             .or(parsed.title)
             .unwrap_or_default();
         let normalized_generated = normalize_track_title(&generated_title);
         let matches = tracks
             .iter()
             .filter(|track| lidarr_track_number(track) == Some(number))
+            .filter(|track| {
+                disc.is_none_or(|disc| {
+                    track.medium_number == 0 || track.medium_number == i64::from(disc.disc_number)
+                })
+            })
             .filter_map(|track| {
                 let normalized_lidarr = normalize_track_title(&track.title);
                 track_title_match_kind(&normalized_generated, &normalized_lidarr)
@@ -2647,8 +2857,9 @@ fn map_generated_tracks_to_lidarr_tracks(
             .collect::<Vec<_>>();
 
         diagnostic.push_str(&format!(
-            "  - generated={} number={} title={} normalized={} matches={}\n",
+            "  - generated={} disc={} number={} title={} normalized={} matches={}\n",
             generated_track.display(),
+            disc.map_or_else(|| "-".to_owned(), |disc| disc.disc_number.to_string()),
             number,
             generated_title,
             normalized_generated,
@@ -2679,6 +2890,19 @@ fn map_generated_tracks_to_lidarr_tracks(
     Ok(mapped)
 }
 
+fn disc_track_title(request: &ManualImportRequest, cue_path: &Path, number: i64) -> Option<String> {
+    request
+        .cue_hints
+        .iter()
+        .find(|hint| hint.path == cue_path)?
+        .tracks
+        .iter()
+        .find(|track| parse_track_number(&track.number) == Some(number))?
+        .title
+        .clone()
+        .filter(|title| !title.trim().is_empty())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct GeneratedTrackHint {
     number: Option<i64>,
@@ -2886,11 +3110,13 @@ mod tests {
     use tokio::net::TcpListener;
 
     use super::LidarrQueueSource;
+    use crate::application::metrics::{LidarrOperation, ManualImportOutcome, Metrics};
     use crate::application::ports::{
         CueMetadataHint, CueTrackHint, DiscReleaseCandidate, DiscReleaseLookup,
-        DiscReleaseLookupRequest, DiscReleaseLookupResult, ManualImportRequest, ManualImportResult,
-        ManualImportTrigger, MusicBrainzDiscLookupRequest, MusicBrainzDiscLookupResult,
-        MusicBrainzDiscRelease, MusicBrainzDiscReleaseLookup, QueueSource,
+        DiscReleaseLookupRequest, DiscReleaseLookupResult, ImportDisc, ManualImportRequest,
+        ManualImportResult, ManualImportTrigger, MusicBrainzDiscLookupRequest,
+        MusicBrainzDiscLookupResult, MusicBrainzDiscRelease, MusicBrainzDiscReleaseLookup,
+        QueueSource,
     };
     use crate::bootstrap::settings::LidarrSettings;
     use crate::domain::{FailedImportCandidate, TrackedDownload};
@@ -3286,7 +3512,8 @@ mod tests {
     #[tokio::test]
     async fn manual_import_reports_api_errors() {
         let url = serve_once("500 Internal Server Error", "boom").await;
-        let client = lidarr_client(url, true);
+        let metrics = Metrics::default();
+        let client = lidarr_client(url, true).with_metrics(metrics.clone());
 
         let err = client
             .trigger_manual_import(manual_import_request(vec!["/downloads/album/01.flac"], 1))
@@ -3295,6 +3522,17 @@ mod tests {
 
         assert!(err.to_string().contains("HTTP 500"));
         assert!(err.to_string().contains("manual import candidates"));
+        let metrics = metrics.snapshot();
+        assert_eq!(
+            metrics.manual_imports.get(&ManualImportOutcome::Failed),
+            Some(&1)
+        );
+        assert_eq!(
+            metrics
+                .lidarr_api_errors
+                .get(&LidarrOperation::ManualImport),
+            Some(&1)
+        );
     }
 
     #[tokio::test]
@@ -3768,6 +4006,150 @@ mod tests {
         assert_eq!(command["files"][0]["albumReleaseId"], 4);
     }
 
+    #[tokio::test]
+    async fn manual_import_fallback_maps_multi_disc_tracks_by_medium() {
+        let candidates = r#"[
+            {"path":"/downloads/box/CD1/Artist - Box Set - 01 - Opening.flac","artist":{"id":1,"artistName":"Artist"},"quality":{"quality":{"id":6}}},
+            {"path":"/downloads/box/CD2/Artist - Box Set - 01 - Encore.flac","artist":{"id":1,"artistName":"Artist"},"quality":{"quality":{"id":6}}}
+        ]"#;
+        let albums = r#"[
+            {"id":2,"title":"Box Set","artistId":1,"releaseDate":"1984-01-01","releases":[
+                {"id":3,"albumId":2,"foreignReleaseId":"aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa","title":"Box Set","trackCount":2,"monitored":false},
+                {"id":4,"albumId":2,"foreignReleaseId":"bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb","title":"Box Set","trackCount":2,"monitored":false}
+            ]}
+        ]"#;
+        let tracks = r#"[
+            {"id":11,"albumId":2,"absoluteTrackNumber":1,"trackNumber":"1","mediumNumber":1,"title":"Opening"},
+            {"id":21,"albumId":2,"absoluteTrackNumber":2,"trackNumber":"1","mediumNumber":2,"title":"Encore"}
+        ]"#;
+        let (url, requests) = serve_sequence(vec![
+            ("200 OK", candidates),
+            ("200 OK", albums),
+            ("200 OK", tracks),
+            ("201 Created", r#"{"id":7}"#),
+        ])
+        .await;
+        let shared = musicbrainz_release("bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb", 2);
+        let lookup = Arc::new(PerDiscMusicBrainzLookup {
+            results: vec![
+                (
+                    PathBuf::from("/downloads/box/CD1/box.cue"),
+                    vec![
+                        musicbrainz_release("aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa", 2),
+                        shared.clone(),
+                    ],
+                ),
+                (PathBuf::from("/downloads/box/CD2/box.cue"), vec![shared]),
+            ],
+            requests: Mutex::new(Vec::new()),
+        });
+        let client = lidarr_client(url, true).with_musicbrainz_disc_release_lookup(lookup.clone());
+        let mut request = manual_import_request(Vec::new(), 0);
+        request.import_folder = "/downloads/box".into();
+        request.cue_hints = vec![
+            disc_hint("/downloads/box/CD1/box.cue", 1, "Opening"),
+            disc_hint("/downloads/box/CD2/box.cue", 2, "Encore"),
+        ];
+        for (disc_number, track) in [
+            (1, "/downloads/box/CD1/Artist - Box Set - 01 - Opening.flac"),
+            (2, "/downloads/box/CD2/Artist - Box Set - 01 - Encore.flac"),
+        ] {
+            request.generated_tracks.push(PathBuf::from(track));
+            request.discs.push(ImportDisc {
+                disc_number,
+                cue_path: PathBuf::from(format!("/downloads/box/CD{disc_number}/box.cue")),
+                tracks: vec![PathBuf::from(track)],
+            });
+        }
+
+        let result = client.trigger_manual_import(request).await.unwrap();
+
+        let ManualImportResult::Started { diagnostic, .. } = result else {
+            panic!("expected manual import to start");
+        };
+        assert!(diagnostic.contains("Fallback hints: album=Box Set"));
+        assert!(diagnostic.contains(
+            "Fallback MusicBrainz releases shared by all discs: [bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb]"
+        ));
+        assert_eq!(
+            *lookup.requests.lock().unwrap(),
+            vec![
+                vec![PathBuf::from("/downloads/box/CD1/box.cue")],
+                vec![PathBuf::from("/downloads/box/CD2/box.cue")],
+            ]
+        );
+        let command: Value =
+            serde_json::from_str(request_body(&requests.lock().unwrap()[3])).unwrap();
+        assert_eq!(command["files"][0]["albumReleaseId"], 4);
+        assert_eq!(command["files"][0]["trackIds"], serde_json::json!([11]));
+        assert_eq!(command["files"][1]["trackIds"], serde_json::json!([21]));
+    }
+
+    #[tokio::test]
+    async fn manual_import_fallback_reuses_the_musicbrainz_lookup_made_before_splitting() {
+        let candidates = r#"[
+            {"path":"/downloads/box/CD1/Artist - Box Set - 01 - Opening.flac","artist":{"id":1,"artistName":"Artist"},"quality":{"quality":{"id":6}}},
+            {"path":"/downloads/box/CD2/Artist - Box Set - 01 - Encore.flac","artist":{"id":1,"artistName":"Artist"},"quality":{"quality":{"id":6}}}
+        ]"#;
+        let albums = r#"[
+            {"id":2,"title":"Box Set","artistId":1,"releaseDate":"1984-01-01","releases":[
+                {"id":3,"albumId":2,"foreignReleaseId":"aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa","title":"Box Set","trackCount":2,"monitored":false},
+                {"id":4,"albumId":2,"foreignReleaseId":"bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb","title":"Box Set","trackCount":2,"monitored":false}
+            ]}
+        ]"#;
+        let tracks = r#"[
+            {"id":11,"albumId":2,"absoluteTrackNumber":1,"trackNumber":"1","mediumNumber":1,"title":"Opening"},
+            {"id":21,"albumId":2,"absoluteTrackNumber":2,"trackNumber":"1","mediumNumber":2,"title":"Encore"}
+        ]"#;
+        let (url, requests) = serve_sequence(vec![
+            ("200 OK", candidates),
+            ("200 OK", albums),
+            ("200 OK", tracks),
+            ("201 Created", r#"{"id":7}"#),
+        ])
+        .await;
+        let lookup = Arc::new(PerDiscMusicBrainzLookup {
+            results: Vec::new(),
+            requests: Mutex::new(Vec::new()),
+        });
+        let client = lidarr_client(url, true).with_musicbrainz_disc_release_lookup(lookup.clone());
+        let mut request = manual_import_request(Vec::new(), 0);
+        request.import_folder = "/downloads/box".into();
+        request.cue_hints = vec![
+            disc_hint("/downloads/box/CD1/box.cue", 1, "Opening"),
+            disc_hint("/downloads/box/CD2/box.cue", 2, "Encore"),
+        ];
+        for (disc_number, track) in [
+            (1, "/downloads/box/CD1/Artist - Box Set - 01 - Opening.flac"),
+            (2, "/downloads/box/CD2/Artist - Box Set - 01 - Encore.flac"),
+        ] {
+            request.generated_tracks.push(PathBuf::from(track));
+            request.discs.push(ImportDisc {
+                disc_number,
+                cue_path: PathBuf::from(format!("/downloads/box/CD{disc_number}/box.cue")),
+                tracks: vec![PathBuf::from(track)],
+            });
+        }
+        request.musicbrainz_lookup = Some(MusicBrainzDiscLookupResult::Found {
+            releases: vec![musicbrainz_release(
+                "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb",
+                2,
+            )],
+            diagnostic: "MusicBrainz lookup: found 1 release(s)\n".into(),
+        });
+
+        let result = client.trigger_manual_import(request).await.unwrap();
+
+        let ManualImportResult::Started { diagnostic, .. } = result else {
+            panic!("expected manual import to start");
+        };
+        assert!(diagnostic.contains("reusing the lookup made before splitting"));
+        assert!(lookup.requests.lock().unwrap().is_empty());
+        let command: Value =
+            serde_json::from_str(request_body(&requests.lock().unwrap()[3])).unwrap();
+        assert_eq!(command["files"][0]["albumReleaseId"], 4);
+    }
+
     #[tokio::test]
     async fn manual_import_fallback_ranks_multiple_musicbrainz_exact_matches_by_monitored_release()
     {
@@ -3836,6 +4218,72 @@ mod tests {
         assert_eq!(command["files"][0]["albumReleaseId"], 4);
     }
 
+    #[tokio::test]
+    async fn manual_import_dry_run_maps_planned_tracks_without_posting() {
+        let candidates = r#"[
+            {"path":"/downloads/album/album.flac","artist":{"id":1,"artistName":"Artist"},"quality":{"quality":{"id":6}}}
+        ]"#;
+        let albums = r#"[
+            {"id":2,"title":"Album","artistId":1,"releaseDate":"1984-01-01","releases":[
+                {"id":4,"albumId":2,"foreignReleaseId":"bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb","title":"Album","trackCount":2,"monitored":true,"format":"CD"}
+            ]}
+        ]"#;
+        let tracks = r#"[
+            {"id":11,"albumId":2,"absoluteTrackNumber":1,"trackNumber":"1","title":"One"},
+            {"id":12,"albumId":2,"absoluteTrackNumber":2,"trackNumber":"2","title":"Two"}
+        ]"#;
+        let (url, requests) = serve_sequence(vec![
+            ("200 OK", candidates),
+            ("200 OK", albums),
+            ("200 OK", tracks),
+        ])
+        .await;
+        let client = lidarr_client(url, true)
+            .with_musicbrainz_disc_release_lookup(Arc::new(FakeMusicBrainzLookup {
+                result: MusicBrainzDiscLookupResult::Found {
+                    releases: vec![musicbrainz_release(
+                        "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb",
+                        2,
+                    )],
+                    diagnostic: "MusicBrainz lookup: found 1 release(s)\n".into(),
+                },
+            }))
+            .with_dry_run(true);
+
+        let result = client
+            .trigger_manual_import(manual_import_request_with_metadata_and_disc_id(
+                "Artist - Album",
+                "/downloads/album",
+                "Album",
+                "Artist",
+                "1984",
+                Some("C60C9D10"),
+                vec![
+                    ("/downloads/album/Artist - Album - 01 - One.flac", "One"),
+                    ("/downloads/album/Artist - Album - 02 - Two.flac", "Two"),
+                ],
+            ))
+            .await
+            .unwrap();
+
+        let ManualImportResult::Planned {
+            imported_track_count,
+            diagnostic,
+        } = result
+        else {
+            panic!("expected a planned manual import");
+        };
+        assert_eq!(imported_track_count, 2);
+        assert!(diagnostic.starts_with(
+            "Dry run: planning 2 track(s) from 1 Lidarr candidate(s) in /downloads/album"
+        ));
+        assert!(diagnostic.contains("dry run, not posting /api/v1/command files=2"));
+        assert!(diagnostic.contains("Artist - Album - 02 - Two.flac"));
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].contains("folder=%2Fdownloads%2Falbum"));
+    }
+
     #[tokio::test]
     async fn manual_import_fallback_best_effort_musicbrainz_selects_monitored_compatible_release() {
         let candidates = r#"[
@@ -4680,6 +5128,7 @@ mod tests {
                 "/downloads/album".into(),
                 "importFailed".into(),
             ),
+            import_folder: "/downloads/album".into(),
             generated_tracks: paths.into_iter().map(PathBuf::from).collect(),
             cue_hints: vec![CueMetadataHint {
                 path: PathBuf::from("/downloads/album/album.cue"),
@@ -4687,6 +5136,7 @@ mod tests {
                 performer: Some("Artist".into()),
                 catalog: None,
                 disc_id: None,
+                disc_number: None,
                 comments: Vec::new(),
                 track_count,
                 tracks: (1..=track_count)
@@ -4697,6 +5147,8 @@ mod tests {
                     })
                     .collect(),
             }],
+            discs: Vec::new(),
+            musicbrainz_lookup: None,
         }
     }
 
@@ -4736,6 +5188,7 @@ mod tests {
                 output_path.into(),
                 "importFailed".into(),
             ),
+            import_folder: output_path.into(),
             generated_tracks: tracks.iter().map(|(path, _)| PathBuf::from(path)).collect(),
             cue_hints: vec![CueMetadataHint {
                 path: PathBuf::from(format!("{output_path}/album.cue")),
@@ -4743,6 +5196,7 @@ mod tests {
                 performer: Some(performer.into()),
                 catalog: None,
                 disc_id: disc_id.map(str::to_owned),
+                disc_number: None,
                 comments: vec![("DATE".into(), year.into())],
                 track_count: tracks.len(),
                 tracks: tracks
@@ -4755,6 +5209,26 @@ mod tests {
                     })
                     .collect(),
             }],
+            discs: Vec::new(),
+            musicbrainz_lookup: None,
+        }
+    }
+
+    fn disc_hint(path: &str, disc_number: u32, track_title: &str) -> CueMetadataHint {
+        CueMetadataHint {
+            path: PathBuf::from(path),
+            album_title: Some(format!("Box Set (CD{disc_number})")),
+            performer: Some("Artist".into()),
+            catalog: None,
+            disc_id: None,
+            disc_number: Some(disc_number),
+            comments: vec![("DATE".into(), "1984".into())],
+            track_count: 1,
+            tracks: vec![CueTrackHint {
+                number: "1".into(),
+                title: Some(track_title.into()),
+                performer: Some("Artist".into()),
+            }],
         }
     }
 
@@ -4786,6 +5260,31 @@ mod tests {
         }
     }
 
+    struct PerDiscMusicBrainzLookup {
+        results: Vec<(PathBuf, Vec<MusicBrainzDiscRelease>)>,
+        requests: Mutex<Vec<Vec<PathBuf>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MusicBrainzDiscReleaseLookup for PerDiscMusicBrainzLookup {
+        async fn lookup_musicbrainz_disc_releases(
+            &self,
+            request: MusicBrainzDiscLookupRequest,
+        ) -> anyhow::Result<MusicBrainzDiscLookupResult> {
+            let releases = self
+                .results
+                .iter()
+                .find(|(path, _)| request.cue_paths.contains(path))
+                .map(|(_, releases)| releases.clone())
+                .unwrap_or_default();
+            self.requests.lock().unwrap().push(request.cue_paths);
+            Ok(MusicBrainzDiscLookupResult::Found {
+                releases,
+                diagnostic: String::new(),
+            })
+        }
+    }
+
     fn musicbrainz_release(id: &str, track_count: usize) -> MusicBrainzDiscRelease {
         musicbrainz_release_with_title(id, "Album", track_count)
     }
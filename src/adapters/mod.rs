@@ -1,10 +1,25 @@
+pub mod artwork;
+pub mod audio_format;
+pub mod cli_output;
+pub mod cue_layout;
+pub mod cue_repair;
+pub mod cue_text;
+pub mod cue_validation;
 pub mod filesystem_cleanup;
 pub mod filesystem_cue_input_inspector;
 pub mod filesystem_cue_scanner;
 pub mod filesystem_download_log;
+pub mod flac_tagger;
+pub mod flac_writer;
 pub mod gnudb_api;
 pub mod lidarr_api;
 pub mod musicbrainz_api;
+pub mod native_splitter;
+pub mod prometheus;
 pub mod shnsplit_splitter;
+pub mod smtp_notifier;
 pub mod sqlite_download_store;
 pub mod web;
+pub mod web_api;
+pub mod web_auth;
+pub mod webhook_notifier;
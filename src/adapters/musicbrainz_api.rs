@@ -8,12 +8,14 @@ use std::time::{Duration, Instant};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use base64::Engine;
-use rcue::parser::parse_from_file;
 use reqwest::Client;
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
 use tokio::time::sleep;
 
+use crate::adapters::audio_format::{read_stream_info, AudioFormat};
+use crate::adapters::cue_text::parse_cue_file;
+use crate::application::metrics::{LookupOutcome, LookupService, Metrics};
 use crate::application::ports::{
     MusicBrainzDiscLookupRequest, MusicBrainzDiscLookupResult, MusicBrainzDiscRelease,
     MusicBrainzDiscReleaseLookup,
@@ -32,6 +34,7 @@ pub struct FilesystemMusicBrainzDiscReleaseLookup {
     base_url: String,
     client: Client,
     rate_limiter: Arc<MusicBrainzRateLimiter>,
+    metrics: Metrics,
 }
 
 impl FilesystemMusicBrainzDiscReleaseLookup {
@@ -41,9 +44,20 @@ impl FilesystemMusicBrainzDiscReleaseLookup {
             base_url: settings.base_url.trim_end_matches('/').to_owned(),
             client: Client::new(),
             rate_limiter: global_rate_limiter(),
+            metrics: Metrics::default(),
         }
     }
 
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    fn record_lookup(&self, outcome: LookupOutcome) {
+        self.metrics
+            .record_disc_lookup(LookupService::MusicBrainz, outcome);
+    }
+
     #[cfg(test)]
     fn with_rate_limiter(mut self, rate_limiter: Arc<MusicBrainzRateLimiter>) -> Self {
         self.rate_limiter = rate_limiter;
@@ -174,6 +188,7 @@ impl MusicBrainzDiscReleaseLookup for FilesystemMusicBrainzDiscReleaseLookup {
             Ok(toc) => toc,
             Err(err) => {
                 diagnostic.push_str(&format!("MusicBrainz lookup: skipped: {err}\n"));
+                self.record_lookup(LookupOutcome::Skipped);
                 return Ok(MusicBrainzDiscLookupResult::NotFound { diagnostic });
             }
         };
@@ -183,18 +198,21 @@ impl MusicBrainzDiscReleaseLookup for FilesystemMusicBrainzDiscReleaseLookup {
             Ok(releases) => releases,
             Err(err) => {
                 diagnostic.push_str(&format!("MusicBrainz lookup failed: {err}\n"));
+                self.record_lookup(LookupOutcome::Error);
                 return Ok(MusicBrainzDiscLookupResult::NotFound { diagnostic });
             }
         };
 
         if releases.is_empty() {
             diagnostic.push_str("MusicBrainz lookup: no releases\n");
+            self.record_lookup(LookupOutcome::NotFound);
             Ok(MusicBrainzDiscLookupResult::NotFound { diagnostic })
         } else {
             diagnostic.push_str(&format!(
                 "MusicBrainz lookup: found {} release(s)\n",
                 releases.len()
             ));
+            self.record_lookup(LookupOutcome::Found);
             Ok(MusicBrainzDiscLookupResult::Found {
                 releases,
                 diagnostic,
@@ -211,11 +229,11 @@ struct MusicBrainzToc {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct AudioFileLength {
-    path: PathBuf,
-    samples: u64,
-    sample_rate: u64,
-    cd_frames: u64,
+pub struct AudioFileLength {
+    pub path: PathBuf,
+    pub samples: u64,
+    pub sample_rate: u64,
+    pub cd_frames: u64,
 }
 
 fn build_musicbrainz_toc(cue_paths: &[PathBuf]) -> Result<MusicBrainzToc> {
@@ -232,13 +250,21 @@ fn build_musicbrainz_toc(cue_paths: &[PathBuf]) -> Result<MusicBrainzToc> {
             "MusicBrainz TOC CUE path: {}\n",
             cue_path.display()
         ));
-        let cue = parse_from_file(&cue_path.to_string_lossy(), false)
+        let cue = parse_cue_file(cue_path, false)
             .map_err(|err| anyhow!("failed to parse CUE {}: {err}", cue_path.display()))?;
         let cue_dir = cue_path.parent().unwrap_or_else(|| Path::new("."));
 
         for cue_file in cue.files {
             let audio_path = cue_dir.join(&cue_file.file);
             let audio = read_audio_file_length(&audio_path)?;
+            if audio.sample_rate != CD_SAMPLE_RATE {
+                return Err(anyhow!(
+                    "unsupported sample rate {} for {}, expected {}",
+                    audio.sample_rate,
+                    audio.path.display(),
+                    CD_SAMPLE_RATE
+                ));
+            }
             diagnostic.push_str(&format!(
                 "MusicBrainz TOC audio: path={} samples={} sample_rate={} cd_frames={}\n",
                 audio.path.display(),
@@ -318,27 +344,35 @@ fn track_index_01_frames(track: &rcue::cue::Track) -> Option<u64> {
         .map(|(_, duration)| duration_to_cd_frames(*duration))
 }
 
-fn duration_to_cd_frames(duration: Duration) -> u64 {
+pub fn duration_to_cd_frames(duration: Duration) -> u64 {
     duration.as_secs() * CD_FRAMES_PER_SECOND
         + ((u64::from(duration.subsec_nanos()) * CD_FRAMES_PER_SECOND + 500_000_000)
             / 1_000_000_000)
 }
 
-fn read_audio_file_length(path: &Path) -> Result<AudioFileLength> {
+pub fn read_audio_file_length(path: &Path) -> Result<AudioFileLength> {
     if !path.exists() {
         return Err(anyhow!(
             "referenced audio file is missing: {}",
             path.display()
         ));
     }
+    if let Some(format) = AudioFormat::detect(path) {
+        return match format {
+            AudioFormat::Flac => read_flac_file_length(path),
+            AudioFormat::Wav => read_wav_file_length(path),
+            format => {
+                let info = read_stream_info(path, format)?;
+                audio_length(path, info.samples, info.sample_rate)
+            }
+        };
+    }
     match path
         .extension()
         .and_then(|extension| extension.to_str())
         .map(str::to_ascii_lowercase)
         .as_deref()
     {
-        Some("flac") => read_flac_file_length(path),
-        Some("wav") | Some("wave") => read_wav_file_length(path),
         Some(extension) => Err(anyhow!(
             "unsupported referenced audio extension .{} for {}",
             extension,
@@ -478,13 +512,8 @@ fn skip_wav_bytes(file: &mut File, bytes: u64, path: &Path, label: &str) -> Resu
 }
 
 fn audio_length(path: &Path, samples: u64, sample_rate: u64) -> Result<AudioFileLength> {
-    if sample_rate != CD_SAMPLE_RATE {
-        return Err(anyhow!(
-            "unsupported sample rate {} for {}, expected {}",
-            sample_rate,
-            path.display(),
-            CD_SAMPLE_RATE
-        ));
+    if sample_rate == 0 {
+        return Err(anyhow!("sample rate is zero for {}", path.display()));
     }
     Ok(AudioFileLength {
         path: path.to_path_buf(),
@@ -626,6 +655,34 @@ mod tests {
         assert!(toc.diagnostic.contains("track offsets: [150, 300]"));
     }
 
+    #[test]
+    fn builds_toc_from_tta_header_length() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut tta = b"TTA1".to_vec();
+        tta.extend_from_slice(&1_u16.to_le_bytes());
+        tta.extend_from_slice(&2_u16.to_le_bytes());
+        tta.extend_from_slice(&16_u16.to_le_bytes());
+        tta.extend_from_slice(&44_100_u32.to_le_bytes());
+        tta.extend_from_slice(&(44_100_u32 * 4).to_le_bytes());
+        tta.extend_from_slice(&0_u32.to_le_bytes());
+        fs::write(tmp.path().join("album.tta"), tta).unwrap();
+        let cue = tmp.path().join("album.cue");
+        fs::write(
+            &cue,
+            r#"FILE "album.tta" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    INDEX 01 00:02:00
+"#,
+        )
+        .unwrap();
+
+        let toc = build_musicbrainz_toc(&[cue]).unwrap();
+
+        assert_eq!(toc.toc, "1 2 450 150 300");
+    }
+
     #[test]
     fn builds_multi_file_toc_with_cumulative_offsets() {
         let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,1222 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use rcue::cue::{Cue, Track};
+
+use crate::adapters::audio_format::{command_for, find_executable, AudioFormat};
+use crate::adapters::cue_layout::{
+    has_hidden_track_audio, has_one_file_per_track, index_offset, PER_TRACK_FILES_MESSAGE,
+};
+use crate::adapters::cue_text::parse_cue_file;
+use crate::adapters::flac_writer::{FlacStreamFormat, FlacTrackWriter};
+use crate::adapters::shnsplit_splitter::sanitize_file_name_str;
+use crate::application::ports::CueSplitter;
+use crate::domain::{OutputCodec, PregapPolicy, SplitOutcome, SplitStatus};
+
+const CD_FRAMES_PER_SECOND: u64 = 75;
+const DECODE_BLOCK_FRAMES: usize = 4096;
+const HIDDEN_TRACK_TITLE: &str = "Hidden Track";
+
+#[derive(Debug, Clone)]
+pub struct NativeCueSplitter {
+    cue_strict: bool,
+    overwrite: bool,
+    format: String,
+    codec: OutputCodec,
+    pregap: PregapPolicy,
+    dry_run: bool,
+}
+
+impl NativeCueSplitter {
+    pub fn new(
+        cue_strict: bool,
+        overwrite: bool,
+        format: String,
+        codec: OutputCodec,
+        pregap: PregapPolicy,
+    ) -> Self {
+        Self {
+            cue_strict,
+            overwrite,
+            format,
+            codec,
+            pregap,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl CueSplitter for NativeCueSplitter {
+    async fn split_cue(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
+        let splitter = self.clone();
+        let cue_path = cue_path.to_path_buf();
+        let output_dir = output_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || splitter.split_cue_sync(&cue_path, &output_dir))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+}
+
+impl NativeCueSplitter {
+    fn split_cue_sync(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
+        let cue_dir = cue_path
+            .parent()
+            .ok_or_else(|| anyhow!("cue file has no parent directory: {}", cue_path.display()))?;
+
+        let cue = parse_cue_file(cue_path, self.cue_strict)
+            .map_err(|err| anyhow!("failed to parse cue file {}: {err}", cue_path.display()))?;
+
+        let source_files = cue
+            .files
+            .iter()
+            .map(|file| cue_dir.join(&file.file))
+            .collect::<Vec<_>>();
+        if !source_files.iter().any(|path| path.exists()) {
+            return Ok(SplitOutcome {
+                status: SplitStatus::Skipped,
+                tracks: Vec::new(),
+                codec: self.codec,
+                message: Some("cue file does not reference an audio file in its directory".into()),
+                pregap_policy: None,
+                hidden_track: None,
+            });
+        }
+        if has_one_file_per_track(&cue) {
+            return Ok(SplitOutcome {
+                status: SplitStatus::Skipped,
+                tracks: Vec::new(),
+                codec: self.codec,
+                message: Some(PER_TRACK_FILES_MESSAGE.into()),
+                pregap_policy: None,
+                hidden_track: None,
+            });
+        }
+        if let Some(missing) = source_files.iter().find(|path| !path.exists()) {
+            return Err(anyhow!(
+                "cue file {} references missing audio file {}",
+                cue_path.display(),
+                missing.display()
+            ));
+        }
+
+        let plan = build_split_plan(&cue, output_dir, &self.format, self.codec, self.pregap)?;
+        if plan.tracks.is_empty() {
+            return Err(anyhow!(
+                "cue file {} contains no tracks",
+                cue_path.display()
+            ));
+        }
+        if !self.overwrite {
+            if let Some(existing) = plan.tracks.iter().find(|track| track.path.exists()) {
+                return Err(anyhow!(
+                    "refusing to overwrite existing track {}",
+                    existing.path.display()
+                ));
+            }
+        }
+
+        let (hidden, tracks): (Vec<_>, Vec<_>) = plan.tracks.iter().partition(|track| track.hidden);
+        if self.dry_run {
+            return Ok(SplitOutcome {
+                status: SplitStatus::Planned,
+                tracks: tracks.into_iter().map(|track| track.path.clone()).collect(),
+                codec: self.codec,
+                message: None,
+                pregap_policy: Some(self.pregap),
+                hidden_track: hidden.into_iter().next().map(|track| track.path.clone()),
+            });
+        }
+
+        fs::create_dir_all(output_dir).map_err(|err| {
+            anyhow!(
+                "failed to create output directory {}: {err}",
+                output_dir.display()
+            )
+        })?;
+        let mut written = Vec::new();
+        match write_tracks(&source_files, &plan, self.codec, &mut written) {
+            Ok(()) => Ok(SplitOutcome {
+                status: SplitStatus::Split,
+                tracks: tracks.into_iter().map(|track| track.path.clone()).collect(),
+                codec: self.codec,
+                message: None,
+                pregap_policy: Some(self.pregap),
+                hidden_track: hidden.into_iter().next().map(|track| track.path.clone()),
+            }),
+            Err(err) => {
+                for path in written {
+                    let _ = fs::remove_file(path);
+                }
+                Err(anyhow!(
+                    "native split failed for {}: {err}",
+                    cue_path.display()
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PlannedTrack {
+    path: PathBuf,
+    hidden: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PlannedCut {
+    file_index: usize,
+    cd_frames: u64,
+    track: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SplitPlan {
+    tracks: Vec<PlannedTrack>,
+    cuts: Vec<PlannedCut>,
+}
+
+impl SplitPlan {
+    fn add_track(
+        &mut self,
+        path: PathBuf,
+        hidden: bool,
+        file_index: usize,
+        start: u64,
+    ) -> Result<()> {
+        if self.tracks.iter().any(|track| track.path == path) {
+            return Err(anyhow!(
+                "multiple tracks would be written to {}",
+                path.display()
+            ));
+        }
+        self.cuts.push(PlannedCut {
+            file_index,
+            cd_frames: start,
+            track: Some(self.tracks.len()),
+        });
+        self.tracks.push(PlannedTrack { path, hidden });
+        Ok(())
+    }
+
+    fn add_stop(&mut self, file_index: usize, at: u64) {
+        self.cuts.push(PlannedCut {
+            file_index,
+            cd_frames: at,
+            track: None,
+        });
+    }
+}
+
+fn build_split_plan(
+    cue: &Cue,
+    output_dir: &Path,
+    format: &str,
+    codec: OutputCodec,
+    pregap: PregapPolicy,
+) -> Result<SplitPlan> {
+    let mut plan = SplitPlan::default();
+    if pregap == PregapPolicy::TrackZero && has_hidden_track_audio(cue) {
+        let performer = cue.performer.as_deref().unwrap_or_default();
+        let album = cue.title.as_deref().unwrap_or_default();
+        let name = file_name_from_format(format, performer, album, "00", HIDDEN_TRACK_TITLE);
+        let path = output_dir.join(format!("{name}.{}", codec.extension()));
+        plan.add_track(path, true, 0, 0)?;
+    }
+    for (file_index, file) in cue.files.iter().enumerate() {
+        for (track_index, track) in file.tracks.iter().enumerate() {
+            let start = track_start(track)?;
+            let gap_start = index_offset(track, 0)
+                .map(duration_to_cd_frames)
+                .filter(|gap_start| *gap_start < start);
+            let opens_file = track_index == 0;
+            let start = match pregap {
+                PregapPolicy::PrependNext if opens_file => 0,
+                PregapPolicy::PrependNext => gap_start.unwrap_or(start),
+                PregapPolicy::Discard | PregapPolicy::AppendPrevious | PregapPolicy::TrackZero => {
+                    start
+                }
+            };
+            if pregap == PregapPolicy::Discard {
+                match gap_start {
+                    Some(gap_start) => plan.add_stop(file_index, gap_start),
+                    None if opens_file && start > 0 => plan.add_stop(file_index, 0),
+                    None => {}
+                }
+            }
+            let name = track_file_name(format, cue, track);
+            let path = output_dir.join(format!("{name}.{}", codec.extension()));
+            plan.add_track(path, false, file_index, start)?;
+        }
+    }
+    plan.cuts
+        .sort_by_key(|cut| (cut.file_index, cut.cd_frames, cut.track.is_some()));
+    Ok(plan)
+}
+
+fn track_start(track: &Track) -> Result<u64> {
+    index_offset(track, 1)
+        .map(duration_to_cd_frames)
+        .ok_or_else(|| anyhow!("track {} has no INDEX 01", track.no))
+}
+
+fn duration_to_cd_frames(offset: Duration) -> u64 {
+    let frames =
+        (u64::from(offset.subsec_nanos()) * CD_FRAMES_PER_SECOND + 500_000_000) / 1_000_000_000;
+    offset.as_secs() * CD_FRAMES_PER_SECOND + frames
+}
+
+fn cd_frames_to_samples(frames: u64, sample_rate: u32) -> u64 {
+    frames * u64::from(sample_rate) / CD_FRAMES_PER_SECOND
+}
+
+fn track_file_name(format: &str, cue: &Cue, track: &Track) -> String {
+    let number = track
+        .no
+        .parse::<u32>()
+        .map_or_else(|_| track.no.clone(), |number| format!("{number:02}"));
+    let performer = track
+        .performer
+        .as_deref()
+        .or(cue.performer.as_deref())
+        .unwrap_or_default();
+    file_name_from_format(
+        format,
+        performer,
+        cue.title.as_deref().unwrap_or_default(),
+        &number,
+        track.title.as_deref().unwrap_or_default(),
+    )
+}
+
+fn file_name_from_format(
+    format: &str,
+    performer: &str,
+    album: &str,
+    number: &str,
+    title: &str,
+) -> String {
+    let mut name = String::with_capacity(format.len());
+    let mut chars = format.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            name.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('p') => name.push_str(performer),
+            Some('a') => name.push_str(album),
+            Some('n') => name.push_str(number),
+            Some('t') => name.push_str(title),
+            Some('%') => name.push('%'),
+            Some(other) => {
+                name.push('%');
+                name.push(other);
+            }
+            None => name.push('%'),
+        }
+    }
+
+    let sanitized = sanitize_file_name_str(&name);
+    if sanitized.is_empty() {
+        number.to_owned()
+    } else {
+        sanitized
+    }
+}
+
+fn write_tracks(
+    source_files: &[PathBuf],
+    plan: &SplitPlan,
+    codec: OutputCodec,
+    written: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let mut current: Option<TrackWriter> = None;
+    let mut next_track = 0;
+
+    for (file_index, source_path) in source_files.iter().enumerate() {
+        let mut reader = PcmReader::open(source_path)?;
+        let format = reader.format();
+        let cuts = plan
+            .cuts
+            .iter()
+            .filter(|cut| cut.file_index == file_index)
+            .map(|cut| {
+                (
+                    cut.track,
+                    cd_frames_to_samples(cut.cd_frames, format.sample_rate),
+                )
+            })
+            .collect::<Vec<_>>();
+        if let Some(writer) = &current {
+            let leading_audio = cuts.first().is_none_or(|(_, start)| *start > 0);
+            if leading_audio && writer.format() != format {
+                return Err(anyhow!(
+                    "{} does not match the audio format of the previous file",
+                    source_path.display()
+                ));
+            }
+        }
+
+        let mut cuts = cuts.into_iter().peekable();
+        let mut position = 0_u64;
+        while let Some(block) = reader.read_block()? {
+            let block_len = block[0].len() as u64;
+            let mut offset = 0_u64;
+            while offset < block_len {
+                let cut = cuts.peek().copied();
+                let segment_end = match cut {
+                    Some((_, start)) if start < position + block_len => start - position,
+                    _ => block_len,
+                };
+                if segment_end > offset {
+                    if let Some(writer) = current.as_mut() {
+                        let channels = block
+                            .iter()
+                            .map(|channel| &channel[offset as usize..segment_end as usize])
+                            .collect::<Vec<_>>();
+                        writer.write_samples(&channels)?;
+                    }
+                    offset = segment_end;
+                }
+                if let Some((track, start)) = cut {
+                    if start == position + offset {
+                        cuts.next();
+                        if let Some(writer) = current.take() {
+                            writer.finish()?;
+                        }
+                        if let Some(index) = track {
+                            let path = &plan.tracks[index].path;
+                            written.push(path.clone());
+                            current = Some(TrackWriter::create(codec, path, format)?);
+                            next_track = index + 1;
+                        }
+                    }
+                }
+            }
+            position += block_len;
+        }
+        reader.finish()?;
+
+        if let Some(index) = cuts.find_map(|(track, _)| track) {
+            return Err(anyhow!(
+                "track {} starts beyond the end of {}",
+                index + 1,
+                source_path.display()
+            ));
+        }
+    }
+
+    if let Some(writer) = current.take() {
+        writer.finish()?;
+    }
+    if next_track != plan.tracks.len() {
+        return Err(anyhow!(
+            "wrote {next_track} of {} planned track(s)",
+            plan.tracks.len()
+        ));
+    }
+    Ok(())
+}
+
+enum TrackWriter {
+    Flac(FlacTrackWriter),
+    Wav {
+        writer: hound::WavWriter<BufWriter<File>>,
+        format: FlacStreamFormat,
+    },
+}
+
+impl TrackWriter {
+    fn create(codec: OutputCodec, path: &Path, format: FlacStreamFormat) -> Result<Self> {
+        match codec {
+            OutputCodec::Flac => Ok(Self::Flac(FlacTrackWriter::create(path, format)?)),
+            OutputCodec::Wav => {
+                let spec = hound::WavSpec {
+                    channels: u16::try_from(format.channels)
+                        .map_err(|_| anyhow!("too many channels for WAV output"))?,
+                    sample_rate: format.sample_rate,
+                    bits_per_sample: u16::try_from(format.bits_per_sample)
+                        .map_err(|_| anyhow!("unsupported WAV bit depth"))?,
+                    sample_format: hound::SampleFormat::Int,
+                };
+                let writer = hound::WavWriter::create(path, spec)
+                    .map_err(|err| anyhow!("failed to create {}: {err}", path.display()))?;
+                Ok(Self::Wav { writer, format })
+            }
+            codec => Err(anyhow!(
+                "native splitter cannot write {} output",
+                codec.as_str()
+            )),
+        }
+    }
+
+    fn format(&self) -> FlacStreamFormat {
+        match self {
+            Self::Flac(writer) => writer.format(),
+            Self::Wav { format, .. } => *format,
+        }
+    }
+
+    fn write_samples(&mut self, channels: &[&[i32]]) -> Result<()> {
+        match self {
+            Self::Flac(writer) => writer.write_samples(channels),
+            Self::Wav { writer, .. } => {
+                let frames = channels.first().map_or(0, |channel| channel.len());
+                for frame in 0..frames {
+                    for channel in channels {
+                        writer
+                            .write_sample(channel[frame])
+                            .map_err(|err| anyhow!("failed to write WAV audio: {err}"))?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Flac(writer) => writer.finish().map(|_| ()),
+            Self::Wav { writer, .. } => writer
+                .finalize()
+                .map_err(|err| anyhow!("failed to finish WAV track: {err}")),
+        }
+    }
+}
+
+enum PcmReader {
+    Flac {
+        reader: Box<claxon::FlacReader<File>>,
+        buffer: Vec<i32>,
+    },
+    Wav {
+        reader: hound::WavReader<BufReader<File>>,
+    },
+    Decoded {
+        reader: hound::WavReader<BufReader<ChildStdout>>,
+        process: DecoderProcess,
+    },
+}
+
+impl PcmReader {
+    fn open(path: &Path) -> Result<Self> {
+        match AudioFormat::detect(path) {
+            Some(AudioFormat::Flac) => {
+                let reader = claxon::FlacReader::open(path)
+                    .map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?;
+                Ok(Self::Flac {
+                    reader: Box::new(reader),
+                    buffer: Vec::new(),
+                })
+            }
+            Some(AudioFormat::Wav) => {
+                let reader = hound::WavReader::open(path)
+                    .map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?;
+                ensure_integer_wav(reader.spec(), path)?;
+                Ok(Self::Wav { reader })
+            }
+            Some(format) => {
+                let decoder = format
+                    .external_decoder()
+                    .ok_or_else(|| anyhow!("no decoder is known for {}", path.display()))?;
+                let program = find_executable(decoder.program).ok_or_else(|| {
+                    anyhow!(
+                        "{} decoder {} was not found in PATH for {}",
+                        format.label(),
+                        decoder.program,
+                        path.display()
+                    )
+                })?;
+                Self::decode(path, command_for(&program, decoder.args, path))
+            }
+            None => Err(anyhow!(
+                "native splitter does not recognise the audio format of {}",
+                path.display()
+            )),
+        }
+    }
+
+    fn decode(path: &Path, mut command: Command) -> Result<Self> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| anyhow!("failed to start decoder for {}: {err}", path.display()))?;
+        let stdout = child.stdout.take().expect("decoder stdout is piped");
+        let process = DecoderProcess {
+            child,
+            path: path.to_path_buf(),
+        };
+        let reader = hound::WavReader::new(BufReader::new(stdout))
+            .map_err(|err| anyhow!("failed to read decoded audio for {}: {err}", path.display()))?;
+        ensure_integer_wav(reader.spec(), path)?;
+        Ok(Self::Decoded { reader, process })
+    }
+
+    fn format(&self) -> FlacStreamFormat {
+        match self {
+            Self::Flac { reader, .. } => {
+                let info = reader.streaminfo();
+                FlacStreamFormat {
+                    sample_rate: info.sample_rate,
+                    channels: info.channels,
+                    bits_per_sample: info.bits_per_sample,
+                }
+            }
+            Self::Wav { reader } => wav_format(reader.spec()),
+            Self::Decoded { reader, .. } => wav_format(reader.spec()),
+        }
+    }
+
+    fn read_block(&mut self) -> Result<Option<Vec<Vec<i32>>>> {
+        match self {
+            Self::Flac { reader, buffer } => {
+                let channels = reader.streaminfo().channels;
+                let block = reader
+                    .blocks()
+                    .read_next_or_eof(std::mem::take(buffer))
+                    .map_err(|err| anyhow!("failed to decode FLAC audio: {err}"))?;
+                let Some(block) = block else {
+                    return Ok(None);
+                };
+                let samples = (0..channels)
+                    .map(|channel| block.channel(channel).to_vec())
+                    .collect();
+                *buffer = block.into_buffer();
+                Ok(Some(samples))
+            }
+            Self::Wav { reader } => read_wav_block(reader),
+            Self::Decoded { reader, .. } => read_wav_block(reader),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Decoded { reader, process } => {
+                drop(reader);
+                process.wait()
+            }
+            Self::Flac { .. } | Self::Wav { .. } => Ok(()),
+        }
+    }
+}
+
+struct DecoderProcess {
+    child: Child,
+    path: PathBuf,
+}
+
+impl DecoderProcess {
+    fn wait(mut self) -> Result<()> {
+        let status = self.child.wait().map_err(|err| {
+            anyhow!(
+                "failed to wait for decoder of {}: {err}",
+                self.path.display()
+            )
+        })?;
+        if !status.success() {
+            let status = status.code().map_or_else(
+                || "terminated by signal".to_owned(),
+                |code| code.to_string(),
+            );
+            return Err(anyhow!(
+                "decoder failed for {} with status {status}",
+                self.path.display()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for DecoderProcess {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn ensure_integer_wav(spec: hound::WavSpec, path: &Path) -> Result<()> {
+    if spec.sample_format != hound::SampleFormat::Int {
+        return Err(anyhow!(
+            "floating point WAV is not supported: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn wav_format(spec: hound::WavSpec) -> FlacStreamFormat {
+    FlacStreamFormat {
+        sample_rate: spec.sample_rate,
+        channels: u32::from(spec.channels),
+        bits_per_sample: u32::from(spec.bits_per_sample),
+    }
+}
+
+fn read_wav_block<R: Read>(reader: &mut hound::WavReader<R>) -> Result<Option<Vec<Vec<i32>>>> {
+    let channels = usize::from(reader.spec().channels);
+    let mut samples = vec![Vec::with_capacity(DECODE_BLOCK_FRAMES); channels];
+    for (index, sample) in reader
+        .samples::<i32>()
+        .take(DECODE_BLOCK_FRAMES * channels)
+        .enumerate()
+    {
+        let sample = sample.map_err(|err| anyhow!("failed to decode WAV audio: {err}"))?;
+        samples[index % channels].push(sample);
+    }
+    if samples[0].is_empty() {
+        return Ok(None);
+    }
+    let frames = samples[channels - 1].len();
+    for channel in &mut samples {
+        channel.truncate(frames);
+    }
+    Ok(Some(samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::tempdir;
+
+    use super::{duration_to_cd_frames, NativeCueSplitter, PcmReader};
+    use crate::adapters::audio_format::command_for;
+    use crate::adapters::flac_writer::{FlacStreamFormat, FlacTrackWriter};
+    use crate::domain::{OutputCodec, PregapPolicy, SplitStatus};
+
+    const RATE: u32 = 44_100;
+
+    #[test]
+    fn splits_wav_on_index_01_boundaries() {
+        let tmp = tempdir().unwrap();
+        let audio = test_audio(RATE as usize * 3);
+        write_wav(&tmp.path().join("album.wav"), &audio);
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Track One"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Track/Two"
+    PERFORMER "Guest"
+    INDEX 00 00:01:00
+    INDEX 01 00:01:37
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        let first = tmp.path().join("Artist - Album - 01 - Track One.flac");
+        let second = tmp.path().join("Guest - Album - 02 - Track_Two.flac");
+        assert_eq!(result.status, SplitStatus::Split);
+        assert_eq!(result.tracks, vec![first.clone(), second.clone()]);
+        let cut = (75 + 37) * RATE as usize / 75;
+        assert_eq!(decode(&first), slice(&audio, 0, cut));
+        assert_eq!(decode(&second), slice(&audio, cut, audio[0].len()));
+    }
+
+    #[test]
+    fn writes_tracks_into_a_separate_output_directory() {
+        let tmp = tempdir().unwrap();
+        let source_dir = tmp.path().join("download");
+        let output_dir = tmp.path().join("staging").join("download-1").join("album");
+        fs::create_dir_all(&source_dir).unwrap();
+        write_wav(&source_dir.join("album.wav"), &test_audio(RATE as usize));
+        let cue_path = write_cue(
+            &source_dir,
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:00
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, &output_dir)
+            .unwrap();
+
+        assert_eq!(
+            result.tracks,
+            vec![output_dir.join("Artist - Album - 01 - One.flac")]
+        );
+        assert!(result.tracks[0].exists());
+        assert_eq!(fs::read_dir(&source_dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn appends_pregap_from_next_file_to_previous_track_and_splits_flac_input() {
+        let tmp = tempdir().unwrap();
+        let first_audio = test_audio(RATE as usize);
+        let second_audio = test_audio(RATE as usize * 2);
+        write_wav(&tmp.path().join("one.wav"), &first_audio);
+        write_flac(&tmp.path().join("two.flac"), &second_audio);
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "one.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:00
+FILE "two.flac" WAVE
+  TRACK 02 AUDIO
+    TITLE "Two"
+    INDEX 00 00:00:00
+    INDEX 01 00:00:30
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        let gap = 30 * RATE as usize / 75;
+        let mut expected_first = first_audio.clone();
+        for (channel, gap_samples) in expected_first.iter_mut().zip(&second_audio) {
+            channel.extend_from_slice(&gap_samples[..gap]);
+        }
+        assert_eq!(decode(&result.tracks[0]), expected_first);
+        assert_eq!(
+            decode(&result.tracks[1]),
+            slice(&second_audio, gap, second_audio[0].len())
+        );
+    }
+
+    #[test]
+    fn discards_hidden_audio_before_first_track() {
+        let tmp = tempdir().unwrap();
+        let audio = test_audio(RATE as usize);
+        write_wav(&tmp.path().join("album.wav"), &audio);
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:15
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        let start = 15 * RATE as usize / 75;
+        assert_eq!(
+            result.tracks,
+            vec![tmp.path().join("Artist - Album - 01 - One.flac")]
+        );
+        assert_eq!(
+            decode(&result.tracks[0]),
+            slice(&audio, start, audio[0].len())
+        );
+    }
+
+    #[test]
+    fn applies_the_configured_pregap_policy() {
+        let tmp = tempdir().unwrap();
+        let audio = test_audio(RATE as usize * 2);
+        write_wav(&tmp.path().join("album.wav"), &audio);
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:15
+  TRACK 02 AUDIO
+    TITLE "Two"
+    INDEX 00 00:01:00
+    INDEX 01 00:01:30
+"#,
+        );
+        let hidden_end = 15 * RATE as usize / 75;
+        let gap_start = RATE as usize;
+        let second_start = (75 + 30) * RATE as usize / 75;
+        let end = audio[0].len();
+
+        let split = |pregap| {
+            let output_dir = tmp.path().join(format!("{pregap:?}"));
+            NativeCueSplitter::new(true, true, "%n - %t".into(), OutputCodec::Flac, pregap)
+                .split_cue_sync(&cue_path, &output_dir)
+                .unwrap()
+        };
+
+        let result = split(PregapPolicy::Discard);
+        assert_eq!(result.pregap_policy, Some(PregapPolicy::Discard));
+        assert_eq!(
+            decode(&result.tracks[0]),
+            slice(&audio, hidden_end, gap_start)
+        );
+        assert_eq!(decode(&result.tracks[1]), slice(&audio, second_start, end));
+
+        let result = split(PregapPolicy::PrependNext);
+        assert_eq!(decode(&result.tracks[0]), slice(&audio, 0, gap_start));
+        assert_eq!(decode(&result.tracks[1]), slice(&audio, gap_start, end));
+
+        let result = split(PregapPolicy::TrackZero);
+        let hidden_track = result.hidden_track.unwrap();
+        assert!(hidden_track.ends_with("00 - Hidden Track.flac"));
+        assert_eq!(decode(&hidden_track), slice(&audio, 0, hidden_end));
+        assert_eq!(result.tracks.len(), 2);
+        assert_eq!(
+            decode(&result.tracks[0]),
+            slice(&audio, hidden_end, second_start)
+        );
+    }
+
+    #[test]
+    fn skips_cue_sheets_with_one_file_per_track() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("01.wav"), &test_audio(RATE as usize));
+        write_wav(&tmp.path().join("02.wav"), &test_audio(RATE as usize));
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"FILE "01.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+FILE "02.wav" WAVE
+  TRACK 02 AUDIO
+    INDEX 01 00:00:00
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, &tmp.path().join("split"))
+            .unwrap();
+
+        assert_eq!(result.status, SplitStatus::Skipped);
+        assert!(result.tracks.is_empty());
+        assert!(result.message.unwrap().contains("one FILE per track"));
+        assert!(!tmp.path().join("split").exists());
+    }
+
+    #[test]
+    fn dry_run_plans_track_paths_without_writing() {
+        let tmp = tempdir().unwrap();
+        write_wav(
+            &tmp.path().join("album.wav"),
+            &test_audio(RATE as usize * 2),
+        );
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Two"
+    INDEX 01 00:01:00
+"#,
+        );
+        let output_dir = tmp.path().join("split");
+
+        let result = test_splitter(true)
+            .with_dry_run(true)
+            .split_cue_sync(&cue_path, &output_dir)
+            .unwrap();
+
+        assert_eq!(result.status, SplitStatus::Planned);
+        assert_eq!(
+            result.tracks,
+            vec![
+                output_dir.join("Artist - Album - 01 - One.flac"),
+                output_dir.join("Artist - Album - 02 - Two.flac"),
+            ]
+        );
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn writes_wav_tracks_when_configured() {
+        let tmp = tempdir().unwrap();
+        let audio = test_audio(RATE as usize * 2);
+        write_wav(&tmp.path().join("album.wav"), &audio);
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    INDEX 01 00:01:00
+"#,
+        );
+        let splitter = NativeCueSplitter::new(
+            true,
+            true,
+            "%n".into(),
+            OutputCodec::Wav,
+            PregapPolicy::AppendPrevious,
+        );
+
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        let second = tmp.path().join("02.wav");
+        assert_eq!(result.codec, OutputCodec::Wav);
+        assert_eq!(
+            result.tracks,
+            vec![tmp.path().join("01.wav"), second.clone()]
+        );
+        let mut reader = hound::WavReader::open(&second).unwrap();
+        let samples = reader
+            .samples::<i32>()
+            .map(|sample| sample.unwrap())
+            .collect::<Vec<_>>();
+        let expected = slice(&audio, RATE as usize, audio[0].len());
+        assert_eq!(samples.len(), expected[0].len() * 2);
+        assert_eq!(samples[0], expected[0][0]);
+        assert_eq!(samples[1], expected[1][0]);
+    }
+
+    #[test]
+    fn reads_audio_streamed_from_an_external_decoder() {
+        let tmp = tempdir().unwrap();
+        let audio = test_audio(10_000);
+        let source = tmp.path().join("album.ape");
+        write_wav(&source, &audio);
+        let decoder = write_script(tmp.path(), "decoder", "cat \"$1\"\n");
+
+        let mut reader =
+            PcmReader::decode(&source, command_for(&decoder, &["%f"], &source)).unwrap();
+        let mut decoded = vec![Vec::new(); 2];
+        while let Some(block) = reader.read_block().unwrap() {
+            for (channel, samples) in decoded.iter_mut().zip(block) {
+                channel.extend(samples);
+            }
+        }
+        reader.finish().unwrap();
+
+        assert_eq!(decoded, audio);
+    }
+
+    #[test]
+    fn reports_external_decoder_failures() {
+        let tmp = tempdir().unwrap();
+        let source = tmp.path().join("album.wv");
+        write_wav(&source, &test_audio(100));
+        let decoder = write_script(tmp.path(), "decoder", "cat \"$1\"\nexit 3\n");
+
+        let mut reader =
+            PcmReader::decode(&source, command_for(&decoder, &["%f"], &source)).unwrap();
+        while reader.read_block().unwrap().is_some() {}
+        let err = reader.finish().unwrap_err();
+
+        assert!(err.to_string().contains("decoder failed"));
+        assert!(err.to_string().contains("status 3"));
+    }
+
+    #[test]
+    fn skips_when_cue_references_no_existing_audio() {
+        let tmp = tempdir().unwrap();
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 01 00:00:00
+"#,
+        );
+
+        let result = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        assert_eq!(result.status, SplitStatus::Skipped);
+        assert!(result.message.unwrap().contains("does not reference"));
+    }
+
+    #[test]
+    fn refuses_to_overwrite_existing_tracks_when_disabled() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("album.wav"), &test_audio(RATE as usize));
+        let existing = tmp.path().join("Artist - Album - 01 - One.flac");
+        fs::write(&existing, "keep").unwrap();
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:00
+"#,
+        );
+
+        let err = test_splitter(false)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("refusing to overwrite"));
+        assert_eq!(fs::read_to_string(existing).unwrap(), "keep");
+    }
+
+    #[test]
+    fn removes_written_tracks_when_a_track_starts_past_the_end() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("album.wav"), &test_audio(RATE as usize));
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "One"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Two"
+    INDEX 01 00:05:00
+"#,
+        );
+
+        let err = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("starts beyond the end"));
+        assert!(!tmp.path().join("Artist - Album - 01 - One.flac").exists());
+    }
+
+    #[test]
+    fn missing_index_01_is_an_error() {
+        let tmp = tempdir().unwrap();
+        write_wav(&tmp.path().join("album.wav"), &test_audio(100));
+        let cue_path = write_cue(
+            tmp.path(),
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    INDEX 00 00:00:00
+"#,
+        );
+
+        let err = test_splitter(true)
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("has no INDEX 01"));
+    }
+
+    #[test]
+    fn rounds_cue_offsets_to_cd_frames() {
+        let offset = rcue::util::timestamp_to_duration("01:02:74").unwrap();
+
+        assert_eq!(duration_to_cd_frames(offset), (60 + 2) * 75 + 74);
+    }
+
+    fn test_splitter(overwrite: bool) -> NativeCueSplitter {
+        NativeCueSplitter::new(
+            true,
+            overwrite,
+            "%p - %a - %n - %t".into(),
+            OutputCodec::Flac,
+            PregapPolicy::AppendPrevious,
+        )
+    }
+
+    fn write_cue(dir: &Path, contents: &str) -> PathBuf {
+        let cue_path = dir.join("album.cue");
+        fs::write(&cue_path, contents).unwrap();
+        cue_path
+    }
+
+    fn test_audio(frames: usize) -> Vec<Vec<i32>> {
+        vec![
+            (0..frames)
+                .map(|index| ((index as f64 * 0.03).sin() * 20_000.0) as i32)
+                .collect(),
+            (0..frames)
+                .map(|index| ((index * 31) % 4_096) as i32 - 2_048)
+                .collect(),
+        ]
+    }
+
+    fn slice(audio: &[Vec<i32>], start: usize, end: usize) -> Vec<Vec<i32>> {
+        audio
+            .iter()
+            .map(|channel| channel[start..end].to_vec())
+            .collect()
+    }
+
+    fn write_wav(path: &Path, audio: &[Vec<i32>]) {
+        let spec = hound::WavSpec {
+            channels: audio.len() as u16,
+            sample_rate: RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for index in 0..audio[0].len() {
+            for channel in audio {
+                writer.write_sample(channel[index] as i16).unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn write_flac(path: &Path, audio: &[Vec<i32>]) {
+        let mut writer = FlacTrackWriter::create(
+            path,
+            FlacStreamFormat {
+                sample_rate: RATE,
+                channels: audio.len() as u32,
+                bits_per_sample: 16,
+            },
+        )
+        .unwrap();
+        let channels = audio.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        writer.write_samples(&channels).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn decode(path: &Path) -> Vec<Vec<i32>> {
+        let mut reader = claxon::FlacReader::open(path).unwrap();
+        let channels = reader.streaminfo().channels;
+        let mut decoded = vec![Vec::new(); channels as usize];
+        let mut blocks = reader.blocks();
+        let mut buffer = Vec::new();
+        while let Some(block) = blocks.read_next_or_eof(buffer).unwrap() {
+            for (channel, samples) in decoded.iter_mut().enumerate() {
+                samples.extend_from_slice(block.channel(channel as u32));
+            }
+            buffer = block.into_buffer();
+        }
+        decoded
+    }
+
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{body}")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+}
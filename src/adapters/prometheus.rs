@@ -0,0 +1,301 @@
+use std::fmt::Write;
+
+use crate::application::metrics::{
+    LidarrOperation, LookupOutcome, LookupService, ManualImportOutcome, MetricValues, SplitResult,
+};
+use crate::application::ports::DownloadHistoryRow;
+use crate::domain::{DownloadLifecycleState, TrackCleanupStatus};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const LIFECYCLE_STATES: [(DownloadLifecycleState, &str); 9] = [
+    (DownloadLifecycleState::Detected, "detected"),
+    (DownloadLifecycleState::Processing, "processing"),
+    (DownloadLifecycleState::AwaitingImport, "awaiting_import"),
+    (DownloadLifecycleState::CleaningUp, "cleaning_up"),
+    (DownloadLifecycleState::Completed, "completed"),
+    (DownloadLifecycleState::Failed, "failed"),
+    (DownloadLifecycleState::Planned, "planned"),
+    (DownloadLifecycleState::Ignored, "ignored"),
+    (DownloadLifecycleState::GaveUp, "gave_up"),
+];
+const SPLIT_RESULTS: [(SplitResult, &str); 4] = [
+    (SplitResult::Succeeded, "succeeded"),
+    (SplitResult::Skipped, "skipped"),
+    (SplitResult::Planned, "planned"),
+    (SplitResult::Failed, "failed"),
+];
+const MANUAL_IMPORT_OUTCOMES: [(ManualImportOutcome, &str); 3] = [
+    (ManualImportOutcome::Started, "started"),
+    (ManualImportOutcome::Skipped, "skipped"),
+    (ManualImportOutcome::Failed, "failed"),
+];
+const LOOKUP_SERVICES: [(LookupService, &str); 2] = [
+    (LookupService::MusicBrainz, "musicbrainz"),
+    (LookupService::Gnudb, "gnudb"),
+];
+const LOOKUP_OUTCOMES: [(LookupOutcome, &str); 4] = [
+    (LookupOutcome::Found, "found"),
+    (LookupOutcome::NotFound, "not_found"),
+    (LookupOutcome::Skipped, "skipped"),
+    (LookupOutcome::Error, "error"),
+];
+const CLEANUP_STATUSES: [(TrackCleanupStatus, &str); 4] = [
+    (TrackCleanupStatus::Pending, "pending"),
+    (TrackCleanupStatus::Deleted, "deleted"),
+    (TrackCleanupStatus::DeleteFailed, "delete_failed"),
+    (TrackCleanupStatus::Missing, "missing"),
+];
+const LIDARR_OPERATIONS: [(LidarrOperation, &str); 2] = [
+    (LidarrOperation::Queue, "queue"),
+    (LidarrOperation::ManualImport, "manual_import"),
+];
+
+/// Renders the Prometheus text format. Every label value is written, even at
+/// zero, so rates and alerts work from the first scrape.
+pub fn render_metrics(values: &MetricValues, downloads: &[DownloadHistoryRow]) -> String {
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "splittarr_downloads",
+        "gauge",
+        "Tracked downloads by lifecycle state.",
+    );
+    for (state, label) in LIFECYCLE_STATES {
+        let count = downloads
+            .iter()
+            .filter(|download| download.lifecycle_state == state)
+            .count();
+        sample(
+            &mut out,
+            "splittarr_downloads",
+            &[("state", label)],
+            count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "splittarr_split_attempts_total",
+        "counter",
+        "CUE sheets handed to the splitter.",
+    );
+    sample(
+        &mut out,
+        "splittarr_split_attempts_total",
+        &[],
+        values.split_attempts as f64,
+    );
+
+    header(
+        &mut out,
+        "splittarr_splits_total",
+        "counter",
+        "Finished splits by result.",
+    );
+    for (result, label) in SPLIT_RESULTS {
+        let count = values.splits.get(&result).copied().unwrap_or_default();
+        sample(
+            &mut out,
+            "splittarr_splits_total",
+            &[("result", label)],
+            count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "splittarr_manual_imports_total",
+        "counter",
+        "Lidarr manual imports by outcome.",
+    );
+    for (outcome, label) in MANUAL_IMPORT_OUTCOMES {
+        let count = values
+            .manual_imports
+            .get(&outcome)
+            .copied()
+            .unwrap_or_default();
+        sample(
+            &mut out,
+            "splittarr_manual_imports_total",
+            &[("result", label)],
+            count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "splittarr_disc_lookups_total",
+        "counter",
+        "MusicBrainz and GnuDB disc lookups by outcome.",
+    );
+    for (service, service_label) in LOOKUP_SERVICES {
+        for (outcome, outcome_label) in LOOKUP_OUTCOMES {
+            let count = values
+                .disc_lookups
+                .get(&(service, outcome))
+                .copied()
+                .unwrap_or_default();
+            sample(
+                &mut out,
+                "splittarr_disc_lookups_total",
+                &[("service", service_label), ("result", outcome_label)],
+                count as f64,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "splittarr_track_cleanups_total",
+        "counter",
+        "Generated tracks handled by cleanup, by resulting status.",
+    );
+    for (status, label) in CLEANUP_STATUSES {
+        let count = values
+            .track_cleanups
+            .get(&status)
+            .copied()
+            .unwrap_or_default();
+        sample(
+            &mut out,
+            "splittarr_track_cleanups_total",
+            &[("status", label)],
+            count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "splittarr_lidarr_api_errors_total",
+        "counter",
+        "Failed Lidarr API operations.",
+    );
+    for (operation, label) in LIDARR_OPERATIONS {
+        let count = values
+            .lidarr_api_errors
+            .get(&operation)
+            .copied()
+            .unwrap_or_default();
+        sample(
+            &mut out,
+            "splittarr_lidarr_api_errors_total",
+            &[("operation", label)],
+            count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "splittarr_poll_duration_seconds",
+        "summary",
+        "Time spent checking Lidarr's queue and processing downloads.",
+    );
+    sample(
+        &mut out,
+        "splittarr_poll_duration_seconds_sum",
+        &[],
+        values.poll_duration_seconds,
+    );
+    sample(
+        &mut out,
+        "splittarr_poll_duration_seconds_count",
+        &[],
+        values.polls as f64,
+    );
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{value}\""))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = write!(out, "{{{labels}}}");
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::render_metrics;
+    use crate::application::metrics::{
+        LidarrOperation, LookupOutcome, LookupService, ManualImportOutcome, Metrics, SplitResult,
+    };
+    use crate::application::ports::DownloadHistoryRow;
+    use crate::domain::{DownloadLifecycleState, TrackCleanupStatus};
+
+    fn row(download_id: &str, lifecycle_state: DownloadLifecycleState) -> DownloadHistoryRow {
+        DownloadHistoryRow {
+            download_id: download_id.into(),
+            title: download_id.into(),
+            status: "completed".into(),
+            output_path: format!("/downloads/{download_id}"),
+            tracked_download_state: "importPending".into(),
+            lifecycle_state,
+            updated_at: "2026-10-18T10:00:00Z".into(),
+            completed_at: None,
+            generated_track_count: 0,
+        }
+    }
+
+    #[test]
+    fn renders_counters_and_per_state_gauges() {
+        let metrics = Metrics::default();
+        metrics.record_split_attempt();
+        metrics.record_split_attempt();
+        metrics.record_split(SplitResult::Succeeded);
+        metrics.record_split(SplitResult::Failed);
+        metrics.record_manual_import(ManualImportOutcome::Started);
+        metrics.record_disc_lookup(LookupService::Gnudb, LookupOutcome::NotFound);
+        metrics.record_track_cleanup(TrackCleanupStatus::Deleted);
+        metrics.record_track_cleanup(TrackCleanupStatus::Deleted);
+        metrics.record_lidarr_api_error(LidarrOperation::Queue);
+        metrics.record_poll(Duration::from_millis(1500));
+
+        let rendered = render_metrics(
+            &metrics.snapshot(),
+            &[
+                row("a", DownloadLifecycleState::Completed),
+                row("b", DownloadLifecycleState::Completed),
+                row("c", DownloadLifecycleState::Failed),
+            ],
+        );
+
+        for line in [
+            "# TYPE splittarr_downloads gauge",
+            "splittarr_downloads{state=\"completed\"} 2",
+            "splittarr_downloads{state=\"failed\"} 1",
+            "splittarr_downloads{state=\"gave_up\"} 0",
+            "splittarr_split_attempts_total 2",
+            "splittarr_splits_total{result=\"succeeded\"} 1",
+            "splittarr_splits_total{result=\"failed\"} 1",
+            "splittarr_manual_imports_total{result=\"started\"} 1",
+            "splittarr_manual_imports_total{result=\"skipped\"} 0",
+            "splittarr_disc_lookups_total{service=\"gnudb\",result=\"not_found\"} 1",
+            "splittarr_disc_lookups_total{service=\"musicbrainz\",result=\"found\"} 0",
+            "splittarr_track_cleanups_total{status=\"deleted\"} 2",
+            "splittarr_lidarr_api_errors_total{operation=\"queue\"} 1",
+            "# TYPE splittarr_poll_duration_seconds summary",
+            "splittarr_poll_duration_seconds_sum 1.5",
+            "splittarr_poll_duration_seconds_count 1",
+        ] {
+            assert!(
+                rendered.lines().any(|rendered| rendered == line),
+                "missing {line:?} in:\n{rendered}"
+            );
+        }
+    }
+}
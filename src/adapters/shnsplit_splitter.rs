@@ -5,11 +5,19 @@ use std::process::Command;
 use std::time::SystemTime;
 
 use anyhow::{anyhow, Result};
-use rcue::parser::parse_from_file;
 use regex::bytes::Regex;
-
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::adapters::audio_format::AudioFormat;
+use crate::adapters::cue_layout::{
+    has_hidden_track_audio, has_one_file_per_track, prepend_gaps_to_next_track,
+    PER_TRACK_FILES_MESSAGE,
+};
+use crate::adapters::cue_text::{parse_cue_text, read_cue_text};
 use crate::application::ports::CueSplitter;
-use crate::domain::{SplitOutcome, SplitStatus};
+use crate::bootstrap::settings::OutputSettings;
+use crate::domain::{OutputCodec, PregapPolicy, SplitOutcome, SplitStatus};
 
 #[derive(Debug, Clone)]
 pub struct ShnsplitCueSplitter {
@@ -17,34 +25,43 @@ pub struct ShnsplitCueSplitter {
     shnsplit_path: PathBuf,
     overwrite: bool,
     format: String,
+    output: OutputSettings,
+    pregap: PregapPolicy,
 }
 
 impl ShnsplitCueSplitter {
-    pub fn new(cue_strict: bool, shnsplit_path: PathBuf, overwrite: bool, format: String) -> Self {
+    pub fn new(
+        cue_strict: bool,
+        shnsplit_path: PathBuf,
+        overwrite: bool,
+        format: String,
+        output: OutputSettings,
+        pregap: PregapPolicy,
+    ) -> Self {
         Self {
             cue_strict,
             shnsplit_path,
             overwrite,
             format,
+            output,
+            pregap,
         }
     }
 }
 
 impl CueSplitter for ShnsplitCueSplitter {
-    async fn split_cue(&self, cue_path: &Path) -> Result<SplitOutcome> {
+    async fn split_cue(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
         let splitter = self.clone();
         let cue_path = cue_path.to_path_buf();
-        tokio::task::spawn_blocking(move || splitter.split_cue_sync(&cue_path))
+        let output_dir = output_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || splitter.split_cue_sync(&cue_path, &output_dir))
             .await
             .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 }
 
 impl ShnsplitCueSplitter {
-    fn split_cue_sync(&self, cue_path: &Path) -> Result<SplitOutcome> {
-        let cue_path_str = cue_path
-            .to_str()
-            .ok_or_else(|| anyhow!("cue path is not valid UTF-8: {}", cue_path.display()))?;
+    fn split_cue_sync(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
         let cue_dir = cue_path
             .parent()
             .ok_or_else(|| anyhow!("cue file has no parent directory: {}", cue_path.display()))?;
@@ -52,7 +69,8 @@ impl ShnsplitCueSplitter {
             .file_name()
             .ok_or_else(|| anyhow!("cue file name is not valid UTF-8: {}", cue_path.display()))?;
 
-        let cue = parse_from_file(cue_path_str, self.cue_strict)
+        let cue_text = read_cue_text(cue_path)?;
+        let cue = parse_cue_text(&cue_text, self.cue_strict)
             .map_err(|err| anyhow!("failed to parse cue file {}: {err}", cue_path.display()))?;
         let expected_tracks = cue
             .files
@@ -75,33 +93,67 @@ impl ShnsplitCueSplitter {
             return Ok(SplitOutcome {
                 status: SplitStatus::Skipped,
                 tracks: Vec::new(),
+                codec: self.output.codec,
                 message: Some("cue file does not reference an audio file in its directory".into()),
+                pregap_policy: None,
+                hidden_track: None,
             });
         }
+        if has_one_file_per_track(&cue) {
+            return Ok(SplitOutcome {
+                status: SplitStatus::Skipped,
+                tracks: Vec::new(),
+                codec: self.output.codec,
+                message: Some(PER_TRACK_FILES_MESSAGE.into()),
+                pregap_policy: None,
+                hidden_track: None,
+            });
+        }
+        let pregap_track = self.pregap != PregapPolicy::PrependNext && has_hidden_track_audio(&cue);
 
         let overwrite = if self.overwrite { "always" } else { "never" };
-        let files_before = snapshot_audio_files_best_effort(cue_dir, cue_path);
+        fs::create_dir_all(output_dir).map_err(|err| {
+            anyhow!(
+                "failed to create output directory {}: {err}",
+                output_dir.display()
+            )
+        })?;
+        let files_before = snapshot_audio_files_best_effort(output_dir, cue_path);
+        let cue_copy = match self.pregap {
+            PregapPolicy::PrependNext => Some(prepend_gaps_to_next_track(&cue_text.text)),
+            _ if !cue_text.is_utf8() => Some(cue_text.text.clone()),
+            _ => None,
+        };
+        let cue_copy = cue_copy.map(|text| write_cue_copy(&text)).transpose()?;
         let output = {
             let mut command = Command::new(&self.shnsplit_path);
             command
                 .current_dir(cue_dir)
                 .arg("-f")
-                .arg(cue_file_name)
+                .arg(
+                    cue_copy
+                        .as_ref()
+                        .map_or(cue_file_name, |path| path.as_os_str()),
+                )
                 .arg("-d")
-                .arg(cue_dir)
+                .arg(output_dir)
                 .arg("-t")
                 .arg(&self.format)
                 .args(decoder_args(&referenced_paths))
                 .arg("-O")
                 .arg(overwrite)
                 .arg("-o")
-                .arg("flac flac -s -8 -o %f -");
+                .arg(output_format_spec(&self.output));
 
             for file in referenced_files {
                 command.arg(file);
             }
 
-            command.output().map_err(|err| {
+            let output = command.output();
+            if let Some(cue_copy) = &cue_copy {
+                let _ = fs::remove_file(cue_copy);
+            }
+            output.map_err(|err| {
                 anyhow!("failed to run shnsplit for {}: {err}", cue_path.display())
             })?
         };
@@ -119,48 +171,71 @@ impl ShnsplitCueSplitter {
         }
 
         let parsed_tracks = parse_generated_tracks(cue_dir, &output.stderr);
-        let files_after = snapshot_audio_files_best_effort(cue_dir, cue_path);
+        let files_after = snapshot_audio_files_best_effort(output_dir, cue_path);
         let detected_tracks =
             detect_generated_tracks(&referenced_paths, &files_before, &files_after);
         let tracks = select_generated_tracks(
             cue_path,
             expected_tracks,
+            pregap_track,
             &referenced_paths,
             parsed_tracks,
             detected_tracks,
             &files_after,
         )?;
-        let tracks = normalize_generated_track_filenames(tracks)?;
+        let mut tracks = normalize_generated_track_filenames(tracks)?;
+        let hidden_track = if tracks.len() > expected_tracks {
+            let pregap = tracks.remove(0);
+            self.keep_or_remove_pregap_track(pregap)?
+        } else {
+            None
+        };
 
         Ok(SplitOutcome {
             status: SplitStatus::Split,
             tracks,
+            codec: self.output.codec,
             message: None,
+            pregap_policy: Some(self.pregap),
+            hidden_track,
         })
     }
+
+    fn keep_or_remove_pregap_track(&self, pregap: PathBuf) -> Result<Option<PathBuf>> {
+        if self.pregap == PregapPolicy::TrackZero {
+            return Ok(Some(pregap));
+        }
+        fs::remove_file(&pregap)
+            .map_err(|err| anyhow!("failed to remove pregap track {}: {err}", pregap.display()))?;
+        Ok(None)
+    }
 }
 
 fn select_generated_tracks(
     cue_path: &Path,
     expected_tracks: usize,
+    pregap_track: bool,
     referenced_paths: &[PathBuf],
     parsed_tracks: Vec<PathBuf>,
     detected_tracks: Vec<PathBuf>,
     files_after: &BTreeSet<FileSnapshot>,
 ) -> Result<Vec<PathBuf>> {
+    let complete = |tracks: &[PathBuf]| {
+        tracks.len() == expected_tracks || (pregap_track && tracks.len() == expected_tracks + 1)
+    };
     let parsed_existing = existing_unique_tracks(parsed_tracks);
-    if parsed_existing.len() == expected_tracks {
+    if complete(&parsed_existing) {
         return Ok(parsed_existing);
     }
 
     let combined_tracks =
         existing_unique_tracks(parsed_existing.into_iter().chain(detected_tracks).collect());
-    if combined_tracks.len() == expected_tracks {
+    if complete(&combined_tracks) {
         return Ok(combined_tracks);
     }
 
     let directory_tracks = non_source_audio_tracks(referenced_paths, files_after);
-    if directory_tracks.len() == expected_tracks {
+    if complete(&directory_tracks) {
         return Ok(directory_tracks);
     }
 
@@ -278,6 +353,12 @@ fn build_generated_track_rename_plan(tracks: Vec<PathBuf>) -> Result<Vec<Generat
     Ok(plan)
 }
 
+fn write_cue_copy(text: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("splittarr-{}.cue", Uuid::new_v4()));
+    fs::write(&path, text).map_err(|err| anyhow!("failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
+
 fn sanitized_generated_track_path(path: &Path) -> Result<PathBuf> {
     let file_name = path
         .file_name()
@@ -343,7 +424,7 @@ fn sanitize_file_name_lossy_bytes(bytes: &[u8]) -> String {
     trim_sanitized_file_name(sanitized)
 }
 
-fn sanitize_file_name_str(value: &str) -> String {
+pub(crate) fn sanitize_file_name_str(value: &str) -> String {
     let mut sanitized = String::with_capacity(value.len());
     let mut previous_was_underscore = false;
     for ch in value.chars() {
@@ -415,11 +496,71 @@ fn ascii_replacement_for_char(ch: char) -> CharReplacement {
     }
 }
 
-fn decoder_args(referenced_paths: &[PathBuf]) -> Vec<&'static str> {
-    if referenced_paths.is_empty() || !referenced_paths.iter().all(|path| is_flac_file(path)) {
-        return Vec::new();
+fn decoder_args(referenced_paths: &[PathBuf]) -> Vec<String> {
+    let formats = referenced_paths
+        .iter()
+        .map(|path| AudioFormat::detect(path))
+        .collect::<Option<BTreeSet<_>>>()
+        .unwrap_or_default();
+    let mut formats = formats.into_iter();
+    match (formats.next(), formats.next()) {
+        (Some(format), None) => format
+            .external_decoder()
+            .map(|decoder| vec!["-i".to_owned(), decoder.shntool_spec(format)])
+            .unwrap_or_default(),
+        _ => Vec::new(),
     }
-    vec!["-i", "flac flac -cd -s %f"]
+}
+
+fn output_format_spec(output: &OutputSettings) -> String {
+    let bitrate = output.bitrate_kbps.to_string();
+    let compression = format!("-{}", output.compression_level);
+    let extra = output.encoder_args.iter().map(String::as_str);
+    let parts: Vec<&str> = match output.codec {
+        OutputCodec::Flac => ["flac", "flac", "-s", compression.as_str()]
+            .into_iter()
+            .chain(extra)
+            .chain(["-o", "%f", "-"])
+            .collect(),
+        OutputCodec::Wav => vec!["wav"],
+        OutputCodec::Alac => [
+            "cust",
+            "ext=m4a",
+            "ffmpeg",
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-y",
+            "-f",
+            "wav",
+            "-i",
+            "-",
+            "-c:a",
+            "alac",
+        ]
+        .into_iter()
+        .chain(extra)
+        .chain(["%f"])
+        .collect(),
+        OutputCodec::Opus => [
+            "cust",
+            "ext=opus",
+            "opusenc",
+            "--quiet",
+            "--bitrate",
+            bitrate.as_str(),
+        ]
+        .into_iter()
+        .chain(extra)
+        .chain(["-", "%f"])
+        .collect(),
+        OutputCodec::Mp3 => ["cust", "ext=mp3", "lame", "--quiet", "-b", bitrate.as_str()]
+            .into_iter()
+            .chain(extra)
+            .chain(["-", "%f"])
+            .collect(),
+    };
+    parts.join(" ")
 }
 
 fn detect_generated_tracks(
@@ -460,7 +601,7 @@ fn snapshot_audio_files_best_effort(root: &Path, cue_path: &Path) -> BTreeSet<Fi
     match snapshot_audio_files(root) {
         Ok(files) => files,
         Err(err) => {
-            eprintln!(
+            warn!(
                 "Unable to snapshot audio files for {}: {err}",
                 cue_path.display()
             );
@@ -469,19 +610,23 @@ fn snapshot_audio_files_best_effort(root: &Path, cue_path: &Path) -> BTreeSet<Fi
     }
 }
 
-fn is_flac_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|extension| extension.to_str())
-        .is_some_and(|extension| extension.eq_ignore_ascii_case("flac"))
-}
-
 fn is_audio_file(path: &Path) -> bool {
     path.extension()
         .and_then(|extension| extension.to_str())
         .is_some_and(|extension| {
             matches!(
                 extension.to_ascii_lowercase().as_str(),
-                "flac" | "wav" | "ape" | "wv" | "m4a" | "mp3" | "ogg" | "opus" | "tta" | "aiff"
+                "flac"
+                    | "wav"
+                    | "ape"
+                    | "wv"
+                    | "m4a"
+                    | "mp3"
+                    | "ogg"
+                    | "opus"
+                    | "tta"
+                    | "aiff"
+                    | "aif"
             )
         })
 }
@@ -509,10 +654,11 @@ mod tests {
 
     use super::{
         decoder_args, detect_generated_tracks, normalize_generated_track_filenames,
-        parse_generated_tracks, sanitize_file_name_str, snapshot_audio_files_best_effort,
-        ShnsplitCueSplitter,
+        output_format_spec, parse_generated_tracks, sanitize_file_name_str,
+        snapshot_audio_files_best_effort, ShnsplitCueSplitter,
     };
-    use crate::domain::SplitStatus;
+    use crate::bootstrap::settings::OutputSettings;
+    use crate::domain::{OutputCodec, PregapPolicy, SplitStatus};
 
     #[test]
     fn splits_with_fake_shnsplit_and_records_absolute_tracks() {
@@ -527,7 +673,9 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        let result = splitter.split_cue_sync(&cue_path).unwrap();
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
 
         assert_eq!(result.status, SplitStatus::Split);
         assert_eq!(
@@ -543,7 +691,9 @@ exit 0
         let fake = write_fake_shnsplit(tmp.path(), "exit 1\n");
         let splitter = test_splitter(fake);
 
-        let result = splitter.split_cue_sync(&cue_path).unwrap();
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
 
         assert_eq!(result.status, SplitStatus::Skipped);
         assert!(result.tracks.is_empty());
@@ -562,7 +712,9 @@ exit 2
         );
         let splitter = test_splitter(fake);
 
-        let err = splitter.split_cue_sync(&cue_path).unwrap_err();
+        let err = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
 
         assert!(err.to_string().contains("shnsplit failed"));
     }
@@ -596,7 +748,9 @@ exit 0
         )));
         let expected = tmp.path().join("Artist - Album - 01 - You're Lost.flac");
 
-        let result = splitter.split_cue_sync(&cue_path).unwrap();
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
 
         assert_eq!(result.status, SplitStatus::Split);
         assert_eq!(result.tracks, vec![expected]);
@@ -636,7 +790,9 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        let result = splitter.split_cue_sync(&cue_path).unwrap();
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
 
         assert_eq!(result.status, SplitStatus::Split);
         assert_eq!(result.tracks.len(), 10);
@@ -732,7 +888,9 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        let err = splitter.split_cue_sync(&cue_path).unwrap_err();
+        let err = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
 
         assert!(err
             .to_string()
@@ -761,6 +919,47 @@ exit 0
         assert_eq!(args, vec!["-i", "flac flac -cd -s %f"]);
     }
 
+    #[test]
+    fn builds_output_format_for_each_codec() {
+        let mut flac = output(OutputCodec::Flac, &["--replay-gain"]);
+        flac.compression_level = 5;
+        assert_eq!(
+            output_format_spec(&flac),
+            "flac flac -s -5 --replay-gain -o %f -"
+        );
+        assert_eq!(output_format_spec(&output(OutputCodec::Wav, &[])), "wav");
+        assert_eq!(
+            output_format_spec(&output(OutputCodec::Alac, &[])),
+            "cust ext=m4a ffmpeg -hide_banner -loglevel error -y -f wav -i - -c:a alac %f"
+        );
+        assert_eq!(
+            output_format_spec(&output(OutputCodec::Opus, &["--vbr"])),
+            "cust ext=opus opusenc --quiet --bitrate 192 --vbr - %f"
+        );
+        assert_eq!(
+            output_format_spec(&output(OutputCodec::Mp3, &[])),
+            "cust ext=mp3 lame --quiet -b 192 - %f"
+        );
+    }
+
+    #[test]
+    fn uses_format_specific_decoders_for_lossless_images() {
+        assert_eq!(
+            decoder_args(&[PathBuf::from("album.ape")]),
+            vec!["-i", "ape mac %f - -d"]
+        );
+        assert_eq!(
+            decoder_args(&[PathBuf::from("album.wv")]),
+            vec!["-i", "wv wvunpack -q %f -o -"]
+        );
+        assert_eq!(
+            decoder_args(&[PathBuf::from("album.tta")]),
+            vec!["-i", "tta ttaenc -d -o - %f"]
+        );
+        assert!(decoder_args(&[PathBuf::from("album.wav")]).is_empty());
+        assert!(decoder_args(&[PathBuf::from("one.flac"), PathBuf::from("two.ape")]).is_empty());
+    }
+
     #[test]
     fn splitter_detects_tracks_without_matching_stderr() {
         let tmp = tempdir().unwrap();
@@ -774,7 +973,9 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        let result = splitter.split_cue_sync(&cue_path).unwrap();
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
 
         assert_eq!(result.status, SplitStatus::Split);
         assert_eq!(
@@ -796,7 +997,9 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        let err = splitter.split_cue_sync(&cue_path).unwrap_err();
+        let err = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap_err();
 
         assert!(err.to_string().contains("shnsplit generated 1 track(s)"));
         assert!(err.to_string().contains("cue contains 2 track(s)"));
@@ -816,13 +1019,157 @@ exit 0
         );
         let splitter = test_splitter(fake);
 
-        splitter.split_cue_sync(&cue_path).unwrap();
+        splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
         let args = fs::read_to_string(args_log).unwrap();
 
         assert!(args.contains("-i"));
         assert!(args.contains("flac flac -cd -s %f"));
     }
 
+    #[test]
+    fn splitter_passes_a_utf8_copy_of_legacy_encoded_cues() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            b"PERFORMER \"Bj\xf6rk\"\nTITLE \"Caf\xe9 del Mar\"\nFILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"D\xe9j\xe0 vu\"\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("album.flac"), "").unwrap();
+        let copy_log = tmp.path().join("copy.log");
+        let fake = write_fake_shnsplit(
+            tmp.path(),
+            &format!(
+                "cp \"$2\" \"{}\"\necho \"$2\" >> \"{}\"\ntouch \"Björk - Café del Mar - 01 - Déjà vu.flac\"\necho \"Splitting [album.flac] (0:01.00) --> [Björk - Café del Mar - 01 - Déjà vu.flac] (0:01.00) :\" >&2\nexit 0\n",
+                copy_log.with_extension("cue").display(),
+                copy_log.display()
+            ),
+        );
+        let splitter = test_splitter(fake);
+
+        let result = splitter
+            .split_cue_sync(&cue_path, cue_path.parent().unwrap())
+            .unwrap();
+
+        let copy_path = fs::read_to_string(&copy_log).unwrap();
+        assert!(!Path::new(copy_path.trim()).exists());
+        assert!(fs::read_to_string(copy_log.with_extension("cue"))
+            .unwrap()
+            .contains("TITLE \"Déjà vu\""));
+        assert_eq!(
+            result.tracks,
+            vec![tmp.path().join("Bj_rk - Caf_ del Mar - 01 - D_j_ vu.flac")]
+        );
+    }
+
+    #[test]
+    fn splitter_drops_or_keeps_the_hidden_pregap_track() {
+        let tmp = tempdir().unwrap();
+        let cue_path = write_hidden_track_fixture_album(tmp.path());
+        let fake = write_fake_shnsplit(
+            tmp.path(),
+            r#"touch "Artist - Album - 00 - pregap.flac" "Artist - Album - 01 - Track One.flac"
+echo "Splitting [album.flac] (0:01.00) --> [Artist - Album - 00 - pregap.flac] (0:00.15) :" >&2
+echo "Splitting [album.flac] (0:01.00) --> [Artist - Album - 01 - Track One.flac] (0:00.60) :" >&2
+exit 0
+"#,
+        );
+        let pregap = tmp.path().join("Artist - Album - 00 - pregap.flac");
+        let track = tmp.path().join("Artist - Album - 01 - Track One.flac");
+
+        let result = test_splitter(fake.clone())
+            .split_cue_sync(&cue_path, tmp.path())
+            .unwrap();
+        assert_eq!(result.tracks, vec![track.clone()]);
+        assert_eq!(result.hidden_track, None);
+        assert!(!pregap.exists());
+
+        let result = test_splitter_with_pregap(fake, PregapPolicy::TrackZero)
+            .split_cue_sync(&cue_path, tmp.path())
+            .unwrap();
+        assert_eq!(result.tracks, vec![track]);
+        assert_eq!(result.hidden_track, Some(pregap.clone()));
+        assert_eq!(result.pregap_policy, Some(PregapPolicy::TrackZero));
+        assert!(pregap.exists());
+    }
+
+    #[test]
+    fn splitter_prepends_pregaps_through_a_rewritten_cue() {
+        let tmp = tempdir().unwrap();
+        let cue_path = write_hidden_track_fixture_album(tmp.path());
+        let copy = tmp.path().join("copy.cue");
+        let fake = write_fake_shnsplit(
+            tmp.path(),
+            &format!(
+                "cp \"$2\" \"{}\"\ntouch \"Artist - Album - 01 - Track One.flac\"\necho \"Splitting [album.flac] (0:01.00) --> [Artist - Album - 01 - Track One.flac] (0:01.00) :\" >&2\nexit 0\n",
+                copy.display()
+            ),
+        );
+
+        let result = test_splitter_with_pregap(fake, PregapPolicy::PrependNext)
+            .split_cue_sync(&cue_path, tmp.path())
+            .unwrap();
+
+        assert!(fs::read_to_string(&copy)
+            .unwrap()
+            .contains("    INDEX 01 00:00:00\n"));
+        assert_eq!(result.tracks.len(), 1);
+        assert_eq!(result.hidden_track, None);
+    }
+
+    #[test]
+    fn splitter_skips_cue_sheets_with_one_file_per_track() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(
+            &cue_path,
+            "FILE \"01.flac\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\nFILE \"02.flac\" WAVE\n  TRACK 02 AUDIO\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("01.flac"), "").unwrap();
+        fs::write(tmp.path().join("02.flac"), "").unwrap();
+        let fake = write_fake_shnsplit(tmp.path(), "exit 1\n");
+
+        let result = test_splitter(fake)
+            .split_cue_sync(&cue_path, tmp.path())
+            .unwrap();
+
+        assert_eq!(result.status, SplitStatus::Skipped);
+        assert!(result.message.unwrap().contains("one FILE per track"));
+    }
+
+    #[test]
+    fn splitter_writes_into_requested_output_directory() {
+        let tmp = tempdir().unwrap();
+        let cue_path = write_fixture_album(tmp.path(), true);
+        let output_dir = tmp.path().join("staging").join("download-1").join("album");
+        let fake = write_fake_shnsplit(
+            tmp.path(),
+            r#"while [ $# -gt 0 ]; do
+  if [ "$1" = "-d" ]; then out="$2"; fi
+  shift
+done
+touch "$out/Artist - Album - 01 - Track One.flac"
+echo "Splitting [album.flac] (0:01.00) --> [$out/Artist - Album - 01 - Track One.flac] (0:01.00) :" >&2
+exit 0
+"#,
+        );
+        let splitter = test_splitter(fake);
+
+        let result = splitter.split_cue_sync(&cue_path, &output_dir).unwrap();
+
+        assert_eq!(
+            result.tracks,
+            vec![output_dir.join("Artist - Album - 01 - Track One.flac")]
+        );
+        assert!(!tmp
+            .path()
+            .join("Artist - Album - 01 - Track One.flac")
+            .exists());
+    }
+
     #[test]
     fn best_effort_snapshot_failure_does_not_block_stderr_track_detection() {
         let tmp = tempdir().unwrap();
@@ -835,7 +1182,30 @@ exit 0
     }
 
     fn test_splitter(shnsplit_path: PathBuf) -> ShnsplitCueSplitter {
-        ShnsplitCueSplitter::new(true, shnsplit_path, true, "%p - %a - %n - %t".into())
+        test_splitter_with_pregap(shnsplit_path, PregapPolicy::AppendPrevious)
+    }
+
+    fn test_splitter_with_pregap(
+        shnsplit_path: PathBuf,
+        pregap: PregapPolicy,
+    ) -> ShnsplitCueSplitter {
+        ShnsplitCueSplitter::new(
+            true,
+            shnsplit_path,
+            true,
+            "%p - %a - %n - %t".into(),
+            output(OutputCodec::Flac, &[]),
+            pregap,
+        )
+    }
+
+    fn output(codec: OutputCodec, encoder_args: &[&str]) -> OutputSettings {
+        OutputSettings {
+            codec,
+            compression_level: 8,
+            bitrate_kbps: 192,
+            encoder_args: encoder_args.iter().map(|arg| (*arg).to_owned()).collect(),
+        }
     }
 
     fn write_fixture_album(dir: &Path, with_audio: bool) -> PathBuf {
@@ -858,6 +1228,24 @@ FILE "album.flac" WAVE
         cue_path
     }
 
+    fn write_hidden_track_fixture_album(dir: &Path) -> PathBuf {
+        let cue_path = dir.join("album.cue");
+        fs::write(
+            &cue_path,
+            r#"PERFORMER "Artist"
+TITLE "Album"
+FILE "album.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Track One"
+    INDEX 00 00:00:00
+    INDEX 01 00:00:15
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("album.flac"), "").unwrap();
+        cue_path
+    }
+
     fn write_two_track_fixture_album(dir: &Path) -> PathBuf {
         let cue_path = dir.join("album.cue");
         fs::write(
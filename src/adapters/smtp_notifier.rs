@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::application::ports::Notifier;
+use crate::bootstrap::settings::{NotifierSettings, SmtpTls};
+use crate::domain::Notification;
+
+#[derive(Debug, Clone)]
+pub struct SmtpNotifier {
+    host: String,
+    port: u16,
+    tls: SmtpTls,
+    username: String,
+    password: String,
+    from: String,
+    to: Vec<String>,
+}
+
+impl SmtpNotifier {
+    pub fn new(settings: &NotifierSettings) -> Self {
+        Self {
+            host: settings.smtp_host.trim().to_owned(),
+            port: settings.smtp_port,
+            tls: settings.smtp_tls,
+            username: settings.smtp_username.clone(),
+            password: settings.smtp_password.clone(),
+            from: settings.from.trim().to_owned(),
+            to: settings.to.iter().map(|to| to.trim().to_owned()).collect(),
+        }
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let mut builder = match self.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)
+                    .with_context(|| format!("configure STARTTLS for {}", self.host))?
+            }
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)
+                .with_context(|| format!("configure TLS for {}", self.host))?,
+        }
+        .port(self.port);
+        if !self.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                self.username.clone(),
+                self.password.clone(),
+            ));
+        }
+        Ok(builder.build())
+    }
+
+    fn message(&self, notification: &Notification) -> Result<Message> {
+        let mut builder = Message::builder()
+            .from(parse_mailbox(&self.from)?)
+            .subject(format!("Splittarr: {}", notification.summary()))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(parse_mailbox(to)?);
+        }
+        builder
+            .body(format!(
+                "{}\n\nDownload: {}\nEvent: {}\n",
+                notification.message,
+                notification.download_id,
+                notification.event.as_str()
+            ))
+            .context("build notification email")
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        let message = self.message(notification)?;
+        self.transport()?
+            .send(message)
+            .await
+            .with_context(|| format!("send notification email via {}", self.host))?;
+        Ok(())
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+    address
+        .parse()
+        .with_context(|| format!("invalid email address: {address}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SmtpNotifier;
+    use crate::bootstrap::settings::{NotifierKind, NotifierSettings, SmtpTls};
+    use crate::domain::{Notification, NotificationEvent};
+
+    #[test]
+    fn message_addresses_every_recipient_with_summary_subject() {
+        let notifier = SmtpNotifier::new(&NotifierSettings {
+            kind: NotifierKind::Email,
+            name: "email".into(),
+            events: NotificationEvent::ALL.to_vec(),
+            url: String::new(),
+            smtp_host: "smtp.example.com".into(),
+            smtp_port: 587,
+            smtp_tls: SmtpTls::StartTls,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            from: "splittarr@example.com".into(),
+            to: vec!["me@example.com".into(), "you@example.com".into()],
+        });
+
+        let message = notifier
+            .message(&Notification {
+                event: NotificationEvent::Failed,
+                download_id: "ABC123".into(),
+                title: "Artist - Album".into(),
+                message: "no cue files found".into(),
+            })
+            .unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(formatted.contains("Subject: Splittarr: Artist - Album failed"));
+        assert!(formatted.contains("To: me@example.com, you@example.com"));
+        assert!(formatted.contains("no cue files found"));
+    }
+}
@@ -8,19 +8,29 @@ use rusqlite::{named_params, params, params_from_iter, Connection, OptionalExten
 use uuid::Uuid;
 
 use crate::application::ports::{
-    DownloadHistoryRow, DownloadReadStore, DownloadStats, DownloadStore,
+    DownloadActionQueue, DownloadHistoryRow, DownloadReadStore, DownloadStats, DownloadStore,
+    IgnoreRuleStore,
 };
 use crate::domain::{
-    CueSheet, CueSheetStatus, DownloadLifecycleState, GeneratedTrack, InputFile, InputFileKind,
-    RecordedTrack, TrackCleanupOutcome, TrackCleanupStatus, TrackedDownload,
+    CueFinding, CueFindingKind, CueFindingSeverity, CueRepair, CueRepairKind, CueSheet,
+    CueSheetStatus, DownloadAction, DownloadActionStatus, DownloadAuditEvent,
+    DownloadLifecycleState, FileFingerprint, GeneratedTrack, IgnoreRule, IgnoreRuleKind,
+    InProgressSplit, InputFile, InputFileKind, NotificationDelivery, NotificationDeliveryStatus,
+    NotificationEvent, OutputCodec, PregapPolicy, RecordedTrack, SourceVerification,
+    SourceVerificationStatus, TrackCleanupOutcome, TrackCleanupStatus, TrackTag, TrackedDownload,
 };
 
 #[derive(Debug, Clone)]
 pub struct SqliteDownloadStore {
     db_path: PathBuf,
+    lock_owner: String,
 }
 
 const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Download locks whose holder stopped refreshing them for this long belong to
+/// a process that was killed or crashed, and are taken over. Holders refresh
+/// every `download_lock::LOCK_HEARTBEAT`.
+const STALE_DOWNLOAD_LOCK: &str = "-2 minutes";
 
 impl SqliteDownloadStore {
     pub fn open(data_dir: impl AsRef<Path>) -> Result<Self> {
@@ -29,7 +39,10 @@ impl SqliteDownloadStore {
         let mut conn = Connection::open(&db_path)?;
         configure_connection(&conn)?;
         migrate(&mut conn)?;
-        Ok(Self { db_path })
+        Ok(Self {
+            db_path,
+            lock_owner: Uuid::new_v4().to_string(),
+        })
     }
 
     fn connect(&self) -> Result<Connection> {
@@ -44,7 +57,8 @@ impl SqliteDownloadStore {
             "SELECT download_id, title, status, output_path, tracked_download_state,
                     lifecycle_state, created_at, updated_at, first_seen_at, last_seen_in_queue_at,
                     processing_started_at, processing_finished_at, cleanup_started_at,
-                    cleanup_finished_at, completed_at, last_error
+                    cleanup_finished_at, completed_at, last_error, import_plan, attempt_count,
+                    next_retry_at
              FROM downloads
              ORDER BY updated_at DESC, download_id DESC",
         )?;
@@ -64,14 +78,15 @@ impl SqliteDownloadStore {
                     d.lifecycle_state, d.created_at, d.updated_at, d.first_seen_at,
                     d.last_seen_in_queue_at, d.processing_started_at, d.processing_finished_at,
                     d.cleanup_started_at, d.cleanup_finished_at, d.completed_at, d.last_error,
-                    COUNT(t.id) AS generated_track_count
+                    COUNT(t.id) AS generated_track_count, d.attempt_count, d.next_retry_at
              FROM downloads d
              LEFT JOIN cue_files c ON c.download_id = d.download_id
              LEFT JOIN tracks t ON t.cue_file_id = c.id
              GROUP BY d.download_id, d.title, d.status, d.output_path, d.tracked_download_state,
                       d.lifecycle_state, d.created_at, d.updated_at, d.first_seen_at,
                       d.last_seen_in_queue_at, d.processing_started_at, d.processing_finished_at,
-                      d.cleanup_started_at, d.cleanup_finished_at, d.completed_at, d.last_error
+                      d.cleanup_started_at, d.cleanup_finished_at, d.completed_at, d.last_error,
+                      d.attempt_count, d.next_retry_at
              ORDER BY d.updated_at DESC, d.download_id DESC",
         )?;
         let rows = stmt.query_map([], map_download_summary_row)?;
@@ -129,7 +144,8 @@ impl SqliteDownloadStore {
                 "SELECT download_id, title, status, output_path, tracked_download_state,
                     lifecycle_state, created_at, updated_at, first_seen_at, last_seen_in_queue_at,
                     processing_started_at, processing_finished_at, cleanup_started_at,
-                    cleanup_finished_at, completed_at, last_error
+                    cleanup_finished_at, completed_at, last_error, import_plan, attempt_count,
+                    next_retry_at
              FROM downloads
              WHERE download_id = ?",
                 [download_id],
@@ -153,7 +169,8 @@ impl SqliteDownloadStore {
             "SELECT download_id, title, status, output_path, tracked_download_state,
                     lifecycle_state, created_at, updated_at, first_seen_at, last_seen_in_queue_at,
                     processing_started_at, processing_finished_at, cleanup_started_at,
-                    cleanup_finished_at, completed_at, last_error
+                    cleanup_finished_at, completed_at, last_error, import_plan, attempt_count,
+                    next_retry_at
              FROM downloads
              WHERE download_id IN ({placeholders})"
         );
@@ -194,6 +211,9 @@ impl SqliteDownloadStore {
                 DownloadLifecycleState::Completed => stats.completed += count,
                 DownloadLifecycleState::Failed => stats.failed += count,
                 DownloadLifecycleState::AwaitingImport => stats.awaiting_import += count,
+                DownloadLifecycleState::Planned => stats.planned += count,
+                DownloadLifecycleState::Ignored => stats.ignored += count,
+                DownloadLifecycleState::GaveUp => stats.gave_up += count,
                 DownloadLifecycleState::Detected
                 | DownloadLifecycleState::Processing
                 | DownloadLifecycleState::CleaningUp => stats.in_progress += count,
@@ -242,6 +262,7 @@ impl SqliteDownloadStore {
              SET lifecycle_state = 'processing',
                  processing_started_at = COALESCE(processing_started_at, CURRENT_TIMESTAMP),
                  last_error = NULL,
+                 import_plan = NULL,
                  updated_at = CURRENT_TIMESTAMP
              WHERE download_id = ?",
             [download_id],
@@ -310,6 +331,323 @@ impl SqliteDownloadStore {
         Ok(())
     }
 
+    fn mark_download_planned_sync(
+        &self,
+        download_id: &str,
+        import_plan: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE downloads
+             SET lifecycle_state = 'planned',
+                 processing_finished_at = COALESCE(processing_finished_at, CURRENT_TIMESTAMP),
+                 import_plan = ?2,
+                 last_error = NULL,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?1",
+            params![download_id, import_plan],
+        )?;
+        Ok(())
+    }
+
+    fn mark_download_ignored_sync(&self, download_id: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE downloads
+             SET lifecycle_state = 'ignored',
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?",
+            [download_id],
+        )?;
+        Ok(())
+    }
+
+    fn schedule_download_retry_sync(
+        &self,
+        download_id: &str,
+        attempt_count: u32,
+        next_retry_at: &str,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE downloads
+             SET attempt_count = ?2,
+                 next_retry_at = ?3,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?1",
+            params![download_id, attempt_count, next_retry_at],
+        )?;
+        Ok(())
+    }
+
+    fn mark_download_gave_up_sync(&self, download_id: &str, attempt_count: u32) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE downloads
+             SET lifecycle_state = 'gave_up',
+                 attempt_count = ?2,
+                 next_retry_at = NULL,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?1",
+            params![download_id, attempt_count],
+        )?;
+        Ok(())
+    }
+
+    fn reset_download_attempts_sync(&self, download_id: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE downloads
+             SET attempt_count = 0,
+                 next_retry_at = NULL,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?",
+            [download_id],
+        )?;
+        Ok(())
+    }
+
+    fn try_lock_download_sync(&self, download_id: &str) -> Result<bool> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM download_locks
+             WHERE download_id = ?1 AND heartbeat_at < datetime('now', ?2)",
+            params![download_id, STALE_DOWNLOAD_LOCK],
+        )?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO download_locks (download_id, owner) VALUES (?1, ?2)",
+            params![download_id, self.lock_owner],
+        )?;
+        tx.commit()?;
+        Ok(inserted == 1)
+    }
+
+    fn refresh_download_lock_sync(&self, download_id: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE download_locks SET heartbeat_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?1 AND owner = ?2",
+            params![download_id, self.lock_owner],
+        )?;
+        Ok(())
+    }
+
+    fn unlock_download_sync(&self, download_id: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "DELETE FROM download_locks WHERE download_id = ?1 AND owner = ?2",
+            params![download_id, self.lock_owner],
+        )?;
+        Ok(())
+    }
+
+    fn release_download_locks_sync(&self) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "DELETE FROM download_locks WHERE owner = ?",
+            [&self.lock_owner],
+        )?;
+        Ok(())
+    }
+
+    fn record_split_started_sync(
+        &self,
+        download_id: &str,
+        output_dir: &str,
+        existing_files: &[String],
+    ) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM in_progress_splits WHERE download_id = ?1 AND output_dir = ?2",
+            params![download_id, output_dir],
+        )?;
+        tx.execute(
+            "INSERT INTO in_progress_splits (download_id, output_dir) VALUES (?1, ?2)",
+            params![download_id, output_dir],
+        )?;
+        let split_id = tx.last_insert_rowid();
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO in_progress_split_files (split_id, path) VALUES (?1, ?2)",
+            )?;
+            for path in existing_files {
+                stmt.execute(params![split_id, path])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn record_split_finished_sync(&self, download_id: &str, output_dir: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "DELETE FROM in_progress_splits WHERE download_id = ?1 AND output_dir = ?2",
+            params![download_id, output_dir],
+        )?;
+        Ok(())
+    }
+
+    fn load_in_progress_splits_sync(&self, download_id: &str) -> Result<Vec<InProgressSplit>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, download_id, output_dir, started_at
+             FROM in_progress_splits
+             WHERE download_id = ?
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map([download_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                InProgressSplit {
+                    download_id: row.get(1)?,
+                    output_dir: row.get(2)?,
+                    existing_files: Vec::new(),
+                    started_at: row.get(3)?,
+                },
+            ))
+        })?;
+
+        let mut splits = Vec::new();
+        for row in rows {
+            let (split_id, mut split) = row?;
+            let mut files = conn.prepare(
+                "SELECT path FROM in_progress_split_files WHERE split_id = ? ORDER BY path",
+            )?;
+            let paths = files.query_map([split_id], |row| row.get(0))?;
+            for path in paths {
+                split.existing_files.push(path?);
+            }
+            splits.push(split);
+        }
+        Ok(splits)
+    }
+
+    fn requeue_interrupted_download_sync(&self, download_id: &str) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE downloads
+             SET lifecycle_state = CASE lifecycle_state
+                     WHEN 'cleaning_up' THEN 'awaiting_import'
+                     ELSE 'detected'
+                 END,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE download_id = ? AND lifecycle_state IN ('processing', 'cleaning_up')",
+            [download_id],
+        )?;
+        tx.execute(
+            "DELETE FROM in_progress_splits WHERE download_id = ?",
+            [download_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_ignore_rules_sync(&self) -> Result<Vec<IgnoreRule>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, pattern, created_at
+             FROM ignore_rules
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], map_ignore_rule_row)?;
+
+        let mut rules = Vec::new();
+        for row in rows {
+            rules.extend(row?);
+        }
+        Ok(rules)
+    }
+
+    fn add_ignore_rule_sync(&self, kind: IgnoreRuleKind, pattern: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO ignore_rules (kind, pattern) VALUES (?1, ?2)",
+            params![kind.as_str(), pattern.trim()],
+        )?;
+        Ok(())
+    }
+
+    fn remove_ignore_rule_sync(&self, rule_id: i64) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute("DELETE FROM ignore_rules WHERE id = ?", [rule_id])?;
+        Ok(())
+    }
+
+    fn request_download_action_sync(
+        &self,
+        download_id: &str,
+        action: DownloadAction,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "INSERT INTO download_audit_events (download_id, action, status)
+             VALUES (?1, ?2, 'pending')",
+            params![download_id, action.as_str()],
+        )?;
+        Ok(())
+    }
+
+    fn load_pending_download_actions_sync(&self) -> Result<Vec<DownloadAuditEvent>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, download_id, action, status, message, requested_at, finished_at
+             FROM download_audit_events
+             WHERE status = 'pending'
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], map_audit_event_row)?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            events.extend(row?);
+        }
+        Ok(events)
+    }
+
+    fn finish_download_action_sync(
+        &self,
+        event_id: i64,
+        status: DownloadActionStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE download_audit_events
+             SET status = ?2,
+                 message = ?3,
+                 finished_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            params![event_id, download_action_status_to_db(status), message],
+        )?;
+        Ok(())
+    }
+
+    fn record_notification_delivery_sync(
+        &self,
+        download_id: &str,
+        notifier: &str,
+        event: NotificationEvent,
+        status: NotificationDeliveryStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "INSERT INTO notification_deliveries (download_id, notifier, event, status, message)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                download_id,
+                notifier,
+                event.as_str(),
+                notification_delivery_status_to_db(status),
+                message,
+            ],
+        )?;
+        Ok(())
+    }
+
     fn record_download_warning_sync(&self, download_id: &str, message: &str) -> Result<()> {
         let conn = self.connect()?;
         conn.execute(
@@ -348,15 +686,21 @@ impl SqliteDownloadStore {
         path: &Path,
         kind: InputFileKind,
         size_bytes: Option<i64>,
+        audio_format: Option<&str>,
     ) -> Result<()> {
         let conn = self.connect()?;
         conn.execute(
-            "INSERT INTO input_files (id, download_id, cue_file_id, path, kind, size_bytes, captured_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
+            "INSERT INTO input_files (id, download_id, cue_file_id, path, kind, size_bytes, audio_format, captured_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
              ON CONFLICT(download_id, path) DO UPDATE SET
                 cue_file_id = excluded.cue_file_id,
                 kind = excluded.kind,
-                size_bytes = excluded.size_bytes,
+                size_bytes = CASE WHEN input_files.fingerprinted_at IS NULL
+                    THEN excluded.size_bytes ELSE input_files.size_bytes END,
+                audio_format = excluded.audio_format,
+                verification_status = NULL,
+                verification_message = NULL,
+                verified_at = NULL,
                 captured_at = CURRENT_TIMESTAMP",
             params![
                 Uuid::new_v4().to_string(),
@@ -365,8 +709,166 @@ impl SqliteDownloadStore {
                 path.to_string_lossy().to_string(),
                 input_file_kind_to_db(kind),
                 size_bytes,
+                audio_format,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn record_input_fingerprint_sync(
+        &self,
+        download_id: &str,
+        path: &Path,
+        fingerprint: &FileFingerprint,
+    ) -> Result<FileFingerprint> {
+        let conn = self.connect()?;
+        let path = path.to_string_lossy().to_string();
+        let updated = conn.execute(
+            "UPDATE input_files
+             SET size_bytes = CASE WHEN fingerprinted_at IS NULL THEN ?3 ELSE size_bytes END,
+                 modified_ns = CASE WHEN fingerprinted_at IS NULL THEN ?4 ELSE modified_ns END,
+                 content_hash = CASE WHEN fingerprinted_at IS NULL THEN ?5 ELSE content_hash END,
+                 fingerprinted_at = COALESCE(fingerprinted_at, CURRENT_TIMESTAMP),
+                 verification_status = NULL,
+                 verification_message = NULL,
+                 verified_at = NULL
+             WHERE download_id = ?1 AND path = ?2",
+            params![
+                download_id,
+                &path,
+                fingerprint.size_bytes,
+                fingerprint.modified_ns,
+                fingerprint.content_hash,
+            ],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("no recorded input file for {path}"));
+        }
+        let recorded = conn.query_row(
+            "SELECT size_bytes, modified_ns, content_hash
+             FROM input_files
+             WHERE download_id = ?1 AND path = ?2",
+            params![download_id, &path],
+            |row| {
+                Ok(FileFingerprint {
+                    size_bytes: row.get(0)?,
+                    modified_ns: row.get(1)?,
+                    content_hash: row.get(2)?,
+                })
+            },
+        )?;
+        Ok(recorded)
+    }
+
+    fn clear_input_fingerprints_sync(&self, download_id: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE input_files SET fingerprinted_at = NULL WHERE download_id = ?",
+            [download_id],
+        )?;
+        Ok(())
+    }
+
+    fn record_input_verification_sync(
+        &self,
+        download_id: &str,
+        path: &Path,
+        status: SourceVerificationStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        let path = path.to_string_lossy().to_string();
+        let updated = conn.execute(
+            "UPDATE input_files
+             SET verification_status = ?3,
+                 verification_message = ?4,
+                 verified_at = CURRENT_TIMESTAMP
+             WHERE download_id = ?1 AND path = ?2",
+            params![
+                download_id,
+                &path,
+                source_verification_status_to_db(status),
+                message,
             ],
         )?;
+        if updated == 0 {
+            return Err(anyhow!("no recorded input file for {path}"));
+        }
+        Ok(())
+    }
+
+    fn record_cue_findings_sync(
+        &self,
+        cue_sheet: &CueSheet,
+        findings: &[CueFinding],
+    ) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM cue_findings WHERE cue_file_id = ?",
+            [&cue_sheet.id],
+        )?;
+        for (position, finding) in findings.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO cue_findings (cue_file_id, position, severity, kind, track, message)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &cue_sheet.id,
+                    position as i64,
+                    cue_finding_severity_to_db(finding.severity),
+                    cue_finding_kind_to_db(finding.kind),
+                    &finding.track,
+                    &finding.message,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn record_cue_encoding_sync(&self, cue_sheet: &CueSheet, encoding: &str) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE cue_files SET encoding = ?1 WHERE id = ?2",
+            params![encoding, &cue_sheet.id],
+        )?;
+        Ok(())
+    }
+
+    fn record_cue_pregap_policy_sync(
+        &self,
+        cue_sheet: &CueSheet,
+        pregap_policy: PregapPolicy,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        conn.execute(
+            "UPDATE cue_files SET pregap_policy = ?1 WHERE id = ?2",
+            params![pregap_policy.as_str(), &cue_sheet.id],
+        )?;
+        Ok(())
+    }
+
+    fn record_cue_repairs_sync(&self, cue_sheet: &CueSheet, repairs: &[CueRepair]) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM cue_repairs WHERE cue_file_id = ?",
+            [&cue_sheet.id],
+        )?;
+        for (position, repair) in repairs.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO cue_repairs (cue_file_id, position, kind, original, replacement)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    &cue_sheet.id,
+                    position as i64,
+                    cue_repair_kind_to_db(repair.kind),
+                    &repair.original,
+                    &repair.replacement,
+                ],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
@@ -395,12 +897,13 @@ impl SqliteDownloadStore {
         for track in tracks {
             tx.execute(
                 "INSERT INTO tracks (
-                    id, path, cue_file_id, download_id, size_bytes, cleanup_status, cleanup_message, deleted_at
+                    id, path, cue_file_id, download_id, size_bytes, codec, cleanup_status, cleanup_message, deleted_at
                  )
-                 VALUES (?1, ?2, ?3, ?4, ?5, 'pending', NULL, NULL)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', NULL, NULL)
                  ON CONFLICT(download_id, path) DO UPDATE SET
                     cue_file_id = excluded.cue_file_id,
                     size_bytes = excluded.size_bytes,
+                    codec = excluded.codec,
                     cleanup_status = excluded.cleanup_status,
                     cleanup_message = excluded.cleanup_message,
                     deleted_at = excluded.deleted_at",
@@ -410,6 +913,7 @@ impl SqliteDownloadStore {
                     &cue_sheet.id,
                     &cue_sheet.download_id,
                     track.size_bytes,
+                    track.codec.as_str(),
                 ],
             )?;
         }
@@ -418,6 +922,35 @@ impl SqliteDownloadStore {
         Ok(())
     }
 
+    fn record_track_tags_sync(
+        &self,
+        download_id: &str,
+        track_path: &Path,
+        tags: &[TrackTag],
+    ) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        let path = track_path.to_string_lossy().to_string();
+        let track_id = tx
+            .query_row(
+                "SELECT id FROM tracks WHERE download_id = ? AND path = ?",
+                params![download_id, &path],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow!("no recorded track for {path}"))?;
+        tx.execute("DELETE FROM track_tags WHERE track_id = ?", [&track_id])?;
+        for (position, tag) in tags.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO track_tags (track_id, position, name, value)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![&track_id, position as i64, &tag.name, &tag.value],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     fn record_track_cleanup_sync(
         &self,
         download_id: &str,
@@ -569,6 +1102,21 @@ impl DownloadStore for SqliteDownloadStore {
         .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
+    async fn mark_download_planned(
+        &self,
+        download_id: &str,
+        import_plan: Option<&str>,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let import_plan = import_plan.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            store.mark_download_planned_sync(&download_id, import_plan.as_deref())
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
     async fn record_download_warning(&self, download_id: &str, message: &str) -> Result<()> {
         let store = self.clone();
         let download_id = download_id.to_owned();
@@ -580,40 +1128,310 @@ impl DownloadStore for SqliteDownloadStore {
         .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
-    async fn get_or_create_cue_sheet(&self, download_id: &str, path: &Path) -> Result<CueSheet> {
+    async fn mark_download_ignored(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.mark_download_ignored_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn schedule_download_retry(
+        &self,
+        download_id: &str,
+        attempt_count: u32,
+        next_retry_at: &str,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let next_retry_at = next_retry_at.to_owned();
+        tokio::task::spawn_blocking(move || {
+            store.schedule_download_retry_sync(&download_id, attempt_count, &next_retry_at)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn mark_download_gave_up(&self, download_id: &str, attempt_count: u32) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            store.mark_download_gave_up_sync(&download_id, attempt_count)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn reset_download_attempts(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.reset_download_attempts_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn load_ignore_rules(&self) -> Result<Vec<IgnoreRule>> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.load_ignore_rules_sync())
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn try_lock_download(&self, download_id: &str) -> Result<bool> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.try_lock_download_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn refresh_download_lock(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.refresh_download_lock_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn unlock_download(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.unlock_download_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn release_download_locks(&self) -> Result<()> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.release_download_locks_sync())
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_split_started(
+        &self,
+        download_id: &str,
+        output_dir: &str,
+        existing_files: &[String],
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let output_dir = output_dir.to_owned();
+        let existing_files = existing_files.to_vec();
+        tokio::task::spawn_blocking(move || {
+            store.record_split_started_sync(&download_id, &output_dir, &existing_files)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_split_finished(&self, download_id: &str, output_dir: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let output_dir = output_dir.to_owned();
+        tokio::task::spawn_blocking(move || {
+            store.record_split_finished_sync(&download_id, &output_dir)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn load_in_progress_splits(&self, download_id: &str) -> Result<Vec<InProgressSplit>> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.load_in_progress_splits_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn requeue_interrupted_download(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.requeue_interrupted_download_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn add_ignore_rule(&self, kind: IgnoreRuleKind, pattern: &str) -> Result<()> {
+        let store = self.clone();
+        let pattern = pattern.to_owned();
+        tokio::task::spawn_blocking(move || store.add_ignore_rule_sync(kind, &pattern))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn load_pending_download_actions(&self) -> Result<Vec<DownloadAuditEvent>> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.load_pending_download_actions_sync())
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn finish_download_action(
+        &self,
+        event_id: i64,
+        status: DownloadActionStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let store = self.clone();
+        let message = message.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            store.finish_download_action_sync(event_id, status, message.as_deref())
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_notification_delivery(
+        &self,
+        download_id: &str,
+        notifier: &str,
+        event: NotificationEvent,
+        status: NotificationDeliveryStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let notifier = notifier.to_owned();
+        let message = message.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            store.record_notification_delivery_sync(
+                &download_id,
+                &notifier,
+                event,
+                status,
+                message.as_deref(),
+            )
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn get_or_create_cue_sheet(&self, download_id: &str, path: &Path) -> Result<CueSheet> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || store.get_or_create_cue_sheet_sync(&download_id, &path))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_input_file(
+        &self,
+        download_id: &str,
+        cue_sheet_id: Option<&str>,
+        path: &Path,
+        kind: InputFileKind,
+        size_bytes: Option<i64>,
+        audio_format: Option<&str>,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let cue_sheet_id = cue_sheet_id.map(str::to_owned);
+        let path = path.to_path_buf();
+        let audio_format = audio_format.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            store.record_input_file_sync(
+                &download_id,
+                cue_sheet_id.as_deref(),
+                &path,
+                kind,
+                size_bytes,
+                audio_format.as_deref(),
+            )
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_input_fingerprint(
+        &self,
+        download_id: &str,
+        path: &Path,
+        fingerprint: &FileFingerprint,
+    ) -> Result<FileFingerprint> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let path = path.to_path_buf();
+        let fingerprint = fingerprint.clone();
+        tokio::task::spawn_blocking(move || {
+            store.record_input_fingerprint_sync(&download_id, &path, &fingerprint)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn clear_input_fingerprints(&self, download_id: &str) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || store.clear_input_fingerprints_sync(&download_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_input_verification(
+        &self,
+        download_id: &str,
+        path: &Path,
+        status: SourceVerificationStatus,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let path = path.to_path_buf();
+        let message = message.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            store.record_input_verification_sync(&download_id, &path, status, message.as_deref())
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_cue_findings(
+        &self,
+        cue_sheet: &CueSheet,
+        findings: &[CueFinding],
+    ) -> Result<()> {
+        let store = self.clone();
+        let cue_sheet = cue_sheet.clone();
+        let findings = findings.to_vec();
+        tokio::task::spawn_blocking(move || store.record_cue_findings_sync(&cue_sheet, &findings))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn record_cue_encoding(&self, cue_sheet: &CueSheet, encoding: &str) -> Result<()> {
         let store = self.clone();
-        let download_id = download_id.to_owned();
-        let path = path.to_path_buf();
-        tokio::task::spawn_blocking(move || store.get_or_create_cue_sheet_sync(&download_id, &path))
+        let cue_sheet = cue_sheet.clone();
+        let encoding = encoding.to_owned();
+        tokio::task::spawn_blocking(move || store.record_cue_encoding_sync(&cue_sheet, &encoding))
             .await
             .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
-    async fn record_input_file(
+    async fn record_cue_pregap_policy(
         &self,
-        download_id: &str,
-        cue_sheet_id: Option<&str>,
-        path: &Path,
-        kind: InputFileKind,
-        size_bytes: Option<i64>,
+        cue_sheet: &CueSheet,
+        pregap_policy: PregapPolicy,
     ) -> Result<()> {
         let store = self.clone();
-        let download_id = download_id.to_owned();
-        let cue_sheet_id = cue_sheet_id.map(str::to_owned);
-        let path = path.to_path_buf();
+        let cue_sheet = cue_sheet.clone();
         tokio::task::spawn_blocking(move || {
-            store.record_input_file_sync(
-                &download_id,
-                cue_sheet_id.as_deref(),
-                &path,
-                kind,
-                size_bytes,
-            )
+            store.record_cue_pregap_policy_sync(&cue_sheet, pregap_policy)
         })
         .await
         .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
+    async fn record_cue_repairs(&self, cue_sheet: &CueSheet, repairs: &[CueRepair]) -> Result<()> {
+        let store = self.clone();
+        let cue_sheet = cue_sheet.clone();
+        let repairs = repairs.to_vec();
+        tokio::task::spawn_blocking(move || store.record_cue_repairs_sync(&cue_sheet, &repairs))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
     async fn record_cue_result(
         &self,
         cue_sheet: &CueSheet,
@@ -632,6 +1450,23 @@ impl DownloadStore for SqliteDownloadStore {
         .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
     }
 
+    async fn record_track_tags(
+        &self,
+        download_id: &str,
+        track_path: &Path,
+        tags: &[TrackTag],
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        let track_path = track_path.to_path_buf();
+        let tags = tags.to_vec();
+        tokio::task::spawn_blocking(move || {
+            store.record_track_tags_sync(&download_id, &track_path, &tags)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
     async fn record_track_cleanup(
         &self,
         download_id: &str,
@@ -699,6 +1534,48 @@ impl DownloadReadStore for SqliteDownloadStore {
     }
 }
 
+#[async_trait]
+impl DownloadActionQueue for SqliteDownloadStore {
+    async fn request_download_action(
+        &self,
+        download_id: &str,
+        action: DownloadAction,
+    ) -> Result<()> {
+        let store = self.clone();
+        let download_id = download_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            store.request_download_action_sync(&download_id, action)
+        })
+        .await
+        .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+}
+
+#[async_trait]
+impl IgnoreRuleStore for SqliteDownloadStore {
+    async fn load_ignore_rules(&self) -> Result<Vec<IgnoreRule>> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.load_ignore_rules_sync())
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn add_ignore_rule(&self, kind: IgnoreRuleKind, pattern: &str) -> Result<()> {
+        let store = self.clone();
+        let pattern = pattern.to_owned();
+        tokio::task::spawn_blocking(move || store.add_ignore_rule_sync(kind, &pattern))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+
+    async fn remove_ignore_rule(&self, rule_id: i64) -> Result<()> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.remove_ignore_rule_sync(rule_id))
+            .await
+            .map_err(|err| anyhow!("blocking task failed to join: {err}"))?
+    }
+}
+
 fn map_download_row(
     conn: &Connection,
     row: &rusqlite::Row<'_>,
@@ -706,6 +1583,8 @@ fn map_download_row(
     let download_id: String = row.get(0)?;
     let cue_sheets = cue_sheets_for(conn, &download_id)?;
     let generated_track_count = cue_sheets.iter().map(|cue| cue.tracks.len()).sum();
+    let audit_events = audit_events_for(conn, &download_id)?;
+    let notification_deliveries = notification_deliveries_for(conn, &download_id)?;
     Ok(TrackedDownload {
         input_files: input_files_for(conn, &download_id)?,
         cue_sheets,
@@ -726,6 +1605,11 @@ fn map_download_row(
         completed_at: row.get(14)?,
         generated_track_count,
         last_error: row.get(15)?,
+        import_plan: row.get(16)?,
+        audit_events,
+        notification_deliveries,
+        attempt_count: row.get(17)?,
+        next_retry_at: row.get(18)?,
     })
 }
 
@@ -764,6 +1648,11 @@ fn map_download_summary_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Tracked
         completed_at: row.get(14)?,
         generated_track_count: row.get::<_, i64>(16)? as usize,
         last_error: row.get(15)?,
+        import_plan: None,
+        audit_events: Vec::new(),
+        notification_deliveries: Vec::new(),
+        attempt_count: row.get(17)?,
+        next_retry_at: row.get(18)?,
     })
 }
 
@@ -775,6 +1664,9 @@ fn download_lifecycle_state_to_db(state: &DownloadLifecycleState) -> &'static st
         DownloadLifecycleState::CleaningUp => "cleaning_up",
         DownloadLifecycleState::Completed => "completed",
         DownloadLifecycleState::Failed => "failed",
+        DownloadLifecycleState::Planned => "planned",
+        DownloadLifecycleState::Ignored => "ignored",
+        DownloadLifecycleState::GaveUp => "gave_up",
     }
 }
 
@@ -785,6 +1677,9 @@ fn download_lifecycle_state_from_db(value: &str) -> DownloadLifecycleState {
         "cleaning_up" => DownloadLifecycleState::CleaningUp,
         "completed" => DownloadLifecycleState::Completed,
         "failed" => DownloadLifecycleState::Failed,
+        "planned" => DownloadLifecycleState::Planned,
+        "ignored" => DownloadLifecycleState::Ignored,
+        "gave_up" => DownloadLifecycleState::GaveUp,
         _ => DownloadLifecycleState::Detected,
     }
 }
@@ -795,6 +1690,7 @@ fn cue_sheet_status_to_db(status: CueSheetStatus) -> &'static str {
         CueSheetStatus::Split => "split",
         CueSheetStatus::Skipped => "skipped",
         CueSheetStatus::Failed => "failed",
+        CueSheetStatus::Planned => "planned",
     }
 }
 
@@ -803,24 +1699,131 @@ fn cue_sheet_status_from_db(value: &str) -> CueSheetStatus {
         "split" => CueSheetStatus::Split,
         "skipped" => CueSheetStatus::Skipped,
         "failed" => CueSheetStatus::Failed,
+        "planned" => CueSheetStatus::Planned,
         _ => CueSheetStatus::Pending,
     }
 }
 
+fn download_action_status_to_db(status: DownloadActionStatus) -> &'static str {
+    match status {
+        DownloadActionStatus::Pending => "pending",
+        DownloadActionStatus::Succeeded => "succeeded",
+        DownloadActionStatus::Failed => "failed",
+    }
+}
+
+fn download_action_status_from_db(value: &str) -> DownloadActionStatus {
+    match value {
+        "succeeded" => DownloadActionStatus::Succeeded,
+        "failed" => DownloadActionStatus::Failed,
+        _ => DownloadActionStatus::Pending,
+    }
+}
+
+fn notification_delivery_status_to_db(status: NotificationDeliveryStatus) -> &'static str {
+    match status {
+        NotificationDeliveryStatus::Delivered => "delivered",
+        NotificationDeliveryStatus::Failed => "failed",
+    }
+}
+
+fn notification_delivery_status_from_db(value: &str) -> NotificationDeliveryStatus {
+    match value {
+        "delivered" => NotificationDeliveryStatus::Delivered,
+        _ => NotificationDeliveryStatus::Failed,
+    }
+}
+
 fn input_file_kind_to_db(kind: InputFileKind) -> &'static str {
     match kind {
         InputFileKind::Cue => "cue",
         InputFileKind::Audio => "audio",
+        InputFileKind::Artwork => "artwork",
     }
 }
 
 fn input_file_kind_from_db(value: &str) -> InputFileKind {
     match value {
         "audio" => InputFileKind::Audio,
+        "artwork" => InputFileKind::Artwork,
         _ => InputFileKind::Cue,
     }
 }
 
+fn cue_finding_severity_to_db(severity: CueFindingSeverity) -> &'static str {
+    match severity {
+        CueFindingSeverity::Error => "error",
+        CueFindingSeverity::Warning => "warning",
+    }
+}
+
+fn cue_finding_severity_from_db(value: &str) -> CueFindingSeverity {
+    match value {
+        "error" => CueFindingSeverity::Error,
+        _ => CueFindingSeverity::Warning,
+    }
+}
+
+fn cue_finding_kind_to_db(kind: CueFindingKind) -> &'static str {
+    match kind {
+        CueFindingKind::Unparseable => "unparseable",
+        CueFindingKind::MissingFile => "missing_file",
+        CueFindingKind::EmptyFile => "empty_file",
+        CueFindingKind::UnreadableAudio => "unreadable_audio",
+        CueFindingKind::MissingIndex => "missing_index",
+        CueFindingKind::IndexOrder => "index_order",
+        CueFindingKind::ShortTrack => "short_track",
+        CueFindingKind::PastEndOfAudio => "past_end_of_audio",
+    }
+}
+
+fn cue_finding_kind_from_db(value: &str) -> CueFindingKind {
+    match value {
+        "unparseable" => CueFindingKind::Unparseable,
+        "missing_file" => CueFindingKind::MissingFile,
+        "empty_file" => CueFindingKind::EmptyFile,
+        "unreadable_audio" => CueFindingKind::UnreadableAudio,
+        "missing_index" => CueFindingKind::MissingIndex,
+        "short_track" => CueFindingKind::ShortTrack,
+        "past_end_of_audio" => CueFindingKind::PastEndOfAudio,
+        _ => CueFindingKind::IndexOrder,
+    }
+}
+
+fn cue_repair_kind_to_db(kind: CueRepairKind) -> &'static str {
+    match kind {
+        CueRepairKind::FileCase => "file_case",
+        CueRepairKind::FileExtension => "file_extension",
+        CueRepairKind::OnlyAudioFile => "only_audio_file",
+        CueRepairKind::Encoding => "encoding",
+    }
+}
+
+fn cue_repair_kind_from_db(value: &str) -> CueRepairKind {
+    match value {
+        "file_case" => CueRepairKind::FileCase,
+        "only_audio_file" => CueRepairKind::OnlyAudioFile,
+        "encoding" => CueRepairKind::Encoding,
+        _ => CueRepairKind::FileExtension,
+    }
+}
+
+fn source_verification_status_to_db(status: SourceVerificationStatus) -> &'static str {
+    match status {
+        SourceVerificationStatus::Unchanged => "unchanged",
+        SourceVerificationStatus::Changed => "changed",
+        SourceVerificationStatus::Missing => "missing",
+    }
+}
+
+fn source_verification_status_from_db(value: &str) -> SourceVerificationStatus {
+    match value {
+        "changed" => SourceVerificationStatus::Changed,
+        "missing" => SourceVerificationStatus::Missing,
+        _ => SourceVerificationStatus::Unchanged,
+    }
+}
+
 fn track_cleanup_status_to_db(status: TrackCleanupStatus) -> &'static str {
     match status {
         TrackCleanupStatus::Pending => "pending",
@@ -839,6 +1842,10 @@ fn track_cleanup_status_from_db(value: &str) -> TrackCleanupStatus {
     }
 }
 
+fn output_codec_from_db(value: &str) -> OutputCodec {
+    OutputCodec::parse(value).unwrap_or(OutputCodec::Flac)
+}
+
 fn lifecycle_state_from_row(
     row: &rusqlite::Row<'_>,
     index: usize,
@@ -852,7 +1859,7 @@ fn lifecycle_state_from_row(
 
 fn cue_sheets_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec<CueSheet>> {
     let mut stmt = conn.prepare(
-        "SELECT id, path, download_id, status, message, updated_at
+        "SELECT id, path, download_id, status, message, updated_at, encoding, pregap_policy
          FROM cue_files
          WHERE download_id = ?
          ORDER BY path",
@@ -861,12 +1868,19 @@ fn cue_sheets_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec<
         let id: String = row.get(0)?;
         Ok(CueSheet {
             tracks: tracks_for(conn, &id)?,
+            findings: cue_findings_for(conn, &id)?,
+            repairs: cue_repairs_for(conn, &id)?,
             id,
             path: row.get(1)?,
             download_id: row.get(2)?,
             status: cue_sheet_status_from_db(row.get::<_, String>(3)?.as_str()),
             message: row.get(4)?,
             updated_at: row.get(5)?,
+            encoding: row.get(6)?,
+            pregap_policy: row
+                .get::<_, Option<String>>(7)?
+                .as_deref()
+                .and_then(PregapPolicy::parse),
         })
     })?;
 
@@ -879,12 +1893,23 @@ fn cue_sheets_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec<
 
 fn input_files_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec<InputFile>> {
     let mut stmt = conn.prepare(
-        "SELECT id, download_id, cue_file_id, path, kind, size_bytes, captured_at
+        "SELECT id, download_id, cue_file_id, path, kind, size_bytes, audio_format,
+                modified_ns, content_hash, captured_at,
+                verification_status, verification_message, verified_at
          FROM input_files
          WHERE download_id = ?
          ORDER BY kind, path",
     )?;
     let rows = stmt.query_map([download_id], |row| {
+        let verification_status: Option<String> = row.get(10)?;
+        let verification = match verification_status {
+            Some(status) => Some(SourceVerification {
+                status: source_verification_status_from_db(&status),
+                message: row.get(11)?,
+                verified_at: row.get::<_, Option<String>>(12)?.unwrap_or_default(),
+            }),
+            None => None,
+        };
         Ok(InputFile {
             id: row.get(0)?,
             download_id: row.get(1)?,
@@ -892,7 +1917,11 @@ fn input_files_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec
             path: row.get(3)?,
             kind: input_file_kind_from_db(row.get::<_, String>(4)?.as_str()),
             size_bytes: row.get(5)?,
-            captured_at: row.get(6)?,
+            audio_format: row.get(6)?,
+            modified_ns: row.get(7)?,
+            content_hash: row.get(8)?,
+            captured_at: row.get(9)?,
+            verification,
         })
     })?;
 
@@ -903,13 +1932,89 @@ fn input_files_for(conn: &Connection, download_id: &str) -> rusqlite::Result<Vec
     Ok(files)
 }
 
+fn audit_events_for(
+    conn: &Connection,
+    download_id: &str,
+) -> rusqlite::Result<Vec<DownloadAuditEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, download_id, action, status, message, requested_at, finished_at
+         FROM download_audit_events
+         WHERE download_id = ?
+         ORDER BY id DESC",
+    )?;
+    let rows = stmt.query_map([download_id], map_audit_event_row)?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        events.extend(row?);
+    }
+    Ok(events)
+}
+
+fn map_audit_event_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Option<DownloadAuditEvent>> {
+    let Some(action) = DownloadAction::parse(&row.get::<_, String>(2)?) else {
+        return Ok(None);
+    };
+    Ok(Some(DownloadAuditEvent {
+        id: row.get(0)?,
+        download_id: row.get(1)?,
+        action,
+        status: download_action_status_from_db(row.get::<_, String>(3)?.as_str()),
+        message: row.get(4)?,
+        requested_at: row.get(5)?,
+        finished_at: row.get(6)?,
+    }))
+}
+
+fn notification_deliveries_for(
+    conn: &Connection,
+    download_id: &str,
+) -> rusqlite::Result<Vec<NotificationDelivery>> {
+    let mut stmt = conn.prepare(
+        "SELECT notifier, event, status, message, sent_at
+         FROM notification_deliveries
+         WHERE download_id = ?
+         ORDER BY id DESC",
+    )?;
+    let rows = stmt.query_map([download_id], |row| {
+        let Some(event) = NotificationEvent::parse(&row.get::<_, String>(1)?) else {
+            return Ok(None);
+        };
+        Ok(Some(NotificationDelivery {
+            notifier: row.get(0)?,
+            event,
+            status: notification_delivery_status_from_db(row.get::<_, String>(2)?.as_str()),
+            message: row.get(3)?,
+            sent_at: row.get(4)?,
+        }))
+    })?;
+
+    let mut deliveries = Vec::new();
+    for row in rows {
+        deliveries.extend(row?);
+    }
+    Ok(deliveries)
+}
+
+fn map_ignore_rule_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Option<IgnoreRule>> {
+    let Some(kind) = IgnoreRuleKind::parse(&row.get::<_, String>(1)?) else {
+        return Ok(None);
+    };
+    Ok(Some(IgnoreRule {
+        id: Some(row.get(0)?),
+        kind,
+        pattern: row.get(2)?,
+        created_at: row.get(3)?,
+    }))
+}
+
 fn cue_sheet_by_download_and_path(
     conn: &Connection,
     download_id: &str,
     path: &str,
 ) -> rusqlite::Result<Option<CueSheet>> {
     conn.query_row(
-        "SELECT id, path, download_id, status, message, updated_at
+        "SELECT id, path, download_id, status, message, updated_at, encoding, pregap_policy
          FROM cue_files
          WHERE download_id = ? AND path = ?",
         params![download_id, path],
@@ -917,12 +2022,19 @@ fn cue_sheet_by_download_and_path(
             let id: String = row.get(0)?;
             Ok(CueSheet {
                 tracks: tracks_for(conn, &id)?,
+                findings: cue_findings_for(conn, &id)?,
+                repairs: cue_repairs_for(conn, &id)?,
                 id,
                 path: row.get(1)?,
                 download_id: row.get(2)?,
                 status: cue_sheet_status_from_db(row.get::<_, String>(3)?.as_str()),
                 message: row.get(4)?,
                 updated_at: row.get(5)?,
+                encoding: row.get(6)?,
+                pregap_policy: row
+                    .get::<_, Option<String>>(7)?
+                    .as_deref()
+                    .and_then(PregapPolicy::parse),
             })
         },
     )
@@ -934,21 +2046,24 @@ fn tracks_for(
     cue_sheet_id: &str,
 ) -> Result<Vec<GeneratedTrack>, rusqlite::Error> {
     let mut stmt = conn.prepare(
-        "SELECT id, cue_file_id, download_id, path, size_bytes, cleanup_status, cleanup_message, deleted_at
+        "SELECT id, cue_file_id, download_id, path, size_bytes, codec, cleanup_status, cleanup_message, deleted_at
          FROM tracks
          WHERE cue_file_id = ?
          ORDER BY path",
     )?;
     let rows = stmt.query_map([cue_sheet_id], |row| {
+        let id: String = row.get(0)?;
         Ok(GeneratedTrack {
-            id: row.get(0)?,
+            tags: track_tags_for(conn, &id)?,
+            id,
             cue_sheet_id: row.get(1)?,
             download_id: row.get(2)?,
             path: row.get(3)?,
             size_bytes: row.get(4)?,
-            cleanup_status: track_cleanup_status_from_db(row.get::<_, String>(5)?.as_str()),
-            cleanup_message: row.get(6)?,
-            deleted_at: row.get(7)?,
+            codec: output_codec_from_db(row.get::<_, String>(5)?.as_str()),
+            cleanup_status: track_cleanup_status_from_db(row.get::<_, String>(6)?.as_str()),
+            cleanup_message: row.get(7)?,
+            deleted_at: row.get(8)?,
         })
     })?;
 
@@ -959,6 +2074,72 @@ fn tracks_for(
     Ok(tracks)
 }
 
+fn cue_findings_for(conn: &Connection, cue_file_id: &str) -> rusqlite::Result<Vec<CueFinding>> {
+    let mut stmt = conn.prepare(
+        "SELECT severity, kind, track, message
+         FROM cue_findings
+         WHERE cue_file_id = ?
+         ORDER BY position",
+    )?;
+    let rows = stmt.query_map([cue_file_id], |row| {
+        Ok(CueFinding {
+            severity: cue_finding_severity_from_db(row.get::<_, String>(0)?.as_str()),
+            kind: cue_finding_kind_from_db(row.get::<_, String>(1)?.as_str()),
+            track: row.get(2)?,
+            message: row.get(3)?,
+        })
+    })?;
+
+    let mut findings = Vec::new();
+    for row in rows {
+        findings.push(row?);
+    }
+    Ok(findings)
+}
+
+fn cue_repairs_for(conn: &Connection, cue_file_id: &str) -> rusqlite::Result<Vec<CueRepair>> {
+    let mut stmt = conn.prepare(
+        "SELECT kind, original, replacement
+         FROM cue_repairs
+         WHERE cue_file_id = ?
+         ORDER BY position",
+    )?;
+    let rows = stmt.query_map([cue_file_id], |row| {
+        Ok(CueRepair {
+            kind: cue_repair_kind_from_db(row.get::<_, String>(0)?.as_str()),
+            original: row.get(1)?,
+            replacement: row.get(2)?,
+        })
+    })?;
+
+    let mut repairs = Vec::new();
+    for row in rows {
+        repairs.push(row?);
+    }
+    Ok(repairs)
+}
+
+fn track_tags_for(conn: &Connection, track_id: &str) -> rusqlite::Result<Vec<TrackTag>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, value
+         FROM track_tags
+         WHERE track_id = ?
+         ORDER BY position",
+    )?;
+    let rows = stmt.query_map([track_id], |row| {
+        Ok(TrackTag {
+            name: row.get(0)?,
+            value: row.get(1)?,
+        })
+    })?;
+
+    let mut tags = Vec::new();
+    for row in rows {
+        tags.push(row?);
+    }
+    Ok(tags)
+}
+
 fn migrate(conn: &mut Connection) -> Result<()> {
     let tx = conn.transaction()?;
     tx.execute_batch(
@@ -999,7 +2180,14 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             path        TEXT NOT NULL,
             kind        TEXT NOT NULL,
             size_bytes  INTEGER,
+            audio_format TEXT,
+            modified_ns INTEGER,
+            content_hash TEXT,
             captured_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            verification_status TEXT,
+            verification_message TEXT,
+            verified_at TEXT,
+            fingerprinted_at TEXT,
             FOREIGN KEY(download_id) REFERENCES downloads(download_id) ON DELETE CASCADE,
             FOREIGN KEY(cue_file_id) REFERENCES cue_files(id) ON DELETE SET NULL
         );
@@ -1010,11 +2198,95 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             cue_file_id     TEXT NOT NULL,
             download_id     TEXT NOT NULL,
             size_bytes      INTEGER,
+            codec           TEXT NOT NULL DEFAULT 'flac',
             cleanup_status  TEXT NOT NULL DEFAULT 'pending',
             cleanup_message TEXT,
             deleted_at      TEXT,
             FOREIGN KEY(cue_file_id) REFERENCES cue_files(id) ON DELETE CASCADE,
             FOREIGN KEY(download_id) REFERENCES downloads(download_id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS track_tags (
+            track_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            name     TEXT NOT NULL,
+            value    TEXT NOT NULL,
+            PRIMARY KEY(track_id, position),
+            FOREIGN KEY(track_id) REFERENCES tracks(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS cue_findings (
+            cue_file_id TEXT NOT NULL,
+            position    INTEGER NOT NULL,
+            severity    TEXT NOT NULL,
+            kind        TEXT NOT NULL,
+            track       TEXT,
+            message     TEXT NOT NULL,
+            PRIMARY KEY(cue_file_id, position),
+            FOREIGN KEY(cue_file_id) REFERENCES cue_files(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS cue_repairs (
+            cue_file_id TEXT NOT NULL,
+            position    INTEGER NOT NULL,
+            kind        TEXT NOT NULL,
+            original    TEXT NOT NULL,
+            replacement TEXT NOT NULL,
+            PRIMARY KEY(cue_file_id, position),
+            FOREIGN KEY(cue_file_id) REFERENCES cue_files(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS download_audit_events (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            download_id  TEXT NOT NULL,
+            action       TEXT NOT NULL,
+            status       TEXT NOT NULL DEFAULT 'pending',
+            message      TEXT,
+            requested_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            finished_at  TEXT,
+            FOREIGN KEY(download_id) REFERENCES downloads(download_id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS notification_deliveries (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            download_id TEXT NOT NULL,
+            notifier    TEXT NOT NULL,
+            event       TEXT NOT NULL,
+            status      TEXT NOT NULL,
+            message     TEXT,
+            sent_at     TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(download_id) REFERENCES downloads(download_id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS ignore_rules (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind       TEXT NOT NULL,
+            pattern    TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(kind, pattern)
+        );
+
+        CREATE TABLE IF NOT EXISTS download_locks (
+            download_id TEXT PRIMARY KEY,
+            owner        TEXT NOT NULL,
+            locked_at    TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            heartbeat_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS in_progress_splits (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            download_id TEXT NOT NULL,
+            output_dir  TEXT NOT NULL,
+            started_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(download_id, output_dir),
+            FOREIGN KEY(download_id) REFERENCES downloads(download_id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS in_progress_split_files (
+            split_id INTEGER NOT NULL,
+            path     TEXT NOT NULL,
+            PRIMARY KEY(split_id, path),
+            FOREIGN KEY(split_id) REFERENCES in_progress_splits(id) ON DELETE CASCADE
         );",
     )?;
 
@@ -1102,6 +2374,18 @@ fn migrate(conn: &mut Connection) -> Result<()> {
         "updated_at",
         "ALTER TABLE cue_files ADD COLUMN updated_at TEXT NOT NULL DEFAULT '1970-01-01 00:00:00'",
     )?;
+    add_column_if_missing(
+        &tx,
+        "cue_files",
+        "encoding",
+        "ALTER TABLE cue_files ADD COLUMN encoding TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "cue_files",
+        "pregap_policy",
+        "ALTER TABLE cue_files ADD COLUMN pregap_policy TEXT",
+    )?;
     add_column_if_missing(
         &tx,
         "tracks",
@@ -1126,6 +2410,78 @@ fn migrate(conn: &mut Connection) -> Result<()> {
         "deleted_at",
         "ALTER TABLE tracks ADD COLUMN deleted_at TEXT",
     )?;
+    add_column_if_missing(
+        &tx,
+        "tracks",
+        "codec",
+        "ALTER TABLE tracks ADD COLUMN codec TEXT NOT NULL DEFAULT 'flac'",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "audio_format",
+        "ALTER TABLE input_files ADD COLUMN audio_format TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "modified_ns",
+        "ALTER TABLE input_files ADD COLUMN modified_ns INTEGER",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "content_hash",
+        "ALTER TABLE input_files ADD COLUMN content_hash TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "verification_status",
+        "ALTER TABLE input_files ADD COLUMN verification_status TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "verification_message",
+        "ALTER TABLE input_files ADD COLUMN verification_message TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "verified_at",
+        "ALTER TABLE input_files ADD COLUMN verified_at TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "input_files",
+        "fingerprinted_at",
+        "ALTER TABLE input_files ADD COLUMN fingerprinted_at TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "downloads",
+        "import_plan",
+        "ALTER TABLE downloads ADD COLUMN import_plan TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "downloads",
+        "attempt_count",
+        "ALTER TABLE downloads ADD COLUMN attempt_count INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "downloads",
+        "next_retry_at",
+        "ALTER TABLE downloads ADD COLUMN next_retry_at TEXT",
+    )?;
+    add_column_if_missing(
+        &tx,
+        "download_locks",
+        "heartbeat_at",
+        "ALTER TABLE download_locks ADD COLUMN heartbeat_at TEXT NOT NULL DEFAULT '1970-01-01 00:00:00'",
+    )?;
 
     tx.execute(
         "UPDATE downloads
@@ -1194,7 +2550,7 @@ fn migrate(conn: &mut Connection) -> Result<()> {
          ON tracks(download_id, path)",
         [],
     )?;
-    tx.pragma_update(None, "user_version", 2)?;
+    tx.pragma_update(None, "user_version", 16)?;
     tx.commit()?;
     Ok(())
 }
@@ -1237,8 +2593,11 @@ mod tests {
 
     use super::SqliteDownloadStore;
     use crate::domain::{
-        CueSheetStatus, DownloadLifecycleState, InputFileKind, RecordedTrack, TrackCleanupOutcome,
-        TrackCleanupStatus, TrackedDownload,
+        CueFinding, CueFindingKind, CueFindingSeverity, CueRepair, CueRepairKind, CueSheetStatus,
+        DownloadActionStatus, DownloadLifecycleState, FileFingerprint, IgnoreRuleKind,
+        InputFileKind, NotificationDeliveryStatus, NotificationEvent, OutputCodec, PregapPolicy,
+        RecordedTrack, SourceVerificationStatus, TrackCleanupOutcome, TrackCleanupStatus, TrackTag,
+        TrackedDownload,
     };
 
     #[test]
@@ -1287,11 +2646,40 @@ mod tests {
             super::download_lifecycle_state_from_db("failed"),
             DownloadLifecycleState::Failed
         );
+        assert_eq!(
+            super::download_lifecycle_state_to_db(&DownloadLifecycleState::Ignored),
+            "ignored"
+        );
+        assert_eq!(
+            super::download_lifecycle_state_from_db("ignored"),
+            DownloadLifecycleState::Ignored
+        );
+        assert_eq!(
+            super::download_lifecycle_state_to_db(&DownloadLifecycleState::GaveUp),
+            "gave_up"
+        );
+        assert_eq!(
+            super::download_lifecycle_state_from_db("gave_up"),
+            DownloadLifecycleState::GaveUp
+        );
         assert_eq!(
             super::download_lifecycle_state_from_db("unexpected"),
             DownloadLifecycleState::Detected
         );
 
+        assert_eq!(
+            super::download_action_status_to_db(DownloadActionStatus::Succeeded),
+            "succeeded"
+        );
+        assert_eq!(
+            super::download_action_status_from_db("failed"),
+            DownloadActionStatus::Failed
+        );
+        assert_eq!(
+            super::download_action_status_from_db("unexpected"),
+            DownloadActionStatus::Pending
+        );
+
         assert_eq!(
             super::cue_sheet_status_to_db(CueSheetStatus::Pending),
             "pending"
@@ -1396,6 +2784,16 @@ mod tests {
             Path::new("/downloads/album/album.cue"),
             InputFileKind::Cue,
             Some(123),
+            None,
+        )
+        .unwrap();
+        repo.record_input_file_sync(
+            &download.download_id,
+            Some(&cue.id),
+            Path::new("/downloads/album/album.ape"),
+            InputFileKind::Audio,
+            Some(789),
+            Some("APE"),
         )
         .unwrap();
         repo.record_cue_result_sync(
@@ -1403,8 +2801,9 @@ mod tests {
             CueSheetStatus::Split,
             None,
             &[RecordedTrack {
-                path: "/downloads/album/01.flac".into(),
+                path: "/downloads/album/01.opus".into(),
                 size_bytes: Some(456),
+                codec: OutputCodec::Opus,
             }],
         )
         .unwrap();
@@ -1425,16 +2824,230 @@ mod tests {
             downloads[0].lifecycle_state,
             DownloadLifecycleState::Completed
         );
-        assert_eq!(downloads[0].input_files.len(), 1);
+        assert_eq!(downloads[0].input_files.len(), 2);
+        assert_eq!(
+            downloads[0].input_files[0].audio_format.as_deref(),
+            Some("APE")
+        );
+        assert_eq!(downloads[0].input_files[1].audio_format, None);
         assert_eq!(downloads[0].cue_sheets.len(), 1);
         assert_eq!(downloads[0].cue_sheets[0].tracks.len(), 1);
         assert_eq!(downloads[0].cue_sheets[0].tracks[0].size_bytes, Some(456));
+        assert_eq!(
+            downloads[0].cue_sheets[0].tracks[0].codec,
+            OutputCodec::Opus
+        );
         assert_eq!(
             downloads[0].cue_sheets[0].tracks[0].cleanup_status,
             TrackCleanupStatus::Deleted
         );
     }
 
+    #[test]
+    fn first_input_fingerprints_and_verification_are_persisted() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        let audio_path = Path::new("/downloads/album/album.flac");
+
+        repo.upsert_tracked_download_sync(&download).unwrap();
+        repo.record_input_file_sync(
+            &download.download_id,
+            None,
+            audio_path,
+            InputFileKind::Audio,
+            Some(100),
+            Some("FLAC"),
+        )
+        .unwrap();
+        repo.record_input_fingerprint_sync(
+            &download.download_id,
+            audio_path,
+            &FileFingerprint {
+                size_bytes: 100,
+                modified_ns: Some(1_700_000_000_000_000_000),
+                content_hash: Some("abc".into()),
+            },
+        )
+        .unwrap();
+        repo.record_input_verification_sync(
+            &download.download_id,
+            audio_path,
+            SourceVerificationStatus::Changed,
+            Some("size changed from 100 to 120 bytes"),
+        )
+        .unwrap();
+
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        let input = &stored.input_files[0];
+        assert_eq!(input.modified_ns, Some(1_700_000_000_000_000_000));
+        assert_eq!(input.content_hash.as_deref(), Some("abc"));
+        let verification = input.verification.as_ref().unwrap();
+        assert_eq!(verification.status, SourceVerificationStatus::Changed);
+        assert_eq!(
+            verification.message.as_deref(),
+            Some("size changed from 100 to 120 bytes")
+        );
+        assert!(!verification.verified_at.is_empty());
+
+        repo.record_input_file_sync(
+            &download.download_id,
+            None,
+            audio_path,
+            InputFileKind::Audio,
+            Some(120),
+            Some("FLAC"),
+        )
+        .unwrap();
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.input_files[0].verification, None);
+        assert_eq!(stored.input_files[0].size_bytes, Some(100));
+        assert_eq!(stored.input_files[0].content_hash.as_deref(), Some("abc"));
+
+        let original = FileFingerprint {
+            size_bytes: 100,
+            modified_ns: Some(1_700_000_000_000_000_000),
+            content_hash: Some("abc".into()),
+        };
+        let changed = FileFingerprint {
+            size_bytes: 120,
+            modified_ns: Some(1_800_000_000_000_000_000),
+            content_hash: Some("def".into()),
+        };
+        assert_eq!(
+            repo.record_input_fingerprint_sync(&download.download_id, audio_path, &changed)
+                .unwrap(),
+            original
+        );
+        repo.clear_input_fingerprints_sync(&download.download_id)
+            .unwrap();
+        assert_eq!(
+            repo.record_input_fingerprint_sync(&download.download_id, audio_path, &changed)
+                .unwrap(),
+            changed
+        );
+        assert!(repo
+            .record_input_verification_sync(
+                &download.download_id,
+                Path::new("/downloads/album/missing.flac"),
+                SourceVerificationStatus::Missing,
+                None,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn cue_findings_replace_previous_findings_in_order() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        repo.upsert_tracked_download_sync(&download).unwrap();
+        let cue = repo
+            .get_or_create_cue_sheet_sync(
+                &download.download_id,
+                Path::new("/downloads/album/album.cue"),
+            )
+            .unwrap();
+        let missing = CueFinding {
+            severity: CueFindingSeverity::Error,
+            kind: CueFindingKind::MissingFile,
+            track: None,
+            message: "FILE \"album.wav\" does not exist".into(),
+        };
+        let short = CueFinding {
+            severity: CueFindingSeverity::Warning,
+            kind: CueFindingKind::ShortTrack,
+            track: Some("02".into()),
+            message: "is only 2.000 seconds long".into(),
+        };
+
+        repo.record_cue_findings_sync(&cue, std::slice::from_ref(&missing))
+            .unwrap();
+        repo.record_cue_findings_sync(&cue, &[short.clone(), missing.clone()])
+            .unwrap();
+
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.cue_sheets[0].findings, vec![short, missing]);
+    }
+
+    #[test]
+    fn cue_repairs_are_persisted_per_cue_sheet() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        repo.upsert_tracked_download_sync(&download).unwrap();
+        let cue = repo
+            .get_or_create_cue_sheet_sync(
+                &download.download_id,
+                Path::new("/downloads/album/album.cue"),
+            )
+            .unwrap();
+        let repairs = vec![
+            CueRepair {
+                kind: CueRepairKind::Encoding,
+                original: "windows-1252".into(),
+                replacement: "UTF-8".into(),
+            },
+            CueRepair {
+                kind: CueRepairKind::FileExtension,
+                original: "album.wav".into(),
+                replacement: "album.flac".into(),
+            },
+        ];
+
+        repo.record_cue_repairs_sync(&cue, &repairs).unwrap();
+        repo.record_cue_encoding_sync(&cue, "windows-1252").unwrap();
+        repo.record_cue_pregap_policy_sync(&cue, PregapPolicy::TrackZero)
+            .unwrap();
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.cue_sheets[0].repairs, repairs);
+        assert_eq!(
+            stored.cue_sheets[0].encoding.as_deref(),
+            Some("windows-1252")
+        );
+        assert_eq!(
+            stored.cue_sheets[0].pregap_policy,
+            Some(PregapPolicy::TrackZero)
+        );
+
+        repo.record_cue_repairs_sync(&cue, &[]).unwrap();
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert!(stored.cue_sheets[0].repairs.is_empty());
+    }
+
     #[test]
     fn awaiting_import_preserves_first_processing_finished_timestamp() {
         let tmp = tempdir().unwrap();
@@ -1500,6 +3113,7 @@ mod tests {
             &[RecordedTrack {
                 path: "/downloads/album/01.flac".into(),
                 size_bytes: Some(456),
+                codec: OutputCodec::Flac,
             }],
         )
         .unwrap();
@@ -1524,6 +3138,7 @@ mod tests {
             &[RecordedTrack {
                 path: "/downloads/album/01.flac".into(),
                 size_bytes: Some(789),
+                codec: OutputCodec::Flac,
             }],
         )
         .unwrap();
@@ -1539,6 +3154,59 @@ mod tests {
         assert_eq!(track.deleted_at, None);
     }
 
+    #[test]
+    fn record_track_tags_replaces_previous_tags_in_order() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        let track_path = Path::new("/downloads/album/01.flac");
+
+        repo.upsert_tracked_download_sync(&download).unwrap();
+        let cue = repo
+            .get_or_create_cue_sheet_sync(
+                &download.download_id,
+                Path::new("/downloads/album/album.cue"),
+            )
+            .unwrap();
+        repo.record_cue_result_sync(
+            &cue,
+            CueSheetStatus::Split,
+            None,
+            &[RecordedTrack {
+                path: track_path.to_string_lossy().to_string(),
+                size_bytes: Some(456),
+                codec: OutputCodec::Flac,
+            }],
+        )
+        .unwrap();
+        repo.record_track_tags_sync("download-1", track_path, &[tag("TITLE", "Old")])
+            .unwrap();
+        repo.record_track_tags_sync(
+            "download-1",
+            track_path,
+            &[tag("TITLE", "One"), tag("ARTIST", "Artist")],
+        )
+        .unwrap();
+
+        let stored = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.cue_sheets[0].tracks[0].tags,
+            vec![tag("TITLE", "One"), tag("ARTIST", "Artist")]
+        );
+        assert!(repo
+            .record_track_tags_sync("download-1", Path::new("/missing.flac"), &[])
+            .is_err());
+    }
+
     #[test]
     fn load_download_row_fetches_only_requested_history_row() {
         let tmp = tempdir().unwrap();
@@ -1575,6 +3243,7 @@ mod tests {
             &[RecordedTrack {
                 path: "/downloads/album/01.flac".into(),
                 size_bytes: Some(456),
+                codec: OutputCodec::Flac,
             }],
         )
         .unwrap();
@@ -1586,6 +3255,203 @@ mod tests {
         assert_eq!(repo.load_download_row_sync("missing").unwrap(), None);
     }
 
+    #[test]
+    fn ignore_rules_are_deduplicated_and_removable() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+
+        repo.add_ignore_rule_sync(IgnoreRuleKind::DownloadId, "download-1")
+            .unwrap();
+        repo.add_ignore_rule_sync(IgnoreRuleKind::DownloadId, " download-1 ")
+            .unwrap();
+        repo.add_ignore_rule_sync(IgnoreRuleKind::PathGlob, "/downloads/podcasts/**")
+            .unwrap();
+
+        let rules = repo.load_ignore_rules_sync().unwrap();
+        assert_eq!(
+            rules
+                .iter()
+                .map(|rule| (rule.kind, rule.pattern.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (IgnoreRuleKind::DownloadId, "download-1"),
+                (IgnoreRuleKind::PathGlob, "/downloads/podcasts/**"),
+            ]
+        );
+        assert!(rules.iter().all(|rule| rule.created_at.is_some()));
+
+        repo.remove_ignore_rule_sync(rules[0].id.unwrap()).unwrap();
+        assert_eq!(repo.load_ignore_rules_sync().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn notification_deliveries_are_listed_newest_first() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        repo.upsert_tracked_download_sync(&TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importPending".into(),
+        ))
+        .unwrap();
+
+        repo.record_notification_delivery_sync(
+            "download-1",
+            "discord",
+            NotificationEvent::Failed,
+            NotificationDeliveryStatus::Delivered,
+            None,
+        )
+        .unwrap();
+        repo.record_notification_delivery_sync(
+            "download-1",
+            "email",
+            NotificationEvent::Failed,
+            NotificationDeliveryStatus::Failed,
+            Some("connection refused"),
+        )
+        .unwrap();
+
+        let download = repo
+            .get_tracked_download_sync("download-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            download
+                .notification_deliveries
+                .iter()
+                .map(|delivery| (
+                    delivery.notifier.as_str(),
+                    delivery.event,
+                    delivery.status,
+                    delivery.message.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "email",
+                    NotificationEvent::Failed,
+                    NotificationDeliveryStatus::Failed,
+                    Some("connection refused")
+                ),
+                (
+                    "discord",
+                    NotificationEvent::Failed,
+                    NotificationDeliveryStatus::Delivered,
+                    None
+                ),
+            ]
+        );
+        assert!(download
+            .notification_deliveries
+            .iter()
+            .all(|delivery| !delivery.sent_at.is_empty()));
+    }
+
+    #[test]
+    fn download_locks_are_exclusive_until_released_or_no_longer_refreshed() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let other_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+
+        assert!(repo.try_lock_download_sync("download-1").unwrap());
+        assert!(!repo.try_lock_download_sync("download-1").unwrap());
+        assert!(!other_process.try_lock_download_sync("download-1").unwrap());
+        assert!(other_process.try_lock_download_sync("download-2").unwrap());
+
+        other_process.unlock_download_sync("download-1").unwrap();
+        assert!(!other_process.try_lock_download_sync("download-1").unwrap());
+        repo.unlock_download_sync("download-1").unwrap();
+        assert!(other_process.try_lock_download_sync("download-1").unwrap());
+
+        repo.connect()
+            .unwrap()
+            .execute(
+                "UPDATE download_locks SET heartbeat_at = datetime('now', '-3 minutes')
+                 WHERE download_id IN ('download-1', 'download-2')",
+                [],
+            )
+            .unwrap();
+        other_process
+            .refresh_download_lock_sync("download-1")
+            .unwrap();
+        assert!(!repo.try_lock_download_sync("download-1").unwrap());
+        assert!(repo.try_lock_download_sync("download-2").unwrap());
+
+        repo.release_download_locks_sync().unwrap();
+        assert!(other_process.try_lock_download_sync("download-2").unwrap());
+        assert!(!other_process.try_lock_download_sync("download-1").unwrap());
+    }
+
+    #[test]
+    fn interrupted_downloads_are_requeued_and_forget_their_in_progress_splits() {
+        let tmp = tempdir().unwrap();
+        let repo = SqliteDownloadStore::open(tmp.path()).unwrap();
+        for download_id in ["processing", "cleaning-up"] {
+            repo.upsert_tracked_download_sync(&TrackedDownload::pending(
+                download_id.into(),
+                download_id.into(),
+                "completed".into(),
+                format!("/downloads/{download_id}"),
+                "importFailed".into(),
+            ))
+            .unwrap();
+        }
+        repo.mark_download_processing_sync("processing").unwrap();
+        repo.mark_download_cleanup_started_sync("cleaning-up")
+            .unwrap();
+
+        repo.record_split_started_sync(
+            "processing",
+            "/downloads/processing/cd1",
+            &["/downloads/processing/cd1/image.flac".to_owned()],
+        )
+        .unwrap();
+        repo.record_split_started_sync("processing", "/downloads/processing/cd2", &[])
+            .unwrap();
+        repo.record_split_finished_sync("processing", "/downloads/processing/cd2")
+            .unwrap();
+        repo.record_split_started_sync(
+            "processing",
+            "/downloads/processing/cd1",
+            &[
+                "/downloads/processing/cd1/image.flac".to_owned(),
+                "/downloads/processing/cd1/image.cue".to_owned(),
+            ],
+        )
+        .unwrap();
+
+        let splits = repo.load_in_progress_splits_sync("processing").unwrap();
+        assert_eq!(splits.len(), 1);
+        assert_eq!(splits[0].output_dir, "/downloads/processing/cd1");
+        assert_eq!(
+            splits[0].existing_files,
+            vec![
+                "/downloads/processing/cd1/image.cue",
+                "/downloads/processing/cd1/image.flac",
+            ]
+        );
+
+        repo.requeue_interrupted_download_sync("processing")
+            .unwrap();
+        repo.requeue_interrupted_download_sync("cleaning-up")
+            .unwrap();
+        assert!(repo
+            .load_in_progress_splits_sync("processing")
+            .unwrap()
+            .is_empty());
+        let state = |download_id| {
+            repo.get_tracked_download_sync(download_id)
+                .unwrap()
+                .unwrap()
+                .lifecycle_state
+        };
+        assert_eq!(state("processing"), DownloadLifecycleState::Detected);
+        assert_eq!(state("cleaning-up"), DownloadLifecycleState::AwaitingImport);
+    }
+
     #[test]
     fn bulk_get_tracked_downloads_preserves_requested_order() {
         let tmp = tempdir().unwrap();
@@ -1665,10 +3531,12 @@ mod tests {
                 RecordedTrack {
                     path: "/downloads/album/01.flac".into(),
                     size_bytes: Some(111),
+                    codec: OutputCodec::Flac,
                 },
                 RecordedTrack {
                     path: "/downloads/album/02.flac".into(),
                     size_bytes: Some(222),
+                    codec: OutputCodec::Flac,
                 },
             ],
         )
@@ -1788,4 +3656,11 @@ mod tests {
         assert_eq!(done.lifecycle_state, DownloadLifecycleState::AwaitingImport);
         assert_eq!(bad.lifecycle_state, DownloadLifecycleState::Failed);
     }
+
+    fn tag(name: &str, value: &str) -> TrackTag {
+        TrackTag {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
 }
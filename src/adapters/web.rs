@@ -1,15 +1,30 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::{Html, IntoResponse, Response},
-    routing::get,
+    body::Bytes,
+    extract::{Form, Path, Query, State},
+    http::{header, Extensions, HeaderMap, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
     Router,
 };
 use maud::{html, Markup, PreEscaped, DOCTYPE};
+use serde::Deserialize;
+use tracing::{error, info};
 
-use crate::application::ports::{DownloadHistoryRow, DownloadReadStore, DownloadStats};
+use crate::adapters::audio_format::{AudioFormatCapability, FormatSupport};
+use crate::adapters::prometheus;
+use crate::adapters::web_api;
+use crate::adapters::web_auth::{self, LoginMethod, WebAuth};
+use crate::application::ignore_list::validate_ignore_rule;
+use crate::application::metrics::Metrics;
+use crate::application::ports::{
+    DownloadActionQueue, DownloadHistoryRow, DownloadReadStore, DownloadStats, IgnoreRuleStore,
+};
+use crate::application::service::QueueCheckRequests;
 use crate::domain::{
-    CueSheet, CueSheetStatus, DownloadLifecycleState, GeneratedTrack, InputFile, InputFileKind,
+    CueFindingSeverity, CueSheet, CueSheetStatus, DownloadAction, DownloadActionStatus,
+    DownloadAuditEvent, DownloadLifecycleState, GeneratedTrack, IgnoreRule, IgnoreRuleKind,
+    InputFile, InputFileKind, NotificationDeliveryStatus, SourceVerificationStatus,
     TrackCleanupStatus, TrackedDownload,
 };
 
@@ -17,7 +32,16 @@ use crate::domain::{
 pub struct StatusConfig {
     pub version: &'static str,
     pub data_dir: String,
+    pub staging_dir: Option<String>,
     pub check_frequency_seconds: u64,
+    pub max_concurrent_downloads: usize,
+    pub webhook_enabled: bool,
+    pub api_key_enabled: bool,
+    pub login_method: Option<LoginMethod>,
+    pub retry_max_attempts: u32,
+    pub retry_initial_delay_seconds: u64,
+    pub retry_max_delay_seconds: u64,
+    pub dry_run: bool,
     pub download_log_enabled: bool,
     pub lidarr_url: String,
     pub manual_import_enabled: bool,
@@ -28,39 +52,214 @@ pub struct StatusConfig {
     pub gnudb_enabled: bool,
     pub gnudb_server: String,
     pub cue_strict: bool,
+    pub splitter_backend: String,
     pub shnsplit_path: String,
     pub shnsplit_overwrite: bool,
     pub shnsplit_format: String,
+    pub audio_formats: Vec<AudioFormatCapability>,
+    pub ignore_rules: Vec<IgnoreRule>,
+}
+
+#[derive(Clone)]
+pub struct LidarrWebhook {
+    pub username: String,
+    pub password: String,
+}
+
+impl LidarrWebhook {
+    fn accepts(&self, username: &str, password: &str) -> bool {
+        web_auth::secrets_match(&self.username, username)
+            && web_auth::secrets_match(&self.password, password)
+    }
 }
 
 #[derive(Clone)]
 struct WebState<S> {
     store: S,
     status: StatusConfig,
+    webhook: Option<LidarrWebhook>,
+    auth: WebAuth,
+    metrics: Metrics,
+    queue_checks: QueueCheckRequests,
 }
 
-pub fn router<S>(store: S, status: StatusConfig) -> Router
+pub fn router<S>(
+    store: S,
+    status: StatusConfig,
+    webhook: Option<LidarrWebhook>,
+    auth: WebAuth,
+    metrics: Metrics,
+    queue_checks: QueueCheckRequests,
+) -> Router
 where
-    S: DownloadReadStore + Clone + Send + Sync + 'static,
+    S: DownloadReadStore + DownloadActionQueue + IgnoreRuleStore + Clone + Send + Sync + 'static,
 {
     Router::new()
         .route("/", get(index::<S>))
         .route("/status", get(status_page::<S>))
         .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics_route::<S>))
+        .route("/login", get(login_page::<S>).post(log_in::<S>))
+        .route("/logout", post(log_out::<S>))
         .route("/downloads/{download_id}", get(download_detail::<S>))
         .route(
             "/downloads/{download_id}/content",
             get(download_detail_content::<S>),
         )
+        .route(
+            "/downloads/{download_id}/actions/{action}",
+            post(download_action::<S>),
+        )
         .route("/downloads/{download_id}/row", get(download_row_route::<S>))
         .route("/downloads/rows", get(download_rows_route::<S>))
-        .with_state(WebState { store, status })
+        .route("/ignore-rules", post(add_ignore_rule::<S>))
+        .route(
+            "/ignore-rules/{rule_id}/delete",
+            post(remove_ignore_rule::<S>),
+        )
+        .route("/webhooks/lidarr", post(lidarr_webhook::<S>))
+        .with_state(WebState {
+            store: store.clone(),
+            status,
+            webhook,
+            auth: auth.clone(),
+            metrics,
+            queue_checks: queue_checks.clone(),
+        })
+        .nest("/api/v1", web_api::router(store, queue_checks))
+        .layer(middleware::from_fn_with_state(auth, web_auth::require_auth))
+        .layer(middleware::from_fn(web_auth::reject_cross_site))
 }
 
 async fn healthz() -> impl IntoResponse {
     (StatusCode::OK, "ok")
 }
 
+async fn metrics_route<S>(State(state): State<WebState<S>>) -> Response
+where
+    S: DownloadReadStore,
+{
+    match state.store.load_download_rows().await {
+        Ok(rows) => (
+            [(header::CONTENT_TYPE, prometheus::CONTENT_TYPE)],
+            prometheus::render_metrics(&state.metrics.snapshot(), &rows),
+        )
+            .into_response(),
+        Err(error) => {
+            error!("failed to load downloads for metrics: {error:#}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginQuery {
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+    next: Option<String>,
+}
+
+async fn login_page<S>(
+    State(state): State<WebState<S>>,
+    Query(query): Query<LoginQuery>,
+) -> Response {
+    if !state.auth.forms_login() {
+        return (StatusCode::NOT_FOUND, "login is disabled").into_response();
+    }
+    Html(login_content(query.next.as_deref(), None)).into_response()
+}
+
+async fn log_in<S>(
+    State(state): State<WebState<S>>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Form(form): Form<LoginForm>,
+) -> Response {
+    if !state.auth.forms_login() {
+        return (StatusCode::NOT_FOUND, "login is disabled").into_response();
+    }
+    let client = state.auth.client_address(&headers, &extensions);
+    if let Some(retry_after) = state.auth.locked_out(client) {
+        return web_auth::too_many_attempts(retry_after);
+    }
+    let Some(token) = state.auth.log_in(&form.username, &form.password) else {
+        state.auth.record_failure(client);
+        return (
+            StatusCode::UNAUTHORIZED,
+            Html(login_content(
+                form.next.as_deref(),
+                Some("Wrong username or password."),
+            )),
+        )
+            .into_response();
+    };
+    state.auth.record_success(client);
+    let next = form
+        .next
+        .as_deref()
+        .filter(|next| next.starts_with('/') && !next.starts_with("//") && !next.contains('\\'))
+        .unwrap_or("/");
+    (
+        [(
+            header::SET_COOKIE,
+            format!(
+                "{}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+                web_auth::SESSION_COOKIE,
+                web_auth::SESSION_LIFETIME.as_secs()
+            ),
+        )],
+        Redirect::to(next),
+    )
+        .into_response()
+}
+
+async fn log_out<S>(State(state): State<WebState<S>>, headers: HeaderMap) -> Response {
+    state.auth.log_out(&headers);
+    (
+        [(
+            header::SET_COOKIE,
+            format!(
+                "{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0",
+                web_auth::SESSION_COOKIE
+            ),
+        )],
+        Redirect::to("/login"),
+    )
+        .into_response()
+}
+
+fn login_content(next: Option<&str>, error: Option<&str>) -> String {
+    page(
+        "Splittarr — Log in",
+        html! {
+            h1 { "Splittarr" }
+            section class="panel" {
+                h2 { "Log in" }
+                @if let Some(error) = error {
+                    p class="error-block" { (error) }
+                }
+                form method="post" action="/login" {
+                    @if let Some(next) = next {
+                        input type="hidden" name="next" value=(next);
+                    }
+                    p {
+                        label { "Username " input type="text" name="username" autocomplete="username" required; }
+                    }
+                    p {
+                        label { "Password " input type="password" name="password" autocomplete="current-password" required; }
+                    }
+                    button type="submit" { "Log in" }
+                }
+            }
+        },
+    )
+}
+
 async fn status_page<S>(State(state): State<WebState<S>>) -> Response
 where
     S: DownloadReadStore,
@@ -69,8 +268,13 @@ where
         Ok(stats) => Html(page(
             "Splittarr — Status",
             html! {
-                nav {
+                nav class="actions" {
                     a href="/" { "Download History" }
+                    @if state.status.login_method == Some(LoginMethod::Forms) {
+                        form method="post" action="/logout" {
+                            button type="submit" { "Log out" }
+                        }
+                    }
                 }
                 h1 { "Splittarr" }
                 (status_content(&state.status, &stats))
@@ -78,7 +282,7 @@ where
         ))
         .into_response(),
         Err(error) => {
-            eprintln!("failed to load status: {error:#}");
+            error!("failed to load status: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
@@ -86,46 +290,86 @@ where
 
 async fn index<S>(State(state): State<WebState<S>>) -> Response
 where
-    S: DownloadReadStore,
+    S: DownloadReadStore + IgnoreRuleStore,
 {
-    match state.store.load_download_rows().await {
-        Ok(downloads) => Html(page(
-            "Splittarr",
-            html! {
-                nav {
-                    a href="/status" { "Status" }
-                }
-                h1 { "Splittarr" }
-                section class="panel" {
-                    h2 { "Download History" }
-                    p id="downloads-empty" class="muted" hidden[!downloads.is_empty()] {
-                        "No downloads have been tracked yet."
+    let loaded = async {
+        let rows = state.store.load_download_rows().await?;
+        let stored_rules = state.store.load_ignore_rules().await?;
+        anyhow::Ok((rows, stored_rules))
+    };
+    match loaded.await {
+        Ok((rows, stored_rules)) => {
+            let (ignored, downloads): (Vec<_>, Vec<_>) = rows
+                .into_iter()
+                .partition(|row| row.lifecycle_state == DownloadLifecycleState::Ignored);
+            let ignore_rules = state
+                .status
+                .ignore_rules
+                .iter()
+                .chain(&stored_rules)
+                .collect::<Vec<_>>();
+            Html(page(
+                "Splittarr",
+                html! {
+                    nav {
+                        a href="/status" { "Status" }
                     }
-                    table id="downloads-table" hidden[downloads.is_empty()] {
-                        thead {
-                            tr {
-                                th { "Download" }
-                                th { "Lifecycle" }
-                                th { "Lidarr" }
-                                th { "Output Path" }
-                                th { "Tracks" }
-                                th { "Updated" }
-                                th { "Completed" }
+                    h1 { "Splittarr" }
+                    section class="panel" {
+                        h2 { "Download History" }
+                        p id="downloads-empty" class="muted" hidden[!downloads.is_empty()] {
+                            "No downloads have been tracked yet."
+                        }
+                        table id="downloads-table" hidden[downloads.is_empty()] {
+                            thead {
+                                tr {
+                                    th { "Download" }
+                                    th { "Lifecycle" }
+                                    th { "Lidarr" }
+                                    th { "Output Path" }
+                                    th { "Tracks" }
+                                    th { "Updated" }
+                                    th { "Completed" }
+                                }
+                            }
+                            tbody id="downloads-rows" {
+                                @for download in &downloads {
+                                    (download_row(download))
+                                }
                             }
                         }
-                        tbody id="downloads-rows" {
-                            @for download in &downloads {
-                                (download_row(download))
+                    }
+                    @if !ignored.is_empty() {
+                        section class="panel" {
+                            h2 { "Ignored Downloads" }
+                            table {
+                                thead {
+                                    tr {
+                                        th { "Download" }
+                                        th { "Lifecycle" }
+                                        th { "Lidarr" }
+                                        th { "Output Path" }
+                                        th { "Tracks" }
+                                        th { "Updated" }
+                                        th { "Completed" }
+                                    }
+                                }
+                                tbody {
+                                    @for download in &ignored {
+                                        (download_row(download))
+                                    }
+                                }
                             }
                         }
                     }
-                }
-                script { (PreEscaped(HISTORY_SCRIPT)) }
-            },
-        ))
-        .into_response(),
+                    (ignore_list_panel(&ignore_rules))
+                    script { (PreEscaped(HISTORY_SCRIPT)) }
+                },
+            ))
+            .into_response()
+        }
         Err(error) => {
-            eprintln!("failed to load downloads: {error:#}");
+            error!("failed to load downloads: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
@@ -142,7 +386,7 @@ where
         Ok(Some(download)) => download_row(&download).into_response(),
         Ok(None) => (StatusCode::NOT_FOUND, "download not found").into_response(),
         Err(error) => {
-            eprintln!("failed to load download row: {error:#}");
+            error!("failed to load download row: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
@@ -153,9 +397,12 @@ where
     S: DownloadReadStore,
 {
     match state.store.load_download_rows().await {
-        Ok(downloads) => download_rows(&downloads).into_response(),
+        Ok(mut downloads) => {
+            downloads.retain(|row| row.lifecycle_state != DownloadLifecycleState::Ignored);
+            download_rows(&downloads).into_response()
+        }
         Err(error) => {
-            eprintln!("failed to load download rows: {error:#}");
+            error!("failed to load download rows: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
@@ -182,7 +429,7 @@ where
         .into_response(),
         Ok(None) => (StatusCode::NOT_FOUND, "download not found").into_response(),
         Err(error) => {
-            eprintln!("failed to load download detail: {error:#}");
+            error!("failed to load download detail: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
@@ -199,12 +446,163 @@ where
         Ok(Some(download)) => download_content(&download).into_response(),
         Ok(None) => (StatusCode::NOT_FOUND, "download not found").into_response(),
         Err(error) => {
-            eprintln!("failed to load download detail content: {error:#}");
+            error!("failed to load download detail content: {error:#}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
+    }
+}
+
+async fn download_action<S>(
+    State(state): State<WebState<S>>,
+    Path((download_id, action)): Path<(String, String)>,
+) -> Response
+where
+    S: DownloadReadStore + DownloadActionQueue,
+{
+    let Some(action) = DownloadAction::parse(&action) else {
+        return (StatusCode::NOT_FOUND, "unknown action").into_response();
+    };
+    match state.store.get_tracked_download(&download_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, "download not found").into_response(),
+        Err(error) => {
+            error!("failed to load download for action: {error:#}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response();
+        }
+    }
+    match state
+        .store
+        .request_download_action(&download_id, action)
+        .await
+    {
+        Ok(()) => {
+            state.queue_checks.request();
+            Redirect::to(&format!("/downloads/{download_id}")).into_response()
+        }
+        Err(error) => {
+            error!("failed to request download action: {error:#}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IgnoreRuleForm {
+    kind: String,
+    pattern: String,
+}
+
+async fn add_ignore_rule<S>(
+    State(state): State<WebState<S>>,
+    Form(form): Form<IgnoreRuleForm>,
+) -> Response
+where
+    S: IgnoreRuleStore,
+{
+    let Some(kind) = IgnoreRuleKind::parse(&form.kind) else {
+        return (StatusCode::BAD_REQUEST, "unknown ignore rule kind").into_response();
+    };
+    if let Err(error) = validate_ignore_rule(kind, &form.pattern) {
+        return (StatusCode::BAD_REQUEST, error.to_string()).into_response();
+    }
+    match state.store.add_ignore_rule(kind, &form.pattern).await {
+        Ok(()) => Redirect::to("/").into_response(),
+        Err(error) => {
+            error!("failed to add ignore rule: {error:#}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
+    }
+}
+
+async fn remove_ignore_rule<S>(
+    State(state): State<WebState<S>>,
+    Path(rule_id): Path<i64>,
+) -> Response
+where
+    S: IgnoreRuleStore,
+{
+    match state.store.remove_ignore_rule(rule_id).await {
+        Ok(()) => Redirect::to("/").into_response(),
+        Err(error) => {
+            error!("failed to remove ignore rule: {error:#}");
             (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
         }
     }
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LidarrWebhookPayload {
+    event_type: String,
+    #[serde(default)]
+    download_id: Option<String>,
+}
+
+/// Lidarr events after which the queue may hold a new failed import.
+const QUEUE_CHECK_EVENTS: [&str; 4] = [
+    "Download",
+    "ImportFailure",
+    "DownloadFailure",
+    "ManualInteractionRequired",
+];
+
+async fn lidarr_webhook<S>(
+    State(state): State<WebState<S>>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(webhook) = &state.webhook else {
+        return (StatusCode::NOT_FOUND, "webhook is disabled").into_response();
+    };
+    // The webhook is left open by the auth middleware, so it counts failed
+    // attempts itself.
+    let client = state.auth.client_address(&headers, &extensions);
+    if let Some(retry_after) = state.auth.locked_out(client) {
+        return web_auth::too_many_attempts(retry_after);
+    }
+    match web_auth::basic_credentials(&headers) {
+        Some((username, password)) if webhook.accepts(&username, &password) => {
+            state.auth.record_success(client);
+        }
+        presented => {
+            if presented.is_some() {
+                state.auth.record_failure(client);
+            }
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Basic realm=\"Splittarr\"")],
+                "unauthorized",
+            )
+                .into_response();
+        }
+    }
+    let payload = match serde_json::from_slice::<LidarrWebhookPayload>(&body) {
+        Ok(payload) => payload,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("invalid webhook payload: {error}"),
+            )
+                .into_response()
+        }
+    };
+    if !QUEUE_CHECK_EVENTS.contains(&payload.event_type.as_str()) {
+        return (StatusCode::OK, "ok").into_response();
+    }
+
+    info!(
+        "Lidarr sent {} for {}, checking the queue now",
+        payload.event_type,
+        payload
+            .download_id
+            .as_deref()
+            .unwrap_or("an unknown download")
+    );
+    state.queue_checks.request();
+    (StatusCode::ACCEPTED, "queue check requested").into_response()
+}
+
 fn page(title: &str, body: Markup) -> String {
     html! {
         (DOCTYPE)
@@ -236,6 +634,33 @@ fn status_content(config: &StatusConfig, stats: &DownloadStats) -> Markup {
                     strong { "Check frequency" }
                     span { (config.check_frequency_seconds) " s" }
                 }
+                div {
+                    strong { "Concurrent downloads" }
+                    span { (config.max_concurrent_downloads) }
+                }
+                div {
+                    strong { "Lidarr webhook" }
+                    (feature_badge(config.webhook_enabled))
+                }
+                div {
+                    strong { "Web UI login" }
+                    span { (config.login_method.map_or("none", LoginMethod::as_str)) }
+                }
+                div {
+                    strong { "API key" }
+                    (feature_badge(config.api_key_enabled))
+                }
+                div {
+                    strong { "Retries" }
+                    span {
+                        (config.retry_max_attempts) " attempts, "
+                        (config.retry_initial_delay_seconds) "–" (config.retry_max_delay_seconds) " s apart"
+                    }
+                }
+                div {
+                    strong { "Dry run" }
+                    (feature_badge(config.dry_run))
+                }
                 div {
                     strong { "Download logging" }
                     (feature_badge(config.download_log_enabled))
@@ -244,6 +669,14 @@ fn status_content(config: &StatusConfig, stats: &DownloadStats) -> Markup {
                     strong { "Data directory" }
                     span class="path" { (&config.data_dir) }
                 }
+                div class="wide" {
+                    strong { "Staging directory" }
+                    @if let Some(staging_dir) = &config.staging_dir {
+                        span class="path" { (staging_dir) }
+                    } @else {
+                        span class="muted" { "download folder" }
+                    }
+                }
             }
         }
         section class="panel" {
@@ -294,8 +727,12 @@ fn status_content(config: &StatusConfig, stats: &DownloadStats) -> Markup {
             }
         }
         section class="panel" {
-            h2 { "shnsplit" }
+            h2 { "Splitter" }
             div class="grid" {
+                div {
+                    strong { "Backend" }
+                    code { (&config.splitter_backend) }
+                }
                 div {
                     strong { "Overwrite" }
                     (feature_badge(config.shnsplit_overwrite))
@@ -309,8 +746,31 @@ fn status_content(config: &StatusConfig, stats: &DownloadStats) -> Markup {
                     code { (&config.shnsplit_format) }
                 }
                 div class="wide" {
-                    strong { "Path" }
-                    span class="path" { (&config.shnsplit_path) }
+                    strong { "shnsplit path" }
+                    span class=(if config.splitter_backend == "shnsplit" { "path" } else { "path muted" }) { (&config.shnsplit_path) }
+                }
+            }
+        }
+        section class="panel" {
+            h2 { "Audio Formats" }
+            table {
+                thead {
+                    tr {
+                        th { "Format" }
+                        th { "shnsplit" }
+                        th { "native" }
+                        th { "Disc ID length" }
+                    }
+                }
+                tbody {
+                    @for capability in &config.audio_formats {
+                        tr {
+                            td { (capability.format.label()) }
+                            td { (format_support(&capability.shnsplit)) }
+                            td { (format_support(&capability.native)) }
+                            td { (format_support(&capability.disc_id_length)) }
+                        }
+                    }
                 }
             }
         }
@@ -333,6 +793,18 @@ fn status_content(config: &StatusConfig, stats: &DownloadStats) -> Markup {
                     strong { "Awaiting import" }
                     span class=(if stats.awaiting_import > 0 { "status status-warn" } else { "status" }) { (stats.awaiting_import) }
                 }
+                div {
+                    strong { "Planned" }
+                    span class=(if stats.planned > 0 { "status status-warn" } else { "status" }) { (stats.planned) }
+                }
+                div {
+                    strong { "Gave up" }
+                    span class=(if stats.gave_up > 0 { "status status-error" } else { "status" }) { (stats.gave_up) }
+                }
+                div {
+                    strong { "Ignored" }
+                    span class="status" { (stats.ignored) }
+                }
                 div {
                     strong { "In progress" }
                     span class=(if stats.in_progress > 0 { "status status-active" } else { "status" }) { (stats.in_progress) }
@@ -350,6 +822,20 @@ fn feature_badge(enabled: bool) -> Markup {
     }
 }
 
+fn format_support(support: &FormatSupport) -> Markup {
+    match support {
+        FormatSupport::Builtin => html! { span class="status status-ok" { "built-in" } },
+        FormatSupport::External {
+            program,
+            available: true,
+        } => html! { span class="status status-ok" { "via " code { (program) } } },
+        FormatSupport::External {
+            program,
+            available: false,
+        } => html! { span class="status status-error" { "missing " code { (program) } } },
+    }
+}
+
 fn download_row(download: &DownloadHistoryRow) -> Markup {
     html! {
         tr id=(format!("download-row-{}", download.download_id)) data-download-id=(&download.download_id) {
@@ -391,8 +877,11 @@ fn download_content(download: &TrackedDownload) -> Markup {
             div { strong { "Cleanup started" } span { (download.cleanup_started_at.as_deref().unwrap_or("-")) } }
             div { strong { "Cleanup finished" } span { (download.cleanup_finished_at.as_deref().unwrap_or("-")) } }
             div { strong { "Completed at" } span { (download.completed_at.as_deref().unwrap_or("-")) } }
+            div { strong { "Failed attempts" } span { (download.attempt_count) } }
+            div { strong { "Next retry" } span { (download.next_retry_at.as_deref().unwrap_or("-")) } }
             div class="wide" { strong { "Output path" } span class="path" { (&download.output_path) } }
         }
+        (actions_panel(download))
         section class="panel" {
             h2 { "Last Error" }
             @if let Some(error) = &download.last_error {
@@ -401,6 +890,13 @@ fn download_content(download: &TrackedDownload) -> Markup {
                 p class="muted" { "No error recorded." }
             }
         }
+        @if let Some(import_plan) = &download.import_plan {
+            section class="panel" {
+                h2 { "Import Plan" }
+                p class="muted" { "Dry run: nothing was split, and nothing was sent to Lidarr." }
+                pre { (import_plan) }
+            }
+        }
         section class="panel" {
             h2 { "Input Files" }
             @if download.input_files.is_empty() {
@@ -411,8 +907,10 @@ fn download_content(download: &TrackedDownload) -> Markup {
                         tr {
                             th { "Kind" }
                             th { "Path" }
+                            th { "Format" }
                             th { "Size" }
                             th { "Captured" }
+                            th { "Verification" }
                         }
                     }
                     tbody {
@@ -442,7 +940,9 @@ fn download_content(download: &TrackedDownload) -> Markup {
                     thead {
                         tr {
                             th { "Path" }
+                            th { "Codec" }
                             th { "Size" }
+                            th { "Tags" }
                             th { "Cleanup" }
                             th { "Deleted At" }
                         }
@@ -457,6 +957,178 @@ fn download_content(download: &TrackedDownload) -> Markup {
                 }
             }
         }
+        @if !download.notification_deliveries.is_empty() {
+            section class="panel" {
+                h2 { "Notifications" }
+                table {
+                    thead {
+                        tr {
+                            th { "Sent" }
+                            th { "Notifier" }
+                            th { "Event" }
+                            th { "Status" }
+                            th { "Error" }
+                        }
+                    }
+                    tbody {
+                        @for delivery in &download.notification_deliveries {
+                            tr {
+                                td { (&delivery.sent_at) }
+                                td { (&delivery.notifier) }
+                                td { (delivery.event.as_str()) }
+                                td { span class=(delivery_status_class(delivery.status)) { (delivery.status.as_str()) } }
+                                td {
+                                    @if let Some(message) = &delivery.message {
+                                        (message)
+                                    } @else {
+                                        span class="muted" { "-" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const DOWNLOAD_ACTIONS: [(DownloadAction, &str); 4] = [
+    (
+        DownloadAction::Retry,
+        "Scan, split and import this download again.",
+    ),
+    (
+        DownloadAction::Reimport,
+        "Send the recorded tracks to Lidarr again without splitting.",
+    ),
+    (
+        DownloadAction::ForceCleanup,
+        "Delete the generated tracks now, even if Lidarr still lists the download.",
+    ),
+    (
+        DownloadAction::Ignore,
+        "Stop processing and cleaning up this download.",
+    ),
+];
+
+fn actions_panel(download: &TrackedDownload) -> Markup {
+    html! {
+        section class="panel" {
+            h2 { "Actions" }
+            div class="actions" {
+                @for (action, description) in DOWNLOAD_ACTIONS {
+                    form method="post" action=(format!("/downloads/{}/actions/{}", download.download_id, action.as_str()))
+                        onsubmit=[(action == DownloadAction::ForceCleanup).then_some("return confirm('Delete the generated tracks of this download?')")] {
+                        button type="submit" title=(description) { (action_label(action)) }
+                    }
+                }
+            }
+            p class="muted" { "Actions run on the next queue check." }
+            @if !download.audit_events.is_empty() {
+                table {
+                    thead {
+                        tr {
+                            th { "Requested" }
+                            th { "Action" }
+                            th { "Status" }
+                            th { "Finished" }
+                            th { "Result" }
+                        }
+                    }
+                    tbody {
+                        @for event in &download.audit_events {
+                            (audit_event_row(event))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn audit_event_row(event: &DownloadAuditEvent) -> Markup {
+    html! {
+        tr {
+            td { (&event.requested_at) }
+            td { (action_label(event.action)) }
+            td { span class=(action_status_class(event.status)) { (action_status_label(event.status)) } }
+            td { (event.finished_at.as_deref().unwrap_or("-")) }
+            td {
+                @if let Some(message) = &event.message {
+                    @if let Some((summary, _)) = message.split_once('\n') {
+                        details {
+                            summary { (summary) }
+                            pre { (message) }
+                        }
+                    } @else {
+                        (message)
+                    }
+                } @else {
+                    span class="muted" { "-" }
+                }
+            }
+        }
+    }
+}
+
+const IGNORE_RULE_KINDS: [IgnoreRuleKind; 3] = [
+    IgnoreRuleKind::DownloadId,
+    IgnoreRuleKind::TitleRegex,
+    IgnoreRuleKind::PathGlob,
+];
+
+fn ignore_list_panel(rules: &[&IgnoreRule]) -> Markup {
+    html! {
+        section class="panel" {
+            h2 { "Ignore List" }
+            p class="muted" {
+                "Matching downloads are never split, imported or cleaned up. Rules from the config file can only be changed there."
+            }
+            @if !rules.is_empty() {
+                table {
+                    thead {
+                        tr {
+                            th { "Kind" }
+                            th { "Pattern" }
+                            th { "Source" }
+                            th { "Added" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        @for rule in rules {
+                            tr {
+                                td { (ignore_rule_kind_label(rule.kind)) }
+                                td class="path" { (&rule.pattern) }
+                                @if let Some(rule_id) = rule.id {
+                                    td { "web UI" }
+                                    td { (rule.created_at.as_deref().unwrap_or("-")) }
+                                    td {
+                                        form method="post" action=(format!("/ignore-rules/{rule_id}/delete")) {
+                                            button type="submit" { "Remove" }
+                                        }
+                                    }
+                                } @else {
+                                    td { "config" }
+                                    td { "-" }
+                                    td {}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            form class="actions" method="post" action="/ignore-rules" {
+                select name="kind" {
+                    @for kind in IGNORE_RULE_KINDS {
+                        option value=(kind.as_str()) { (ignore_rule_kind_label(kind)) }
+                    }
+                }
+                input type="text" name="pattern" required placeholder="Download ID, title regex or path glob";
+                button type="submit" { "Add Rule" }
+            }
+        }
     }
 }
 
@@ -465,8 +1137,20 @@ fn input_row(input: &InputFile) -> Markup {
         tr {
             td { (input_kind_label(input.kind)) }
             td class="path" { (&input.path) }
+            td { (input.audio_format.as_deref().unwrap_or("-")) }
             td { (format_size(input.size_bytes)) }
             td { (&input.captured_at) }
+            td {
+                @if let Some(verification) = &input.verification {
+                    span class=(verification_class(verification.status)) { (verification_label(verification.status)) }
+                    @if let Some(message) = &verification.message {
+                        div class="muted" { (message) }
+                    }
+                    div class="muted" { (&verification.verified_at) }
+                } @else {
+                    span class="muted" { "-" }
+                }
+            }
         }
     }
 }
@@ -479,10 +1163,46 @@ fn cue_card(cue: &CueSheet) -> Markup {
                 span class=(cue_status_class(cue.status)) { (cue_status_label(cue.status)) }
                 " "
                 span class="muted" { (&cue.updated_at) }
+                @if let Some(encoding) = &cue.encoding {
+                    " "
+                    span class="muted" { "Encoding: " (encoding) }
+                }
+                @if let Some(pregap_policy) = cue.pregap_policy {
+                    " "
+                    span class="muted" { "Pregap: " (pregap_policy.as_str()) }
+                }
             }
             @if let Some(message) = &cue.message {
                 pre { (message) }
             }
+            @if !cue.repairs.is_empty() {
+                p { strong { "Repaired CUE" } }
+                ul {
+                    @for repair in &cue.repairs {
+                        li class="path" { (repair.describe()) }
+                    }
+                }
+            }
+            @if !cue.findings.is_empty() {
+                table {
+                    thead {
+                        tr {
+                            th { "Severity" }
+                            th { "Track" }
+                            th { "Finding" }
+                        }
+                    }
+                    tbody {
+                        @for finding in &cue.findings {
+                            tr {
+                                td { span class=(finding_class(finding.severity)) { (finding_label(finding.severity)) } }
+                                td { (finding.track.as_deref().unwrap_or("-")) }
+                                td { (&finding.message) }
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -491,10 +1211,23 @@ fn track_row(track: &GeneratedTrack) -> Markup {
     html! {
         tr {
             td class="path" { (&track.path) }
+            td { (track.codec.as_str()) }
             td { (format_size(track.size_bytes)) }
             td {
-                span class=(cleanup_class(track.cleanup_status)) { (cleanup_label(track.cleanup_status)) }
-                @if let Some(message) = &track.cleanup_message {
+                @if track.tags.is_empty() {
+                    span class="muted" { "-" }
+                } @else {
+                    details {
+                        summary { (track.tags.len()) " tag(s)" }
+                        @for tag in &track.tags {
+                            div { code { (&tag.name) } " " (&tag.value) }
+                        }
+                    }
+                }
+            }
+            td {
+                span class=(cleanup_class(track.cleanup_status)) { (cleanup_label(track.cleanup_status)) }
+                @if let Some(message) = &track.cleanup_message {
                     div class="muted" { (message) }
                 }
             }
@@ -507,6 +1240,15 @@ fn input_kind_label(kind: InputFileKind) -> &'static str {
     match kind {
         InputFileKind::Cue => "cue",
         InputFileKind::Audio => "audio",
+        InputFileKind::Artwork => "artwork",
+    }
+}
+
+fn verification_label(status: SourceVerificationStatus) -> &'static str {
+    match status {
+        SourceVerificationStatus::Unchanged => "unchanged",
+        SourceVerificationStatus::Changed => "changed",
+        SourceVerificationStatus::Missing => "missing",
     }
 }
 
@@ -518,6 +1260,34 @@ fn lifecycle_label(state: &DownloadLifecycleState) -> &'static str {
         DownloadLifecycleState::CleaningUp => "cleaning_up",
         DownloadLifecycleState::Completed => "completed",
         DownloadLifecycleState::Failed => "failed",
+        DownloadLifecycleState::Planned => "planned",
+        DownloadLifecycleState::Ignored => "ignored",
+        DownloadLifecycleState::GaveUp => "gave_up",
+    }
+}
+
+fn action_label(action: DownloadAction) -> &'static str {
+    match action {
+        DownloadAction::Retry => "Retry processing",
+        DownloadAction::Reimport => "Retry manual import",
+        DownloadAction::ForceCleanup => "Force cleanup",
+        DownloadAction::Ignore => "Ignore",
+    }
+}
+
+fn ignore_rule_kind_label(kind: IgnoreRuleKind) -> &'static str {
+    match kind {
+        IgnoreRuleKind::DownloadId => "Download ID",
+        IgnoreRuleKind::TitleRegex => "Title regex",
+        IgnoreRuleKind::PathGlob => "Path glob",
+    }
+}
+
+fn action_status_label(status: DownloadActionStatus) -> &'static str {
+    match status {
+        DownloadActionStatus::Pending => "pending",
+        DownloadActionStatus::Succeeded => "succeeded",
+        DownloadActionStatus::Failed => "failed",
     }
 }
 
@@ -527,6 +1297,14 @@ fn cue_status_label(status: CueSheetStatus) -> &'static str {
         CueSheetStatus::Split => "split",
         CueSheetStatus::Skipped => "skipped",
         CueSheetStatus::Failed => "failed",
+        CueSheetStatus::Planned => "planned",
+    }
+}
+
+fn finding_label(severity: CueFindingSeverity) -> &'static str {
+    match severity {
+        CueFindingSeverity::Error => "error",
+        CueFindingSeverity::Warning => "warning",
     }
 }
 
@@ -542,11 +1320,29 @@ fn cleanup_label(status: TrackCleanupStatus) -> &'static str {
 fn lifecycle_class(state: &DownloadLifecycleState) -> &'static str {
     match state {
         DownloadLifecycleState::Completed => "status status-ok",
-        DownloadLifecycleState::Failed => "status status-error",
-        DownloadLifecycleState::AwaitingImport => "status status-warn",
+        DownloadLifecycleState::Failed | DownloadLifecycleState::GaveUp => "status status-error",
+        DownloadLifecycleState::AwaitingImport | DownloadLifecycleState::Planned => {
+            "status status-warn"
+        }
         DownloadLifecycleState::Detected
         | DownloadLifecycleState::Processing
         | DownloadLifecycleState::CleaningUp => "status status-active",
+        DownloadLifecycleState::Ignored => "status",
+    }
+}
+
+fn action_status_class(status: DownloadActionStatus) -> &'static str {
+    match status {
+        DownloadActionStatus::Succeeded => "status status-ok",
+        DownloadActionStatus::Failed => "status status-error",
+        DownloadActionStatus::Pending => "status status-active",
+    }
+}
+
+fn delivery_status_class(status: NotificationDeliveryStatus) -> &'static str {
+    match status {
+        NotificationDeliveryStatus::Delivered => "status status-ok",
+        NotificationDeliveryStatus::Failed => "status status-error",
     }
 }
 
@@ -554,11 +1350,18 @@ fn cue_status_class(state: CueSheetStatus) -> &'static str {
     match state {
         CueSheetStatus::Split => "status status-ok",
         CueSheetStatus::Failed => "status status-error",
-        CueSheetStatus::Skipped => "status status-warn",
+        CueSheetStatus::Skipped | CueSheetStatus::Planned => "status status-warn",
         CueSheetStatus::Pending => "status status-active",
     }
 }
 
+fn finding_class(severity: CueFindingSeverity) -> &'static str {
+    match severity {
+        CueFindingSeverity::Error => "status status-error",
+        CueFindingSeverity::Warning => "status status-warn",
+    }
+}
+
 fn cleanup_class(status: TrackCleanupStatus) -> &'static str {
     match status {
         TrackCleanupStatus::Deleted => "status status-ok",
@@ -568,6 +1371,15 @@ fn cleanup_class(status: TrackCleanupStatus) -> &'static str {
     }
 }
 
+fn verification_class(status: SourceVerificationStatus) -> &'static str {
+    match status {
+        SourceVerificationStatus::Unchanged => "status status-ok",
+        SourceVerificationStatus::Changed | SourceVerificationStatus::Missing => {
+            "status status-error"
+        }
+    }
+}
+
 fn format_size(size: Option<i64>) -> String {
     let Some(size) = size else {
         return "-".into();
@@ -721,26 +1533,65 @@ pre {
   background: color-mix(in srgb, var(--bg), var(--panel) 35%);
 }
 .error-block { color: var(--error); }
+.actions {
+  display: flex;
+  flex-wrap: wrap;
+  gap: 8px;
+}
+button {
+  font: inherit;
+  color: var(--accent);
+  background: var(--panel);
+  border: 1px solid var(--accent);
+  border-radius: 6px;
+  padding: 6px 12px;
+  cursor: pointer;
+}
+button:hover { background: color-mix(in srgb, var(--accent), var(--panel) 88%); }
+input, select {
+  font: inherit;
+  color: var(--text);
+  background: var(--panel);
+  border: 1px solid var(--border);
+  border-radius: 6px;
+  padding: 6px 8px;
+}
+input[type="text"] { flex: 1; min-width: 240px; }
 "#;
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
     use async_trait::async_trait;
     use axum::body::Body;
-    use axum::http::Request;
+    use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
-    use super::{router, StatusConfig};
-    use crate::application::ports::{DownloadHistoryRow, DownloadReadStore, DownloadStats};
+    use super::{router, LidarrWebhook, StatusConfig};
+    use crate::adapters::audio_format::{AudioFormat, AudioFormatCapability, FormatSupport};
+    use crate::adapters::web_auth::{LoginMethod, WebAuth, WebLogin};
+    use crate::application::metrics::Metrics;
+    use crate::application::ports::{
+        DownloadActionQueue, DownloadHistoryRow, DownloadReadStore, DownloadStats, IgnoreRuleStore,
+    };
+    use crate::application::service::QueueCheckRequests;
     use crate::domain::{
-        CueSheet, CueSheetStatus, DownloadLifecycleState, GeneratedTrack, InputFile, InputFileKind,
-        TrackCleanupStatus, TrackedDownload,
+        local_download_id, CueFinding, CueFindingKind, CueFindingSeverity, CueRepair,
+        CueRepairKind, CueSheet, CueSheetStatus, DownloadAction, DownloadActionStatus,
+        DownloadAuditEvent, DownloadLifecycleState, GeneratedTrack, IgnoreRule, IgnoreRuleKind,
+        InputFile, InputFileKind, OutputCodec, PregapPolicy, SourceVerification,
+        SourceVerificationStatus, TrackCleanupStatus, TrackTag, TrackedDownload,
+        LOCAL_DOWNLOAD_STATE,
     };
 
     #[derive(Clone, Default)]
     struct FakeReadStore {
         rows: Vec<DownloadHistoryRow>,
         detail: Option<TrackedDownload>,
+        requested_actions: Arc<Mutex<Vec<(String, DownloadAction)>>>,
+        ignore_rules: Arc<Mutex<Vec<IgnoreRule>>>,
     }
 
     #[async_trait]
@@ -776,11 +1627,62 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl DownloadActionQueue for FakeReadStore {
+        async fn request_download_action(
+            &self,
+            download_id: &str,
+            action: DownloadAction,
+        ) -> anyhow::Result<()> {
+            self.requested_actions
+                .lock()
+                .unwrap()
+                .push((download_id.to_owned(), action));
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl IgnoreRuleStore for FakeReadStore {
+        async fn load_ignore_rules(&self) -> anyhow::Result<Vec<IgnoreRule>> {
+            Ok(self.ignore_rules.lock().unwrap().clone())
+        }
+
+        async fn add_ignore_rule(&self, kind: IgnoreRuleKind, pattern: &str) -> anyhow::Result<()> {
+            let mut rules = self.ignore_rules.lock().unwrap();
+            let id = rules.len() as i64 + 1;
+            rules.push(IgnoreRule {
+                id: Some(id),
+                kind,
+                pattern: pattern.to_owned(),
+                created_at: Some("2026-06-12 12:00:00".to_owned()),
+            });
+            Ok(())
+        }
+
+        async fn remove_ignore_rule(&self, rule_id: i64) -> anyhow::Result<()> {
+            self.ignore_rules
+                .lock()
+                .unwrap()
+                .retain(|rule| rule.id != Some(rule_id));
+            Ok(())
+        }
+    }
+
     fn fake_status_config() -> StatusConfig {
         StatusConfig {
             version: "0.0.0-test",
             data_dir: "/config".into(),
+            staging_dir: Some("/staging".into()),
             check_frequency_seconds: 60,
+            max_concurrent_downloads: 2,
+            webhook_enabled: false,
+            api_key_enabled: true,
+            login_method: Some(LoginMethod::Forms),
+            retry_max_attempts: 5,
+            retry_initial_delay_seconds: 300,
+            retry_max_delay_seconds: 21600,
+            dry_run: false,
             download_log_enabled: true,
             lidarr_url: "http://lidarr:8686".into(),
             manual_import_enabled: true,
@@ -791,15 +1693,36 @@ mod tests {
             gnudb_enabled: false,
             gnudb_server: "gnudb.gnudb.org".into(),
             cue_strict: false,
+            splitter_backend: "native".into(),
             shnsplit_path: "shnsplit".into(),
             shnsplit_overwrite: true,
             shnsplit_format: "%p - %a - %n - %t".into(),
+            audio_formats: vec![AudioFormatCapability {
+                format: AudioFormat::Ape,
+                shnsplit: FormatSupport::External {
+                    program: "mac",
+                    available: false,
+                },
+                native: FormatSupport::External {
+                    program: "mac",
+                    available: false,
+                },
+                disc_id_length: FormatSupport::Builtin,
+            }],
+            ignore_rules: Vec::new(),
         }
     }
 
     #[tokio::test]
     async fn index_renders_empty_state_with_fake_read_store() {
-        let app = router(FakeReadStore::default(), fake_status_config());
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
 
         let response = app
             .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
@@ -828,7 +1751,15 @@ mod tests {
             path: "/downloads/album/album.cue".into(),
             kind: InputFileKind::Cue,
             size_bytes: Some(12),
+            audio_format: None,
+            modified_ns: Some(1_781_265_600_000_000_000),
+            content_hash: None,
             captured_at: "2026-06-12 12:00:00".into(),
+            verification: Some(SourceVerification {
+                status: SourceVerificationStatus::Changed,
+                message: Some("size changed from 12 to 14 bytes".into()),
+                verified_at: "2026-06-12 12:05:00".into(),
+            }),
         }];
         download.cue_sheets = vec![CueSheet {
             id: "cue-1".into(),
@@ -837,15 +1768,33 @@ mod tests {
             status: CueSheetStatus::Split,
             message: None,
             updated_at: "2026-06-12 12:00:00".into(),
+            encoding: Some("windows-1252".into()),
+            pregap_policy: Some(PregapPolicy::TrackZero),
             tracks: vec![GeneratedTrack {
                 id: "track-1".into(),
                 cue_sheet_id: "cue-1".into(),
                 download_id: "abc".into(),
                 path: "/downloads/album/01.flac".into(),
                 size_bytes: Some(64),
+                codec: OutputCodec::Flac,
                 cleanup_status: TrackCleanupStatus::Pending,
                 cleanup_message: None,
                 deleted_at: None,
+                tags: vec![TrackTag {
+                    name: "TITLE".into(),
+                    value: "Opening".into(),
+                }],
+            }],
+            findings: vec![CueFinding {
+                severity: CueFindingSeverity::Warning,
+                kind: CueFindingKind::ShortTrack,
+                track: Some("02".into()),
+                message: "is only 2.000 seconds long".into(),
+            }],
+            repairs: vec![CueRepair {
+                kind: CueRepairKind::FileExtension,
+                original: "album.wav".into(),
+                replacement: "album.flac".into(),
             }],
         }];
 
@@ -855,6 +1804,10 @@ mod tests {
                 ..FakeReadStore::default()
             },
             fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
         );
         let response = app
             .oneshot(
@@ -872,6 +1825,488 @@ mod tests {
         assert!(rendered.contains("Input Files"));
         assert!(rendered.contains("/downloads/album/album.cue"));
         assert!(rendered.contains("/downloads/album/01.flac"));
+        assert!(rendered.contains("<td>flac</td>"));
+        assert!(rendered.contains("<code>TITLE</code> Opening"));
+        assert!(rendered.contains("status status-error\">changed</span>"));
+        assert!(rendered.contains("size changed from 12 to 14 bytes"));
+        assert!(rendered.contains("status status-warn\">warning</span>"));
+        assert!(rendered.contains("is only 2.000 seconds long"));
+        assert!(rendered.contains("album.wav -&gt; album.flac (matched by file stem)"));
+        assert!(rendered.contains("Encoding: windows-1252"));
+        assert!(rendered.contains("Pregap: track_zero"));
+        assert!(!rendered.contains("Import Plan"));
+    }
+
+    #[tokio::test]
+    async fn detail_opens_for_a_local_download() {
+        let download_id = local_download_id(Path::new("/music/Artist - Album"));
+        let download = TrackedDownload::pending(
+            download_id.clone(),
+            "Artist - Album".into(),
+            "completed".into(),
+            "/music/Artist - Album".into(),
+            LOCAL_DOWNLOAD_STATE.into(),
+        );
+        let app = router(
+            FakeReadStore {
+                detail: Some(download),
+                ..FakeReadStore::default()
+            },
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/downloads/{download_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+        assert!(rendered.contains("/music/Artist - Album"));
+    }
+
+    #[tokio::test]
+    async fn detail_renders_dry_run_import_plan() {
+        let mut download = TrackedDownload::pending(
+            "abc".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        download.lifecycle_state = DownloadLifecycleState::Planned;
+        download.import_plan = Some(
+            "Manual import: planned for 2 track(s)\nFallback decision: selected album_id=7".into(),
+        );
+
+        let app = router(
+            FakeReadStore {
+                detail: Some(download),
+                ..FakeReadStore::default()
+            },
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/downloads/abc")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+        assert!(rendered.contains("status status-warn\">planned</span>"));
+        assert!(rendered.contains("Import Plan"));
+        assert!(rendered.contains("Fallback decision: selected album_id=7"));
+    }
+
+    #[tokio::test]
+    async fn detail_renders_actions_and_audit_history() {
+        let mut download = TrackedDownload::pending(
+            "abc".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        download.audit_events = vec![
+            DownloadAuditEvent {
+                id: 2,
+                download_id: "abc".into(),
+                action: DownloadAction::Reimport,
+                status: DownloadActionStatus::Failed,
+                message: Some("cue sheet has not been split: /downloads/album/album.cue".into()),
+                requested_at: "2026-06-12 12:05:00".into(),
+                finished_at: Some("2026-06-12 12:06:00".into()),
+            },
+            DownloadAuditEvent {
+                id: 1,
+                download_id: "abc".into(),
+                action: DownloadAction::Retry,
+                status: DownloadActionStatus::Pending,
+                message: None,
+                requested_at: "2026-06-12 12:00:00".into(),
+                finished_at: None,
+            },
+        ];
+
+        let app = router(
+            FakeReadStore {
+                detail: Some(download),
+                ..FakeReadStore::default()
+            },
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/downloads/abc")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+        assert!(rendered.contains("action=\"/downloads/abc/actions/retry\""));
+        assert!(rendered.contains("action=\"/downloads/abc/actions/reimport\""));
+        assert!(rendered.contains("action=\"/downloads/abc/actions/force_cleanup\""));
+        assert!(rendered.contains("action=\"/downloads/abc/actions/ignore\""));
+        assert!(rendered.contains("status status-error\">failed</span>"));
+        assert!(rendered.contains("cue sheet has not been split: /downloads/album/album.cue"));
+        assert!(rendered.contains("status status-active\">pending</span>"));
+    }
+
+    #[tokio::test]
+    async fn action_post_queues_the_action_and_redirects_to_the_detail_page() {
+        let store = FakeReadStore {
+            detail: Some(TrackedDownload::pending(
+                "abc".into(),
+                "Album".into(),
+                "completed".into(),
+                "/downloads/album".into(),
+                "importFailed".into(),
+            )),
+            ..FakeReadStore::default()
+        };
+        let requested_actions = Arc::clone(&store.requested_actions);
+        let queue_checks = QueueCheckRequests::default();
+        let app = router(
+            store,
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            queue_checks.clone(),
+        );
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/downloads/abc/actions/force_cleanup")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()["location"], "/downloads/abc");
+        assert_eq!(
+            requested_actions.lock().unwrap().as_slice(),
+            &[("abc".to_owned(), DownloadAction::ForceCleanup)]
+        );
+        assert!(tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            queue_checks.requested()
+        )
+        .await
+        .is_ok());
+
+        for uri in [
+            "/downloads/abc/actions/delete",
+            "/downloads/missing/actions/retry",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+        assert_eq!(requested_actions.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn index_separates_ignored_downloads_and_lists_ignore_rules() {
+        let row = |download_id: &str, lifecycle_state| DownloadHistoryRow {
+            download_id: download_id.into(),
+            title: download_id.into(),
+            status: "completed".into(),
+            output_path: "/downloads/album".into(),
+            tracked_download_state: "importFailed".into(),
+            lifecycle_state,
+            updated_at: "2026-06-12 12:00:00".into(),
+            completed_at: None,
+            generated_track_count: 0,
+        };
+        let store = FakeReadStore {
+            rows: vec![
+                row("active", DownloadLifecycleState::Detected),
+                row("skipped", DownloadLifecycleState::Ignored),
+            ],
+            ..FakeReadStore::default()
+        };
+        store
+            .add_ignore_rule(IgnoreRuleKind::DownloadId, "skipped")
+            .await
+            .unwrap();
+        let mut status = fake_status_config();
+        status.ignore_rules = vec![IgnoreRule::configured(
+            IgnoreRuleKind::PathGlob,
+            "/downloads/podcasts/**".to_owned(),
+        )];
+        let app = router(
+            store,
+            status,
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+
+        let (history, ignored) = rendered.split_once("Ignored Downloads").unwrap();
+        assert!(history.contains("download-row-active"));
+        assert!(!history.contains("download-row-skipped"));
+        assert!(ignored.contains("download-row-skipped"));
+        assert!(rendered.contains("/downloads/podcasts/**"));
+        assert!(rendered.contains("action=\"/ignore-rules/1/delete\""));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/downloads/rows")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+        assert!(rendered.contains("download-row-active"));
+        assert!(!rendered.contains("download-row-skipped"));
+    }
+
+    #[tokio::test]
+    async fn ignore_rules_can_be_added_and_removed() {
+        let store = FakeReadStore::default();
+        let ignore_rules = Arc::clone(&store.ignore_rules);
+        let app = router(
+            store,
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+        let post_form = |uri: &str, form: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(form.to_owned()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post_form(
+                "/ignore-rules",
+                "kind=title_regex&pattern=%28%3Fi%29audiobook",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()["location"], "/");
+        assert_eq!(ignore_rules.lock().unwrap()[0].pattern, "(?i)audiobook");
+
+        for form in ["kind=title_regex&pattern=%28", "kind=tag&pattern=x"] {
+            let response = app
+                .clone()
+                .oneshot(post_form("/ignore-rules", form))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(ignore_rules.lock().unwrap().len(), 1);
+
+        let response = app
+            .oneshot(post_form("/ignore-rules/1/delete", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert!(ignore_rules.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn lidarr_webhook_requires_basic_auth_and_requests_a_queue_check() {
+        let queue_checks = QueueCheckRequests::default();
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            Some(LidarrWebhook {
+                username: "splittarr".into(),
+                password: "hunter2".into(),
+            }),
+            WebAuth::default(),
+            Metrics::default(),
+            queue_checks.clone(),
+        );
+        let webhook = |authorization: Option<&str>, body: &str| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/webhooks/lidarr")
+                .header("content-type", "application/json");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            request.body(Body::from(body.to_owned())).unwrap()
+        };
+        let queue_check_requested = || async {
+            tokio::time::timeout(
+                std::time::Duration::from_millis(50),
+                queue_checks.requested(),
+            )
+            .await
+            .is_ok()
+        };
+        let import_failure =
+            r#"{"eventType":"ImportFailure","downloadId":"abc","artist":{"id":1}}"#;
+
+        // "splittarr:hunter2" and "splittarr:wrong"
+        let authorized = Some("Basic c3BsaXR0YXJyOmh1bnRlcjI=");
+        for authorization in [None, Some("Basic c3BsaXR0YXJyOndyb25n")] {
+            let response = app
+                .clone()
+                .oneshot(webhook(authorization, import_failure))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(webhook(authorized, r#"{"eventType":"Test"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!queue_check_requested().await);
+
+        let response = app
+            .clone()
+            .oneshot(webhook(authorized, "not json"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(webhook(authorized, import_failure))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(queue_check_requested().await);
+    }
+
+    #[tokio::test]
+    async fn lidarr_webhook_locks_out_repeated_wrong_credentials() {
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            Some(LidarrWebhook {
+                username: "splittarr".into(),
+                password: "hunter2".into(),
+            }),
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+        let webhook = |authorization: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/lidarr")
+                .header("authorization", authorization)
+                .body(Body::from(r#"{"eventType":"Test"}"#))
+                .unwrap()
+        };
+
+        // "splittarr:wrong" and "splittarr:hunter2"
+        for _ in 0..5 {
+            let response = app
+                .clone()
+                .oneshot(webhook("Basic c3BsaXR0YXJyOndyb25n"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let response = app
+            .clone()
+            .oneshot(webhook("Basic c3BsaXR0YXJyOndyb25n"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let response = app
+            .oneshot(webhook("Basic c3BsaXR0YXJyOmh1bnRlcjI="))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn lidarr_webhook_is_not_found_when_disabled() {
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/lidarr")
+                    .body(Body::from(r#"{"eventType":"Test"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
@@ -889,9 +2324,13 @@ mod tests {
                     completed_at: None,
                     generated_track_count: 0,
                 }],
-                detail: None,
+                ..FakeReadStore::default()
             },
             fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
         );
         let response = app
             .oneshot(
@@ -912,9 +2351,160 @@ mod tests {
         assert!(rendered.contains("0"));
     }
 
+    #[tokio::test]
+    async fn forms_login_sets_a_session_cookie_for_the_ui() {
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::new(
+                None,
+                Some(WebLogin {
+                    method: LoginMethod::Forms,
+                    username: "splittarr".into(),
+                    password: "hunter2".into(),
+                }),
+            ),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+        let log_in = |form: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/login")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(form))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()["location"], "/login?next=/");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/login")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(log_in("username=splittarr&password=wrong"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(log_in(
+                "username=splittarr&password=hunter2&next=%2Fdownloads%2Fabc",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()["location"], "/downloads/abc");
+        let cookie = response.headers()["set-cookie"]
+            .to_str()
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .to_owned();
+        assert!(cookie.starts_with("splittarr_session="));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/status")
+                    .header("cookie", cookie)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn metrics_are_served_in_the_prometheus_text_format() {
+        let metrics = Metrics::default();
+        metrics.record_split_attempt();
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            metrics,
+            QueueCheckRequests::default(),
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rendered = String::from_utf8(body.to_vec()).unwrap();
+        assert!(rendered.contains("\nsplittarr_split_attempts_total 1\n"));
+    }
+
+    #[tokio::test]
+    async fn json_api_is_served_under_api_v1() {
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/json");
+    }
+
     #[tokio::test]
     async fn status_page_renders_config_and_stats() {
-        let app = router(FakeReadStore::default(), fake_status_config());
+        let app = router(
+            FakeReadStore::default(),
+            fake_status_config(),
+            None,
+            WebAuth::default(),
+            Metrics::default(),
+            QueueCheckRequests::default(),
+        );
 
         let response = app
             .oneshot(
@@ -930,9 +2520,17 @@ mod tests {
             .unwrap();
         let rendered = String::from_utf8(body.to_vec()).unwrap();
         assert!(rendered.contains("0.0.0-test"));
+        assert!(rendered.contains("5 attempts, 300–21600 s apart"));
+        assert!(rendered.contains("<strong>Concurrent downloads</strong><span>2</span>"));
+        assert!(rendered.contains("<strong>Web UI login</strong><span>forms</span>"));
+        assert!(rendered.contains("action=\"/logout\""));
         assert!(rendered.contains("http://lidarr:8686"));
         assert!(rendered.contains("gnudb.gnudb.org"));
         assert!(rendered.contains("%p - %a - %n - %t"));
+        assert!(rendered.contains("<code>native</code>"));
+        assert!(rendered.contains("Audio Formats"));
+        assert!(rendered.contains("/staging"));
+        assert!(rendered.contains("missing <code>mac</code>"));
         assert!(rendered.contains("Download History"));
     }
 }
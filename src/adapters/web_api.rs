@@ -0,0 +1,835 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::application::ports::{
+    DownloadActionQueue, DownloadHistoryRow, DownloadReadStore, DownloadStats,
+};
+use crate::application::service::QueueCheckRequests;
+use crate::domain::{
+    CueFinding, CueFindingSeverity, CueRepair, CueRepairKind, CueSheet, CueSheetStatus,
+    DownloadAction, DownloadActionStatus, DownloadAuditEvent, DownloadLifecycleState,
+    GeneratedTrack, InputFile, InputFileKind, NotificationDelivery, SourceVerificationStatus,
+    TrackCleanupStatus, TrackedDownload,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+/// JSON API under `/api/v1`. The models below are the wire format and only
+/// change in a backwards compatible way within a version.
+pub fn router<S>(store: S, queue_checks: QueueCheckRequests) -> Router
+where
+    S: DownloadReadStore + DownloadActionQueue + Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/downloads", get(list_downloads::<S>))
+        .route("/downloads/{download_id}", get(get_download::<S>))
+        .route(
+            "/downloads/{download_id}/actions/{action}",
+            post(request_action::<S>),
+        )
+        .route("/stats", get(get_stats::<S>))
+        .fallback(|| async { api_error(StatusCode::NOT_FOUND, "not found") })
+        .with_state(ApiState {
+            store,
+            queue_checks,
+        })
+}
+
+#[derive(Clone)]
+struct ApiState<S> {
+    store: S,
+    /// Woken when an action is queued, so the monitor runs it right away.
+    queue_checks: QueueCheckRequests,
+}
+
+#[derive(Deserialize)]
+struct DownloadListQuery {
+    /// Comma separated lifecycle states, e.g. `failed,gave_up`.
+    state: Option<String>,
+    /// Case-insensitive match against the title and download ID.
+    q: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct DownloadPage {
+    items: Vec<DownloadSummary>,
+    total: usize,
+    limit: usize,
+    offset: usize,
+}
+
+#[derive(Serialize)]
+struct DownloadSummary {
+    download_id: String,
+    title: String,
+    status: String,
+    output_path: String,
+    tracked_download_state: String,
+    lifecycle_state: &'static str,
+    updated_at: String,
+    completed_at: Option<String>,
+    generated_track_count: usize,
+}
+
+#[derive(Serialize)]
+struct DownloadDetail {
+    download_id: String,
+    title: String,
+    status: String,
+    output_path: String,
+    tracked_download_state: String,
+    lifecycle_state: &'static str,
+    created_at: String,
+    updated_at: String,
+    first_seen_at: Option<String>,
+    last_seen_in_queue_at: Option<String>,
+    processing_started_at: Option<String>,
+    processing_finished_at: Option<String>,
+    cleanup_started_at: Option<String>,
+    cleanup_finished_at: Option<String>,
+    completed_at: Option<String>,
+    last_error: Option<String>,
+    import_plan: Option<String>,
+    attempt_count: u32,
+    next_retry_at: Option<String>,
+    generated_track_count: usize,
+    input_files: Vec<InputFileDetail>,
+    cue_sheets: Vec<CueSheetDetail>,
+    actions: Vec<ActionDetail>,
+    notifications: Vec<NotificationDetail>,
+}
+
+#[derive(Serialize)]
+struct InputFileDetail {
+    path: String,
+    kind: &'static str,
+    cue_sheet_id: Option<String>,
+    size_bytes: Option<i64>,
+    audio_format: Option<String>,
+    modified_ns: Option<i64>,
+    content_hash: Option<String>,
+    captured_at: String,
+    verification: Option<VerificationDetail>,
+}
+
+#[derive(Serialize)]
+struct VerificationDetail {
+    status: &'static str,
+    message: Option<String>,
+    verified_at: String,
+}
+
+#[derive(Serialize)]
+struct CueSheetDetail {
+    id: String,
+    path: String,
+    status: &'static str,
+    message: Option<String>,
+    updated_at: String,
+    encoding: Option<String>,
+    pregap_policy: Option<&'static str>,
+    findings: Vec<FindingDetail>,
+    repairs: Vec<RepairDetail>,
+    tracks: Vec<TrackDetail>,
+}
+
+#[derive(Serialize)]
+struct FindingDetail {
+    severity: &'static str,
+    track: Option<String>,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct RepairDetail {
+    kind: &'static str,
+    original: String,
+    replacement: String,
+}
+
+#[derive(Serialize)]
+struct TrackDetail {
+    id: String,
+    path: String,
+    codec: &'static str,
+    size_bytes: Option<i64>,
+    cleanup_status: &'static str,
+    cleanup_message: Option<String>,
+    deleted_at: Option<String>,
+    tags: Vec<TagDetail>,
+}
+
+#[derive(Serialize)]
+struct TagDetail {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct ActionDetail {
+    id: i64,
+    action: &'static str,
+    status: &'static str,
+    message: Option<String>,
+    requested_at: String,
+    finished_at: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NotificationDetail {
+    notifier: String,
+    event: &'static str,
+    status: &'static str,
+    message: Option<String>,
+    sent_at: String,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    total: usize,
+    completed: usize,
+    failed: usize,
+    awaiting_import: usize,
+    planned: usize,
+    ignored: usize,
+    gave_up: usize,
+    in_progress: usize,
+}
+
+#[derive(Serialize)]
+struct ActionRequested {
+    download_id: String,
+    action: &'static str,
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    error: String,
+}
+
+async fn list_downloads<S>(
+    State(ApiState { store, .. }): State<ApiState<S>>,
+    Query(query): Query<DownloadListQuery>,
+) -> Response
+where
+    S: DownloadReadStore,
+{
+    let states = match query.state.as_deref().map(parse_lifecycle_states) {
+        Some(Ok(states)) => Some(states),
+        Some(Err(state)) => {
+            return api_error(
+                StatusCode::BAD_REQUEST,
+                format!("unknown lifecycle state: {state}"),
+            )
+        }
+        None => None,
+    };
+    let rows = match store.load_download_rows().await {
+        Ok(rows) => rows,
+        Err(error) => return internal_error("failed to load downloads", &error),
+    };
+
+    let search = query
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty())
+        .map(str::to_lowercase);
+    let matching = rows
+        .into_iter()
+        .filter(|row| {
+            states
+                .as_ref()
+                .is_none_or(|states| states.contains(&row.lifecycle_state))
+        })
+        .filter(|row| {
+            search.as_ref().is_none_or(|search| {
+                row.title.to_lowercase().contains(search)
+                    || row.download_id.to_lowercase().contains(search)
+            })
+        })
+        .collect::<Vec<_>>();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+
+    Json(DownloadPage {
+        total: matching.len(),
+        items: matching
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(DownloadSummary::from)
+            .collect(),
+        limit,
+        offset,
+    })
+    .into_response()
+}
+
+async fn get_download<S>(
+    State(ApiState { store, .. }): State<ApiState<S>>,
+    Path(download_id): Path<String>,
+) -> Response
+where
+    S: DownloadReadStore,
+{
+    match store.get_tracked_download(&download_id).await {
+        Ok(Some(download)) => Json(DownloadDetail::from(download)).into_response(),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "download not found"),
+        Err(error) => internal_error("failed to load download", &error),
+    }
+}
+
+async fn get_stats<S>(State(ApiState { store, .. }): State<ApiState<S>>) -> Response
+where
+    S: DownloadReadStore,
+{
+    match store.load_download_stats().await {
+        Ok(stats) => Json(Stats::from(stats)).into_response(),
+        Err(error) => internal_error("failed to load stats", &error),
+    }
+}
+
+async fn request_action<S>(
+    State(ApiState {
+        store,
+        queue_checks,
+    }): State<ApiState<S>>,
+    Path((download_id, action)): Path<(String, String)>,
+) -> Response
+where
+    S: DownloadReadStore + DownloadActionQueue,
+{
+    let Some(action) = DownloadAction::parse(&action) else {
+        return api_error(StatusCode::NOT_FOUND, "unknown action");
+    };
+    match store.get_tracked_download(&download_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return api_error(StatusCode::NOT_FOUND, "download not found"),
+        Err(error) => return internal_error("failed to load download for action", &error),
+    }
+    match store.request_download_action(&download_id, action).await {
+        Ok(()) => {
+            queue_checks.request();
+            (
+                StatusCode::ACCEPTED,
+                Json(ActionRequested {
+                    download_id,
+                    action: action.as_str(),
+                    status: action_status_label(DownloadActionStatus::Pending),
+                }),
+            )
+                .into_response()
+        }
+        Err(error) => internal_error("failed to request download action", &error),
+    }
+}
+
+pub(crate) fn api_error(status: StatusCode, error: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ApiError {
+            error: error.into(),
+        }),
+    )
+        .into_response()
+}
+
+fn internal_error(context: &str, error: &anyhow::Error) -> Response {
+    error!("{context}: {error:#}");
+    api_error(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn parse_lifecycle_states(value: &str) -> Result<Vec<DownloadLifecycleState>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|state| !state.is_empty())
+        .map(|state| parse_lifecycle_state(state).ok_or_else(|| state.to_owned()))
+        .collect()
+}
+
+impl From<DownloadHistoryRow> for DownloadSummary {
+    fn from(row: DownloadHistoryRow) -> Self {
+        Self {
+            lifecycle_state: lifecycle_label(&row.lifecycle_state),
+            download_id: row.download_id,
+            title: row.title,
+            status: row.status,
+            output_path: row.output_path,
+            tracked_download_state: row.tracked_download_state,
+            updated_at: row.updated_at,
+            completed_at: row.completed_at,
+            generated_track_count: row.generated_track_count,
+        }
+    }
+}
+
+impl From<TrackedDownload> for DownloadDetail {
+    fn from(download: TrackedDownload) -> Self {
+        Self {
+            lifecycle_state: lifecycle_label(&download.lifecycle_state),
+            generated_track_count: download.generated_track_count(),
+            download_id: download.download_id,
+            title: download.title,
+            status: download.status,
+            output_path: download.output_path,
+            tracked_download_state: download.tracked_download_state,
+            created_at: download.created_at,
+            updated_at: download.updated_at,
+            first_seen_at: download.first_seen_at,
+            last_seen_in_queue_at: download.last_seen_in_queue_at,
+            processing_started_at: download.processing_started_at,
+            processing_finished_at: download.processing_finished_at,
+            cleanup_started_at: download.cleanup_started_at,
+            cleanup_finished_at: download.cleanup_finished_at,
+            completed_at: download.completed_at,
+            last_error: download.last_error,
+            import_plan: download.import_plan,
+            attempt_count: download.attempt_count,
+            next_retry_at: download.next_retry_at,
+            input_files: download
+                .input_files
+                .into_iter()
+                .map(InputFileDetail::from)
+                .collect(),
+            cue_sheets: download
+                .cue_sheets
+                .into_iter()
+                .map(CueSheetDetail::from)
+                .collect(),
+            actions: download
+                .audit_events
+                .into_iter()
+                .map(ActionDetail::from)
+                .collect(),
+            notifications: download
+                .notification_deliveries
+                .into_iter()
+                .map(NotificationDetail::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<InputFile> for InputFileDetail {
+    fn from(input: InputFile) -> Self {
+        Self {
+            path: input.path,
+            kind: input_kind_label(input.kind),
+            cue_sheet_id: input.cue_sheet_id,
+            size_bytes: input.size_bytes,
+            audio_format: input.audio_format,
+            modified_ns: input.modified_ns,
+            content_hash: input.content_hash,
+            captured_at: input.captured_at,
+            verification: input.verification.map(|verification| VerificationDetail {
+                status: verification_label(verification.status),
+                message: verification.message,
+                verified_at: verification.verified_at,
+            }),
+        }
+    }
+}
+
+impl From<CueSheet> for CueSheetDetail {
+    fn from(cue_sheet: CueSheet) -> Self {
+        Self {
+            id: cue_sheet.id,
+            path: cue_sheet.path,
+            status: cue_status_label(cue_sheet.status),
+            message: cue_sheet.message,
+            updated_at: cue_sheet.updated_at,
+            encoding: cue_sheet.encoding,
+            pregap_policy: cue_sheet.pregap_policy.map(|policy| policy.as_str()),
+            findings: cue_sheet
+                .findings
+                .into_iter()
+                .map(FindingDetail::from)
+                .collect(),
+            repairs: cue_sheet
+                .repairs
+                .into_iter()
+                .map(RepairDetail::from)
+                .collect(),
+            tracks: cue_sheet
+                .tracks
+                .into_iter()
+                .map(TrackDetail::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<CueFinding> for FindingDetail {
+    fn from(finding: CueFinding) -> Self {
+        Self {
+            severity: finding_label(finding.severity),
+            track: finding.track,
+            message: finding.message,
+        }
+    }
+}
+
+impl From<CueRepair> for RepairDetail {
+    fn from(repair: CueRepair) -> Self {
+        Self {
+            kind: repair_label(repair.kind),
+            original: repair.original,
+            replacement: repair.replacement,
+        }
+    }
+}
+
+impl From<GeneratedTrack> for TrackDetail {
+    fn from(track: GeneratedTrack) -> Self {
+        Self {
+            id: track.id,
+            path: track.path,
+            codec: track.codec.as_str(),
+            size_bytes: track.size_bytes,
+            cleanup_status: cleanup_label(track.cleanup_status),
+            cleanup_message: track.cleanup_message,
+            deleted_at: track.deleted_at,
+            tags: track
+                .tags
+                .into_iter()
+                .map(|tag| TagDetail {
+                    name: tag.name,
+                    value: tag.value,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<DownloadAuditEvent> for ActionDetail {
+    fn from(event: DownloadAuditEvent) -> Self {
+        Self {
+            id: event.id,
+            action: event.action.as_str(),
+            status: action_status_label(event.status),
+            message: event.message,
+            requested_at: event.requested_at,
+            finished_at: event.finished_at,
+        }
+    }
+}
+
+impl From<NotificationDelivery> for NotificationDetail {
+    fn from(delivery: NotificationDelivery) -> Self {
+        Self {
+            notifier: delivery.notifier,
+            event: delivery.event.as_str(),
+            status: delivery.status.as_str(),
+            message: delivery.message,
+            sent_at: delivery.sent_at,
+        }
+    }
+}
+
+impl From<DownloadStats> for Stats {
+    fn from(stats: DownloadStats) -> Self {
+        Self {
+            total: stats.total,
+            completed: stats.completed,
+            failed: stats.failed,
+            awaiting_import: stats.awaiting_import,
+            planned: stats.planned,
+            ignored: stats.ignored,
+            gave_up: stats.gave_up,
+            in_progress: stats.in_progress,
+        }
+    }
+}
+
+fn lifecycle_label(state: &DownloadLifecycleState) -> &'static str {
+    match state {
+        DownloadLifecycleState::Detected => "detected",
+        DownloadLifecycleState::Processing => "processing",
+        DownloadLifecycleState::AwaitingImport => "awaiting_import",
+        DownloadLifecycleState::CleaningUp => "cleaning_up",
+        DownloadLifecycleState::Completed => "completed",
+        DownloadLifecycleState::Failed => "failed",
+        DownloadLifecycleState::Planned => "planned",
+        DownloadLifecycleState::Ignored => "ignored",
+        DownloadLifecycleState::GaveUp => "gave_up",
+    }
+}
+
+fn parse_lifecycle_state(value: &str) -> Option<DownloadLifecycleState> {
+    match value.to_ascii_lowercase().as_str() {
+        "detected" => Some(DownloadLifecycleState::Detected),
+        "processing" => Some(DownloadLifecycleState::Processing),
+        "awaiting_import" => Some(DownloadLifecycleState::AwaitingImport),
+        "cleaning_up" => Some(DownloadLifecycleState::CleaningUp),
+        "completed" => Some(DownloadLifecycleState::Completed),
+        "failed" => Some(DownloadLifecycleState::Failed),
+        "planned" => Some(DownloadLifecycleState::Planned),
+        "ignored" => Some(DownloadLifecycleState::Ignored),
+        "gave_up" => Some(DownloadLifecycleState::GaveUp),
+        _ => None,
+    }
+}
+
+fn input_kind_label(kind: InputFileKind) -> &'static str {
+    match kind {
+        InputFileKind::Cue => "cue",
+        InputFileKind::Audio => "audio",
+        InputFileKind::Artwork => "artwork",
+    }
+}
+
+fn verification_label(status: SourceVerificationStatus) -> &'static str {
+    match status {
+        SourceVerificationStatus::Unchanged => "unchanged",
+        SourceVerificationStatus::Changed => "changed",
+        SourceVerificationStatus::Missing => "missing",
+    }
+}
+
+fn cue_status_label(status: CueSheetStatus) -> &'static str {
+    match status {
+        CueSheetStatus::Pending => "pending",
+        CueSheetStatus::Split => "split",
+        CueSheetStatus::Skipped => "skipped",
+        CueSheetStatus::Failed => "failed",
+        CueSheetStatus::Planned => "planned",
+    }
+}
+
+fn finding_label(severity: CueFindingSeverity) -> &'static str {
+    match severity {
+        CueFindingSeverity::Error => "error",
+        CueFindingSeverity::Warning => "warning",
+    }
+}
+
+fn repair_label(kind: CueRepairKind) -> &'static str {
+    match kind {
+        CueRepairKind::FileCase => "file_case",
+        CueRepairKind::FileExtension => "file_extension",
+        CueRepairKind::OnlyAudioFile => "only_audio_file",
+        CueRepairKind::Encoding => "encoding",
+    }
+}
+
+fn cleanup_label(status: TrackCleanupStatus) -> &'static str {
+    match status {
+        TrackCleanupStatus::Pending => "pending",
+        TrackCleanupStatus::Deleted => "deleted",
+        TrackCleanupStatus::DeleteFailed => "delete_failed",
+        TrackCleanupStatus::Missing => "missing",
+    }
+}
+
+fn action_status_label(status: DownloadActionStatus) -> &'static str {
+    match status {
+        DownloadActionStatus::Pending => "pending",
+        DownloadActionStatus::Succeeded => "succeeded",
+        DownloadActionStatus::Failed => "failed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tempfile::tempdir;
+    use tower::ServiceExt;
+
+    use super::router;
+    use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::ports::DownloadStore;
+    use crate::application::service::QueueCheckRequests;
+    use crate::domain::{CueSheetStatus, OutputCodec, RecordedTrack, TrackedDownload};
+
+    async fn seeded_store(root: &Path) -> SqliteDownloadStore {
+        let store = SqliteDownloadStore::open(root).unwrap();
+        for (download_id, title) in [
+            ("download-1", "Artist - Album One"),
+            ("download-2", "Artist - Album Two"),
+            ("download-3", "Other - Live"),
+        ] {
+            store
+                .upsert_tracked_download(&TrackedDownload::pending(
+                    download_id.into(),
+                    title.into(),
+                    "completed".into(),
+                    format!("/downloads/{download_id}"),
+                    "importPending".into(),
+                ))
+                .await
+                .unwrap();
+        }
+        store.mark_download_completed("download-1").await.unwrap();
+        store
+            .mark_download_failed("download-2", Some("shnsplit exited with 1"))
+            .await
+            .unwrap();
+        let cue_sheet = store
+            .get_or_create_cue_sheet("download-1", Path::new("/downloads/download-1/album.cue"))
+            .await
+            .unwrap();
+        store
+            .record_cue_result(
+                &cue_sheet,
+                CueSheetStatus::Split,
+                None,
+                &[RecordedTrack {
+                    path: "/downloads/download-1/01 - Intro.flac".into(),
+                    size_bytes: Some(1024),
+                    codec: OutputCodec::Flac,
+                }],
+            )
+            .await
+            .unwrap();
+        store
+    }
+
+    async fn send(store: SqliteDownloadStore, method: &str, uri: &str) -> (StatusCode, Value) {
+        send_with(store, QueueCheckRequests::default(), method, uri).await
+    }
+
+    async fn send_with(
+        store: SqliteDownloadStore,
+        queue_checks: QueueCheckRequests,
+        method: &str,
+        uri: &str,
+    ) -> (StatusCode, Value) {
+        let response = router(store, queue_checks)
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn lists_downloads_with_filters_and_pagination() {
+        let tmp = tempdir().unwrap();
+        let store = seeded_store(tmp.path()).await;
+
+        let (status, page) = send(store.clone(), "GET", "/downloads?q=artist&limit=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 2);
+        assert_eq!(page["limit"], 1);
+        assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+        let (_, page) = send(store.clone(), "GET", "/downloads?q=artist&limit=1&offset=1").await;
+        assert_eq!(page["offset"], 1);
+        assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+        let (_, page) = send(store.clone(), "GET", "/downloads?state=failed,detected").await;
+        let mut states = page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["lifecycle_state"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        states.sort();
+        assert_eq!(states, ["detected", "failed"]);
+
+        let (status, error) = send(store, "GET", "/downloads?state=exploded").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"], "unknown lifecycle state: exploded");
+    }
+
+    #[tokio::test]
+    async fn returns_download_detail_with_cue_sheets_and_tracks() {
+        let tmp = tempdir().unwrap();
+        let store = seeded_store(tmp.path()).await;
+
+        let (status, download) = send(store.clone(), "GET", "/downloads/download-1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(download["lifecycle_state"], "completed");
+        assert_eq!(download["generated_track_count"], 1);
+        let cue_sheet = &download["cue_sheets"][0];
+        assert_eq!(cue_sheet["status"], "split");
+        assert_eq!(cue_sheet["tracks"][0]["codec"], "flac");
+        assert_eq!(cue_sheet["tracks"][0]["cleanup_status"], "pending");
+
+        let (_, download) = send(store.clone(), "GET", "/downloads/download-2").await;
+        assert_eq!(download["last_error"], "shnsplit exited with 1");
+
+        let (status, error) = send(store, "GET", "/downloads/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["error"], "download not found");
+    }
+
+    #[tokio::test]
+    async fn returns_stats() {
+        let tmp = tempdir().unwrap();
+        let store = seeded_store(tmp.path()).await;
+
+        let (status, stats) = send(store, "GET", "/stats").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stats["total"], 3);
+        assert_eq!(stats["completed"], 1);
+        assert_eq!(stats["failed"], 1);
+    }
+
+    #[tokio::test]
+    async fn queues_actions_for_known_downloads() {
+        let tmp = tempdir().unwrap();
+        let store = seeded_store(tmp.path()).await;
+
+        let queue_checks = QueueCheckRequests::default();
+        let (status, accepted) = send_with(
+            store.clone(),
+            queue_checks.clone(),
+            "POST",
+            "/downloads/download-2/actions/retry",
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            queue_checks.requested()
+        )
+        .await
+        .is_ok());
+        assert_eq!(accepted["action"], "retry");
+        assert_eq!(accepted["status"], "pending");
+        let pending = store.load_pending_download_actions().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].download_id, "download-2");
+
+        let (status, _) = send(
+            store.clone(),
+            "POST",
+            "/downloads/download-2/actions/explode",
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(store, "POST", "/downloads/missing/actions/retry").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::application::ports::Notifier;
+use crate::domain::{Notification, NotificationEvent};
+
+/// The JSON body a webhook expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// The notification as is: `event`, `download_id`, `title` and `message`.
+    Generic,
+    /// The Apprise API `/notify` body: `title`, `body` and `type`.
+    Apprise,
+    /// A Discord webhook message with one embed.
+    Discord,
+    /// A Slack incoming webhook message.
+    Slack,
+}
+
+/// Notifications are sent while the download is locked, so a hanging
+/// endpoint must not hold up its processing.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    url: String,
+    format: WebhookFormat,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, format: WebhookFormat) -> Self {
+        Self {
+            url: url.trim().to_owned(),
+            format,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("build webhook HTTP client"),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .body(webhook_body(self.format, notification).to_string())
+            .send()
+            .await
+            // Webhook URLs carry their secret token, and delivery errors are
+            // shown on the download page.
+            .map_err(|err| anyhow!("failed posting notification: {}", err.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("webhook returned HTTP {status}: {body}"));
+        }
+        Ok(())
+    }
+}
+
+fn webhook_body(format: WebhookFormat, notification: &Notification) -> Value {
+    let summary = notification.summary();
+    match format {
+        WebhookFormat::Generic => json!({
+            "event": notification.event.as_str(),
+            "download_id": notification.download_id,
+            "title": notification.title,
+            "message": notification.message,
+        }),
+        WebhookFormat::Apprise => json!({
+            "title": format!("Splittarr: {summary}"),
+            "body": notification.message,
+            "type": match notification.event {
+                NotificationEvent::Failed => "failure",
+                NotificationEvent::ImportSkipped => "warning",
+                NotificationEvent::Completed => "success",
+            },
+        }),
+        WebhookFormat::Discord => json!({
+            "username": "Splittarr",
+            "embeds": [{
+                "title": summary,
+                "description": notification.message,
+                "color": match notification.event {
+                    NotificationEvent::Failed => 0xd9_3f_3f,
+                    NotificationEvent::ImportSkipped => 0xe0_a1_2b,
+                    NotificationEvent::Completed => 0x3f_a9_5c,
+                },
+                "footer": { "text": notification.download_id },
+            }],
+        }),
+        WebhookFormat::Slack => json!({
+            "text": format!("*{summary}*\n{}", notification.message),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::{webhook_body, WebhookFormat, WebhookNotifier};
+    use crate::application::ports::Notifier;
+    use crate::domain::{Notification, NotificationEvent};
+
+    fn skipped() -> Notification {
+        Notification {
+            event: NotificationEvent::ImportSkipped,
+            download_id: "ABC123".into(),
+            title: "Artist - Album".into(),
+            message: "no matching album in Lidarr".into(),
+        }
+    }
+
+    #[test]
+    fn bodies_match_each_service_format() {
+        let notification = skipped();
+
+        assert_eq!(
+            webhook_body(WebhookFormat::Generic, &notification),
+            json!({
+                "event": "import_skipped",
+                "download_id": "ABC123",
+                "title": "Artist - Album",
+                "message": "no matching album in Lidarr",
+            })
+        );
+        assert_eq!(
+            webhook_body(WebhookFormat::Apprise, &notification),
+            json!({
+                "title": "Splittarr: Artist - Album was not imported",
+                "body": "no matching album in Lidarr",
+                "type": "warning",
+            })
+        );
+        let discord = webhook_body(WebhookFormat::Discord, &notification);
+        assert_eq!(
+            discord["embeds"][0]["title"],
+            "Artist - Album was not imported"
+        );
+        assert_eq!(discord["embeds"][0]["footer"]["text"], "ABC123");
+        assert_eq!(
+            webhook_body(WebhookFormat::Slack, &notification),
+            json!({ "text": "*Artist - Album was not imported*\nno matching album in Lidarr" })
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_json_and_reports_http_errors() {
+        let (url, request) = serve_once("204 No Content").await;
+        WebhookNotifier::new(&url, WebhookFormat::Slack)
+            .notify(&skipped())
+            .await
+            .unwrap();
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /hooks/splittarr HTTP/1.1"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.ends_with(
+            r#"{"text":"*Artist - Album was not imported*\nno matching album in Lidarr"}"#
+        ));
+
+        let (url, _) = serve_once("500 Internal Server Error").await;
+        let err = WebhookNotifier::new(&url, WebhookFormat::Generic)
+            .notify(&skipped())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 500"), "{err}");
+
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/api/webhooks/1/secret-token",
+            closed.local_addr().unwrap()
+        );
+        drop(closed);
+        let err = WebhookNotifier::new(&url, WebhookFormat::Discord)
+            .notify(&skipped())
+            .await
+            .unwrap_err();
+        assert!(!format!("{err:#}").contains("secret-token"), "{err:#}");
+    }
+
+    async fn serve_once(status: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks/splittarr", listener.local_addr().unwrap());
+        let request = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0_u8; 4096];
+            loop {
+                let bytes_read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..bytes_read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length: "))
+                        .and_then(|length| length.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            socket
+                .write_all(format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\n\r\n").as_bytes())
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, request)
+    }
+}
@@ -1,13 +1,26 @@
 use anyhow::{Context, Result};
+use tracing::{error, warn};
 
+use crate::application::notifications::{notify, Notifiers};
 use crate::application::ports::{DownloadLog, DownloadStore, TrackCleanup};
-use crate::domain::{TrackCleanupStatus, TrackedDownload};
+use crate::domain::{NotificationEvent, TrackCleanupStatus, TrackedDownload};
+
+/// Why the generated tracks of a download are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupReason {
+    /// Lidarr imported the tracks and the download left its queue.
+    Imported,
+    /// Force cleanup in the web UI or `splittarr cleanup`.
+    Requested,
+}
 
 pub async fn cleanup_processed_download<S: DownloadStore, C: TrackCleanup, L: DownloadLog>(
     store: &S,
     cleanup: &C,
     download_log: &L,
+    notifiers: &Notifiers,
     download: &TrackedDownload,
+    reason: CleanupReason,
 ) -> Result<()> {
     store
         .mark_download_cleanup_started(&download.download_id)
@@ -21,6 +34,14 @@ pub async fn cleanup_processed_download<S: DownloadStore, C: TrackCleanup, L: Do
                 .mark_download_failed(&download.download_id, Some(&message))
                 .await
                 .context("mark download failed after cleanup error")?;
+            notify(
+                store,
+                notifiers,
+                download,
+                NotificationEvent::Failed,
+                message,
+            )
+            .await;
             return Err(err).with_context(|| format!("cleanup failed for {}", download.title));
         }
     };
@@ -44,26 +65,49 @@ pub async fn cleanup_processed_download<S: DownloadStore, C: TrackCleanup, L: Do
     if failures.is_empty() {
         if let Err(err) = download_log.delete_download_log(download).await {
             let message = format!("download log cleanup failed for {}: {err}", download.title);
-            eprintln!("{message}");
+            warn!("{message}");
             if let Err(warning_err) = store
                 .record_download_warning(&download.download_id, &message)
                 .await
             {
-                eprintln!(
-                    "Failed recording download log cleanup warning for {}: {warning_err:#}",
-                    download.title
-                );
+                error!("Failed recording download log cleanup warning: {warning_err:#}");
             }
         }
         store
             .mark_download_completed(&download.download_id)
             .await
             .context("mark download completed")?;
+        notify(
+            store,
+            notifiers,
+            download,
+            NotificationEvent::Completed,
+            match reason {
+                CleanupReason::Imported => format!(
+                    "imported by Lidarr, {} generated track(s) cleaned up",
+                    outcomes.len()
+                ),
+                CleanupReason::Requested => format!(
+                    "{} generated track(s) cleaned up on request",
+                    outcomes.len()
+                ),
+            },
+        )
+        .await;
     } else {
+        let message = failures.join("; ");
         store
-            .mark_download_failed(&download.download_id, Some(&failures.join("; ")))
+            .mark_download_failed(&download.download_id, Some(&message))
             .await
             .context("mark download failed after cleanup")?;
+        notify(
+            store,
+            notifiers,
+            download,
+            NotificationEvent::Failed,
+            message,
+        )
+        .await;
     }
 
     Ok(())
@@ -72,17 +116,32 @@ pub async fn cleanup_processed_download<S: DownloadStore, C: TrackCleanup, L: Do
 #[cfg(test)]
 mod tests {
     use std::path::Path;
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
 
     use anyhow::Result;
+    use async_trait::async_trait;
 
-    use super::cleanup_processed_download;
-    use crate::application::ports::{DownloadLog, DownloadStore, TrackCleanup};
+    use super::{cleanup_processed_download, CleanupReason};
+    use crate::application::notifications::Notifiers;
+    use crate::application::ports::{DownloadLog, DownloadStore, Notifier, TrackCleanup};
     use crate::domain::{
-        CueSheet, CueSheetStatus, DownloadLifecycleState, InputFileKind, RecordedTrack,
-        TrackCleanupOutcome, TrackCleanupStatus, TrackedDownload,
+        CueSheet, CueSheetStatus, DownloadLifecycleState, InputFileKind, Notification,
+        NotificationEvent, RecordedTrack, TrackCleanupOutcome, TrackCleanupStatus, TrackedDownload,
     };
 
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, notification: &Notification) -> Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
     #[derive(Default)]
     struct FakeStore {
         states: Mutex<Vec<String>>,
@@ -154,6 +213,7 @@ mod tests {
             _path: &Path,
             _kind: InputFileKind,
             _size_bytes: Option<i64>,
+            _audio_format: Option<&str>,
         ) -> Result<()> {
             Ok(())
         }
@@ -265,11 +325,23 @@ mod tests {
             cue_sheets: Vec::new(),
             generated_track_count: 0,
             last_error: None,
+            import_plan: None,
+            audit_events: Vec::new(),
+            notification_deliveries: Vec::new(),
+            attempt_count: 0,
+            next_retry_at: None,
         };
 
-        cleanup_processed_download(&store, &cleanup, &log, &download)
-            .await
-            .unwrap();
+        cleanup_processed_download(
+            &store,
+            &cleanup,
+            &log,
+            &Notifiers::default(),
+            &download,
+            CleanupReason::Imported,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(*log.deletes.lock().unwrap(), 0);
         assert!(store.states.lock().unwrap().contains(&"failed".to_string()));
@@ -304,11 +376,23 @@ mod tests {
             cue_sheets: Vec::new(),
             generated_track_count: 0,
             last_error: None,
+            import_plan: None,
+            audit_events: Vec::new(),
+            notification_deliveries: Vec::new(),
+            attempt_count: 0,
+            next_retry_at: None,
         };
 
-        let err = cleanup_processed_download(&store, &cleanup, &log, &download)
-            .await
-            .unwrap_err();
+        let err = cleanup_processed_download(
+            &store,
+            &cleanup,
+            &log,
+            &Notifiers::default(),
+            &download,
+            CleanupReason::Imported,
+        )
+        .await
+        .unwrap_err();
 
         assert_eq!(*log.deletes.lock().unwrap(), 0);
         assert!(err.to_string().contains("cleanup failed for Album"));
@@ -332,9 +416,16 @@ mod tests {
             "importFailed".into(),
         );
 
-        cleanup_processed_download(&store, &cleanup, &log, &download)
-            .await
-            .unwrap();
+        cleanup_processed_download(
+            &store,
+            &cleanup,
+            &log,
+            &Notifiers::default(),
+            &download,
+            CleanupReason::Imported,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(*log.deletes.lock().unwrap(), 1);
         assert!(store
@@ -360,9 +451,16 @@ mod tests {
             "importFailed".into(),
         );
 
-        cleanup_processed_download(&store, &cleanup, &log, &download)
-            .await
-            .unwrap();
+        cleanup_processed_download(
+            &store,
+            &cleanup,
+            &log,
+            &Notifiers::default(),
+            &download,
+            CleanupReason::Imported,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(*log.deletes.lock().unwrap(), 1);
         assert!(!store.states.lock().unwrap().contains(&"failed".to_string()));
@@ -376,4 +474,55 @@ mod tests {
             .unwrap()
             .contains(&"completed".to_string()));
     }
+
+    #[tokio::test]
+    async fn completed_notification_names_the_cleanup_reason() {
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importFailed".into(),
+        );
+        let notifier = Arc::new(RecordingNotifier::default());
+        let notifiers = Notifiers::default().with_notifier(
+            "chat",
+            NotificationEvent::ALL.to_vec(),
+            notifier.clone(),
+        );
+
+        for reason in [CleanupReason::Imported, CleanupReason::Requested] {
+            cleanup_processed_download(
+                &FakeStore::default(),
+                &SuccessfulCleanup,
+                &FakeDownloadLog::default(),
+                &notifiers,
+                &download,
+                reason,
+            )
+            .await
+            .unwrap();
+        }
+
+        let messages = notifier
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|notification| (notification.event, notification.message.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    NotificationEvent::Completed,
+                    "imported by Lidarr, 0 generated track(s) cleaned up".to_owned()
+                ),
+                (
+                    NotificationEvent::Completed,
+                    "0 generated track(s) cleaned up on request".to_owned()
+                ),
+            ]
+        );
+    }
 }
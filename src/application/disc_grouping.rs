@@ -0,0 +1,141 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+use crate::application::ports::CueMetadataHint;
+
+const DISC_MARKER: &str = r"(?i)\b(?:cd|disc|disk)\s*[-_.#]?\s*0*(\d{1,2})\b";
+
+pub fn assign_disc_numbers(hints: &mut [CueMetadataHint]) {
+    let detected = hints.iter().map(detect_disc_number).collect::<Vec<_>>();
+    let unique = detected.iter().flatten().collect::<BTreeSet<_>>();
+    let usable = hints.len() < 2 || (unique.len() == hints.len() && !detected.contains(&None));
+
+    for (position, (hint, disc_number)) in hints.iter_mut().zip(detected).enumerate() {
+        hint.disc_number = if usable {
+            disc_number
+        } else {
+            u32::try_from(position + 1).ok()
+        };
+    }
+}
+
+pub fn detect_disc_number(hint: &CueMetadataHint) -> Option<u32> {
+    disc_number_from_comment(hint)
+        .or_else(|| {
+            hint.path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .and_then(|name| disc_number_from_text(&name.to_string_lossy()))
+        })
+        .or_else(|| hint.album_title.as_deref().and_then(disc_number_from_text))
+}
+
+pub fn album_title_without_disc(title: &str) -> String {
+    let re = Regex::new(&format!(r"\s*[(\[]?\s*{DISC_MARKER}\s*[)\]]?\s*$"))
+        .expect("disc suffix regex must compile");
+    re.replace(title, "")
+        .trim_end_matches([' ', '-', ':', ','])
+        .to_owned()
+}
+
+fn disc_number_from_comment(hint: &CueMetadataHint) -> Option<u32> {
+    let value = hint
+        .comments
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("DISCNUMBER"))
+        .map(|(_, value)| value.trim().trim_matches('"'))?;
+    value
+        .split('/')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|number| *number > 0)
+}
+
+fn disc_number_from_text(value: &str) -> Option<u32> {
+    let re = Regex::new(DISC_MARKER).expect("disc marker regex must compile");
+    re.captures(value)?
+        .get(1)?
+        .as_str()
+        .parse()
+        .ok()
+        .filter(|number| *number > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{album_title_without_disc, assign_disc_numbers, detect_disc_number};
+    use crate::application::ports::CueMetadataHint;
+
+    #[test]
+    fn detects_disc_numbers_from_comments_folders_and_titles() {
+        let mut from_comment = hint("/downloads/box/CD1/album.cue", Some("Box Set"));
+        from_comment.comments = vec![("DISCNUMBER".into(), "3/4".into())];
+        assert_eq!(detect_disc_number(&from_comment), Some(3));
+
+        for (path, expected) in [
+            ("/downloads/box/CD2/album.cue", Some(2)),
+            ("/downloads/box/Disc 03/album.cue", Some(3)),
+            ("/downloads/box/disk_4 - Live/album.cue", Some(4)),
+            ("/downloads/box/Scans/album.cue", None),
+        ] {
+            assert_eq!(detect_disc_number(&hint(path, None)), expected, "{path}");
+        }
+
+        assert_eq!(
+            detect_disc_number(&hint("/downloads/box/album.cue", Some("Box Set (CD 2)"))),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_scan_order_when_detection_is_ambiguous() {
+        let mut hints = vec![
+            hint("/downloads/box/CD2/album.cue", None),
+            hint("/downloads/box/CD1/album.cue", None),
+        ];
+        assign_disc_numbers(&mut hints);
+        assert_eq!(disc_numbers(&hints), vec![Some(2), Some(1)]);
+
+        let mut hints = vec![
+            hint("/downloads/box/a/album.cue", Some("Box Set (CD1)")),
+            hint("/downloads/box/b/album.cue", None),
+        ];
+        assign_disc_numbers(&mut hints);
+        assert_eq!(disc_numbers(&hints), vec![Some(1), Some(2)]);
+
+        let mut hints = vec![hint("/downloads/album/album.cue", Some("Album"))];
+        assign_disc_numbers(&mut hints);
+        assert_eq!(disc_numbers(&hints), vec![None]);
+    }
+
+    #[test]
+    fn strips_disc_suffixes_from_album_titles() {
+        assert_eq!(album_title_without_disc("Box Set (CD1)"), "Box Set");
+        assert_eq!(album_title_without_disc("Box Set [Disc 2]"), "Box Set");
+        assert_eq!(album_title_without_disc("Box Set - Disk 3"), "Box Set");
+        assert_eq!(album_title_without_disc("CD Box"), "CD Box");
+    }
+
+    fn hint(path: &str, album_title: Option<&str>) -> CueMetadataHint {
+        CueMetadataHint {
+            path: PathBuf::from(path),
+            album_title: album_title.map(str::to_owned),
+            performer: None,
+            catalog: None,
+            disc_id: None,
+            disc_number: None,
+            comments: Vec::new(),
+            track_count: 0,
+            tracks: Vec::new(),
+        }
+    }
+
+    fn disc_numbers(hints: &[CueMetadataHint]) -> Vec<Option<u32>> {
+        hints.iter().map(|hint| hint.disc_number).collect()
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use tracing::{error, info, info_span, Instrument};
+
+use crate::application::cleanup_processed_download::{cleanup_processed_download, CleanupReason};
+use crate::application::download_lock::with_download_lock;
+use crate::application::ports::{
+    CueInputInspector, CueScanner, CueSplitter, DownloadLog, DownloadStore, ManualImportTrigger,
+    TrackCleanup, TrackTagger,
+};
+use crate::application::process_tracked_download::retrigger_manual_import;
+use crate::application::retry_policy::RetryPolicy;
+use crate::application::service::{process_attempt, record_attempt, ProcessingAdapters};
+use crate::domain::{
+    DownloadAction, DownloadActionStatus, DownloadAuditEvent, IgnoreRuleKind, TrackedDownload,
+};
+
+pub async fn run_pending_download_actions<S, C, I, P, M, L, X, T>(
+    store: &S,
+    adapters: &ProcessingAdapters<C, I, P, M, L, X, T>,
+    retry_policy: &RetryPolicy,
+) -> Result<()>
+where
+    S: DownloadStore,
+    C: CueScanner,
+    I: CueInputInspector,
+    P: CueSplitter,
+    M: ManualImportTrigger,
+    L: DownloadLog,
+    X: TrackCleanup,
+    T: TrackTagger,
+{
+    for event in store.load_pending_download_actions().await? {
+        let span = info_span!(
+            "download",
+            download_id = %event.download_id,
+            action = event.action.as_str()
+        );
+        info!(parent: &span, "Running action");
+        let (status, message) = match with_download_lock(
+            store,
+            &event.download_id,
+            run_download_action(store, adapters, retry_policy, &event),
+        )
+        .instrument(span.clone())
+        .await
+        {
+            Ok(Some(message)) => (DownloadActionStatus::Succeeded, message),
+            Ok(None) => {
+                info!(parent: &span, "Download is busy, leaving the action for the next check");
+                continue;
+            }
+            Err(err) => {
+                error!(parent: &span, "Action failed: {err:#}");
+                (DownloadActionStatus::Failed, format!("{err:#}"))
+            }
+        };
+        store
+            .finish_download_action(event.id, status, Some(&message))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn run_download_action<S, C, I, P, M, L, X, T>(
+    store: &S,
+    adapters: &ProcessingAdapters<C, I, P, M, L, X, T>,
+    retry_policy: &RetryPolicy,
+    event: &DownloadAuditEvent,
+) -> Result<String>
+where
+    S: DownloadStore,
+    C: CueScanner,
+    I: CueInputInspector,
+    P: CueSplitter,
+    M: ManualImportTrigger,
+    L: DownloadLog,
+    X: TrackCleanup,
+    T: TrackTagger,
+{
+    let download = store
+        .get_tracked_download(&event.download_id)
+        .await?
+        .ok_or_else(|| anyhow!("download not found: {}", event.download_id))?;
+
+    match event.action {
+        DownloadAction::Retry => {
+            if download.is_local() {
+                return Err(anyhow!(
+                    "local downloads are reprocessed with `splittarr reprocess`"
+                ));
+            }
+            store.reset_download_attempts(&download.download_id).await?;
+            // A manual retry accepts the source files as they are now.
+            store
+                .clear_input_fingerprints(&download.download_id)
+                .await?;
+            let download = TrackedDownload {
+                attempt_count: 0,
+                ..download
+            };
+            // Fails like an automatic attempt, so backoff and giving up apply.
+            let failure = process_attempt(store, adapters, &download).await?;
+            record_attempt(
+                store,
+                &adapters.notifiers,
+                retry_policy,
+                &download,
+                failure.as_deref(),
+            )
+            .await?;
+            if let Some(failure) = failure {
+                return Err(anyhow!("processing failed: {failure}"));
+            }
+            let processed = store
+                .get_tracked_download(&download.download_id)
+                .await?
+                .ok_or_else(|| anyhow!("download not found: {}", download.download_id))?;
+            Ok(format!(
+                "processed {} generated track(s)",
+                processed.generated_track_count()
+            ))
+        }
+        DownloadAction::Reimport => {
+            if download.is_local() {
+                return Err(anyhow!("local downloads are never imported into Lidarr"));
+            }
+            retrigger_manual_import(adapters, &download).await
+        }
+        DownloadAction::ForceCleanup => {
+            if adapters.dry_run {
+                return Err(anyhow!(
+                    "dry run: not deleting {} generated track(s)",
+                    download.generated_track_count()
+                ));
+            }
+            cleanup_processed_download(
+                store,
+                &adapters.track_cleanup,
+                &adapters.download_log,
+                &adapters.notifiers,
+                &download,
+                CleanupReason::Requested,
+            )
+            .await?;
+            Ok(format!(
+                "cleaned up {} generated track(s)",
+                download.generated_track_count()
+            ))
+        }
+        DownloadAction::Ignore => {
+            store
+                .add_ignore_rule(IgnoreRuleKind::DownloadId, &download.download_id)
+                .await?;
+            store.mark_download_ignored(&download.download_id).await?;
+            Ok("download ignored".to_owned())
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::future::Future;
+use std::panic::{resume_unwind, AssertUnwindSafe};
+use std::time::Duration;
+
+use anyhow::Result;
+use futures_util::FutureExt;
+use tracing::warn;
+
+use crate::application::ports::DownloadStore;
+
+/// How often a held lock is refreshed. A process that is killed stops
+/// refreshing, so its locks go stale and are taken over by the next one.
+pub const LOCK_HEARTBEAT: Duration = Duration::from_secs(30);
+
+/// Runs `work` while holding the download's lock. Returns `None` without
+/// running it when the download is already being worked on elsewhere.
+///
+/// The lock is released when `work` returns or panics. If the returned future
+/// is dropped instead, the lock stops being refreshed and goes stale.
+pub async fn with_download_lock<S, F, T>(store: &S, download_id: &str, work: F) -> Result<Option<T>>
+where
+    S: DownloadStore,
+    F: Future<Output = Result<T>>,
+{
+    if !store.try_lock_download(download_id).await? {
+        return Ok(None);
+    }
+    let mut heartbeat = tokio::time::interval(LOCK_HEARTBEAT);
+    heartbeat.tick().await;
+    let work = AssertUnwindSafe(work).catch_unwind();
+    tokio::pin!(work);
+    let result = loop {
+        tokio::select! {
+            result = &mut work => break result,
+            _ = heartbeat.tick() => {
+                if let Err(err) = store.refresh_download_lock(download_id).await {
+                    warn!("Refreshing the lock on {download_id} failed: {err:#}");
+                }
+            }
+        }
+    };
+    let unlocked = store.unlock_download(download_id).await;
+    let value = result.unwrap_or_else(|panic| resume_unwind(panic))?;
+    unlocked?;
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use anyhow::{anyhow, Result};
+    use futures_util::FutureExt;
+    use tempfile::tempdir;
+
+    use super::with_download_lock;
+    use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::ports::DownloadStore;
+
+    #[tokio::test]
+    async fn skips_work_while_the_download_is_locked_and_always_unlocks() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let other_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+
+        assert!(other_process.try_lock_download("download-1").await.unwrap());
+        let skipped = with_download_lock(&store, "download-1", async {
+            panic!("work must not run while another process holds the lock")
+        })
+        .await
+        .unwrap();
+        assert_eq!(skipped, None::<()>);
+        other_process.unlock_download("download-1").await.unwrap();
+
+        let value = with_download_lock(&store, "download-1", async { Ok(7) })
+            .await
+            .unwrap();
+        assert_eq!(value, Some(7));
+
+        let err = with_download_lock(&store, "download-1", async {
+            Err::<(), _>(anyhow!("split failed"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "split failed");
+        assert!(other_process.try_lock_download("download-1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn unlocks_when_the_work_panics() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let other_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+
+        let panicked: std::thread::Result<Result<Option<()>>> =
+            AssertUnwindSafe(with_download_lock(&store, "download-1", async {
+                panic!("splitter bug")
+            }))
+            .catch_unwind()
+            .await;
+
+        assert!(panicked.is_err());
+        assert!(other_process.try_lock_download("download-1").await.unwrap());
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+use crate::domain::{IgnoreRule, IgnoreRuleKind, TrackedDownload};
+
+#[derive(Debug, Default)]
+pub struct IgnoreList {
+    rules: Vec<(IgnoreRule, Matcher)>,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    DownloadId(String),
+    Title(Regex),
+    Path(Regex),
+}
+
+impl IgnoreList {
+    pub fn new(rules: impl IntoIterator<Item = IgnoreRule>) -> Result<Self> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let matcher = compile_rule(rule.kind, &rule.pattern)?;
+                Ok((rule, matcher))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn matching_rule(&self, download: &TrackedDownload) -> Option<&IgnoreRule> {
+        self.rules.iter().find_map(|(rule, matcher)| {
+            let matched = match matcher {
+                Matcher::DownloadId(download_id) => download.download_id == *download_id,
+                Matcher::Title(regex) => regex.is_match(&download.title),
+                Matcher::Path(regex) => regex.is_match(&download.output_path),
+            };
+            matched.then_some(rule)
+        })
+    }
+
+    pub fn is_ignored(&self, download: &TrackedDownload) -> bool {
+        self.matching_rule(download).is_some()
+    }
+}
+
+pub fn validate_ignore_rule(kind: IgnoreRuleKind, pattern: &str) -> Result<()> {
+    compile_rule(kind, pattern).map(|_| ())
+}
+
+fn compile_rule(kind: IgnoreRuleKind, pattern: &str) -> Result<Matcher> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(anyhow!("ignore {} must not be empty", kind.as_str()));
+    }
+    match kind {
+        IgnoreRuleKind::DownloadId => Ok(Matcher::DownloadId(pattern.to_owned())),
+        IgnoreRuleKind::TitleRegex => Regex::new(pattern)
+            .map(Matcher::Title)
+            .map_err(|err| anyhow!("invalid title regex {pattern:?}: {err}")),
+        IgnoreRuleKind::PathGlob => Regex::new(&glob_to_regex(pattern))
+            .map(Matcher::Path)
+            .map_err(|err| anyhow!("invalid path glob {pattern:?}: {err}")),
+    }
+}
+
+/// `*` and `?` stay within one path segment and `**` spans segments; the
+/// glob has to match the whole output path.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_ignore_rule, IgnoreList};
+    use crate::domain::{IgnoreRule, IgnoreRuleKind, TrackedDownload};
+
+    #[test]
+    fn matches_download_ids_title_regexes_and_path_globs() {
+        let list = IgnoreList::new([
+            IgnoreRule::configured(IgnoreRuleKind::DownloadId, "abc"),
+            IgnoreRule::configured(IgnoreRuleKind::TitleRegex, "(?i)\\bbootleg\\b"),
+            IgnoreRule::configured(IgnoreRuleKind::PathGlob, "/downloads/mixes/**"),
+            IgnoreRule::configured(IgnoreRuleKind::PathGlob, "/downloads/*.iso"),
+        ])
+        .unwrap();
+
+        let matching_pattern = |id: &str, title: &str, path: &str| {
+            list.matching_rule(&download(id, title, path))
+                .map(|rule| rule.pattern.clone())
+        };
+        assert_eq!(
+            matching_pattern("abc", "Album", "/downloads/album"),
+            Some("abc".to_owned())
+        );
+        assert_eq!(
+            matching_pattern("d2", "Artist - Live BOOTLEG 1999", "/downloads/live"),
+            Some("(?i)\\bbootleg\\b".to_owned())
+        );
+        assert_eq!(
+            matching_pattern("d3", "Mix", "/downloads/mixes/2024/summer"),
+            Some("/downloads/mixes/**".to_owned())
+        );
+        assert_eq!(
+            matching_pattern("d4", "Image", "/downloads/image.iso"),
+            Some("/downloads/*.iso".to_owned())
+        );
+        assert_eq!(
+            matching_pattern("d5", "Image", "/downloads/nested/image.iso"),
+            None
+        );
+        assert_eq!(matching_pattern("abcd", "Album", "/downloads/album"), None);
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_patterns() {
+        assert!(validate_ignore_rule(IgnoreRuleKind::DownloadId, " ").is_err());
+        assert!(validate_ignore_rule(IgnoreRuleKind::TitleRegex, "(unclosed").is_err());
+        assert!(validate_ignore_rule(IgnoreRuleKind::PathGlob, "/downloads/[x").is_ok());
+    }
+
+    fn download(download_id: &str, title: &str, output_path: &str) -> TrackedDownload {
+        TrackedDownload::pending(
+            download_id.to_owned(),
+            title.to_owned(),
+            "completed".to_owned(),
+            output_path.to_owned(),
+            "importFailed".to_owned(),
+        )
+    }
+}
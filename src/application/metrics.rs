@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::domain::TrackCleanupStatus;
+
+/// Counters for monitoring. Clones share the same counts, so one instance is
+/// handed to the monitor and every adapter that records something.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    values: Arc<Mutex<MetricValues>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricValues {
+    pub split_attempts: u64,
+    pub splits: BTreeMap<SplitResult, u64>,
+    pub manual_imports: BTreeMap<ManualImportOutcome, u64>,
+    pub disc_lookups: BTreeMap<(LookupService, LookupOutcome), u64>,
+    pub track_cleanups: BTreeMap<TrackCleanupStatus, u64>,
+    pub lidarr_api_errors: BTreeMap<LidarrOperation, u64>,
+    pub polls: u64,
+    pub poll_duration_seconds: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SplitResult {
+    Succeeded,
+    Skipped,
+    Planned,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ManualImportOutcome {
+    Started,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LookupService {
+    MusicBrainz,
+    Gnudb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LookupOutcome {
+    Found,
+    NotFound,
+    Skipped,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LidarrOperation {
+    Queue,
+    ManualImport,
+}
+
+impl Metrics {
+    pub fn record_split_attempt(&self) {
+        self.update(|values| values.split_attempts += 1);
+    }
+
+    pub fn record_split(&self, result: SplitResult) {
+        self.update(|values| *values.splits.entry(result).or_default() += 1);
+    }
+
+    pub fn record_manual_import(&self, outcome: ManualImportOutcome) {
+        self.update(|values| *values.manual_imports.entry(outcome).or_default() += 1);
+    }
+
+    pub fn record_disc_lookup(&self, service: LookupService, outcome: LookupOutcome) {
+        self.update(|values| {
+            *values.disc_lookups.entry((service, outcome)).or_default() += 1;
+        });
+    }
+
+    pub fn record_track_cleanup(&self, status: TrackCleanupStatus) {
+        self.update(|values| *values.track_cleanups.entry(status).or_default() += 1);
+    }
+
+    pub fn record_lidarr_api_error(&self, operation: LidarrOperation) {
+        self.update(|values| *values.lidarr_api_errors.entry(operation).or_default() += 1);
+    }
+
+    pub fn record_poll(&self, duration: Duration) {
+        self.update(|values| {
+            values.polls += 1;
+            values.poll_duration_seconds += duration.as_secs_f64();
+        });
+    }
+
+    pub fn snapshot(&self) -> MetricValues {
+        self.values.lock().unwrap().clone()
+    }
+
+    fn update(&self, update: impl FnOnce(&mut MetricValues)) {
+        update(&mut self.values.lock().unwrap());
+    }
+}
@@ -1,5 +1,16 @@
 pub mod cleanup_processed_download;
+pub mod disc_grouping;
+pub mod download_actions;
+pub mod download_lock;
+pub mod ignore_list;
+pub mod metrics;
 pub mod monitor_download_queue;
+pub mod notifications;
 pub mod ports;
 pub mod process_tracked_download;
+pub mod recover_interrupted_downloads;
+pub mod retry_policy;
+pub mod select_musicbrainz_release;
 pub mod service;
+pub mod tag_generated_tracks;
+pub mod verify_source_files;
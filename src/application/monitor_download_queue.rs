@@ -1,18 +1,27 @@
-use crate::domain::{QueueSnapshot, TrackedDownload};
+use crate::application::ignore_list::IgnoreList;
+use crate::domain::{DownloadLifecycleState, QueueSnapshot, TrackedDownload};
 
 pub fn classify_downloads(
     downloads: Vec<TrackedDownload>,
     snapshot: &QueueSnapshot,
+    ignore_list: &IgnoreList,
 ) -> (Vec<TrackedDownload>, Vec<TrackedDownload>) {
     let mut to_process = Vec::new();
     let mut to_cleanup = Vec::new();
 
     for download in downloads {
+        if ignore_list.is_ignored(&download) {
+            continue;
+        }
         if snapshot.active_download_ids.contains(&download.download_id) {
             if download.lifecycle_state.is_ready_for_processing() {
                 to_process.push(download);
             }
-        } else if !download.lifecycle_state.is_terminal() && download.has_generated_tracks() {
+        } else if !download.lifecycle_state.is_terminal()
+            && download.lifecycle_state != DownloadLifecycleState::Planned
+            && !download.is_local()
+            && download.has_generated_tracks()
+        {
             to_cleanup.push(download);
         }
     }
@@ -24,7 +33,10 @@ pub fn classify_downloads(
 mod tests {
     use std::collections::HashSet;
 
-    use crate::domain::{DownloadLifecycleState, QueueSnapshot, TrackedDownload};
+    use crate::application::ignore_list::IgnoreList;
+    use crate::domain::{
+        DownloadLifecycleState, IgnoreRule, IgnoreRuleKind, QueueSnapshot, TrackedDownload,
+    };
 
     use super::classify_downloads;
 
@@ -37,6 +49,7 @@ mod tests {
                 "in-queue".to_owned(),
                 "awaiting-import".to_owned(),
                 "completed".to_owned(),
+                "ignored".to_owned(),
             ]),
             failed_imports: Vec::new(),
         };
@@ -44,9 +57,27 @@ mod tests {
         let mut completed = with_state("completed", DownloadLifecycleState::Completed);
         completed.cue_sheets = awaiting_import.cue_sheets.clone();
         let gone = with_state("gone", DownloadLifecycleState::AwaitingImport);
-        let downloads = vec![download("in-queue"), awaiting_import, completed, gone];
+        let mut local = with_state("local", DownloadLifecycleState::Failed);
+        local.tracked_download_state = "local".into();
+        let planned = with_state("planned", DownloadLifecycleState::Planned);
+        let ignored_gone = with_state("ignored-gone", DownloadLifecycleState::AwaitingImport);
+        let ignore_list = IgnoreList::new([
+            IgnoreRule::configured(IgnoreRuleKind::DownloadId, "ignored".to_owned()),
+            IgnoreRule::configured(IgnoreRuleKind::TitleRegex, "^ignored-".to_owned()),
+        ])
+        .unwrap();
+        let downloads = vec![
+            download("ignored"),
+            ignored_gone,
+            download("in-queue"),
+            awaiting_import,
+            completed,
+            gone,
+            local,
+            planned,
+        ];
 
-        let (to_process, to_cleanup) = classify_downloads(downloads, &snapshot);
+        let (to_process, to_cleanup) = classify_downloads(downloads, &snapshot, &ignore_list);
 
         assert_eq!(
             to_process
@@ -85,10 +116,16 @@ mod tests {
                 download_id: download_id.to_owned(),
                 path: "/downloads/album/01.flac".into(),
                 size_bytes: Some(1),
+                codec: crate::domain::OutputCodec::Flac,
                 cleanup_status: crate::domain::TrackCleanupStatus::Pending,
                 cleanup_message: None,
                 deleted_at: None,
+                tags: Vec::new(),
             }],
+            findings: Vec::new(),
+            repairs: Vec::new(),
+            encoding: None,
+            pregap_policy: None,
         }];
         download
     }
@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use tracing::{error, info, warn};
+
+use crate::application::ports::{DownloadStore, Notifier};
+use crate::domain::{Notification, NotificationDeliveryStatus, NotificationEvent, TrackedDownload};
+
+/// The notifiers configured for this process, each with the events it wants.
+/// Clones share the notifiers.
+#[derive(Clone, Default)]
+pub struct Notifiers {
+    targets: Vec<NotificationTarget>,
+}
+
+#[derive(Clone)]
+struct NotificationTarget {
+    name: String,
+    events: Vec<NotificationEvent>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl Notifiers {
+    pub fn with_notifier(
+        mut self,
+        name: impl Into<String>,
+        events: Vec<NotificationEvent>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
+        self.targets.push(NotificationTarget {
+            name: name.into(),
+            events,
+            notifier,
+        });
+        self
+    }
+}
+
+/// Sends `event` for `download` to every notifier that wants it and records
+/// each delivery on the download. Delivery failures are logged and recorded,
+/// but never fail the processing that triggered them.
+pub async fn notify<S: DownloadStore>(
+    store: &S,
+    notifiers: &Notifiers,
+    download: &TrackedDownload,
+    event: NotificationEvent,
+    message: impl Into<String>,
+) {
+    let notification = Notification {
+        event,
+        download_id: download.download_id.clone(),
+        title: download.title.clone(),
+        message: message.into(),
+    };
+    for target in &notifiers.targets {
+        if !target.events.contains(&event) {
+            continue;
+        }
+        let (status, message) = match target.notifier.notify(&notification).await {
+            Ok(()) => {
+                info!("Sent {} notification to {}", event.as_str(), target.name);
+                (NotificationDeliveryStatus::Delivered, None)
+            }
+            Err(err) => {
+                warn!(
+                    "Sending {} notification to {} failed: {err:#}",
+                    event.as_str(),
+                    target.name
+                );
+                (NotificationDeliveryStatus::Failed, Some(format!("{err:#}")))
+            }
+        };
+        if let Err(err) = store
+            .record_notification_delivery(
+                &download.download_id,
+                &target.name,
+                event,
+                status,
+                message.as_deref(),
+            )
+            .await
+        {
+            error!("Failed recording notification delivery: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::{anyhow, Result};
+    use async_trait::async_trait;
+    use tempfile::tempdir;
+
+    use super::{notify, Notifiers};
+    use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::ports::{DownloadStore, Notifier};
+    use crate::domain::{
+        Notification, NotificationDeliveryStatus, NotificationEvent, TrackedDownload,
+    };
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, notification: &Notification) -> Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            if self.fail {
+                return Err(anyhow!("HTTP 500"));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_only_subscribed_events_and_records_every_delivery() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Artist - Album".into(),
+            "completed".into(),
+            "/downloads/album".into(),
+            "importPending".into(),
+        );
+        store.upsert_tracked_download(&download).await.unwrap();
+        let chat = Arc::new(RecordingNotifier::default());
+        let email = Arc::new(RecordingNotifier {
+            fail: true,
+            ..RecordingNotifier::default()
+        });
+        let notifiers = Notifiers::default()
+            .with_notifier("chat", NotificationEvent::ALL.to_vec(), chat.clone())
+            .with_notifier("email", vec![NotificationEvent::Failed], email.clone());
+
+        notify(
+            &store,
+            &notifiers,
+            &download,
+            NotificationEvent::Completed,
+            "12 track(s) imported",
+        )
+        .await;
+        notify(
+            &store,
+            &notifiers,
+            &download,
+            NotificationEvent::Failed,
+            "no cue files found",
+        )
+        .await;
+
+        assert_eq!(
+            chat.sent
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|notification| notification.event)
+                .collect::<Vec<_>>(),
+            vec![NotificationEvent::Completed, NotificationEvent::Failed]
+        );
+        let sent_email = email.sent.lock().unwrap().clone();
+        assert_eq!(sent_email.len(), 1);
+        assert_eq!(sent_email[0].message, "no cue files found");
+        assert_eq!(sent_email[0].title, "Artist - Album");
+
+        let deliveries = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap()
+            .notification_deliveries;
+        assert_eq!(
+            deliveries
+                .iter()
+                .map(|delivery| (delivery.notifier.as_str(), delivery.event, delivery.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "email",
+                    NotificationEvent::Failed,
+                    NotificationDeliveryStatus::Failed
+                ),
+                (
+                    "chat",
+                    NotificationEvent::Failed,
+                    NotificationDeliveryStatus::Delivered
+                ),
+                (
+                    "chat",
+                    NotificationEvent::Completed,
+                    NotificationDeliveryStatus::Delivered
+                ),
+            ]
+        );
+        assert_eq!(deliveries[0].message.as_deref(), Some("HTTP 500"));
+    }
+}
@@ -1,16 +1,19 @@
+use std::future::Future;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::domain::{
-    CueSheet, CueSheetStatus, DiscoveredCueSheets, DownloadLifecycleState, InputFileKind,
-    QueueSnapshot, RecordedTrack, SplitOutcome, TrackCleanupOutcome, TrackCleanupStatus,
-    TrackedDownload,
+    CueFinding, CueRepair, CueSheet, CueSheetStatus, DiscoveredCueSheets, DownloadAction,
+    DownloadActionStatus, DownloadAuditEvent, DownloadLifecycleState, FileFingerprint, IgnoreRule,
+    IgnoreRuleKind, InProgressSplit, InputFileKind, Notification, NotificationDeliveryStatus,
+    NotificationEvent, PregapPolicy, QueueSnapshot, RecordedTrack, SourceVerificationStatus,
+    SplitOutcome, TrackCleanupOutcome, TrackCleanupStatus, TrackTag, TrackedDownload,
 };
 
-pub trait QueueSource {
-    async fn queue_snapshot(&self) -> Result<QueueSnapshot>;
+pub trait QueueSource: Send + Sync {
+    fn queue_snapshot(&self) -> impl Future<Output = Result<QueueSnapshot>> + Send;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,6 +35,9 @@ pub struct DownloadStats {
     pub completed: usize,
     pub failed: usize,
     pub awaiting_import: usize,
+    pub planned: usize,
+    pub ignored: usize,
+    pub gave_up: usize,
     pub in_progress: usize,
 }
 
@@ -43,74 +49,303 @@ pub trait DownloadReadStore {
     async fn load_download_stats(&self) -> Result<DownloadStats>;
 }
 
-pub trait DownloadStore {
-    async fn load_tracked_downloads(&self) -> Result<Vec<TrackedDownload>>;
-    async fn load_tracked_download_summaries(&self) -> Result<Vec<TrackedDownload>> {
-        self.load_tracked_downloads().await
+#[async_trait]
+pub trait DownloadActionQueue {
+    async fn request_download_action(
+        &self,
+        download_id: &str,
+        action: DownloadAction,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+pub trait IgnoreRuleStore {
+    async fn load_ignore_rules(&self) -> Result<Vec<IgnoreRule>>;
+    async fn add_ignore_rule(&self, kind: IgnoreRuleKind, pattern: &str) -> Result<()>;
+    async fn remove_ignore_rule(&self, rule_id: i64) -> Result<()>;
+}
+
+pub trait DownloadStore: Send + Sync {
+    fn load_tracked_downloads(&self) -> impl Future<Output = Result<Vec<TrackedDownload>>> + Send;
+    fn load_tracked_download_summaries(
+        &self,
+    ) -> impl Future<Output = Result<Vec<TrackedDownload>>> + Send {
+        async move { self.load_tracked_downloads().await }
     }
-    async fn get_tracked_download(&self, download_id: &str) -> Result<Option<TrackedDownload>>;
-    async fn get_tracked_downloads(&self, download_ids: &[String]) -> Result<Vec<TrackedDownload>> {
-        let mut downloads = Vec::new();
-        for download_id in download_ids {
-            if let Some(download) = self.get_tracked_download(download_id).await? {
-                downloads.push(download);
+    fn get_tracked_download(
+        &self,
+        download_id: &str,
+    ) -> impl Future<Output = Result<Option<TrackedDownload>>> + Send;
+    fn get_tracked_downloads(
+        &self,
+        download_ids: &[String],
+    ) -> impl Future<Output = Result<Vec<TrackedDownload>>> + Send {
+        async move {
+            let mut downloads = Vec::new();
+            for download_id in download_ids {
+                if let Some(download) = self.get_tracked_download(download_id).await? {
+                    downloads.push(download);
+                }
             }
+            Ok(downloads)
         }
-        Ok(downloads)
     }
-    async fn upsert_tracked_download(&self, download: &TrackedDownload) -> Result<()>;
-    async fn mark_download_processing(&self, download_id: &str) -> Result<()>;
-    async fn mark_download_awaiting_import(&self, download_id: &str) -> Result<()>;
-    async fn mark_download_cleanup_started(&self, download_id: &str) -> Result<()>;
-    async fn mark_download_completed(&self, download_id: &str) -> Result<()>;
-    async fn mark_download_failed(&self, download_id: &str, last_error: Option<&str>)
-        -> Result<()>;
-    async fn record_download_warning(&self, _download_id: &str, _message: &str) -> Result<()> {
-        Ok(())
+    fn upsert_tracked_download(
+        &self,
+        download: &TrackedDownload,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_processing(
+        &self,
+        download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_awaiting_import(
+        &self,
+        download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_cleanup_started(
+        &self,
+        download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_completed(&self, download_id: &str)
+        -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_failed(
+        &self,
+        download_id: &str,
+        last_error: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn mark_download_planned(
+        &self,
+        _download_id: &str,
+        _import_plan: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_download_warning(
+        &self,
+        _download_id: &str,
+        _message: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn mark_download_ignored(&self, _download_id: &str) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn schedule_download_retry(
+        &self,
+        _download_id: &str,
+        _attempt_count: u32,
+        _next_retry_at: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn mark_download_gave_up(
+        &self,
+        _download_id: &str,
+        _attempt_count: u32,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn reset_download_attempts(
+        &self,
+        _download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn load_ignore_rules(&self) -> impl Future<Output = Result<Vec<IgnoreRule>>> + Send {
+        async move { Ok(Vec::new()) }
+    }
+    fn add_ignore_rule(
+        &self,
+        _kind: IgnoreRuleKind,
+        _pattern: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    /// Claims a download for processing. Returns `false` while another worker
+    /// or process holds the claim.
+    fn try_lock_download(&self, _download_id: &str) -> impl Future<Output = Result<bool>> + Send {
+        async move { Ok(true) }
+    }
+    /// Tells other processes that the holder of the download's lock is still
+    /// alive. Locks that stop being refreshed are eventually taken over.
+    fn refresh_download_lock(&self, _download_id: &str) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn unlock_download(&self, _download_id: &str) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    /// Releases every download lock held by this store, for a shutdown that
+    /// gives up on in-flight work.
+    fn release_download_locks(&self) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_split_started(
+        &self,
+        _download_id: &str,
+        _output_dir: &str,
+        _existing_files: &[String],
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_split_finished(
+        &self,
+        _download_id: &str,
+        _output_dir: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn load_in_progress_splits(
+        &self,
+        _download_id: &str,
+    ) -> impl Future<Output = Result<Vec<InProgressSplit>>> + Send {
+        async move { Ok(Vec::new()) }
     }
-    async fn get_or_create_cue_sheet(&self, download_id: &str, path: &Path) -> Result<CueSheet>;
-    async fn record_input_file(
+    /// Moves a download that was interrupted while processing or cleaning up
+    /// back to the state that makes the monitor pick it up again.
+    fn requeue_interrupted_download(
+        &self,
+        _download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn load_pending_download_actions(
+        &self,
+    ) -> impl Future<Output = Result<Vec<DownloadAuditEvent>>> + Send {
+        async move { Ok(Vec::new()) }
+    }
+    fn finish_download_action(
+        &self,
+        _event_id: i64,
+        _status: DownloadActionStatus,
+        _message: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_notification_delivery(
+        &self,
+        _download_id: &str,
+        _notifier: &str,
+        _event: NotificationEvent,
+        _status: NotificationDeliveryStatus,
+        _message: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn get_or_create_cue_sheet(
+        &self,
+        download_id: &str,
+        path: &Path,
+    ) -> impl Future<Output = Result<CueSheet>> + Send;
+    fn record_input_file(
         &self,
         download_id: &str,
         cue_sheet_id: Option<&str>,
         path: &Path,
         kind: InputFileKind,
         size_bytes: Option<i64>,
-    ) -> Result<()>;
-    async fn record_cue_result(
+        audio_format: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send;
+    /// Keeps the first fingerprint recorded for a file across attempts and
+    /// returns it, so later attempts verify against the original download.
+    fn record_input_fingerprint(
+        &self,
+        _download_id: &str,
+        _path: &Path,
+        fingerprint: &FileFingerprint,
+    ) -> impl Future<Output = Result<FileFingerprint>> + Send {
+        let fingerprint = fingerprint.clone();
+        async move { Ok(fingerprint) }
+    }
+    fn clear_input_fingerprints(
+        &self,
+        _download_id: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_input_verification(
+        &self,
+        _download_id: &str,
+        _path: &Path,
+        _status: SourceVerificationStatus,
+        _message: Option<&str>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_cue_findings(
+        &self,
+        _cue_sheet: &CueSheet,
+        _findings: &[CueFinding],
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_cue_repairs(
+        &self,
+        _cue_sheet: &CueSheet,
+        _repairs: &[CueRepair],
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_cue_encoding(
+        &self,
+        _cue_sheet: &CueSheet,
+        _encoding: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_cue_pregap_policy(
+        &self,
+        _cue_sheet: &CueSheet,
+        _pregap_policy: PregapPolicy,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_cue_result(
         &self,
         cue_sheet: &CueSheet,
         status: CueSheetStatus,
         message: Option<&str>,
         tracks: &[RecordedTrack],
-    ) -> Result<()>;
-    async fn record_track_cleanup(
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn record_track_tags(
+        &self,
+        _download_id: &str,
+        _track_path: &Path,
+        _tags: &[TrackTag],
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn record_track_cleanup(
         &self,
         download_id: &str,
         track_id: &str,
         status: TrackCleanupStatus,
         message: Option<&str>,
-    ) -> Result<()>;
-    async fn record_track_cleanups(
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn record_track_cleanups(
         &self,
         download_id: &str,
         outcomes: &[TrackCleanupOutcome],
-    ) -> Result<()> {
-        for outcome in outcomes {
-            self.record_track_cleanup(
-                download_id,
-                &outcome.track_id,
-                outcome.status,
-                outcome.message.as_deref(),
-            )
-            .await?;
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            for outcome in outcomes {
+                self.record_track_cleanup(
+                    download_id,
+                    &outcome.track_id,
+                    outcome.status,
+                    outcome.message.as_deref(),
+                )
+                .await?;
+            }
+            Ok(())
         }
-        Ok(())
     }
 }
 
-pub trait CueScanner {
-    async fn find_cue_sheets(&self, root: &Path) -> Result<DiscoveredCueSheets>;
+pub trait CueScanner: Send + Sync {
+    fn find_cue_sheets(
+        &self,
+        root: &Path,
+    ) -> impl Future<Output = Result<DiscoveredCueSheets>> + Send;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -123,39 +358,195 @@ pub struct CueInputSnapshot {
 pub struct CueReferencedAudioInput {
     pub path: PathBuf,
     pub size_bytes: Option<i64>,
+    pub audio_format: Option<String>,
 }
 
-pub trait CueInputInspector {
-    async fn file_size(&self, path: &Path) -> Result<Option<i64>>;
-    async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot>;
-    async fn cue_references_audio_file(&self, cue_path: &Path, audio_path: &Path) -> Result<bool>;
-    async fn filter_cue_files_for_audio(
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairedCue {
+    pub path: PathBuf,
+    pub repairs: Vec<CueRepair>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCue {
+    pub text: String,
+    pub encoding: String,
+}
+
+pub trait CueInputInspector: Send + Sync {
+    fn file_size(&self, path: &Path) -> impl Future<Output = Result<Option<i64>>> + Send;
+    fn fingerprint_file(
+        &self,
+        path: &Path,
+    ) -> impl Future<Output = Result<Option<FileFingerprint>>> + Send {
+        async move {
+            Ok(self
+                .file_size(path)
+                .await?
+                .map(|size_bytes| FileFingerprint {
+                    size_bytes,
+                    modified_ns: None,
+                    content_hash: None,
+                }))
+        }
+    }
+    fn snapshot_inputs(
+        &self,
+        cue_path: &Path,
+    ) -> impl Future<Output = Result<CueInputSnapshot>> + Send;
+    fn validate_cue(
+        &self,
+        _cue_path: &Path,
+    ) -> impl Future<Output = Result<Vec<CueFinding>>> + Send {
+        async move { Ok(Vec::new()) }
+    }
+    fn decode_cue(
+        &self,
+        _cue_path: &Path,
+    ) -> impl Future<Output = Result<Option<DecodedCue>>> + Send {
+        async move { Ok(None) }
+    }
+    fn repair_cue(
+        &self,
+        _cue_path: &Path,
+        _cue_sheet_id: &str,
+    ) -> impl Future<Output = Result<Option<RepairedCue>>> + Send {
+        async move { Ok(None) }
+    }
+    fn cue_references_audio_file(
+        &self,
+        cue_path: &Path,
+        audio_path: &Path,
+    ) -> impl Future<Output = Result<bool>> + Send;
+    fn list_output_files(
+        &self,
+        _output_dir: &Path,
+    ) -> impl Future<Output = Result<Vec<PathBuf>>> + Send {
+        async move { Ok(Vec::new()) }
+    }
+    fn filter_cue_files_for_audio(
         &self,
         cue_files: Vec<PathBuf>,
         audio_path: &Path,
-    ) -> Result<Vec<PathBuf>> {
-        let mut matching = Vec::new();
-        for cue_path in cue_files {
-            if self
-                .cue_references_audio_file(&cue_path, audio_path)
-                .await?
-            {
-                matching.push(cue_path);
+    ) -> impl Future<Output = Result<Vec<PathBuf>>> + Send {
+        async move {
+            let mut matching = Vec::new();
+            for cue_path in cue_files {
+                if self
+                    .cue_references_audio_file(&cue_path, audio_path)
+                    .await?
+                {
+                    matching.push(cue_path);
+                }
             }
+            Ok(matching)
         }
-        Ok(matching)
     }
 }
 
-pub trait CueSplitter {
-    async fn split_cue(&self, cue_path: &Path) -> Result<SplitOutcome>;
+pub trait CueSplitter: Send + Sync {
+    fn split_cue(
+        &self,
+        cue_path: &Path,
+        output_dir: &Path,
+    ) -> impl Future<Output = Result<SplitOutcome>> + Send;
+}
+
+pub trait TrackTagger: Send + Sync {
+    fn write_track_tags(
+        &self,
+        track_path: &Path,
+        tags: &[TrackTag],
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn find_artwork(
+        &self,
+        _cue_path: &Path,
+        _audio_inputs: &[PathBuf],
+    ) -> impl Future<Output = Result<Option<TrackArtwork>>> + Send {
+        async move { Ok(None) }
+    }
+    fn write_track_artwork(
+        &self,
+        _track_path: &Path,
+        _artwork: &TrackArtwork,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move { Ok(()) }
+    }
+    fn write_folder_artwork(
+        &self,
+        _directory: &Path,
+        _artwork: &TrackArtwork,
+    ) -> impl Future<Output = Result<Option<PathBuf>>> + Send {
+        async move { Ok(None) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtworkSource {
+    File(PathBuf),
+    Embedded(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackArtwork {
+    pub source: ArtworkSource,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl TrackArtwork {
+    pub fn input_path(&self) -> PathBuf {
+        match &self.source {
+            ArtworkSource::File(path) => path.clone(),
+            ArtworkSource::Embedded(path) => {
+                let mut input_path = path.clone().into_os_string();
+                input_path.push("#picture");
+                PathBuf::from(input_path)
+            }
+        }
+    }
+
+    pub fn format_label(&self) -> &str {
+        match self.mime_type.as_str() {
+            "image/jpeg" => "JPEG",
+            "image/png" => "PNG",
+            other => other,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type.as_str() {
+            "image/png" => "png",
+            _ => "jpg",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManualImportRequest {
     pub download: TrackedDownload,
+    pub import_folder: String,
     pub generated_tracks: Vec<PathBuf>,
     pub cue_hints: Vec<CueMetadataHint>,
+    pub discs: Vec<ImportDisc>,
+    /// The disc lookup made before splitting, reused instead of looking the
+    /// discs up again.
+    pub musicbrainz_lookup: Option<MusicBrainzDiscLookupResult>,
+}
+
+impl ManualImportRequest {
+    pub fn disc_for(&self, track: &Path) -> Option<&ImportDisc> {
+        self.discs
+            .iter()
+            .find(|disc| disc.tracks.iter().any(|candidate| candidate == track))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDisc {
+    pub disc_number: u32,
+    pub cue_path: PathBuf,
+    pub tracks: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -165,6 +556,7 @@ pub struct CueMetadataHint {
     pub performer: Option<String>,
     pub catalog: Option<String>,
     pub disc_id: Option<String>,
+    pub disc_number: Option<u32>,
     pub comments: Vec<(String, String)>,
     pub track_count: usize,
     pub tracks: Vec<CueTrackHint>,
@@ -188,13 +580,17 @@ pub enum ManualImportResult {
         reason: String,
         diagnostic: String,
     },
+    Planned {
+        imported_track_count: usize,
+        diagnostic: String,
+    },
 }
 
-pub trait ManualImportTrigger {
-    async fn trigger_manual_import(
+pub trait ManualImportTrigger: Send + Sync {
+    fn trigger_manual_import(
         &self,
         request: ManualImportRequest,
-    ) -> Result<ManualImportResult>;
+    ) -> impl Future<Output = Result<ManualImportResult>> + Send;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -316,14 +712,36 @@ impl DiscReleaseLookup for NoopDiscReleaseLookup {
     }
 }
 
-pub trait TrackCleanup {
-    async fn cleanup_download_tracks(
+pub trait TrackCleanup: Send + Sync {
+    fn cleanup_download_tracks(
         &self,
         download: &TrackedDownload,
-    ) -> Result<Vec<TrackCleanupOutcome>>;
+    ) -> impl Future<Output = Result<Vec<TrackCleanupOutcome>>> + Send;
+    /// Deletes generated audio files that an interrupted split left in its
+    /// output directory and returns their paths.
+    fn remove_partial_outputs(
+        &self,
+        _split: &InProgressSplit,
+    ) -> impl Future<Output = Result<Vec<PathBuf>>> + Send {
+        async move { Ok(Vec::new()) }
+    }
+}
+
+/// Delivers a notification to one external service, such as a chat webhook
+/// or a mailbox.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &Notification) -> Result<()>;
 }
 
-pub trait DownloadLog {
-    async fn write_download_log(&self, download: &TrackedDownload, content: &str) -> Result<()>;
-    async fn delete_download_log(&self, download: &TrackedDownload) -> Result<()>;
+pub trait DownloadLog: Send + Sync {
+    fn write_download_log(
+        &self,
+        download: &TrackedDownload,
+        content: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn delete_download_log(
+        &self,
+        download: &TrackedDownload,
+    ) -> impl Future<Output = Result<()>> + Send;
 }
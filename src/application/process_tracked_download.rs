@@ -1,21 +1,33 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
-use rcue::parser::parse_from_file;
+use rcue::parser::{parse, parse_from_file};
+use tracing::{error, info, warn};
 
+use crate::application::disc_grouping::assign_disc_numbers;
+use crate::application::ignore_list::IgnoreList;
+use crate::application::notifications::notify;
 use crate::application::ports::{
-    CueInputInspector, CueInputSnapshot, CueMetadataHint, CueScanner, CueSplitter, DownloadLog,
-    DownloadStore, ManualImportRequest, ManualImportResult, ManualImportTrigger,
+    CueInputInspector, CueInputSnapshot, CueMetadataHint, CueScanner, CueSplitter, DecodedCue,
+    DownloadLog, DownloadStore, ImportDisc, ManualImportRequest, ManualImportResult,
+    ManualImportTrigger, TrackTagger,
 };
+use crate::application::service::ProcessingAdapters;
+use crate::application::tag_generated_tracks::{
+    embed_track_artwork, tag_generated_tracks, ArtworkTargets,
+};
+use crate::application::verify_source_files::{fingerprint_source_files, verify_source_files};
 use crate::domain::{
-    CueSheet, CueSheetStatus, FailedImportCandidate, InputFileKind, RecordedTrack, SplitOutcome,
-    SplitStatus, TrackedDownload,
+    CueFinding, CueSheet, CueSheetStatus, DownloadLifecycleState, FailedImportCandidate,
+    InputFileKind, NotificationEvent, OutputCodec, RecordedTrack, SplitOutcome, SplitStatus,
+    TrackCleanupStatus, TrackedDownload,
 };
 
 pub async fn register_failed_imports<S: DownloadStore>(
     store: &S,
     downloads: &mut Vec<TrackedDownload>,
     candidates: &[FailedImportCandidate],
+    ignore_list: &IgnoreList,
 ) -> Result<()> {
     for candidate in candidates {
         if let Some(download) = downloads
@@ -26,17 +38,27 @@ pub async fn register_failed_imports<S: DownloadStore>(
             download.status = candidate.status.clone();
             download.output_path = candidate.output_path.clone();
             download.tracked_download_state = candidate.tracked_download_state.clone();
+            if ignore_list.is_ignored(download) {
+                if download.lifecycle_state.is_ready_for_processing() {
+                    download.lifecycle_state = DownloadLifecycleState::Ignored;
+                }
+            } else if download.lifecycle_state == DownloadLifecycleState::Ignored {
+                download.lifecycle_state = DownloadLifecycleState::Detected;
+            }
             store.upsert_tracked_download(download).await?;
             continue;
         }
 
-        let download = TrackedDownload::pending(
+        let mut download = TrackedDownload::pending(
             candidate.download_id.clone(),
             candidate.title.clone(),
             candidate.status.clone(),
             candidate.output_path.clone(),
             candidate.tracked_download_state.clone(),
         );
+        if ignore_list.is_ignored(&download) {
+            download.lifecycle_state = DownloadLifecycleState::Ignored;
+        }
         store.upsert_tracked_download(&download).await?;
         downloads.push(download);
     }
@@ -44,13 +66,9 @@ pub async fn register_failed_imports<S: DownloadStore>(
     Ok(())
 }
 
-pub async fn process_tracked_download<S, C, I, P, M, L>(
+pub async fn process_tracked_download<S, C, I, P, M, L, X, T>(
     store: &S,
-    scanner: &C,
-    inspector: &I,
-    splitter: &P,
-    manual_import: &M,
-    download_log: &L,
+    adapters: &ProcessingAdapters<C, I, P, M, L, X, T>,
     download: TrackedDownload,
 ) -> Result<()>
 where
@@ -60,7 +78,14 @@ where
     P: CueSplitter,
     M: ManualImportTrigger,
     L: DownloadLog,
+    T: TrackTagger,
 {
+    let scanner = &adapters.cue_scanner;
+    let inspector = &adapters.cue_input_inspector;
+    let splitter = &adapters.cue_splitter;
+    let manual_import = &adapters.manual_import;
+    let download_log = &adapters.download_log;
+
     store
         .mark_download_processing(&download.download_id)
         .await?;
@@ -86,12 +111,12 @@ where
         append_log_line(&mut log, format!("  - {}", cue_path.display()));
     }
     for error in &scan.errors {
-        eprintln!("Scan warning for {}: {error}", download.title);
+        warn!("Scan warning: {error}");
         append_log_line(&mut log, format!("Scan warning: {error}"));
     }
 
     if scan.cue_files.is_empty() {
-        eprintln!("Failed processing {}: no cue files found", download.title);
+        error!("Failed processing: no cue files found");
         append_log_line(&mut log, "Final state: failed");
         append_log_line(&mut log, "Failure: no cue files found");
         write_log_best_effort(download_log, &download, &log).await;
@@ -104,21 +129,70 @@ where
     let mut all_cues_complete = true;
     let mut failures = Vec::new();
     let mut generated_tracks = Vec::new();
+    let mut discs = Vec::new();
+    let mut decoded_cues = Vec::new();
     let mut cue_hints = Vec::new();
-
-    for cue_path in scan.cue_files {
-        cue_hints.push(cue_metadata_hint(&cue_path));
+    for cue_path in &scan.cue_files {
+        let decoded_cue = inspector.decode_cue(cue_path).await?;
+        cue_hints.push(cue_metadata_hint(cue_path, decoded_cue.as_ref()));
+        decoded_cues.push(decoded_cue);
+    }
+    assign_disc_numbers(&mut cue_hints);
+    let musicbrainz = adapters.musicbrainz.select(&cue_hints).await;
+    for line in &musicbrainz.log {
+        append_log_line(&mut log, line);
+    }
+    let output_dirs = adapters
+        .split_layout
+        .output_dirs(&download.download_id, &scan.cue_files);
+
+    for (((cue_path, output_dir), decoded_cue), cue_hint) in scan
+        .cue_files
+        .into_iter()
+        .zip(output_dirs)
+        .zip(decoded_cues)
+        .zip(cue_hints.clone())
+    {
         append_log_line(&mut log, "");
         append_log_line(&mut log, format!("Cue: {}", cue_path.display()));
+        if let Some(disc_number) = cue_hint.disc_number {
+            append_log_line(&mut log, format!("Disc: {disc_number}"));
+        }
         let cue_sheet = store
             .get_or_create_cue_sheet(&download.download_id, &cue_path)
             .await?;
+        if let Some(decoded_cue) = &decoded_cue {
+            append_log_line(&mut log, format!("Encoding: {}", decoded_cue.encoding));
+            store
+                .record_cue_encoding(&cue_sheet, &decoded_cue.encoding)
+                .await?;
+        }
+        let mut split_cue_path = cue_path.clone();
+        if !cue_sheet.status.is_terminal_success() {
+            let repaired = inspector.repair_cue(&cue_path, &cue_sheet.id).await?;
+            let repairs = repaired
+                .as_ref()
+                .map(|repaired| repaired.repairs.as_slice())
+                .unwrap_or_default();
+            store.record_cue_repairs(&cue_sheet, repairs).await?;
+            if let Some(repaired) = repaired {
+                append_log_line(
+                    &mut log,
+                    format!("Repaired CUE: {}", repaired.path.display()),
+                );
+                for repair in &repaired.repairs {
+                    append_log_line(&mut log, format!("  repaired: {}", repair.describe()));
+                }
+                split_cue_path = repaired.path;
+            }
+        }
         let snapshot = snapshot_input_files(
             store,
             inspector,
             &download.download_id,
             &cue_sheet,
             &cue_path,
+            &split_cue_path,
         )
         .await?;
         append_input_snapshot(&mut log, &snapshot);
@@ -132,43 +206,208 @@ where
                     cue_sheet.tracks.len()
                 ),
             );
-            generated_tracks.extend(
+            add_cue_tracks(
+                &mut generated_tracks,
+                &mut discs,
+                &cue_hint,
                 cue_sheet
                     .tracks
                     .iter()
-                    .map(|track| PathBuf::from(&track.path)),
+                    .map(|track| PathBuf::from(&track.path))
+                    .collect(),
+            );
+            continue;
+        }
+
+        // Fingerprinted together with the input files, so changes made any
+        // time before or during the split are caught.
+        let source_paths = std::iter::once(cue_path.clone())
+            .chain(snapshot.audio_inputs.iter().map(|input| input.path.clone()))
+            .collect::<Vec<_>>();
+        let sources =
+            fingerprint_source_files(store, inspector, &download.download_id, &source_paths)
+                .await?;
+        append_log_line(
+            &mut log,
+            format!(
+                "Source fingerprints: {} file(s) recorded",
+                sources.fingerprints.len()
+            ),
+        );
+        if !sources.missing.is_empty() {
+            all_cues_complete = false;
+            let message = format!("source files are missing: {}", sources.missing.join("; "));
+            error!(cue = %cue_path.display(), "Skipping split: {message}");
+            failures.push(format!("{}: {message}", cue_path.display()));
+            append_log_line(&mut log, format!("Split skipped: {message}"));
+            store
+                .record_cue_result(&cue_sheet, CueSheetStatus::Failed, Some(&message), &[])
+                .await?;
+            continue;
+        }
+
+        let findings = inspector.validate_cue(&split_cue_path).await?;
+        store.record_cue_findings(&cue_sheet, &findings).await?;
+        for finding in &findings {
+            append_log_line(
+                &mut log,
+                format!(
+                    "Validation {}: {}",
+                    if finding.is_error() {
+                        "error"
+                    } else {
+                        "warning"
+                    },
+                    finding.describe()
+                ),
             );
+        }
+        let validation_errors = findings
+            .iter()
+            .filter(|finding| finding.is_error())
+            .map(CueFinding::describe)
+            .collect::<Vec<_>>();
+        if !validation_errors.is_empty() {
+            all_cues_complete = false;
+            let message = format!("CUE validation failed: {}", validation_errors.join("; "));
+            error!(cue = %cue_path.display(), "Skipping split: {message}");
+            failures.push(format!("{}: {message}", cue_path.display()));
+            append_log_line(&mut log, format!("Split skipped: {message}"));
+            store
+                .record_cue_result(&cue_sheet, CueSheetStatus::Failed, Some(&message), &[])
+                .await?;
             continue;
         }
 
-        match splitter.split_cue(&cue_path).await {
+        append_log_line(
+            &mut log,
+            format!("Output directory: {}", output_dir.display()),
+        );
+        let problems = verify_source_files(
+            store,
+            inspector,
+            &download.download_id,
+            &sources.fingerprints,
+        )
+        .await?;
+        if !problems.is_empty() {
+            all_cues_complete = false;
+            let message = format!(
+                "source files changed before splitting: {}",
+                problems.join("; ")
+            );
+            error!(cue = %cue_path.display(), "Skipping split: {message}");
+            failures.push(format!("{}: {message}", cue_path.display()));
+            append_log_line(&mut log, format!("Split skipped: {message}"));
+            store
+                .record_cue_result(&cue_sheet, CueSheetStatus::Failed, Some(&message), &[])
+                .await?;
+            continue;
+        }
+        let mut cue_tracks = Vec::new();
+        let output_dir_text = output_dir.to_string_lossy();
+        if !adapters.dry_run {
+            // Lets startup recovery remove what an interrupted split leaves behind.
+            let existing_files = inspector
+                .list_output_files(&output_dir)
+                .await?
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            store
+                .record_split_started(&download.download_id, &output_dir_text, &existing_files)
+                .await?;
+        }
+        let split_result = splitter.split_cue(&split_cue_path, &output_dir).await;
+        if !adapters.dry_run {
+            store
+                .record_split_finished(&download.download_id, &output_dir_text)
+                .await?;
+        }
+        match split_result {
             Ok(result) => {
                 let tracks = result.tracks.clone();
                 append_log_line(
                     &mut log,
                     format!(
-                        "Split status: {:?}; generated {} track(s)",
+                        "Split status: {:?}; generated {} {} track(s)",
                         result.status,
-                        result.tracks.len()
+                        result.tracks.len(),
+                        result.codec.as_str()
                     ),
                 );
                 if let Some(message) = &result.message {
                     append_log_line(&mut log, format!("Split message: {message}"));
                 }
+                if let Some(pregap_policy) = result.pregap_policy {
+                    append_log_line(&mut log, format!("Pregap: {}", pregap_policy.as_str()));
+                }
                 for track in &tracks {
                     append_log_line(&mut log, format!("  generated: {}", track.display()));
                 }
+                if let Some(hidden_track) = &result.hidden_track {
+                    append_log_line(
+                        &mut log,
+                        format!(
+                            "  hidden track: {} (kept, not imported)",
+                            hidden_track.display()
+                        ),
+                    );
+                }
+                let split = result.status == SplitStatus::Split;
+                let codec = result.codec;
                 store_split_result(store, inspector, &cue_sheet, result).await?;
-                generated_tracks.extend(tracks);
+                if split && !tracks.is_empty() {
+                    if codec == OutputCodec::Flac {
+                        for line in tag_generated_tracks(
+                            store,
+                            &adapters.track_tagger,
+                            &download.download_id,
+                            &cue_hint,
+                            musicbrainz.release.as_ref(),
+                            &tracks,
+                        )
+                        .await
+                        {
+                            append_log_line(&mut log, line);
+                        }
+                    } else {
+                        append_log_line(
+                            &mut log,
+                            format!("Tagging: skipped for {} output", codec.as_str()),
+                        );
+                    }
+                    let audio_inputs = snapshot
+                        .audio_inputs
+                        .iter()
+                        .map(|input| input.path.clone())
+                        .collect::<Vec<_>>();
+                    for line in embed_track_artwork(
+                        store,
+                        &adapters.track_tagger,
+                        &cue_sheet,
+                        ArtworkTargets {
+                            cue_path: &cue_path,
+                            audio_inputs: &audio_inputs,
+                            tracks: &tracks,
+                            codec,
+                            folder_artwork: adapters
+                                .split_layout
+                                .staging_root(&download.download_id)
+                                .is_some(),
+                        },
+                    )
+                    .await
+                    {
+                        append_log_line(&mut log, line);
+                    }
+                }
+                cue_tracks = tracks;
             }
             Err(err) => {
                 all_cues_complete = false;
                 let message = err.to_string();
-                eprintln!(
-                    "Failed splitting cue for {} at {}: {err:#}",
-                    download.title,
-                    cue_path.display()
-                );
+                error!(cue = %cue_path.display(), "Failed splitting cue: {err:#}");
                 failures.push(format!("{}: {message}", cue_path.display()));
                 append_log_line(&mut log, format!("Split failed: {message}"));
                 store
@@ -176,6 +415,36 @@ where
                     .await?;
             }
         }
+
+        let problems = verify_source_files(
+            store,
+            inspector,
+            &download.download_id,
+            &sources.fingerprints,
+        )
+        .await?;
+        if problems.is_empty() {
+            append_log_line(
+                &mut log,
+                format!(
+                    "Source verification: {} file(s) unchanged",
+                    sources.fingerprints.len()
+                ),
+            );
+            add_cue_tracks(&mut generated_tracks, &mut discs, &cue_hint, cue_tracks);
+        } else {
+            all_cues_complete = false;
+            let message = format!(
+                "source files changed during processing: {}",
+                problems.join("; ")
+            );
+            error!(cue = %cue_path.display(), "Source verification failed: {message}");
+            failures.push(format!("{}: {message}", cue_path.display()));
+            append_log_line(&mut log, format!("Source verification failed: {message}"));
+            store
+                .record_cue_result(&cue_sheet, CueSheetStatus::Failed, Some(&message), &[])
+                .await?;
+        }
     }
 
     let error_message = if failures.is_empty() {
@@ -184,38 +453,69 @@ where
         Some(failures.join("; "))
     };
     if all_cues_complete {
-        store
-            .mark_download_awaiting_import(&download.download_id)
-            .await?;
+        let final_state = if adapters.dry_run {
+            "planned"
+        } else {
+            store
+                .mark_download_awaiting_import(&download.download_id)
+                .await?;
+            "awaiting_import"
+        };
         append_log_line(&mut log, "");
-        append_log_line(&mut log, "Final processing state: awaiting_import");
+        append_log_line(&mut log, format!("Final processing state: {final_state}"));
         append_log_line(
             &mut log,
             format!("Generated tracks total: {}", generated_tracks.len()),
         );
+        let import_start = log.len();
+        if adapters.dry_run {
+            for track in &generated_tracks {
+                append_log_line(&mut log, format!("  planned: {}", track.display()));
+            }
+        }
         if !generated_tracks.is_empty() {
             let request = ManualImportRequest {
                 download: download.clone(),
+                import_folder: adapters
+                    .split_layout
+                    .import_folder(&download.download_id, &download.output_path),
                 generated_tracks,
                 cue_hints,
+                discs,
+                musicbrainz_lookup: musicbrainz.lookup,
             };
             match manual_import.trigger_manual_import(request).await {
                 Ok(result) => {
-                    println!("Manual import for {}: {result:?}", download.title);
+                    info!("Manual import: {result:?}");
                     append_manual_import_result(&mut log, &result);
+                    if let ManualImportResult::Skipped { reason, .. } = &result {
+                        notify(
+                            store,
+                            &adapters.notifiers,
+                            &download,
+                            NotificationEvent::ImportSkipped,
+                            reason.as_str(),
+                        )
+                        .await;
+                    }
                 }
                 Err(err) => {
                     let message = format!("manual import trigger failed: {err}");
-                    eprintln!(
-                        "Manual import trigger failed for {}: {err:#}",
-                        download.title
-                    );
+                    error!("Manual import trigger failed: {err:#}");
                     append_log_line(&mut log, "");
                     append_log_line(&mut log, "Manual import: failed");
                     append_log_line(&mut log, format!("{err:#}"));
                     store
                         .record_download_warning(&download.download_id, &message)
                         .await?;
+                    notify(
+                        store,
+                        &adapters.notifiers,
+                        &download,
+                        NotificationEvent::Failed,
+                        message,
+                    )
+                    .await;
                 }
             }
         } else {
@@ -224,6 +524,12 @@ where
                 "Manual import: skipped because no generated tracks exist",
             );
         }
+        if adapters.dry_run {
+            let import_plan = log[import_start..].trim_matches('\n');
+            store
+                .mark_download_planned(&download.download_id, Some(import_plan))
+                .await?;
+        }
     } else {
         append_log_line(&mut log, "");
         append_log_line(&mut log, "Final processing state: failed");
@@ -236,10 +542,85 @@ where
     }
 
     write_log_best_effort(download_log, &download, &log).await;
-    println!("Done processing {}", download.title);
+    info!("Done processing");
     Ok(())
 }
 
+/// Sends the tracks recorded for an already split download to Lidarr again,
+/// without scanning or splitting anything.
+pub async fn retrigger_manual_import<C, I, P, M, L, X, T>(
+    adapters: &ProcessingAdapters<C, I, P, M, L, X, T>,
+    download: &TrackedDownload,
+) -> Result<String>
+where
+    I: CueInputInspector,
+    M: ManualImportTrigger,
+{
+    if download.cue_sheets.is_empty() {
+        return Err(anyhow!("no cue sheets have been recorded"));
+    }
+    let mut cue_hints = Vec::new();
+    for cue_sheet in &download.cue_sheets {
+        if !cue_sheet.status.is_terminal_success() {
+            return Err(anyhow!("cue sheet has not been split: {}", cue_sheet.path));
+        }
+        let cue_path = PathBuf::from(&cue_sheet.path);
+        let decoded_cue = adapters.cue_input_inspector.decode_cue(&cue_path).await?;
+        cue_hints.push(cue_metadata_hint(&cue_path, decoded_cue.as_ref()));
+    }
+    assign_disc_numbers(&mut cue_hints);
+
+    let mut generated_tracks = Vec::new();
+    let mut discs = Vec::new();
+    for (cue_sheet, cue_hint) in download.cue_sheets.iter().zip(&cue_hints) {
+        let tracks = cue_sheet
+            .tracks
+            .iter()
+            .filter(|track| track.cleanup_status == TrackCleanupStatus::Pending)
+            .map(|track| PathBuf::from(&track.path))
+            .collect();
+        add_cue_tracks(&mut generated_tracks, &mut discs, cue_hint, tracks);
+    }
+    if generated_tracks.is_empty() {
+        return Err(anyhow!("no generated tracks are left to import"));
+    }
+
+    let request = ManualImportRequest {
+        download: download.clone(),
+        import_folder: adapters
+            .split_layout
+            .import_folder(&download.download_id, &download.output_path),
+        generated_tracks,
+        cue_hints,
+        discs,
+        musicbrainz_lookup: None,
+    };
+    let result = adapters
+        .manual_import
+        .trigger_manual_import(request)
+        .await?;
+    info!("Manual import: {result:?}");
+    let mut log = String::new();
+    append_manual_import_result(&mut log, &result);
+    Ok(log.trim().to_owned())
+}
+
+fn add_cue_tracks(
+    generated_tracks: &mut Vec<PathBuf>,
+    discs: &mut Vec<ImportDisc>,
+    cue_hint: &CueMetadataHint,
+    tracks: Vec<PathBuf>,
+) {
+    if let Some(disc_number) = cue_hint.disc_number {
+        discs.push(ImportDisc {
+            disc_number,
+            cue_path: cue_hint.path.clone(),
+            tracks: tracks.clone(),
+        });
+    }
+    generated_tracks.extend(tracks);
+}
+
 fn initial_download_log(download: &TrackedDownload) -> String {
     let mut log = String::new();
     append_log_line(&mut log, "Splittarr processing log");
@@ -296,6 +677,16 @@ fn append_manual_import_result(log: &mut String, result: &ManualImportResult) {
             append_log_line(log, format!("Manual import: skipped: {reason}"));
             append_log_line(log, diagnostic);
         }
+        ManualImportResult::Planned {
+            imported_track_count,
+            diagnostic,
+        } => {
+            append_log_line(
+                log,
+                format!("Manual import: planned for {imported_track_count} track(s)"),
+            );
+            append_log_line(log, diagnostic);
+        }
     }
 }
 
@@ -316,15 +707,15 @@ async fn write_log_best_effort<L: DownloadLog>(
     content: &str,
 ) {
     if let Err(err) = download_log.write_download_log(download, content).await {
-        eprintln!(
-            "Failed writing download log for {}: {err:#}",
-            download.title
-        );
+        warn!("Failed writing download log: {err:#}");
     }
 }
 
-fn cue_metadata_hint(cue_path: &Path) -> CueMetadataHint {
-    let cue = parse_from_file(&cue_path.to_string_lossy(), false).ok();
+fn cue_metadata_hint(cue_path: &Path, decoded_cue: Option<&DecodedCue>) -> CueMetadataHint {
+    let cue = match decoded_cue {
+        Some(decoded_cue) => parse(&mut decoded_cue.text.as_bytes(), false).ok(),
+        None => parse_from_file(&cue_path.to_string_lossy(), false).ok(),
+    };
     let Some(cue) = cue else {
         return CueMetadataHint {
             path: cue_path.to_path_buf(),
@@ -332,6 +723,7 @@ fn cue_metadata_hint(cue_path: &Path) -> CueMetadataHint {
             performer: None,
             catalog: None,
             disc_id: None,
+            disc_number: None,
             comments: Vec::new(),
             track_count: 0,
             tracks: Vec::new(),
@@ -355,6 +747,7 @@ fn cue_metadata_hint(cue_path: &Path) -> CueMetadataHint {
         performer: cue.performer,
         catalog: cue.catalog,
         disc_id: cue_comment_value(&cue.comments, "DISCID").map(str::to_owned),
+        disc_number: None,
         comments: cue.comments,
         track_count: cue.files.iter().map(|file| file.tracks.len()).sum(),
         tracks,
@@ -378,14 +771,21 @@ async fn store_split_result<S: DownloadStore, I: CueInputInspector>(
     let status = match result.status {
         SplitStatus::Split => CueSheetStatus::Split,
         SplitStatus::Skipped => CueSheetStatus::Skipped,
+        SplitStatus::Planned => CueSheetStatus::Planned,
     };
     let mut tracks = Vec::with_capacity(result.tracks.len());
     for path in &result.tracks {
         tracks.push(RecordedTrack {
             path: path.to_string_lossy().to_string(),
             size_bytes: inspector.file_size(path).await?,
+            codec: result.codec,
         });
     }
+    if let Some(pregap_policy) = result.pregap_policy {
+        store
+            .record_cue_pregap_policy(cue_sheet, pregap_policy)
+            .await?;
+    }
     store
         .record_cue_result(cue_sheet, status, result.message.as_deref(), &tracks)
         .await
@@ -397,8 +797,12 @@ async fn snapshot_input_files<S: DownloadStore, I: CueInputInspector>(
     download_id: &str,
     cue_sheet: &CueSheet,
     cue_path: &Path,
+    split_cue_path: &Path,
 ) -> Result<CueInputSnapshot> {
-    let snapshot = inspector.snapshot_inputs(cue_path).await?;
+    let mut snapshot = inspector.snapshot_inputs(split_cue_path).await?;
+    if split_cue_path != cue_path {
+        snapshot.cue_size_bytes = inspector.file_size(cue_path).await?;
+    }
     store
         .record_input_file(
             download_id,
@@ -406,6 +810,7 @@ async fn snapshot_input_files<S: DownloadStore, I: CueInputInspector>(
             cue_path,
             InputFileKind::Cue,
             snapshot.cue_size_bytes,
+            None,
         )
         .await?;
 
@@ -417,6 +822,7 @@ async fn snapshot_input_files<S: DownloadStore, I: CueInputInspector>(
                 &input.path,
                 InputFileKind::Audio,
                 input.size_bytes,
+                input.audio_format.as_deref(),
             )
             .await?;
     }
@@ -441,21 +847,31 @@ fn scan_root_for(output_path: &Path) -> Result<PathBuf> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::fs;
     use std::path::{Path, PathBuf};
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
 
     use anyhow::Result;
+    use async_trait::async_trait;
     use tempfile::tempdir;
 
-    use super::process_tracked_download;
+    use super::{process_tracked_download, register_failed_imports};
+    use crate::application::ignore_list::IgnoreList;
+    use crate::application::notifications::Notifiers;
     use crate::application::ports::{
         CueInputInspector, CueInputSnapshot, CueReferencedAudioInput, CueScanner, CueSplitter,
-        DownloadLog, DownloadStore, ManualImportRequest, ManualImportResult, ManualImportTrigger,
+        DecodedCue, DownloadLog, DownloadStore, ManualImportRequest, ManualImportResult,
+        ManualImportTrigger, Notifier, RepairedCue, TrackTagger,
     };
+    use crate::application::select_musicbrainz_release::MusicBrainzReleaseSelector;
+    use crate::application::service::ProcessingAdapters;
     use crate::domain::{
-        CueSheet, CueSheetStatus, DiscoveredCueSheets, GeneratedTrack, InputFileKind,
-        RecordedTrack, SplitOutcome, SplitStatus, TrackCleanupStatus, TrackedDownload,
+        CueFinding, CueFindingKind, CueFindingSeverity, CueRepair, CueRepairKind, CueSheet,
+        CueSheetStatus, DiscoveredCueSheets, DownloadLifecycleState, FailedImportCandidate,
+        FileFingerprint, GeneratedTrack, IgnoreRule, IgnoreRuleKind, InputFileKind, Notification,
+        NotificationEvent, OutputCodec, RecordedTrack, SplitLayout, SplitOutcome, SplitStatus,
+        TrackCleanupStatus, TrackTag, TrackedDownload,
     };
 
     #[derive(Default)]
@@ -467,6 +883,8 @@ mod tests {
         recorded_tracks: Mutex<Vec<String>>,
         cue_sheets: Mutex<Vec<CueSheet>>,
         warnings: Mutex<Vec<String>>,
+        import_plan: Mutex<Option<String>>,
+        fingerprints: Mutex<HashMap<PathBuf, FileFingerprint>>,
     }
 
     impl DownloadStore for FakeStore {
@@ -495,6 +913,16 @@ mod tests {
             Ok(())
         }
 
+        async fn mark_download_planned(
+            &self,
+            _download_id: &str,
+            import_plan: Option<&str>,
+        ) -> Result<()> {
+            self.states.lock().unwrap().push("planned".into());
+            *self.import_plan.lock().unwrap() = import_plan.map(str::to_owned);
+            Ok(())
+        }
+
         async fn mark_download_cleanup_started(&self, _download_id: &str) -> Result<()> {
             Ok(())
         }
@@ -548,6 +976,10 @@ mod tests {
                 message: None,
                 updated_at: "now".into(),
                 tracks: Vec::new(),
+                findings: Vec::new(),
+                repairs: Vec::new(),
+                encoding: None,
+                pregap_policy: None,
             })
         }
 
@@ -558,6 +990,7 @@ mod tests {
             path: &Path,
             kind: InputFileKind,
             _size_bytes: Option<i64>,
+            _audio_format: Option<&str>,
         ) -> Result<()> {
             self.recorded_input_files
                 .lock()
@@ -566,6 +999,21 @@ mod tests {
             Ok(())
         }
 
+        async fn record_input_fingerprint(
+            &self,
+            _download_id: &str,
+            path: &Path,
+            fingerprint: &FileFingerprint,
+        ) -> Result<FileFingerprint> {
+            Ok(self
+                .fingerprints
+                .lock()
+                .unwrap()
+                .entry(path.to_path_buf())
+                .or_insert_with(|| fingerprint.clone())
+                .clone())
+        }
+
         async fn record_cue_result(
             &self,
             _cue_sheet: &CueSheet,
@@ -631,6 +1079,7 @@ mod tests {
                 vec![CueReferencedAudioInput {
                     path: audio_path,
                     size_bytes: Some(5),
+                    audio_format: Some("FLAC".into()),
                 }]
             } else {
                 Vec::new()
@@ -654,17 +1103,182 @@ mod tests {
         }
     }
 
+    struct ValidatingInspector {
+        inner: FakeInspector,
+        findings: Vec<CueFinding>,
+    }
+
+    impl CueInputInspector for ValidatingInspector {
+        async fn file_size(&self, path: &Path) -> Result<Option<i64>> {
+            self.inner.file_size(path).await
+        }
+
+        async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot> {
+            self.inner.snapshot_inputs(cue_path).await
+        }
+
+        async fn validate_cue(&self, _cue_path: &Path) -> Result<Vec<CueFinding>> {
+            Ok(self.findings.clone())
+        }
+
+        async fn cue_references_audio_file(
+            &self,
+            cue_path: &Path,
+            audio_path: &Path,
+        ) -> Result<bool> {
+            self.inner
+                .cue_references_audio_file(cue_path, audio_path)
+                .await
+        }
+    }
+
+    /// Loses the referenced audio file after the inputs were recorded, like a
+    /// download client moving it away while the CUE sheet is validated.
+    struct VanishingInspector {
+        inner: FakeInspector,
+    }
+
+    impl CueInputInspector for VanishingInspector {
+        async fn file_size(&self, path: &Path) -> Result<Option<i64>> {
+            self.inner.file_size(path).await
+        }
+
+        async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot> {
+            self.inner.snapshot_inputs(cue_path).await
+        }
+
+        async fn validate_cue(&self, cue_path: &Path) -> Result<Vec<CueFinding>> {
+            fs::remove_file(cue_path.with_extension("flac"))?;
+            Ok(Vec::new())
+        }
+
+        async fn cue_references_audio_file(
+            &self,
+            cue_path: &Path,
+            audio_path: &Path,
+        ) -> Result<bool> {
+            self.inner
+                .cue_references_audio_file(cue_path, audio_path)
+                .await
+        }
+    }
+
+    struct DecodingInspector {
+        inner: FakeInspector,
+        decoded: DecodedCue,
+    }
+
+    impl CueInputInspector for DecodingInspector {
+        async fn file_size(&self, path: &Path) -> Result<Option<i64>> {
+            self.inner.file_size(path).await
+        }
+
+        async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot> {
+            self.inner.snapshot_inputs(cue_path).await
+        }
+
+        async fn decode_cue(&self, _cue_path: &Path) -> Result<Option<DecodedCue>> {
+            Ok(Some(self.decoded.clone()))
+        }
+
+        async fn cue_references_audio_file(
+            &self,
+            cue_path: &Path,
+            audio_path: &Path,
+        ) -> Result<bool> {
+            self.inner
+                .cue_references_audio_file(cue_path, audio_path)
+                .await
+        }
+    }
+
+    struct RepairingInspector {
+        inner: FakeInspector,
+        repair_dir: PathBuf,
+    }
+
+    impl CueInputInspector for RepairingInspector {
+        async fn file_size(&self, path: &Path) -> Result<Option<i64>> {
+            self.inner.file_size(path).await
+        }
+
+        async fn snapshot_inputs(&self, cue_path: &Path) -> Result<CueInputSnapshot> {
+            self.inner.snapshot_inputs(cue_path).await
+        }
+
+        async fn repair_cue(
+            &self,
+            _cue_path: &Path,
+            _cue_sheet_id: &str,
+        ) -> Result<Option<RepairedCue>> {
+            let path = self.repair_dir.join("album.cue");
+            fs::write(&path, "FILE \"/downloads/album.flac\" WAVE\n").unwrap();
+            Ok(Some(RepairedCue {
+                path,
+                repairs: vec![CueRepair {
+                    kind: CueRepairKind::FileExtension,
+                    original: "album.wav".into(),
+                    replacement: "album.flac".into(),
+                }],
+            }))
+        }
+
+        async fn cue_references_audio_file(
+            &self,
+            cue_path: &Path,
+            audio_path: &Path,
+        ) -> Result<bool> {
+            self.inner
+                .cue_references_audio_file(cue_path, audio_path)
+                .await
+        }
+    }
+
     struct FakeSplitter {
         calls: Mutex<Vec<PathBuf>>,
     }
 
     impl CueSplitter for FakeSplitter {
-        async fn split_cue(&self, cue_path: &Path) -> Result<SplitOutcome> {
+        async fn split_cue(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
             self.calls.lock().unwrap().push(cue_path.to_path_buf());
             Ok(SplitOutcome {
                 status: SplitStatus::Split,
-                tracks: vec![cue_path.with_file_name("01 - Track.flac")],
+                tracks: vec![output_dir.join("01 - Track.flac")],
+                codec: OutputCodec::Flac,
+                message: None,
+                pregap_policy: None,
+                hidden_track: None,
+            })
+        }
+    }
+
+    struct PlanningSplitter;
+
+    impl CueSplitter for PlanningSplitter {
+        async fn split_cue(&self, _cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
+            Ok(SplitOutcome {
+                status: SplitStatus::Planned,
+                tracks: vec![output_dir.join("01 - Track.flac")],
+                codec: OutputCodec::Flac,
+                message: None,
+                pregap_policy: None,
+                hidden_track: None,
+            })
+        }
+    }
+
+    struct TamperingSplitter;
+
+    impl CueSplitter for TamperingSplitter {
+        async fn split_cue(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
+            fs::write(cue_path.with_extension("flac"), b"rewritten audio").unwrap();
+            Ok(SplitOutcome {
+                status: SplitStatus::Split,
+                tracks: vec![output_dir.join("01 - Track.flac")],
+                codec: OutputCodec::Flac,
                 message: None,
+                pregap_policy: None,
+                hidden_track: None,
             })
         }
     }
@@ -691,6 +1305,34 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct FakeNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for FakeNotifier {
+        async fn notify(&self, notification: &Notification) -> Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeTagger {
+        calls: Mutex<Vec<(PathBuf, Vec<TrackTag>)>>,
+    }
+
+    impl TrackTagger for FakeTagger {
+        async fn write_track_tags(&self, track_path: &Path, tags: &[TrackTag]) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((track_path.to_path_buf(), tags.to_vec()));
+            Ok(())
+        }
+    }
+
     #[derive(Default)]
     struct FakeDownloadLog {
         writes: Mutex<Vec<String>>,
@@ -711,6 +1353,70 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn register_failed_imports_applies_the_ignore_list() {
+        let candidate = |download_id: &str, title: &str| FailedImportCandidate {
+            download_id: download_id.to_owned(),
+            title: title.to_owned(),
+            status: "completed".to_owned(),
+            output_path: format!("/downloads/{download_id}"),
+            tracked_download_state: "importFailed".to_owned(),
+        };
+        let existing = |download_id: &str, state| {
+            let mut download = TrackedDownload::pending(
+                download_id.to_owned(),
+                download_id.to_owned(),
+                "completed".to_owned(),
+                format!("/downloads/{download_id}"),
+                "importFailed".to_owned(),
+            );
+            download.lifecycle_state = state;
+            download
+        };
+        let mut downloads = vec![
+            existing("failed", DownloadLifecycleState::Failed),
+            existing("awaiting", DownloadLifecycleState::AwaitingImport),
+            existing("unignored", DownloadLifecycleState::Ignored),
+        ];
+        let ignore_list = IgnoreList::new([
+            IgnoreRule::configured(IgnoreRuleKind::TitleRegex, "(?i)audiobook".to_owned()),
+            IgnoreRule::configured(IgnoreRuleKind::PathGlob, "/downloads/fail*".to_owned()),
+        ])
+        .unwrap();
+
+        register_failed_imports(
+            &FakeStore::default(),
+            &mut downloads,
+            &[
+                candidate("failed", "Album"),
+                candidate("awaiting", "Audiobook"),
+                candidate("unignored", "Album"),
+                candidate("new", "Some Audiobook"),
+                candidate("fresh", "Album"),
+            ],
+            &ignore_list,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            downloads
+                .iter()
+                .map(|download| (
+                    download.download_id.as_str(),
+                    download.lifecycle_state.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("failed", DownloadLifecycleState::Ignored),
+                ("awaiting", DownloadLifecycleState::AwaitingImport),
+                ("unignored", DownloadLifecycleState::Detected),
+                ("new", DownloadLifecycleState::Ignored),
+                ("fresh", DownloadLifecycleState::Detected),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn processes_file_output_path_using_parent_directory_and_matching_cue() {
         let tmp = tempdir().unwrap();
@@ -744,36 +1450,311 @@ mod tests {
             "importFailed".into(),
         );
 
-        process_tracked_download(
-            &store,
-            &scanner,
-            &inspector,
-            &splitter,
-            &manual_import,
-            &download_log,
-            download,
-        )
-        .await
-        .unwrap();
+        let adapters = ProcessingAdapters {
+            cue_scanner: scanner,
+            cue_input_inspector: inspector,
+            cue_splitter: splitter,
+            manual_import,
+            download_log,
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
 
         assert_eq!(
-            scanner.roots.lock().unwrap().as_slice(),
+            adapters.cue_scanner.roots.lock().unwrap().as_slice(),
             &[tmp.path().to_path_buf()]
         );
-        assert_eq!(splitter.calls.lock().unwrap().as_slice(), &[cue_path]);
+        assert_eq!(
+            adapters.cue_splitter.calls.lock().unwrap().as_slice(),
+            &[cue_path]
+        );
         assert!(store
             .states
             .lock()
             .unwrap()
             .contains(&"awaiting_import".to_string()));
-        assert_eq!(manual_import.calls.lock().unwrap().len(), 1);
-        let log_writes = download_log.writes.lock().unwrap();
+        assert_eq!(adapters.manual_import.calls.lock().unwrap().len(), 1);
+        let tag_calls = adapters.track_tagger.calls.lock().unwrap();
+        assert_eq!(tag_calls.len(), 1);
+        assert_eq!(tag_calls[0].0, tmp.path().join("01 - Track.flac"));
+        assert!(tag_calls[0].1.contains(&TrackTag {
+            name: "TITLE".into(),
+            value: "Track".into(),
+        }));
+        let log_writes = adapters.download_log.writes.lock().unwrap();
         assert!(log_writes
             .last()
             .unwrap()
             .contains("fake manual import diagnostic"));
     }
 
+    #[tokio::test]
+    async fn dry_run_records_an_import_plan_instead_of_awaiting_import() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(tmp.path().join("album.flac"), b"audio").unwrap();
+        fs::write(
+            &cue_path,
+            "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Track\"\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path],
+            },
+            cue_input_inspector: FakeInspector {
+                matches: Mutex::new(Vec::new()),
+            },
+            cue_splitter: PlanningSplitter,
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: true,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.states.lock().unwrap().as_slice(),
+            &["processing".to_string(), "planned".to_string()]
+        );
+        let import_plan = store.import_plan.lock().unwrap().clone().unwrap();
+        assert!(import_plan.starts_with(&format!(
+            "  planned: {}",
+            tmp.path().join("01 - Track.flac").display()
+        )));
+        assert!(import_plan.contains("fake manual import diagnostic"));
+        assert!(!import_plan.contains("Split status"));
+        assert!(adapters.track_tagger.calls.lock().unwrap().is_empty());
+        assert!(store.recorded_tracks.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn source_changed_during_split_fails_download() {
+        let tmp = tempdir().unwrap();
+        let audio_path = tmp.path().join("album.flac");
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&audio_path, b"audio").unwrap();
+        fs::write(
+            &cue_path,
+            "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Track\"\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: FakeInspector {
+                matches: Mutex::new(Vec::new()),
+            },
+            cue_splitter: TamperingSplitter,
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        assert!(store.states.lock().unwrap().contains(&"failed".to_string()));
+        assert_eq!(
+            store.last_error.lock().unwrap().as_deref(),
+            Some(
+                format!(
+                    "{}: source files changed during processing: {}: size changed from 5 to 15 bytes",
+                    cue_path.display(),
+                    audio_path.display()
+                )
+                .as_str()
+            )
+        );
+        assert!(adapters.manual_import.calls.lock().unwrap().is_empty());
+        assert_eq!(store.recorded_tracks.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn source_missing_before_split_fails_without_splitting() {
+        let tmp = tempdir().unwrap();
+        let audio_path = tmp.path().join("album.flac");
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&audio_path, b"audio").unwrap();
+        fs::write(&cue_path, "FILE \"album.flac\" WAVE\n").unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: VanishingInspector {
+                inner: FakeInspector {
+                    matches: Mutex::new(Vec::new()),
+                },
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        assert!(adapters.cue_splitter.calls.lock().unwrap().is_empty());
+        assert!(store.states.lock().unwrap().contains(&"failed".to_string()));
+        assert_eq!(
+            store.last_error.lock().unwrap().as_deref(),
+            Some(
+                format!(
+                    "{}: source files changed before splitting: {}: file is missing",
+                    cue_path.display(),
+                    audio_path.display()
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn source_changed_between_attempts_fails_the_next_attempt() {
+        let tmp = tempdir().unwrap();
+        let audio_path = tmp.path().join("album.flac");
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&audio_path, b"audio").unwrap();
+        fs::write(&cue_path, "FILE \"album.flac\" WAVE\n").unwrap();
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+        let store = FakeStore::default();
+        let failing = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: ValidatingInspector {
+                inner: FakeInspector {
+                    matches: Mutex::new(Vec::new()),
+                },
+                findings: vec![CueFinding {
+                    severity: CueFindingSeverity::Error,
+                    kind: CueFindingKind::PastEndOfAudio,
+                    track: Some("01".into()),
+                    message: "starts past the end of the audio".into(),
+                }],
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &failing, download.clone())
+            .await
+            .unwrap();
+        assert!(store.states.lock().unwrap().contains(&"failed".to_string()));
+
+        fs::write(&audio_path, b"rewritten audio").unwrap();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: FakeInspector {
+                matches: Mutex::new(Vec::new()),
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        assert!(adapters.cue_splitter.calls.lock().unwrap().is_empty());
+        assert_eq!(
+            store.last_error.lock().unwrap().as_deref(),
+            Some(
+                format!(
+                    "{}: source files changed before splitting: {}: size changed from 5 to 15 bytes",
+                    cue_path.display(),
+                    audio_path.display()
+                )
+                .as_str()
+            )
+        );
+    }
+
     #[tokio::test]
     async fn file_output_path_without_matching_cue_fails_with_no_cue_files_found() {
         let tmp = tempdir().unwrap();
@@ -807,31 +1788,44 @@ mod tests {
             "importFailed".into(),
         );
 
-        process_tracked_download(
-            &store,
-            &scanner,
-            &inspector,
-            &splitter,
-            &manual_import,
-            &download_log,
-            download,
-        )
-        .await
-        .unwrap();
+        let notifier = Arc::new(FakeNotifier::default());
+        let adapters = ProcessingAdapters {
+            cue_scanner: scanner,
+            cue_input_inspector: inspector,
+            cue_splitter: splitter,
+            manual_import,
+            download_log,
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default().with_notifier(
+                "chat",
+                NotificationEvent::ALL.to_vec(),
+                notifier.clone(),
+            ),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
 
-        assert_eq!(splitter.calls.lock().unwrap().len(), 0);
-        assert_eq!(manual_import.calls.lock().unwrap().len(), 0);
+        assert_eq!(adapters.cue_splitter.calls.lock().unwrap().len(), 0);
+        assert_eq!(adapters.manual_import.calls.lock().unwrap().len(), 0);
         assert_eq!(
             store.last_error.lock().unwrap().as_deref(),
             Some("no cue files found")
         );
-        assert!(download_log
+        assert!(adapters
+            .download_log
             .writes
             .lock()
             .unwrap()
             .last()
             .unwrap()
             .contains("Failure: no cue files found"));
+        // Failures are notified by the monitor once it gives up retrying.
+        assert!(notifier.sent.lock().unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -875,19 +1869,27 @@ mod tests {
             "importFailed".into(),
         );
 
-        process_tracked_download(
-            &store,
-            &scanner,
-            &inspector,
-            &splitter,
-            &manual_import,
-            &download_log,
-            download,
-        )
-        .await
-        .unwrap();
+        let adapters = ProcessingAdapters {
+            cue_scanner: scanner,
+            cue_input_inspector: inspector,
+            cue_splitter: splitter,
+            manual_import,
+            download_log,
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
 
-        assert_eq!(splitter.calls.lock().unwrap().as_slice(), &[target_cue]);
+        assert_eq!(
+            adapters.cue_splitter.calls.lock().unwrap().as_slice(),
+            &[target_cue]
+        );
     }
 
     #[tokio::test]
@@ -918,10 +1920,16 @@ mod tests {
                 download_id: "download-1".into(),
                 path: recorded_track.to_string_lossy().to_string(),
                 size_bytes: Some(5),
+                codec: OutputCodec::Flac,
                 cleanup_status: TrackCleanupStatus::Pending,
                 cleanup_message: None,
                 deleted_at: None,
+                tags: Vec::new(),
             }],
+            findings: Vec::new(),
+            repairs: Vec::new(),
+            encoding: None,
+            pregap_policy: None,
         });
         let scanner = FakeScanner {
             roots: Mutex::new(Vec::new()),
@@ -943,25 +1951,116 @@ mod tests {
             "importFailed".into(),
         );
 
-        process_tracked_download(
-            &store,
-            &scanner,
-            &inspector,
-            &splitter,
-            &manual_import,
-            &download_log,
-            download,
-        )
-        .await
-        .unwrap();
+        let adapters = ProcessingAdapters {
+            cue_scanner: scanner,
+            cue_input_inspector: inspector,
+            cue_splitter: splitter,
+            manual_import,
+            download_log,
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
 
-        assert!(splitter.calls.lock().unwrap().is_empty());
-        let calls = manual_import.calls.lock().unwrap();
+        assert!(adapters.cue_splitter.calls.lock().unwrap().is_empty());
+        let calls = adapters.manual_import.calls.lock().unwrap();
         assert_eq!(calls.len(), 1);
         assert_eq!(calls[0].generated_tracks, vec![recorded_track]);
         assert_eq!(calls[0].cue_hints[0].track_count, 1);
     }
 
+    #[tokio::test]
+    async fn staging_layout_splits_each_cue_into_its_own_directory() {
+        let tmp = tempdir().unwrap();
+        let download_dir = tmp.path().join("download");
+        let staging_dir = tmp.path().join("staging");
+        let cue_paths = ["CD1", "CD2"].map(|disc| download_dir.join(disc).join("album.cue"));
+        for cue_path in &cue_paths {
+            fs::create_dir_all(cue_path.parent().unwrap()).unwrap();
+            fs::write(
+                cue_path,
+                "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Track\"\n    INDEX 01 00:00:00\n",
+            )
+            .unwrap();
+        }
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: cue_paths.to_vec(),
+            },
+            cue_input_inspector: FakeInspector {
+                matches: Mutex::new(Vec::new()),
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::new(Some(staging_dir.clone())),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download/1".into(),
+            "Album".into(),
+            "completed".into(),
+            download_dir.to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        let staging_root = staging_dir.join("download_1");
+        assert_eq!(
+            store.recorded_tracks.lock().unwrap().as_slice(),
+            &[
+                staging_root
+                    .join("album")
+                    .join("01 - Track.flac")
+                    .to_string_lossy()
+                    .to_string(),
+                staging_root
+                    .join("album (2)")
+                    .join("01 - Track.flac")
+                    .to_string_lossy()
+                    .to_string(),
+            ]
+        );
+        let calls = adapters.manual_import.calls.lock().unwrap();
+        assert_eq!(calls[0].import_folder, staging_root.to_string_lossy());
+        assert_eq!(
+            calls[0].download.output_path,
+            download_dir.to_string_lossy()
+        );
+        let discs = calls[0]
+            .discs
+            .iter()
+            .map(|disc| (disc.disc_number, disc.cue_path.clone(), disc.tracks.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            discs,
+            vec![(1, cue_paths[0].clone(), 1), (2, cue_paths[1].clone(), 1)]
+        );
+        assert_eq!(
+            calls[0]
+                .disc_for(&calls[0].generated_tracks[1])
+                .map(|disc| disc.disc_number),
+            Some(2)
+        );
+    }
+
     #[tokio::test]
     async fn invalid_cue_snapshot_records_cue_file_and_continues_processing() {
         let tmp = tempdir().unwrap();
@@ -992,20 +2091,25 @@ mod tests {
             "importFailed".into(),
         );
 
-        process_tracked_download(
-            &store,
-            &scanner,
-            &inspector,
-            &splitter,
-            &manual_import,
-            &download_log,
-            download,
-        )
-        .await
-        .unwrap();
+        let adapters = ProcessingAdapters {
+            cue_scanner: scanner,
+            cue_input_inspector: inspector,
+            cue_splitter: splitter,
+            manual_import,
+            download_log,
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
 
         assert_eq!(
-            splitter.calls.lock().unwrap().as_slice(),
+            adapters.cue_splitter.calls.lock().unwrap().as_slice(),
             std::slice::from_ref(&cue_path)
         );
         assert_eq!(
@@ -1021,7 +2125,8 @@ mod tests {
             store.warnings.lock().unwrap().as_slice(),
             &["manual import trigger failed: lidarr is unavailable"]
         );
-        assert!(download_log
+        assert!(adapters
+            .download_log
             .writes
             .lock()
             .unwrap()
@@ -1029,4 +2134,194 @@ mod tests {
             .unwrap()
             .contains("Manual import: failed"));
     }
+
+    #[tokio::test]
+    async fn cue_validation_errors_skip_the_split() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&cue_path, "FILE \"album.flac\" WAVE\n").unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: ValidatingInspector {
+                inner: FakeInspector {
+                    matches: Mutex::new(Vec::new()),
+                },
+                findings: vec![
+                    CueFinding {
+                        severity: CueFindingSeverity::Warning,
+                        kind: CueFindingKind::ShortTrack,
+                        track: Some("02".into()),
+                        message: "is only 1.000 seconds long".into(),
+                    },
+                    CueFinding {
+                        severity: CueFindingSeverity::Error,
+                        kind: CueFindingKind::PastEndOfAudio,
+                        track: Some("03".into()),
+                        message: "INDEX at 10:00:00 is past the end of \"album.flac\"".into(),
+                    },
+                ],
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        assert!(adapters.cue_splitter.calls.lock().unwrap().is_empty());
+        assert_eq!(
+            store.last_error.lock().unwrap().as_deref(),
+            Some(
+                format!(
+                    "{}: CUE validation failed: track 03: INDEX at 10:00:00 is past the end of \"album.flac\"",
+                    cue_path.display()
+                )
+                .as_str()
+            )
+        );
+        let log = adapters.download_log.writes.lock().unwrap();
+        assert!(log
+            .last()
+            .unwrap()
+            .contains("Validation warning: track 02: is only 1.000 seconds long"));
+    }
+
+    #[tokio::test]
+    async fn repaired_cue_is_handed_to_the_splitter() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&cue_path, "FILE \"album.wav\" WAVE\n").unwrap();
+        fs::write(tmp.path().join("album.flac"), b"audio").unwrap();
+        let repair_dir = tmp.path().join("repaired");
+        fs::create_dir(&repair_dir).unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: RepairingInspector {
+                inner: FakeInspector {
+                    matches: Mutex::new(Vec::new()),
+                },
+                repair_dir: repair_dir.clone(),
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        let calls = adapters.cue_splitter.calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].parent(), Some(repair_dir.as_path()));
+        assert_eq!(
+            fs::read_to_string(&cue_path).unwrap(),
+            "FILE \"album.wav\" WAVE\n"
+        );
+        assert_eq!(
+            store.recorded_input_files.lock().unwrap()[0],
+            (cue_path.to_string_lossy().to_string(), InputFileKind::Cue)
+        );
+        let log = adapters.download_log.writes.lock().unwrap();
+        let log = log.last().unwrap();
+        assert!(log.contains(&format!("Repaired CUE: {}", calls[0].display())));
+        assert!(log.contains("  repaired: album.wav -> album.flac (matched by file stem)"));
+    }
+
+    #[tokio::test]
+    async fn decoded_cue_text_feeds_hints_and_log() {
+        let tmp = tempdir().unwrap();
+        let cue_path = tmp.path().join("album.cue");
+        fs::write(&cue_path, b"TITLE \"\x83}\x83W\x83R\"\n").unwrap();
+        fs::write(tmp.path().join("album.flac"), b"audio").unwrap();
+
+        let store = FakeStore::default();
+        let adapters = ProcessingAdapters {
+            cue_scanner: FakeScanner {
+                roots: Mutex::new(Vec::new()),
+                cue_files: vec![cue_path.clone()],
+            },
+            cue_input_inspector: DecodingInspector {
+                inner: FakeInspector {
+                    matches: Mutex::new(Vec::new()),
+                },
+                decoded: DecodedCue {
+                    text: "TITLE \"マジコ\"\nFILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"カタストロフィ\"\n    INDEX 01 00:00:00\n".into(),
+                    encoding: "Shift_JIS".into(),
+                },
+            },
+            cue_splitter: FakeSplitter {
+                calls: Mutex::new(Vec::new()),
+            },
+            manual_import: FakeManualImport::default(),
+            download_log: FakeDownloadLog::default(),
+            track_cleanup: (),
+            track_tagger: FakeTagger::default(),
+            split_layout: SplitLayout::default(),
+            musicbrainz: MusicBrainzReleaseSelector::default(),
+            notifiers: Notifiers::default(),
+            dry_run: false,
+        };
+        let download = TrackedDownload::pending(
+            "download-1".into(),
+            "Album".into(),
+            "completed".into(),
+            tmp.path().to_string_lossy().to_string(),
+            "importFailed".into(),
+        );
+
+        process_tracked_download(&store, &adapters, download)
+            .await
+            .unwrap();
+
+        let calls = adapters.manual_import.calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        let hint = &calls[0].cue_hints[0];
+        assert_eq!(hint.album_title.as_deref(), Some("マジコ"));
+        assert_eq!(hint.tracks[0].title.as_deref(), Some("カタストロフィ"));
+        let log = adapters.download_log.writes.lock().unwrap();
+        assert!(log.last().unwrap().contains("Encoding: Shift_JIS"));
+    }
 }
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use tracing::info;
+
+use crate::application::download_lock::with_download_lock;
+use crate::application::ports::{DownloadStore, TrackCleanup};
+use crate::domain::DownloadLifecycleState;
+
+/// Requeues downloads that a previous run left in `processing` or
+/// `cleaning_up`, after removing the output of splits it did not finish.
+/// Downloads whose lock is still refreshed by a live process are left alone;
+/// once a killed process's lock goes stale, the next call recovers them.
+pub async fn recover_interrupted_downloads<S, X>(store: &S, track_cleanup: &X) -> Result<usize>
+where
+    S: DownloadStore,
+    X: TrackCleanup,
+{
+    let mut recovered = 0;
+    for download in store.load_tracked_download_summaries().await? {
+        if !matches!(
+            download.lifecycle_state,
+            DownloadLifecycleState::Processing | DownloadLifecycleState::CleaningUp
+        ) {
+            continue;
+        }
+        let requeued = with_download_lock(store, &download.download_id, async {
+            // The holder may have finished between loading the summaries and
+            // taking the lock.
+            let interrupted = store
+                .get_tracked_download(&download.download_id)
+                .await?
+                .is_some_and(|current| {
+                    matches!(
+                        current.lifecycle_state,
+                        DownloadLifecycleState::Processing | DownloadLifecycleState::CleaningUp
+                    )
+                });
+            if !interrupted {
+                return Ok(false);
+            }
+            for split in store.load_in_progress_splits(&download.download_id).await? {
+                for path in track_cleanup.remove_partial_outputs(&split).await? {
+                    info!("Removed partial output {}", path.display());
+                }
+            }
+            store
+                .requeue_interrupted_download(&download.download_id)
+                .await?;
+            Ok(true)
+        })
+        .await?;
+        match requeued {
+            Some(true) => {
+                info!("Requeued interrupted download {}", download.title);
+                recovered += 1;
+            }
+            Some(false) => {}
+            None => info!(
+                "Not recovering {}: another process is working on it",
+                download.title
+            ),
+        }
+    }
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::recover_interrupted_downloads;
+    use crate::adapters::filesystem_cleanup::FilesystemTrackCleanup;
+    use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::ports::DownloadStore;
+    use crate::domain::{DownloadLifecycleState, TrackedDownload};
+
+    #[tokio::test]
+    async fn removes_partial_outputs_and_requeues_unlocked_downloads() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let other_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let source = album_dir.join("album.flac");
+        let partial = album_dir.join("01 - Track.flac");
+        fs::write(&source, b"audio").unwrap();
+        for download_id in ["interrupted", "busy", "done"] {
+            store
+                .upsert_tracked_download(&TrackedDownload::pending(
+                    download_id.into(),
+                    download_id.into(),
+                    "completed".into(),
+                    album_dir.to_string_lossy().into_owned(),
+                    "importFailed".into(),
+                ))
+                .await
+                .unwrap();
+        }
+        store.mark_download_processing("interrupted").await.unwrap();
+        store.mark_download_processing("busy").await.unwrap();
+        store.mark_download_completed("done").await.unwrap();
+        store
+            .record_split_started(
+                "interrupted",
+                &album_dir.to_string_lossy(),
+                &[source.to_string_lossy().into_owned()],
+            )
+            .await
+            .unwrap();
+        fs::write(&partial, b"half a track").unwrap();
+        assert!(other_process.try_lock_download("busy").await.unwrap());
+
+        let recovered = recover_interrupted_downloads(&store, &FilesystemTrackCleanup::default())
+            .await
+            .unwrap();
+
+        assert_eq!(recovered, 1);
+        assert!(source.exists());
+        assert!(!partial.exists());
+        let state = |download_id: &'static str| {
+            let store = store.clone();
+            async move {
+                store
+                    .get_tracked_download(download_id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .lifecycle_state
+            }
+        };
+        assert_eq!(state("interrupted").await, DownloadLifecycleState::Detected);
+        assert_eq!(state("busy").await, DownloadLifecycleState::Processing);
+        assert_eq!(state("done").await, DownloadLifecycleState::Completed);
+        assert!(store
+            .load_in_progress_splits("interrupted")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn takes_over_the_lock_of_a_process_that_was_killed() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        store
+            .upsert_tracked_download(&TrackedDownload::pending(
+                "killed".into(),
+                "killed".into(),
+                "completed".into(),
+                tmp.path().to_string_lossy().into_owned(),
+                "importFailed".into(),
+            ))
+            .await
+            .unwrap();
+        store.mark_download_processing("killed").await.unwrap();
+        {
+            let killed_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+            assert!(killed_process.try_lock_download("killed").await.unwrap());
+        }
+
+        let recovered = recover_interrupted_downloads(&store, &FilesystemTrackCleanup::default())
+            .await
+            .unwrap();
+        assert_eq!(recovered, 0, "the lock is still fresh");
+
+        rusqlite::Connection::open(tmp.path().join("data.db"))
+            .unwrap()
+            .execute(
+                "UPDATE download_locks SET heartbeat_at = datetime('now', '-3 minutes')",
+                [],
+            )
+            .unwrap();
+        let recovered = recover_interrupted_downloads(&store, &FilesystemTrackCleanup::default())
+            .await
+            .unwrap();
+
+        assert_eq!(recovered, 1);
+        assert_eq!(
+            store
+                .get_tracked_download("killed")
+                .await
+                .unwrap()
+                .unwrap()
+                .lifecycle_state,
+            DownloadLifecycleState::Detected
+        );
+        assert!(store.try_lock_download("killed").await.unwrap());
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::TrackedDownload;
+
+/// SQLite's `CURRENT_TIMESTAMP` format, so stored timestamps compare as text.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+pub enum RetryDecision {
+    RetryAt(String),
+    GiveUp,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(300),
+            max_delay: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn delay_after(&self, failed_attempts: u32) -> Duration {
+        let doublings = failed_attempts.saturating_sub(1).min(31);
+        self.initial_delay
+            .checked_mul(1 << doublings)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+
+    pub fn after_failure(&self, failed_attempts: u32, now: DateTime<Utc>) -> RetryDecision {
+        if failed_attempts >= self.max_attempts {
+            return RetryDecision::GiveUp;
+        }
+        let delay = chrono::Duration::from_std(self.delay_after(failed_attempts))
+            .unwrap_or(chrono::Duration::MAX);
+        let retry_at = now
+            .checked_add_signed(delay)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        RetryDecision::RetryAt(format_timestamp(retry_at))
+    }
+}
+
+pub fn is_retry_due(download: &TrackedDownload, now: DateTime<Utc>) -> bool {
+    download
+        .next_retry_at
+        .as_deref()
+        .is_none_or(|next_retry_at| next_retry_at <= format_timestamp(now).as_str())
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.format(TIMESTAMP_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{is_retry_due, RetryDecision, RetryPolicy};
+    use crate::domain::TrackedDownload;
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(300),
+        };
+
+        assert_eq!(
+            (1..=5)
+                .map(|attempt| policy.delay_after(attempt).as_secs())
+                .collect::<Vec<_>>(),
+            vec![60, 120, 240, 300, 300]
+        );
+        assert_eq!(policy.delay_after(u32::MAX), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn schedules_retries_until_max_attempts_then_gives_up() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(3600),
+        };
+        let now = Utc.with_ymd_and_hms(2026, 6, 12, 12, 0, 0).unwrap();
+
+        assert!(matches!(
+            policy.after_failure(2, now),
+            RetryDecision::RetryAt(retry_at) if retry_at == "2026-06-12 12:02:00"
+        ));
+        assert!(matches!(
+            policy.after_failure(3, now),
+            RetryDecision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn download_is_due_once_next_retry_has_passed() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 12, 12, 0, 0).unwrap();
+        let mut download = TrackedDownload::pending(
+            "d1".to_owned(),
+            "Album".to_owned(),
+            "completed".to_owned(),
+            "/downloads/album".to_owned(),
+            "importFailed".to_owned(),
+        );
+        assert!(is_retry_due(&download, now));
+
+        download.next_retry_at = Some("2026-06-12 12:00:01".to_owned());
+        assert!(!is_retry_due(&download, now));
+
+        download.next_retry_at = Some("2026-06-12 12:00:00".to_owned());
+        assert!(is_retry_due(&download, now));
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::application::ports::{
+    CueMetadataHint, MusicBrainzDiscLookupRequest, MusicBrainzDiscLookupResult,
+    MusicBrainzDiscRelease, MusicBrainzDiscReleaseLookup,
+};
+
+/// Looks a download's discs up on MusicBrainz once per processing run and
+/// chooses the release its generated tracks are tagged with. Clones share the
+/// lookup; the default never looks anything up.
+#[derive(Clone, Default)]
+pub struct MusicBrainzReleaseSelector {
+    lookup: Option<Arc<dyn MusicBrainzDiscReleaseLookup>>,
+    trust_disc_lookup: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MusicBrainzSelection {
+    /// Handed on to the manual import so the discs are not looked up again.
+    pub lookup: Option<MusicBrainzDiscLookupResult>,
+    pub release: Option<MusicBrainzDiscRelease>,
+    pub log: Vec<String>,
+}
+
+impl MusicBrainzReleaseSelector {
+    pub fn new(lookup: Arc<dyn MusicBrainzDiscReleaseLookup>, trust_disc_lookup: bool) -> Self {
+        Self {
+            lookup: Some(lookup),
+            trust_disc_lookup,
+        }
+    }
+
+    pub async fn select(&self, cue_hints: &[CueMetadataHint]) -> MusicBrainzSelection {
+        let mut selection = MusicBrainzSelection::default();
+        let Some(lookup) = &self.lookup else {
+            return selection;
+        };
+        let result = match lookup_disc_releases(lookup.as_ref(), cue_hints).await {
+            Ok(result) => result,
+            Err(err) => {
+                selection
+                    .log
+                    .push(format!("MusicBrainz: release lookup failed: {err}"));
+                return selection;
+            }
+        };
+        match &result {
+            MusicBrainzDiscLookupResult::Found { releases, .. } => {
+                match select_release(releases, cue_hints, self.trust_disc_lookup) {
+                    Ok(release) => {
+                        selection
+                            .log
+                            .push(format!("MusicBrainz: selected release {}", release.id));
+                        selection.release = Some(release.clone());
+                    }
+                    Err(reason) => selection
+                        .log
+                        .push(format!("MusicBrainz: no release selected: {reason}")),
+                }
+            }
+            MusicBrainzDiscLookupResult::NotFound { .. } => selection
+                .log
+                .push("MusicBrainz: no release found for these discs".to_owned()),
+            MusicBrainzDiscLookupResult::Disabled { .. } => {}
+        }
+        selection.lookup = Some(result);
+        selection
+    }
+}
+
+/// Looks every disc up separately when the download has several, keeping
+/// only the releases all of them belong to, the same way the manual import
+/// does.
+async fn lookup_disc_releases(
+    lookup: &dyn MusicBrainzDiscReleaseLookup,
+    cue_hints: &[CueMetadataHint],
+) -> Result<MusicBrainzDiscLookupResult> {
+    let mut disc_groups = BTreeMap::<u32, Vec<PathBuf>>::new();
+    for hint in cue_hints {
+        if let Some(disc_number) = hint.disc_number {
+            disc_groups
+                .entry(disc_number)
+                .or_default()
+                .push(hint.path.clone());
+        }
+    }
+    if disc_groups.len() <= 1 {
+        return lookup
+            .lookup_musicbrainz_disc_releases(MusicBrainzDiscLookupRequest {
+                cue_paths: cue_hints.iter().map(|hint| hint.path.clone()).collect(),
+            })
+            .await;
+    }
+
+    let mut diagnostic = String::new();
+    let mut shared: Option<Vec<MusicBrainzDiscRelease>> = None;
+    for (disc_number, cue_paths) in disc_groups {
+        diagnostic.push_str(&format!("MusicBrainz lookup for disc {disc_number}\n"));
+        let releases = match lookup
+            .lookup_musicbrainz_disc_releases(MusicBrainzDiscLookupRequest { cue_paths })
+            .await?
+        {
+            MusicBrainzDiscLookupResult::Disabled {
+                diagnostic: lookup_diagnostic,
+            } => {
+                diagnostic.push_str(&lookup_diagnostic);
+                return Ok(MusicBrainzDiscLookupResult::Disabled { diagnostic });
+            }
+            MusicBrainzDiscLookupResult::NotFound {
+                diagnostic: lookup_diagnostic,
+            } => {
+                diagnostic.push_str(&lookup_diagnostic);
+                return Ok(MusicBrainzDiscLookupResult::NotFound { diagnostic });
+            }
+            MusicBrainzDiscLookupResult::Found {
+                releases,
+                diagnostic: lookup_diagnostic,
+            } => {
+                diagnostic.push_str(&lookup_diagnostic);
+                releases
+            }
+        };
+        shared = Some(match shared {
+            None => releases,
+            Some(shared) => shared
+                .into_iter()
+                .filter(|release| {
+                    releases
+                        .iter()
+                        .any(|other| other.id.eq_ignore_ascii_case(&release.id))
+                })
+                .collect(),
+        });
+    }
+
+    let shared = shared.unwrap_or_default();
+    diagnostic.push_str(&format!(
+        "MusicBrainz releases shared by all discs: [{}]\n",
+        shared
+            .iter()
+            .map(|release| release.id.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    if shared.is_empty() {
+        return Ok(MusicBrainzDiscLookupResult::NotFound { diagnostic });
+    }
+    Ok(MusicBrainzDiscLookupResult::Found {
+        releases: shared,
+        diagnostic,
+    })
+}
+
+/// Narrows the releases down by track counts and then album title. Only
+/// trusted disc lookups may pick between releases that are still tied.
+fn select_release<'a>(
+    releases: &'a [MusicBrainzDiscRelease],
+    cue_hints: &[CueMetadataHint],
+    trust_disc_lookup: bool,
+) -> std::result::Result<&'a MusicBrainzDiscRelease, String> {
+    let compatible = releases
+        .iter()
+        .filter(|release| track_counts_match(release, cue_hints))
+        .collect::<Vec<_>>();
+    if let [release] = compatible.as_slice() {
+        return Ok(release);
+    }
+    if compatible.is_empty() {
+        return Err("no release has the discs' track counts".to_owned());
+    }
+
+    let album_title = cue_hints
+        .iter()
+        .filter_map(|hint| hint.album_title.as_deref())
+        .map(normalize_title)
+        .find(|title| !title.is_empty());
+    let titled = compatible
+        .iter()
+        .copied()
+        .filter(|release| {
+            album_title
+                .as_deref()
+                .is_some_and(|album_title| release_title_matches(release, album_title))
+        })
+        .collect::<Vec<_>>();
+    if let [release] = titled.as_slice() {
+        return Ok(release);
+    }
+    let tied = if titled.is_empty() {
+        compatible
+    } else {
+        titled
+    };
+    if !trust_disc_lookup {
+        return Err(format!(
+            "{} releases match equally well and musicbrainz.trust_disc_lookup is off",
+            tied.len()
+        ));
+    }
+    Ok(tied
+        .into_iter()
+        .rev()
+        .max_by_key(|release| metadata_completeness(release))
+        .expect("tied releases are not empty"))
+}
+
+fn track_counts_match(release: &MusicBrainzDiscRelease, cue_hints: &[CueMetadataHint]) -> bool {
+    release.media_track_counts.is_empty()
+        || cue_hints
+            .iter()
+            .all(|hint| release.media_track_counts.contains(&hint.track_count))
+}
+
+fn release_title_matches(release: &MusicBrainzDiscRelease, album_title: &str) -> bool {
+    [
+        release.title.as_deref(),
+        release.release_group_title.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(normalize_title)
+    .any(|title| !title.is_empty() && album_title.contains(&title))
+}
+
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|character| character.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn metadata_completeness(release: &MusicBrainzDiscRelease) -> usize {
+    [
+        release.date.is_some(),
+        release.country.is_some(),
+        release.barcode.is_some(),
+        release.label_count > 0,
+        release.release_group_id.is_some(),
+    ]
+    .into_iter()
+    .filter(|present| *present)
+    .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use super::MusicBrainzReleaseSelector;
+    use crate::application::ports::{
+        CueMetadataHint, MusicBrainzDiscLookupRequest, MusicBrainzDiscLookupResult,
+        MusicBrainzDiscRelease, MusicBrainzDiscReleaseLookup,
+    };
+
+    #[tokio::test]
+    async fn looks_up_once_and_picks_the_release_matching_track_count_and_title() {
+        let lookup = Arc::new(FakeLookup::found(vec![
+            release("other-count", "Album", vec![12]),
+            release("other-title", "Live Album", vec![10]),
+            release("chosen", "Album", vec![10]),
+        ]));
+        let selector = MusicBrainzReleaseSelector::new(lookup.clone(), false);
+
+        let selection = selector
+            .select(&[cue_hint("/album/album.cue", "Album", 10, None)])
+            .await;
+
+        assert_eq!(
+            selection.release.map(|release| release.id),
+            Some("chosen".to_owned())
+        );
+        assert!(matches!(
+            selection.lookup,
+            Some(MusicBrainzDiscLookupResult::Found { .. })
+        ));
+        assert_eq!(
+            lookup.requests.lock().unwrap().as_slice(),
+            &[vec![PathBuf::from("/album/album.cue")]]
+        );
+    }
+
+    #[tokio::test]
+    async fn picks_between_tied_releases_only_when_the_disc_lookup_is_trusted() {
+        let releases = vec![
+            release("sparse", "Album", vec![10]),
+            MusicBrainzDiscRelease {
+                date: Some("2001".into()),
+                barcode: Some("123".into()),
+                ..release("complete", "Album", vec![10])
+            },
+        ];
+        let hints = [cue_hint("/album/album.cue", "Album", 10, None)];
+
+        let untrusted =
+            MusicBrainzReleaseSelector::new(Arc::new(FakeLookup::found(releases.clone())), false)
+                .select(&hints)
+                .await;
+        let trusted = MusicBrainzReleaseSelector::new(Arc::new(FakeLookup::found(releases)), true)
+            .select(&hints)
+            .await;
+
+        assert_eq!(untrusted.release, None);
+        assert!(untrusted
+            .log
+            .iter()
+            .any(|line| line.contains("musicbrainz.trust_disc_lookup is off")));
+        assert_eq!(
+            trusted.release.map(|release| release.id),
+            Some("complete".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_only_releases_shared_by_every_disc() {
+        let lookup = Arc::new(FakeLookup {
+            per_disc: vec![
+                (
+                    PathBuf::from("/album/cd1.cue"),
+                    vec![
+                        release("a", "Album", vec![10, 8]),
+                        release("b", "Album", vec![10, 8]),
+                    ],
+                ),
+                (
+                    PathBuf::from("/album/cd2.cue"),
+                    vec![release("b", "Album", vec![10, 8])],
+                ),
+            ],
+            ..FakeLookup::default()
+        });
+        let selector = MusicBrainzReleaseSelector::new(lookup.clone(), false);
+
+        let selection = selector
+            .select(&[
+                cue_hint("/album/cd1.cue", "Album (Disc 1)", 10, Some(1)),
+                cue_hint("/album/cd2.cue", "Album (Disc 2)", 8, Some(2)),
+            ])
+            .await;
+
+        assert_eq!(
+            selection.release.map(|release| release.id),
+            Some("b".to_owned())
+        );
+        assert_eq!(lookup.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn selects_nothing_without_a_lookup() {
+        let selection = MusicBrainzReleaseSelector::default()
+            .select(&[cue_hint("/album/album.cue", "Album", 10, None)])
+            .await;
+
+        assert_eq!(selection, Default::default());
+    }
+
+    #[derive(Default)]
+    struct FakeLookup {
+        releases: Vec<MusicBrainzDiscRelease>,
+        per_disc: Vec<(PathBuf, Vec<MusicBrainzDiscRelease>)>,
+        requests: Mutex<Vec<Vec<PathBuf>>>,
+    }
+
+    impl FakeLookup {
+        fn found(releases: Vec<MusicBrainzDiscRelease>) -> Self {
+            Self {
+                releases,
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MusicBrainzDiscReleaseLookup for FakeLookup {
+        async fn lookup_musicbrainz_disc_releases(
+            &self,
+            request: MusicBrainzDiscLookupRequest,
+        ) -> anyhow::Result<MusicBrainzDiscLookupResult> {
+            let releases = self
+                .per_disc
+                .iter()
+                .find(|(path, _)| request.cue_paths.contains(path))
+                .map_or_else(|| self.releases.clone(), |(_, releases)| releases.clone());
+            self.requests.lock().unwrap().push(request.cue_paths);
+            Ok(MusicBrainzDiscLookupResult::Found {
+                releases,
+                diagnostic: String::new(),
+            })
+        }
+    }
+
+    fn release(id: &str, title: &str, media_track_counts: Vec<usize>) -> MusicBrainzDiscRelease {
+        MusicBrainzDiscRelease {
+            id: id.into(),
+            title: Some(title.into()),
+            media_count: media_track_counts.len(),
+            media_track_counts,
+            ..MusicBrainzDiscRelease::default()
+        }
+    }
+
+    fn cue_hint(
+        path: &str,
+        album_title: &str,
+        track_count: usize,
+        disc_number: Option<u32>,
+    ) -> CueMetadataHint {
+        CueMetadataHint {
+            path: PathBuf::from(path),
+            album_title: Some(album_title.into()),
+            performer: None,
+            catalog: None,
+            disc_id: None,
+            disc_number,
+            comments: Vec::new(),
+            track_count,
+            tracks: Vec::new(),
+        }
+    }
+}
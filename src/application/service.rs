@@ -1,61 +1,163 @@
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use chrono::prelude::*;
+use futures_util::future;
+use futures_util::stream::{self, StreamExt};
+use tokio::sync::{watch, Notify};
+use tracing::{error, info, info_span, warn, Instrument, Span};
 
-use crate::application::cleanup_processed_download::cleanup_processed_download;
+use crate::application::cleanup_processed_download::{cleanup_processed_download, CleanupReason};
+use crate::application::download_actions::run_pending_download_actions;
+use crate::application::download_lock::with_download_lock;
+use crate::application::ignore_list::IgnoreList;
+use crate::application::metrics::Metrics;
 use crate::application::monitor_download_queue::classify_downloads;
+use crate::application::notifications::{notify, Notifiers};
 use crate::application::ports::{
     CueInputInspector, CueScanner, CueSplitter, DownloadLog, DownloadStore, ManualImportTrigger,
-    QueueSource, TrackCleanup,
+    QueueSource, TrackCleanup, TrackTagger,
 };
 use crate::application::process_tracked_download::{
     process_tracked_download, register_failed_imports,
 };
+use crate::application::recover_interrupted_downloads::recover_interrupted_downloads;
+use crate::application::retry_policy::{is_retry_due, RetryDecision, RetryPolicy};
+use crate::application::select_musicbrainz_release::MusicBrainzReleaseSelector;
+use crate::domain::{
+    DownloadLifecycleState, IgnoreRule, NotificationEvent, SplitLayout, TrackedDownload,
+};
 
-pub struct MonitorService<Q, S, C, I, P, M, L, X> {
+pub struct MonitorService<Q, S, C, I, P, M, L, X, T> {
     queue_source: Q,
     download_store: S,
-    cue_scanner: C,
-    cue_input_inspector: I,
-    cue_splitter: P,
-    manual_import: M,
-    download_log: L,
-    track_cleanup: X,
+    adapters: ProcessingAdapters<C, I, P, M, L, X, T>,
     check_frequency_seconds: u64,
+    ignore_rules: Vec<IgnoreRule>,
+    retry_policy: RetryPolicy,
+    queue_checks: QueueCheckRequests,
+    max_concurrent_downloads: usize,
+    shutdown: Shutdown,
+    metrics: Metrics,
+}
+
+/// Lets other parts of the process, such as the Lidarr webhook, cut the wait
+/// before the next queue check short. Requests made while a check is running
+/// start another check right after it.
+#[derive(Clone, Default)]
+pub struct QueueCheckRequests {
+    notify: Arc<Notify>,
+}
+
+impl QueueCheckRequests {
+    pub fn request(&self) {
+        self.notify.notify_one();
+    }
+
+    pub async fn requested(&self) {
+        self.notify.notified().await;
+    }
+}
+
+/// Tells the monitor to stop. Downloads that are being processed are allowed
+/// to finish, but no new ones are started.
+#[derive(Clone)]
+pub struct Shutdown {
+    requested: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            requested: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn request(&self) {
+        self.requested.send_replace(true);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.requested.borrow()
+    }
+
+    pub async fn requested(&self) {
+        let mut requested = self.requested.subscribe();
+        // The sender lives in `self`, so this only ends once shutdown is requested.
+        let _ = requested.wait_for(|requested| *requested).await;
+    }
 }
 
-pub struct ProcessingAdapters<C, I, P, M, L, X> {
+pub struct ProcessingAdapters<C, I, P, M, L, X, T> {
     pub cue_scanner: C,
     pub cue_input_inspector: I,
     pub cue_splitter: P,
     pub manual_import: M,
     pub download_log: L,
     pub track_cleanup: X,
+    pub track_tagger: T,
+    pub split_layout: SplitLayout,
+    pub musicbrainz: MusicBrainzReleaseSelector,
+    pub notifiers: Notifiers,
+    pub dry_run: bool,
 }
 
-impl<Q, S, C, I, P, M, L, X> MonitorService<Q, S, C, I, P, M, L, X> {
+impl<Q, S, C, I, P, M, L, X, T> MonitorService<Q, S, C, I, P, M, L, X, T> {
     pub fn new(
         queue_source: Q,
         download_store: S,
-        adapters: ProcessingAdapters<C, I, P, M, L, X>,
+        adapters: ProcessingAdapters<C, I, P, M, L, X, T>,
         check_frequency_seconds: u64,
     ) -> Self {
         Self {
             queue_source,
             download_store,
-            cue_scanner: adapters.cue_scanner,
-            cue_input_inspector: adapters.cue_input_inspector,
-            cue_splitter: adapters.cue_splitter,
-            manual_import: adapters.manual_import,
-            download_log: adapters.download_log,
-            track_cleanup: adapters.track_cleanup,
+            adapters,
             check_frequency_seconds,
+            ignore_rules: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            queue_checks: QueueCheckRequests::default(),
+            max_concurrent_downloads: 1,
+            shutdown: Shutdown::default(),
+            metrics: Metrics::default(),
         }
     }
+
+    pub fn with_ignore_rules(mut self, ignore_rules: Vec<IgnoreRule>) -> Self {
+        self.ignore_rules = ignore_rules;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_queue_check_requests(mut self, queue_checks: QueueCheckRequests) -> Self {
+        self.queue_checks = queue_checks;
+        self
+    }
+
+    pub fn with_max_concurrent_downloads(mut self, max_concurrent_downloads: usize) -> Self {
+        self.max_concurrent_downloads = max_concurrent_downloads.max(1);
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
 }
 
-impl<Q, S, C, I, P, M, L, X> MonitorService<Q, S, C, I, P, M, L, X>
+impl<Q, S, C, I, P, M, L, X, T> MonitorService<Q, S, C, I, P, M, L, X, T>
 where
     Q: QueueSource,
     S: DownloadStore,
@@ -65,52 +167,91 @@ where
     M: ManualImportTrigger,
     L: DownloadLog,
     X: TrackCleanup,
+    T: TrackTagger,
 {
     pub async fn run(&self) -> Result<()> {
         let interval = Duration::from_secs(self.check_frequency_seconds);
 
-        println!("Splittarr");
-        println!("Checking every {} seconds", self.check_frequency_seconds);
+        info!(
+            "Splittarr {} checking every {} seconds",
+            env!("CARGO_PKG_VERSION"),
+            self.check_frequency_seconds
+        );
 
-        loop {
-            println!(
-                "Checking Lidarr's download queue at {}",
-                Local::now().format("%Y-%m-%d %H:%M:%S %z")
-            );
+        while !self.shutdown.is_requested() {
+            info!("Checking Lidarr's download queue");
 
             if let Err(err) = self.run_once().await {
-                eprintln!("Splittarr cycle failed: {err:#}");
+                error!("Splittarr cycle failed: {err:#}");
             }
 
-            tokio::time::sleep(interval).await;
+            tokio::select! {
+                () = tokio::time::sleep(interval) => {}
+                () = self.queue_checks.requested() => {}
+                () = self.shutdown.requested() => {}
+            }
         }
+
+        info!("Queue monitor stopped");
+        Ok(())
     }
 
     pub async fn run_once(&self) -> Result<()> {
+        let started = Instant::now();
+        let result = self.check_queue().await;
+        self.metrics.record_poll(started.elapsed());
+        result
+    }
+
+    async fn check_queue(&self) -> Result<()> {
+        // Runs on every check, not only at startup: after a hard kill the dead
+        // process's locks only go stale a little while later.
+        match recover_interrupted_downloads(&self.download_store, &self.adapters.track_cleanup)
+            .await
+        {
+            Ok(0) => {}
+            Ok(recovered) => info!("Recovered {recovered} interrupted download(s)"),
+            Err(err) => error!("Recovering interrupted downloads failed: {err:#}"),
+        }
+        run_pending_download_actions(&self.download_store, &self.adapters, &self.retry_policy)
+            .await?;
+
         let mut downloads = self
             .download_store
             .load_tracked_download_summaries()
             .await?;
-        println!("{} downloads registered in Splittarr", downloads.len());
+        info!("{} downloads registered in Splittarr", downloads.len());
 
         let snapshot = self.queue_source.queue_snapshot().await?;
-        println!(
-            "Found {} records in Lidarr's download queue",
-            snapshot.total_records
-        );
-        println!(
-            "Fetched {} queue page(s) from Lidarr",
-            snapshot.pages_fetched
+        info!(
+            "Found {} records in Lidarr's download queue across {} page(s)",
+            snapshot.total_records, snapshot.pages_fetched
         );
 
+        let stored_ignore_rules = self.download_store.load_ignore_rules().await?;
+        let ignore_list =
+            IgnoreList::new(self.ignore_rules.iter().cloned().chain(stored_ignore_rules))?;
         register_failed_imports(
             &self.download_store,
             &mut downloads,
             &snapshot.failed_imports,
+            &ignore_list,
         )
         .await?;
 
-        let (to_process, to_cleanup_candidates) = classify_downloads(downloads, &snapshot);
+        let (mut to_process, to_cleanup_candidates) =
+            classify_downloads(downloads, &snapshot, &ignore_list);
+        if self.adapters.dry_run {
+            to_process
+                .retain(|download| download.lifecycle_state != DownloadLifecycleState::Planned);
+        }
+        let now = Utc::now();
+        let waiting = to_process.len();
+        to_process.retain(|download| is_retry_due(download, now));
+        let waiting = waiting - to_process.len();
+        if waiting > 0 {
+            info!("{waiting} failed download(s) waiting for their next retry");
+        }
         let to_cleanup_ids = to_cleanup_candidates
             .into_iter()
             .map(|download| download.download_id)
@@ -120,67 +261,235 @@ where
             .get_tracked_downloads(&to_cleanup_ids)
             .await?;
 
-        println!("{} downloads to be processed", to_process.len());
+        info!("{} downloads to be processed", to_process.len());
 
-        for download in to_process {
-            if let Err(err) = process_tracked_download(
-                &self.download_store,
-                &self.cue_scanner,
-                &self.cue_input_inspector,
-                &self.cue_splitter,
-                &self.manual_import,
-                &self.download_log,
-                download.clone(),
-            )
-            .await
-            {
-                eprintln!("Failed processing {}: {err:#}", download.title);
-                let message = err.to_string();
-                self.download_store
-                    .mark_download_failed(&download.download_id, Some(&message))
-                    .await?;
+        // Cleanup runs next to processing so one large image does not hold it up.
+        let processing = stream::iter(to_process)
+            .take_while(|_| future::ready(!self.shutdown.is_requested()))
+            .map(|download| self.process_download(download))
+            .buffer_unordered(self.max_concurrent_downloads)
+            .collect::<Vec<_>>();
+        let cleanup = async {
+            if self.adapters.dry_run && !to_cleanup.is_empty() {
+                info!(
+                    "Dry run: skipping cleanup of {} download(s)",
+                    to_cleanup.len()
+                );
+                return;
+            }
+            for download in to_cleanup {
+                if self.shutdown.is_requested() {
+                    break;
+                }
+                self.cleanup_download(download).await;
             }
+        };
+        let (processed, ()) = tokio::join!(processing, cleanup);
+        processed.into_iter().collect()
+    }
+
+    async fn process_download(&self, download: TrackedDownload) -> Result<()> {
+        let span = download_span(&download);
+        async {
+            let download_id = download.download_id.clone();
+            let processed = with_download_lock(&self.download_store, &download_id, async {
+                let failure =
+                    process_attempt(&self.download_store, &self.adapters, &download).await?;
+                record_attempt(
+                    &self.download_store,
+                    &self.adapters.notifiers,
+                    &self.retry_policy,
+                    &download,
+                    failure.as_deref(),
+                )
+                .await
+            })
+            .await?;
+            if processed.is_none() {
+                info!("Skipping, it is already being processed");
+            }
+            Ok(())
         }
+        .instrument(span)
+        .await
+    }
 
-        for download in to_cleanup {
-            println!("Cleaning up {}", download.title);
-            if let Err(err) = cleanup_processed_download(
+    async fn cleanup_download(&self, download: TrackedDownload) {
+        let span = download_span(&download);
+        async {
+            info!("Cleaning up");
+            let cleaned = with_download_lock(
                 &self.download_store,
-                &self.track_cleanup,
-                &self.download_log,
-                &download,
+                &download.download_id,
+                cleanup_processed_download(
+                    &self.download_store,
+                    &self.adapters.track_cleanup,
+                    &self.adapters.download_log,
+                    &self.adapters.notifiers,
+                    &download,
+                    CleanupReason::Imported,
+                ),
             )
-            .await
-            {
-                eprintln!("Failed cleaning up {}: {err:#}", download.title);
+            .await;
+            match cleaned {
+                Ok(Some(())) => {}
+                Ok(None) => info!("Skipping cleanup, it is busy"),
+                Err(err) => error!("Failed cleaning up: {err:#}"),
             }
         }
+        .instrument(span)
+        .await
+    }
+}
 
-        Ok(())
+/// Processes a download once and returns why it failed, if it did.
+pub(crate) async fn process_attempt<S, C, I, P, M, L, X, T>(
+    store: &S,
+    adapters: &ProcessingAdapters<C, I, P, M, L, X, T>,
+    download: &TrackedDownload,
+) -> Result<Option<String>>
+where
+    S: DownloadStore,
+    C: CueScanner,
+    I: CueInputInspector,
+    P: CueSplitter,
+    M: ManualImportTrigger,
+    L: DownloadLog,
+    X: TrackCleanup,
+    T: TrackTagger,
+{
+    match process_tracked_download(store, adapters, download.clone()).await {
+        Ok(()) => Ok(store
+            .get_tracked_download(&download.download_id)
+            .await?
+            .filter(|processed| processed.lifecycle_state == DownloadLifecycleState::Failed)
+            .map(|processed| {
+                processed
+                    .last_error
+                    .unwrap_or_else(|| "processing failed".to_owned())
+            })),
+        Err(err) => {
+            error!("Failed processing: {err:#}");
+            let message = err.to_string();
+            store
+                .mark_download_failed(&download.download_id, Some(&message))
+                .await?;
+            Ok(Some(message))
+        }
     }
 }
 
+/// Schedules the next retry after a failed attempt. The first failure and
+/// giving up are notified; the attempts in between are not.
+pub(crate) async fn record_attempt<S: DownloadStore>(
+    store: &S,
+    notifiers: &Notifiers,
+    retry_policy: &RetryPolicy,
+    download: &TrackedDownload,
+    failure: Option<&str>,
+) -> Result<()> {
+    let Some(failure) = failure else {
+        if download.attempt_count > 0 {
+            store.reset_download_attempts(&download.download_id).await?;
+        }
+        return Ok(());
+    };
+
+    let attempt_count = download.attempt_count + 1;
+    match retry_policy.after_failure(attempt_count, Utc::now()) {
+        RetryDecision::RetryAt(next_retry_at) => {
+            warn!(
+                "Retrying after {next_retry_at} UTC ({attempt_count} of {} attempts failed)",
+                retry_policy.max_attempts
+            );
+            store
+                .schedule_download_retry(&download.download_id, attempt_count, &next_retry_at)
+                .await?;
+            if attempt_count == 1 {
+                notify(
+                    store,
+                    notifiers,
+                    download,
+                    NotificationEvent::Failed,
+                    format!(
+                        "attempt 1 of {} failed, retrying after {next_retry_at} UTC: {failure}",
+                        retry_policy.max_attempts
+                    ),
+                )
+                .await;
+            }
+            Ok(())
+        }
+        RetryDecision::GiveUp => {
+            error!("Giving up after {attempt_count} failed attempt(s)");
+            store
+                .mark_download_gave_up(&download.download_id, attempt_count)
+                .await?;
+            notify(
+                store,
+                notifiers,
+                download,
+                NotificationEvent::Failed,
+                format!("gave up after {attempt_count} failed attempt(s): {failure}"),
+            )
+            .await;
+            Ok(())
+        }
+    }
+}
+
+/// Span carried by everything logged while working on one download.
+pub fn download_span(download: &TrackedDownload) -> Span {
+    info_span!(
+        "download",
+        download_id = %download.download_id,
+        title = %download.title
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
     use std::fs;
     use std::path::{Path, PathBuf};
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
+    use async_trait::async_trait;
     use tempfile::tempdir;
+    use tokio::sync::Barrier;
 
-    use super::{MonitorService, ProcessingAdapters};
+    use super::{MonitorService, ProcessingAdapters, Shutdown};
     use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::metrics::Metrics;
+    use crate::application::notifications::Notifiers;
     use crate::application::ports::{
         CueInputInspector, CueInputSnapshot, CueReferencedAudioInput, CueScanner, CueSplitter,
-        DownloadLog, DownloadStore, ManualImportRequest, ManualImportResult, ManualImportTrigger,
-        QueueSource, TrackCleanup,
+        DownloadActionQueue, DownloadLog, DownloadStore, ManualImportRequest, ManualImportResult,
+        ManualImportTrigger, Notifier, QueueSource, TrackCleanup, TrackTagger,
     };
+    use crate::application::retry_policy::RetryPolicy;
+    use crate::application::select_musicbrainz_release::MusicBrainzReleaseSelector;
     use crate::domain::{
-        DiscoveredCueSheets, DownloadLifecycleState, FailedImportCandidate, QueueSnapshot,
-        SplitOutcome, SplitStatus, TrackCleanupOutcome, TrackCleanupStatus,
+        DiscoveredCueSheets, DownloadAction, DownloadActionStatus, DownloadLifecycleState,
+        FailedImportCandidate, Notification, NotificationEvent, OutputCodec, QueueSnapshot,
+        SplitLayout, SplitOutcome, SplitStatus, TrackCleanupOutcome, TrackCleanupStatus,
+        TrackedDownload,
     };
 
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
     struct FakeQueue {
         snapshots: Mutex<Vec<QueueSnapshot>>,
     }
@@ -209,6 +518,36 @@ mod tests {
         }
     }
 
+    struct EmptyScanner;
+
+    impl CueScanner for EmptyScanner {
+        async fn find_cue_sheets(&self, _root: &Path) -> anyhow::Result<DiscoveredCueSheets> {
+            Ok(DiscoveredCueSheets {
+                cue_files: Vec::new(),
+                errors: Vec::new(),
+            })
+        }
+    }
+
+    struct FailingScanner;
+
+    impl CueScanner for FailingScanner {
+        async fn find_cue_sheets(&self, _root: &Path) -> anyhow::Result<DiscoveredCueSheets> {
+            anyhow::bail!("permission denied")
+        }
+    }
+
+    struct AlbumDirScanner;
+
+    impl CueScanner for AlbumDirScanner {
+        async fn find_cue_sheets(&self, root: &Path) -> anyhow::Result<DiscoveredCueSheets> {
+            Ok(DiscoveredCueSheets {
+                cue_files: vec![root.join("album.cue")],
+                errors: Vec::new(),
+            })
+        }
+    }
+
     struct FakeInspector;
 
     impl CueInputInspector for FakeInspector {
@@ -226,6 +565,7 @@ mod tests {
                 audio_inputs: vec![CueReferencedAudioInput {
                     path: cue_path.with_extension("flac"),
                     size_bytes: Some(5),
+                    audio_format: Some("FLAC".into()),
                 }],
             })
         }
@@ -244,12 +584,45 @@ mod tests {
     }
 
     impl CueSplitter for FakeSplitter {
-        async fn split_cue(&self, _cue_path: &Path) -> anyhow::Result<SplitOutcome> {
+        async fn split_cue(
+            &self,
+            _cue_path: &Path,
+            _output_dir: &Path,
+        ) -> anyhow::Result<SplitOutcome> {
             fs::write(&self.output_track, b"track").unwrap();
             Ok(SplitOutcome {
                 status: SplitStatus::Split,
                 tracks: vec![self.output_track.clone()],
+                codec: OutputCodec::Flac,
+                message: None,
+                pregap_policy: None,
+                hidden_track: None,
+            })
+        }
+    }
+
+    /// Only finishes once `barrier` has been reached by as many splits as it
+    /// was created for, so it hangs unless those splits run at the same time.
+    struct BarrierSplitter {
+        barrier: Barrier,
+    }
+
+    impl CueSplitter for BarrierSplitter {
+        async fn split_cue(
+            &self,
+            _cue_path: &Path,
+            output_dir: &Path,
+        ) -> anyhow::Result<SplitOutcome> {
+            self.barrier.wait().await;
+            let output_track = output_dir.join("01 - Track.flac");
+            fs::write(&output_track, b"track").unwrap();
+            Ok(SplitOutcome {
+                status: SplitStatus::Split,
+                tracks: vec![output_track],
+                codec: OutputCodec::Flac,
                 message: None,
+                pregap_policy: None,
+                hidden_track: None,
             })
         }
     }
@@ -285,6 +658,18 @@ mod tests {
         }
     }
 
+    struct FakeTagger;
+
+    impl TrackTagger for FakeTagger {
+        async fn write_track_tags(
+            &self,
+            _track_path: &Path,
+            _tags: &[crate::domain::TrackTag],
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
     struct FakeDownloadLog;
 
     impl DownloadLog for FakeDownloadLog {
@@ -348,6 +733,7 @@ FILE "album.flac" WAVE
             snapshots: Mutex::new(vec![snapshot_active, snapshot_gone]),
         };
         let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let metrics = Metrics::default();
         let service = MonitorService::new(
             queue,
             store.clone(),
@@ -362,9 +748,15 @@ FILE "album.flac" WAVE
                 manual_import: FakeManualImport,
                 download_log: FakeDownloadLog,
                 track_cleanup: FakeCleanup,
+                track_tagger: FakeTagger,
+                split_layout: SplitLayout::default(),
+                musicbrainz: MusicBrainzReleaseSelector::default(),
+                notifiers: Notifiers::default(),
+                dry_run: false,
             },
             60,
-        );
+        )
+        .with_metrics(metrics.clone());
 
         service.run_once().await.unwrap();
         let after_split = store
@@ -378,6 +770,11 @@ FILE "album.flac" WAVE
         );
         assert_eq!(after_split.generated_track_count(), 1);
         assert_eq!(after_split.input_files.len(), 2);
+        assert!(store
+            .load_in_progress_splits("download-1")
+            .await
+            .unwrap()
+            .is_empty());
 
         service.run_once().await.unwrap();
         let completed = store
@@ -391,5 +788,540 @@ FILE "album.flac" WAVE
             TrackCleanupStatus::Deleted
         );
         assert!(completed.completed_at.is_some());
+        assert_eq!(metrics.snapshot().polls, 2);
+    }
+
+    #[tokio::test]
+    async fn run_once_splits_downloads_concurrently_and_skips_locked_ones() {
+        let tmp = tempdir().unwrap();
+        let mut failed_imports = Vec::new();
+        for download_id in ["download-1", "download-2", "download-3"] {
+            let album_dir = tmp.path().join(download_id);
+            fs::create_dir_all(&album_dir).unwrap();
+            fs::write(
+                album_dir.join("album.cue"),
+                "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Track One\"\n    INDEX 01 00:00:00\n",
+            )
+            .unwrap();
+            fs::write(album_dir.join("album.flac"), b"audio").unwrap();
+            failed_imports.push(FailedImportCandidate {
+                download_id: download_id.into(),
+                title: download_id.into(),
+                status: "completed".into(),
+                output_path: album_dir.to_string_lossy().to_string(),
+                tracked_download_state: "importFailed".into(),
+            });
+        }
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let other_process = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let service = MonitorService::new(
+            FakeQueue {
+                snapshots: Mutex::new(vec![QueueSnapshot {
+                    total_records: 3,
+                    pages_fetched: 1,
+                    active_download_ids: failed_imports
+                        .iter()
+                        .map(|candidate| candidate.download_id.clone())
+                        .collect(),
+                    failed_imports,
+                }]),
+            },
+            store.clone(),
+            ProcessingAdapters {
+                cue_scanner: AlbumDirScanner,
+                cue_input_inspector: FakeInspector,
+                cue_splitter: BarrierSplitter {
+                    barrier: Barrier::new(2),
+                },
+                manual_import: FakeManualImport,
+                download_log: FakeDownloadLog,
+                track_cleanup: FakeCleanup,
+                track_tagger: FakeTagger,
+                split_layout: SplitLayout::default(),
+                musicbrainz: MusicBrainzReleaseSelector::default(),
+                notifiers: Notifiers::default(),
+                dry_run: false,
+            },
+            60,
+        )
+        .with_max_concurrent_downloads(2);
+
+        assert!(other_process.try_lock_download("download-3").await.unwrap());
+        tokio::time::timeout(Duration::from_secs(10), service.run_once())
+            .await
+            .expect("downloads were not split concurrently")
+            .unwrap();
+
+        let states = store
+            .get_tracked_downloads(&[
+                "download-1".to_owned(),
+                "download-2".to_owned(),
+                "download-3".to_owned(),
+            ])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|download| download.lifecycle_state)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                DownloadLifecycleState::AwaitingImport,
+                DownloadLifecycleState::AwaitingImport,
+                DownloadLifecycleState::Detected,
+            ]
+        );
+        assert!(!store.try_lock_download("download-3").await.unwrap());
+        assert!(store.try_lock_download("download-1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_starting_new_downloads_and_ends_the_monitor_loop() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let service = |failed_imports: Vec<FailedImportCandidate>, shutdown: Shutdown| {
+            MonitorService::new(
+                FakeQueue {
+                    snapshots: Mutex::new(vec![QueueSnapshot {
+                        total_records: failed_imports.len(),
+                        pages_fetched: 1,
+                        active_download_ids: failed_imports
+                            .iter()
+                            .map(|candidate| candidate.download_id.clone())
+                            .collect(),
+                        failed_imports,
+                    }]),
+                },
+                store.clone(),
+                ProcessingAdapters {
+                    cue_scanner: EmptyScanner,
+                    cue_input_inspector: FakeInspector,
+                    cue_splitter: FakeSplitter {
+                        output_track: album_dir.join("01 - Track.flac"),
+                    },
+                    manual_import: FakeManualImport,
+                    download_log: FakeDownloadLog,
+                    track_cleanup: FakeCleanup,
+                    track_tagger: FakeTagger,
+                    split_layout: SplitLayout::default(),
+                    musicbrainz: MusicBrainzReleaseSelector::default(),
+                    notifiers: Notifiers::default(),
+                    dry_run: false,
+                },
+                3600,
+            )
+            .with_shutdown(shutdown)
+        };
+
+        let shutdown = Shutdown::default();
+        let idle = service(Vec::new(), shutdown.clone());
+        let (stopped, ()) = tokio::join!(
+            tokio::time::timeout(Duration::from_secs(10), idle.run()),
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                shutdown.request();
+            }
+        );
+        stopped
+            .expect("monitor kept sleeping after shutdown")
+            .unwrap();
+
+        let stopping = service(
+            vec![FailedImportCandidate {
+                download_id: "download-1".into(),
+                title: "Album".into(),
+                status: "completed".into(),
+                output_path: album_dir.to_string_lossy().to_string(),
+                tracked_download_state: "importFailed".into(),
+            }],
+            shutdown,
+        );
+        stopping.run_once().await.unwrap();
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::Detected);
+        assert_eq!(download.attempt_count, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_downloads_back_off_and_give_up_after_max_attempts() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        let service = |retry_policy| {
+            MonitorService::new(
+                FakeQueue {
+                    snapshots: Mutex::new(vec![QueueSnapshot {
+                        total_records: 1,
+                        pages_fetched: 1,
+                        active_download_ids: HashSet::from(["download-1".to_owned()]),
+                        failed_imports: vec![FailedImportCandidate {
+                            download_id: "download-1".into(),
+                            title: "Album".into(),
+                            status: "completed".into(),
+                            output_path: album_dir.to_string_lossy().to_string(),
+                            tracked_download_state: "importFailed".into(),
+                        }],
+                    }]),
+                },
+                store.clone(),
+                ProcessingAdapters {
+                    cue_scanner: EmptyScanner,
+                    cue_input_inspector: FakeInspector,
+                    cue_splitter: FakeSplitter {
+                        output_track: album_dir.join("01 - Track.flac"),
+                    },
+                    manual_import: FakeManualImport,
+                    download_log: FakeDownloadLog,
+                    track_cleanup: FakeCleanup,
+                    track_tagger: FakeTagger,
+                    split_layout: SplitLayout::default(),
+                    musicbrainz: MusicBrainzReleaseSelector::default(),
+                    notifiers: Notifiers::default().with_notifier(
+                        "chat",
+                        vec![NotificationEvent::Failed],
+                        notifier.clone(),
+                    ),
+                    dry_run: false,
+                },
+                60,
+            )
+            .with_retry_policy(retry_policy)
+        };
+        let load = || async {
+            store
+                .get_tracked_download("download-1")
+                .await
+                .unwrap()
+                .unwrap()
+        };
+
+        let waiting = service(RetryPolicy {
+            max_attempts: 2,
+            initial_delay: Duration::from_secs(3600),
+            max_delay: Duration::from_secs(3600),
+        });
+        waiting.run_once().await.unwrap();
+        waiting.run_once().await.unwrap();
+        let download = load().await;
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::Failed);
+        assert_eq!(download.attempt_count, 1);
+        assert!(download.next_retry_at.is_some());
+        {
+            let sent = notifier.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].event, NotificationEvent::Failed);
+            assert!(
+                sent[0]
+                    .message
+                    .starts_with("attempt 1 of 2 failed, retrying after "),
+                "{}",
+                sent[0].message
+            );
+            assert!(sent[0].message.ends_with(" UTC: no cue files found"));
+        }
+
+        store.reset_download_attempts("download-1").await.unwrap();
+        notifier.sent.lock().unwrap().clear();
+        let immediate = service(RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        });
+        for _ in 0..4 {
+            immediate.run_once().await.unwrap();
+        }
+        let download = load().await;
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::GaveUp);
+        assert_eq!(download.attempt_count, 3);
+        assert_eq!(download.next_retry_at, None);
+        assert_eq!(download.last_error.as_deref(), Some("no cue files found"));
+        // The second attempt is retried without another notification.
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[0].message.starts_with("attempt 1 of 3 failed"));
+        assert_eq!(sent[1].event, NotificationEvent::Failed);
+        assert_eq!(
+            sent[1].message,
+            "gave up after 3 failed attempt(s): no cue files found"
+        );
+    }
+
+    #[tokio::test]
+    async fn processing_errors_are_notified_when_giving_up() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        let service = MonitorService::new(
+            FakeQueue {
+                snapshots: Mutex::new(vec![QueueSnapshot {
+                    total_records: 1,
+                    pages_fetched: 1,
+                    active_download_ids: HashSet::from(["download-1".to_owned()]),
+                    failed_imports: vec![FailedImportCandidate {
+                        download_id: "download-1".into(),
+                        title: "Album".into(),
+                        status: "completed".into(),
+                        output_path: album_dir.to_string_lossy().to_string(),
+                        tracked_download_state: "importFailed".into(),
+                    }],
+                }]),
+            },
+            store.clone(),
+            ProcessingAdapters {
+                cue_scanner: FailingScanner,
+                cue_input_inspector: FakeInspector,
+                cue_splitter: FakeSplitter {
+                    output_track: album_dir.join("01 - Track.flac"),
+                },
+                manual_import: FakeManualImport,
+                download_log: FakeDownloadLog,
+                track_cleanup: FakeCleanup,
+                track_tagger: FakeTagger,
+                split_layout: SplitLayout::default(),
+                musicbrainz: MusicBrainzReleaseSelector::default(),
+                notifiers: Notifiers::default().with_notifier(
+                    "chat",
+                    NotificationEvent::ALL.to_vec(),
+                    notifier.clone(),
+                ),
+                dry_run: false,
+            },
+            60,
+        )
+        .with_retry_policy(RetryPolicy {
+            max_attempts: 1,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        });
+
+        service.run_once().await.unwrap();
+
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::GaveUp);
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].event, NotificationEvent::Failed);
+        assert!(
+            sent[0]
+                .message
+                .starts_with("gave up after 1 failed attempt(s): "),
+            "{}",
+            sent[0].message
+        );
+        assert_eq!(download.notification_deliveries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_manual_retry_backs_off_like_an_automatic_attempt() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let candidate = FailedImportCandidate {
+            download_id: "download-1".into(),
+            title: "Album".into(),
+            status: "completed".into(),
+            output_path: album_dir.to_string_lossy().to_string(),
+            tracked_download_state: "importFailed".into(),
+        };
+        store
+            .upsert_tracked_download(&TrackedDownload::pending(
+                candidate.download_id.clone(),
+                candidate.title.clone(),
+                candidate.status.clone(),
+                candidate.output_path.clone(),
+                candidate.tracked_download_state.clone(),
+            ))
+            .await
+            .unwrap();
+        store.mark_download_gave_up("download-1", 3).await.unwrap();
+        store
+            .request_download_action("download-1", DownloadAction::Retry)
+            .await
+            .unwrap();
+        let service = MonitorService::new(
+            FakeQueue {
+                snapshots: Mutex::new(vec![QueueSnapshot {
+                    total_records: 1,
+                    pages_fetched: 1,
+                    active_download_ids: HashSet::from(["download-1".to_owned()]),
+                    failed_imports: vec![candidate],
+                }]),
+            },
+            store.clone(),
+            ProcessingAdapters {
+                cue_scanner: FailingScanner,
+                cue_input_inspector: FakeInspector,
+                cue_splitter: FakeSplitter {
+                    output_track: album_dir.join("01 - Track.flac"),
+                },
+                manual_import: FakeManualImport,
+                download_log: FakeDownloadLog,
+                track_cleanup: FakeCleanup,
+                track_tagger: FakeTagger,
+                split_layout: SplitLayout::default(),
+                musicbrainz: MusicBrainzReleaseSelector::default(),
+                notifiers: Notifiers::default(),
+                dry_run: false,
+            },
+            60,
+        )
+        .with_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(3600),
+            max_delay: Duration::from_secs(3600),
+        });
+
+        service.run_once().await.unwrap();
+
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        // The queue check right after the action does not process it again.
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::Failed);
+        assert_eq!(download.attempt_count, 1);
+        assert!(download.next_retry_at.is_some());
+        let retry = &download.audit_events[0];
+        assert_eq!(retry.action, DownloadAction::Retry);
+        assert_eq!(retry.status, DownloadActionStatus::Failed);
+        assert!(retry
+            .message
+            .as_deref()
+            .is_some_and(|message| message.starts_with("processing failed: ")));
+    }
+
+    #[tokio::test]
+    async fn run_once_runs_requested_actions_before_polling_the_queue() {
+        let tmp = tempdir().unwrap();
+        let album_dir = tmp.path().join("album");
+        fs::create_dir_all(&album_dir).unwrap();
+        let cue_path = album_dir.join("album.cue");
+        let split_track = album_dir.join("01 - Track.flac");
+        fs::write(
+            &cue_path,
+            "FILE \"album.flac\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"Track One\"\n    INDEX 01 00:00:00\n",
+        )
+        .unwrap();
+        fs::write(album_dir.join("album.flac"), b"audio").unwrap();
+
+        let queue = FakeQueue {
+            snapshots: Mutex::new(vec![QueueSnapshot {
+                total_records: 1,
+                pages_fetched: 1,
+                active_download_ids: HashSet::from(["download-1".to_owned()]),
+                failed_imports: vec![FailedImportCandidate {
+                    download_id: "download-1".into(),
+                    title: "Album".into(),
+                    status: "completed".into(),
+                    output_path: album_dir.to_string_lossy().to_string(),
+                    tracked_download_state: "importFailed".into(),
+                }],
+            }]),
+        };
+        let store = SqliteDownloadStore::open(tmp.path()).unwrap();
+        let service = MonitorService::new(
+            queue,
+            store.clone(),
+            ProcessingAdapters {
+                cue_scanner: FakeScanner { cue_path },
+                cue_input_inspector: FakeInspector,
+                cue_splitter: FakeSplitter {
+                    output_track: split_track.clone(),
+                },
+                manual_import: FakeManualImport,
+                download_log: FakeDownloadLog,
+                track_cleanup: FakeCleanup,
+                track_tagger: FakeTagger,
+                split_layout: SplitLayout::default(),
+                musicbrainz: MusicBrainzReleaseSelector::default(),
+                notifiers: Notifiers::default(),
+                dry_run: false,
+            },
+            60,
+        );
+        service.run_once().await.unwrap();
+
+        for action in [
+            DownloadAction::Reimport,
+            DownloadAction::ForceCleanup,
+            DownloadAction::Reimport,
+            DownloadAction::Ignore,
+        ] {
+            store
+                .request_download_action("download-1", action)
+                .await
+                .unwrap();
+        }
+        service.run_once().await.unwrap();
+
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::Ignored);
+        assert_eq!(
+            download.cue_sheets[0].tracks[0].cleanup_status,
+            TrackCleanupStatus::Deleted
+        );
+        let events = download
+            .audit_events
+            .iter()
+            .rev()
+            .map(|event| (event.action, event.status, event.message.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    DownloadAction::Reimport,
+                    DownloadActionStatus::Succeeded,
+                    Some("Manual import: disabled")
+                ),
+                (
+                    DownloadAction::ForceCleanup,
+                    DownloadActionStatus::Succeeded,
+                    Some("cleaned up 1 generated track(s)")
+                ),
+                (
+                    DownloadAction::Reimport,
+                    DownloadActionStatus::Failed,
+                    Some("no generated tracks are left to import")
+                ),
+                (
+                    DownloadAction::Ignore,
+                    DownloadActionStatus::Succeeded,
+                    Some("download ignored")
+                ),
+            ]
+        );
+        assert!(download
+            .audit_events
+            .iter()
+            .all(|event| event.finished_at.is_some()));
+
+        service.run_once().await.unwrap();
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.lifecycle_state, DownloadLifecycleState::Ignored);
     }
 }
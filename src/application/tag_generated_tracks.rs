@@ -0,0 +1,620 @@
+use std::path::{Path, PathBuf};
+
+use crate::application::ports::{
+    CueMetadataHint, DownloadStore, MusicBrainzDiscRelease, TrackTagger,
+};
+use crate::domain::{CueSheet, InputFileKind, OutputCodec, TrackTag};
+
+pub async fn tag_generated_tracks<S, T>(
+    store: &S,
+    tagger: &T,
+    download_id: &str,
+    cue_hint: &CueMetadataHint,
+    release: Option<&MusicBrainzDiscRelease>,
+    tracks: &[PathBuf],
+) -> Vec<String>
+where
+    S: DownloadStore,
+    T: TrackTagger,
+{
+    let mut log = Vec::new();
+    if let Some(release) = release {
+        log.push(format!("Tagging: using MusicBrainz release {}", release.id));
+    }
+
+    for (index, path) in tracks.iter().enumerate() {
+        let tags = track_tags(cue_hint, index, release);
+        if let Err(err) = tagger.write_track_tags(path, &tags).await {
+            log.push(format!("Tagging failed for {}: {err}", path.display()));
+            continue;
+        }
+        if let Err(err) = store.record_track_tags(download_id, path, &tags).await {
+            log.push(format!(
+                "Recording tags failed for {}: {err}",
+                path.display()
+            ));
+            continue;
+        }
+        log.push(format!(
+            "  tagged: {} ({} tag(s))",
+            path.display(),
+            tags.len()
+        ));
+    }
+    log
+}
+
+pub struct ArtworkTargets<'a> {
+    pub cue_path: &'a Path,
+    pub audio_inputs: &'a [PathBuf],
+    pub tracks: &'a [PathBuf],
+    pub codec: OutputCodec,
+    /// Only staged output gets a `folder.<ext>`; the download folder is
+    /// left as the download client wrote it.
+    pub folder_artwork: bool,
+}
+
+pub async fn embed_track_artwork<S, T>(
+    store: &S,
+    tagger: &T,
+    cue_sheet: &CueSheet,
+    targets: ArtworkTargets<'_>,
+) -> Vec<String>
+where
+    S: DownloadStore,
+    T: TrackTagger,
+{
+    let mut log = Vec::new();
+    let artwork = match tagger
+        .find_artwork(targets.cue_path, targets.audio_inputs)
+        .await
+    {
+        Ok(Some(artwork)) => artwork,
+        Ok(None) => {
+            log.push("Artwork: none found".to_owned());
+            return log;
+        }
+        Err(err) => {
+            log.push(format!("Artwork: lookup failed: {err}"));
+            return log;
+        }
+    };
+    let input_path = artwork.input_path();
+    log.push(format!(
+        "Artwork: using {} ({}, {} bytes)",
+        input_path.display(),
+        artwork.format_label(),
+        artwork.data.len()
+    ));
+    if let Err(err) = store
+        .record_input_file(
+            &cue_sheet.download_id,
+            Some(&cue_sheet.id),
+            &input_path,
+            InputFileKind::Artwork,
+            i64::try_from(artwork.data.len()).ok(),
+            Some(artwork.format_label()),
+        )
+        .await
+    {
+        log.push(format!("Artwork: recording source failed: {err}"));
+    }
+
+    if let Some(directory) = targets
+        .tracks
+        .first()
+        .and_then(|track| track.parent())
+        .filter(|_| targets.folder_artwork)
+    {
+        match tagger.write_folder_artwork(directory, &artwork).await {
+            Ok(Some(path)) => log.push(format!("Artwork: wrote {}", path.display())),
+            Ok(None) => {}
+            Err(err) => log.push(format!("Artwork: writing folder image failed: {err}")),
+        }
+    }
+
+    if targets.codec != OutputCodec::Flac {
+        log.push(format!(
+            "Artwork: embedding skipped for {} output",
+            targets.codec.as_str()
+        ));
+        return log;
+    }
+    let mut embedded = 0;
+    for path in targets.tracks {
+        match tagger.write_track_artwork(path, &artwork).await {
+            Ok(()) => embedded += 1,
+            Err(err) => log.push(format!(
+                "Artwork embedding failed for {}: {err}",
+                path.display()
+            )),
+        }
+    }
+    log.push(format!("Artwork: embedded into {embedded} track(s)"));
+    log
+}
+
+pub fn track_tags(
+    cue_hint: &CueMetadataHint,
+    track_index: usize,
+    release: Option<&MusicBrainzDiscRelease>,
+) -> Vec<TrackTag> {
+    let track = cue_hint.tracks.get(track_index);
+    let mut tags = Vec::new();
+    let mut push = |name: &str, value: Option<&str>| {
+        if let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) {
+            tags.push(TrackTag {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            });
+        }
+    };
+
+    let album_artist = cue_hint.performer.as_deref();
+    let artist = track
+        .and_then(|track| track.performer.as_deref())
+        .or(album_artist);
+    let track_number = track.map_or_else(
+        || (track_index + 1).to_string(),
+        |track| {
+            track
+                .number
+                .trim()
+                .parse::<u32>()
+                .map_or_else(|_| track.number.clone(), |number| number.to_string())
+        },
+    );
+    let total_tracks = cue_hint.track_count.max(cue_hint.tracks.len()).to_string();
+    let date =
+        comment(cue_hint, "DATE").or_else(|| release.and_then(|release| release.date.as_deref()));
+
+    push("ARTIST", artist);
+    push("ALBUMARTIST", album_artist);
+    push("ALBUM", cue_hint.album_title.as_deref());
+    push("TITLE", track.and_then(|track| track.title.as_deref()));
+    push("TRACKNUMBER", Some(&track_number));
+    push("TOTALTRACKS", Some(&total_tracks));
+    push("DATE", date);
+    push("GENRE", comment(cue_hint, "GENRE"));
+    let disc_number = cue_hint.disc_number.map(|number| number.to_string());
+    push(
+        "DISCNUMBER",
+        comment(cue_hint, "DISCNUMBER").or(disc_number.as_deref()),
+    );
+    push("TOTALDISCS", comment(cue_hint, "TOTALDISCS"));
+    push("CATALOGNUMBER", cue_hint.catalog.as_deref());
+    if let Some(release) = release {
+        push("MUSICBRAINZ_ALBUMID", Some(&release.id));
+        push(
+            "MUSICBRAINZ_RELEASEGROUPID",
+            release.release_group_id.as_deref(),
+        );
+    }
+    tags
+}
+
+fn comment<'a>(cue_hint: &'a CueMetadataHint, key: &str) -> Option<&'a str> {
+    cue_hint
+        .comments
+        .iter()
+        .find(|(comment_key, _)| comment_key.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+
+    use anyhow::{anyhow, Result};
+
+    use super::{embed_track_artwork, tag_generated_tracks, track_tags, ArtworkTargets};
+    use crate::application::ports::{
+        ArtworkSource, CueMetadataHint, CueTrackHint, DownloadStore, MusicBrainzDiscRelease,
+        TrackArtwork, TrackTagger,
+    };
+    use crate::domain::{
+        CueSheet, CueSheetStatus, InputFileKind, OutputCodec, RecordedTrack, TrackCleanupStatus,
+        TrackTag, TrackedDownload,
+    };
+
+    #[test]
+    fn builds_tags_from_cue_hint_and_selected_release() {
+        let release = MusicBrainzDiscRelease {
+            id: "release-1".into(),
+            date: Some("2001-05-01".into()),
+            release_group_id: Some("group-1".into()),
+            ..MusicBrainzDiscRelease::default()
+        };
+
+        let tags = track_tags(&cue_hint(), 1, Some(&release));
+
+        assert_eq!(
+            tags,
+            vec![
+                tag("ARTIST", "Guest"),
+                tag("ALBUMARTIST", "Artist"),
+                tag("ALBUM", "Album"),
+                tag("TITLE", "Two"),
+                tag("TRACKNUMBER", "2"),
+                tag("TOTALTRACKS", "2"),
+                tag("DATE", "1999"),
+                tag("GENRE", "Rock"),
+                tag("DISCNUMBER", "1"),
+                tag("MUSICBRAINZ_ALBUMID", "release-1"),
+                tag("MUSICBRAINZ_RELEASEGROUPID", "group-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_album_artist_and_release_date() {
+        let mut hint = cue_hint();
+        hint.comments.retain(|(key, _)| key != "DATE");
+        let release = MusicBrainzDiscRelease {
+            id: "release-1".into(),
+            date: Some("2001-05-01".into()),
+            ..MusicBrainzDiscRelease::default()
+        };
+
+        let tags = track_tags(&hint, 0, Some(&release));
+
+        assert!(tags.contains(&tag("ARTIST", "Artist")));
+        assert!(tags.contains(&tag("DATE", "2001-05-01")));
+        assert!(!tags
+            .iter()
+            .any(|tag| tag.name == "MUSICBRAINZ_RELEASEGROUPID"));
+    }
+
+    #[tokio::test]
+    async fn records_tags_and_reports_failures_without_aborting() {
+        let store = FakeStore::default();
+        let tagger = FakeTagger {
+            fail_path: PathBuf::from("/album/02.flac"),
+            ..FakeTagger::default()
+        };
+
+        let log = tag_generated_tracks(
+            &store,
+            &tagger,
+            "download-1",
+            &cue_hint(),
+            None,
+            &[
+                PathBuf::from("/album/01.flac"),
+                PathBuf::from("/album/02.flac"),
+            ],
+        )
+        .await;
+
+        let recorded = store.recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].0, PathBuf::from("/album/01.flac"));
+        assert!(recorded[0].1.contains(&tag("TITLE", "One")));
+        assert!(log
+            .iter()
+            .any(|line| line.contains("Tagging failed for /album/02.flac")));
+    }
+
+    #[tokio::test]
+    async fn embeds_artwork_and_records_its_source() {
+        let store = FakeStore::default();
+        let tagger = FakeTagger {
+            fail_path: PathBuf::from("/album/02.flac"),
+            artwork: Some(TrackArtwork {
+                source: ArtworkSource::Embedded(PathBuf::from("/album/album.flac")),
+                mime_type: "image/png".into(),
+                data: vec![0; 12],
+            }),
+            ..FakeTagger::default()
+        };
+        let tracks = [
+            PathBuf::from("/album/01.flac"),
+            PathBuf::from("/album/02.flac"),
+        ];
+
+        let log = embed_track_artwork(
+            &store,
+            &tagger,
+            &cue_sheet(),
+            ArtworkTargets {
+                cue_path: Path::new("/album/album.cue"),
+                audio_inputs: &[PathBuf::from("/album/album.flac")],
+                tracks: &tracks,
+                codec: OutputCodec::Flac,
+                folder_artwork: true,
+            },
+        )
+        .await;
+
+        assert_eq!(
+            store.inputs.lock().unwrap().as_slice(),
+            &[(
+                PathBuf::from("/album/album.flac#picture"),
+                InputFileKind::Artwork,
+                Some("PNG".to_owned())
+            )]
+        );
+        assert_eq!(
+            tagger.embedded.lock().unwrap().as_slice(),
+            &[PathBuf::from("/album/01.flac")]
+        );
+        assert_eq!(
+            tagger.folders.lock().unwrap().as_slice(),
+            &[PathBuf::from("/album")]
+        );
+        assert!(log
+            .iter()
+            .any(|line| line.contains("Artwork embedding failed for /album/02.flac")));
+        assert!(log.contains(&"Artwork: embedded into 1 track(s)".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn skips_embedding_artwork_for_non_flac_output() {
+        let store = FakeStore::default();
+        let tagger = FakeTagger {
+            artwork: Some(TrackArtwork {
+                source: ArtworkSource::File(PathBuf::from("/album/cover.jpg")),
+                mime_type: "image/jpeg".into(),
+                data: vec![0; 4],
+            }),
+            ..FakeTagger::default()
+        };
+
+        let log = embed_track_artwork(
+            &store,
+            &tagger,
+            &cue_sheet(),
+            ArtworkTargets {
+                cue_path: Path::new("/album/album.cue"),
+                audio_inputs: &[],
+                tracks: &[PathBuf::from("/album/01.mp3")],
+                codec: OutputCodec::Mp3,
+                folder_artwork: true,
+            },
+        )
+        .await;
+
+        assert_eq!(store.inputs.lock().unwrap().len(), 1);
+        assert!(tagger.embedded.lock().unwrap().is_empty());
+        assert!(log.contains(&"Artwork: embedding skipped for mp3 output".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn leaves_folder_artwork_out_of_an_unstaged_download_folder() {
+        let store = FakeStore::default();
+        let tagger = FakeTagger {
+            artwork: Some(TrackArtwork {
+                source: ArtworkSource::File(PathBuf::from("/album/cover.jpg")),
+                mime_type: "image/jpeg".into(),
+                data: vec![0; 4],
+            }),
+            ..FakeTagger::default()
+        };
+
+        let log = embed_track_artwork(
+            &store,
+            &tagger,
+            &cue_sheet(),
+            ArtworkTargets {
+                cue_path: Path::new("/album/album.cue"),
+                audio_inputs: &[],
+                tracks: &[PathBuf::from("/album/01.flac")],
+                codec: OutputCodec::Flac,
+                folder_artwork: false,
+            },
+        )
+        .await;
+
+        assert!(tagger.folders.lock().unwrap().is_empty());
+        assert_eq!(
+            tagger.embedded.lock().unwrap().as_slice(),
+            &[PathBuf::from("/album/01.flac")]
+        );
+        assert!(log.contains(&"Artwork: embedded into 1 track(s)".to_owned()));
+    }
+
+    fn cue_sheet() -> CueSheet {
+        CueSheet {
+            id: "cue-1".into(),
+            download_id: "download-1".into(),
+            path: "/album/album.cue".into(),
+            status: CueSheetStatus::Split,
+            message: None,
+            updated_at: String::new(),
+            tracks: Vec::new(),
+            findings: Vec::new(),
+            repairs: Vec::new(),
+            encoding: None,
+            pregap_policy: None,
+        }
+    }
+
+    fn cue_hint() -> CueMetadataHint {
+        CueMetadataHint {
+            path: PathBuf::from("/album/album.cue"),
+            album_title: Some("Album".into()),
+            performer: Some("Artist".into()),
+            catalog: None,
+            disc_id: None,
+            disc_number: None,
+            comments: vec![
+                ("DATE".into(), "1999".into()),
+                ("GENRE".into(), "\"Rock\"".into()),
+                ("DISCNUMBER".into(), "1".into()),
+            ],
+            track_count: 2,
+            tracks: vec![
+                CueTrackHint {
+                    number: "01".into(),
+                    title: Some("One".into()),
+                    performer: None,
+                },
+                CueTrackHint {
+                    number: "02".into(),
+                    title: Some("Two".into()),
+                    performer: Some("Guest".into()),
+                },
+            ],
+        }
+    }
+
+    fn tag(name: &str, value: &str) -> TrackTag {
+        TrackTag {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeTagger {
+        fail_path: PathBuf,
+        artwork: Option<TrackArtwork>,
+        embedded: Mutex<Vec<PathBuf>>,
+        folders: Mutex<Vec<PathBuf>>,
+    }
+
+    impl TrackTagger for FakeTagger {
+        async fn write_track_tags(&self, track_path: &Path, _tags: &[TrackTag]) -> Result<()> {
+            if track_path == self.fail_path {
+                return Err(anyhow!("not a FLAC file"));
+            }
+            Ok(())
+        }
+
+        async fn find_artwork(
+            &self,
+            _cue_path: &Path,
+            _audio_inputs: &[PathBuf],
+        ) -> Result<Option<TrackArtwork>> {
+            Ok(self.artwork.clone())
+        }
+
+        async fn write_track_artwork(
+            &self,
+            track_path: &Path,
+            _artwork: &TrackArtwork,
+        ) -> Result<()> {
+            if track_path == self.fail_path {
+                return Err(anyhow!("not a FLAC file"));
+            }
+            self.embedded.lock().unwrap().push(track_path.to_path_buf());
+            Ok(())
+        }
+
+        async fn write_folder_artwork(
+            &self,
+            directory: &Path,
+            _artwork: &TrackArtwork,
+        ) -> Result<Option<PathBuf>> {
+            self.folders.lock().unwrap().push(directory.to_path_buf());
+            Ok(None)
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeStore {
+        recorded: Mutex<Vec<(PathBuf, Vec<TrackTag>)>>,
+        inputs: Mutex<Vec<(PathBuf, InputFileKind, Option<String>)>>,
+    }
+
+    impl DownloadStore for FakeStore {
+        async fn load_tracked_downloads(&self) -> Result<Vec<TrackedDownload>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_tracked_download(
+            &self,
+            _download_id: &str,
+        ) -> Result<Option<TrackedDownload>> {
+            Ok(None)
+        }
+
+        async fn upsert_tracked_download(&self, _download: &TrackedDownload) -> Result<()> {
+            Ok(())
+        }
+
+        async fn mark_download_processing(&self, _download_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn mark_download_awaiting_import(&self, _download_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn mark_download_cleanup_started(&self, _download_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn mark_download_completed(&self, _download_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn mark_download_failed(
+            &self,
+            _download_id: &str,
+            _last_error: Option<&str>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_or_create_cue_sheet(
+            &self,
+            _download_id: &str,
+            _path: &Path,
+        ) -> Result<CueSheet> {
+            Err(anyhow!("not used"))
+        }
+
+        async fn record_input_file(
+            &self,
+            _download_id: &str,
+            _cue_sheet_id: Option<&str>,
+            path: &Path,
+            kind: InputFileKind,
+            _size_bytes: Option<i64>,
+            audio_format: Option<&str>,
+        ) -> Result<()> {
+            self.inputs.lock().unwrap().push((
+                path.to_path_buf(),
+                kind,
+                audio_format.map(str::to_owned),
+            ));
+            Ok(())
+        }
+
+        async fn record_cue_result(
+            &self,
+            _cue_sheet: &CueSheet,
+            _status: CueSheetStatus,
+            _message: Option<&str>,
+            _tracks: &[RecordedTrack],
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn record_track_tags(
+            &self,
+            _download_id: &str,
+            track_path: &Path,
+            tags: &[TrackTag],
+        ) -> Result<()> {
+            self.recorded
+                .lock()
+                .unwrap()
+                .push((track_path.to_path_buf(), tags.to_vec()));
+            Ok(())
+        }
+
+        async fn record_track_cleanup(
+            &self,
+            _download_id: &str,
+            _track_id: &str,
+            _status: TrackCleanupStatus,
+            _message: Option<&str>,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+}
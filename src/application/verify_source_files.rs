@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::application::ports::{CueInputInspector, DownloadStore};
+use crate::domain::{FileFingerprint, SourceVerificationStatus};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFingerprint {
+    pub path: PathBuf,
+    pub fingerprint: FileFingerprint,
+}
+
+/// The fingerprints stored with a cue sheet's `InputFile`s, the baseline every
+/// later verification compares against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordedSources {
+    pub fingerprints: Vec<SourceFingerprint>,
+    /// Problems for source files that were already gone when recorded.
+    pub missing: Vec<String>,
+}
+
+/// Records the fingerprint of every source file next to its `InputFile`,
+/// keeping one recorded by an earlier attempt. Files that no longer exist are
+/// marked missing instead of being skipped.
+pub async fn fingerprint_source_files<S, I>(
+    store: &S,
+    inspector: &I,
+    download_id: &str,
+    paths: &[PathBuf],
+) -> Result<RecordedSources>
+where
+    S: DownloadStore,
+    I: CueInputInspector,
+{
+    let mut sources = RecordedSources::default();
+    for path in paths {
+        let Some(fingerprint) = inspector.fingerprint_file(path).await? else {
+            store
+                .record_input_verification(
+                    download_id,
+                    path,
+                    SourceVerificationStatus::Missing,
+                    Some("file is missing"),
+                )
+                .await?;
+            sources
+                .missing
+                .push(format!("{}: file is missing", path.display()));
+            continue;
+        };
+        let fingerprint = store
+            .record_input_fingerprint(download_id, path, &fingerprint)
+            .await?;
+        sources.fingerprints.push(SourceFingerprint {
+            path: path.clone(),
+            fingerprint,
+        });
+    }
+    Ok(sources)
+}
+
+/// Compares every source file with its recorded fingerprint and records the
+/// result. Returns one problem per file that changed or disappeared.
+pub async fn verify_source_files<S, I>(
+    store: &S,
+    inspector: &I,
+    download_id: &str,
+    sources: &[SourceFingerprint],
+) -> Result<Vec<String>>
+where
+    S: DownloadStore,
+    I: CueInputInspector,
+{
+    let mut problems = Vec::new();
+    for source in sources {
+        let (status, message) = match inspector.fingerprint_file(&source.path).await? {
+            None => (
+                SourceVerificationStatus::Missing,
+                Some("file is missing".to_owned()),
+            ),
+            Some(current) => {
+                let changes = current.changes_since(&source.fingerprint);
+                if changes.is_empty() {
+                    (SourceVerificationStatus::Unchanged, None)
+                } else {
+                    (SourceVerificationStatus::Changed, Some(changes.join(", ")))
+                }
+            }
+        };
+        store
+            .record_input_verification(download_id, &source.path, status, message.as_deref())
+            .await?;
+        if let Some(message) = message {
+            problems.push(format!("{}: {message}", source.path.display()));
+        }
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::{fingerprint_source_files, verify_source_files};
+    use crate::adapters::filesystem_cue_input_inspector::FilesystemCueInputInspector;
+    use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+    use crate::application::ports::DownloadStore;
+    use crate::domain::{InputFileKind, SourceVerificationStatus, TrackedDownload};
+
+    #[tokio::test]
+    async fn reports_changed_and_missing_sources() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path().join("data")).unwrap();
+        let inspector = FilesystemCueInputInspector::new(true);
+        let cue_path = tmp.path().join("album.cue");
+        let audio_path = tmp.path().join("album.flac");
+        let untouched_path = tmp.path().join("disc2.flac");
+        fs::write(&cue_path, b"FILE \"album.flac\" WAVE").unwrap();
+        fs::write(&audio_path, b"audio").unwrap();
+        fs::write(&untouched_path, b"audio").unwrap();
+        store
+            .upsert_tracked_download(&TrackedDownload::pending(
+                "download-1".into(),
+                "Album".into(),
+                "completed".into(),
+                tmp.path().to_string_lossy().into_owned(),
+                "importFailed".into(),
+            ))
+            .await
+            .unwrap();
+        let paths = vec![cue_path.clone(), audio_path.clone(), untouched_path.clone()];
+        for path in &paths {
+            store
+                .record_input_file("download-1", None, path, InputFileKind::Audio, None, None)
+                .await
+                .unwrap();
+        }
+
+        let sources = fingerprint_source_files(&store, &inspector, "download-1", &paths)
+            .await
+            .unwrap();
+        assert!(sources.missing.is_empty());
+        fs::remove_file(&cue_path).unwrap();
+        fs::write(&audio_path, b"tagged audio").unwrap();
+
+        let problems = verify_source_files(&store, &inspector, "download-1", &sources.fingerprints)
+            .await
+            .unwrap();
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(
+            problems[0],
+            format!("{}: file is missing", cue_path.display())
+        );
+        assert!(problems[1].starts_with(&format!(
+            "{}: size changed from 5 to 12 bytes",
+            audio_path.display()
+        )));
+        assert!(problems[1].ends_with("content hash changed"));
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        let status_for = |path: &Path| {
+            download
+                .input_files
+                .iter()
+                .find(|input| input.path == path.to_string_lossy())
+                .and_then(|input| input.verification.as_ref())
+                .map(|verification| verification.status)
+        };
+        assert_eq!(
+            status_for(&cue_path),
+            Some(SourceVerificationStatus::Missing)
+        );
+        assert_eq!(
+            status_for(&audio_path),
+            Some(SourceVerificationStatus::Changed)
+        );
+        assert_eq!(
+            status_for(&untouched_path),
+            Some(SourceVerificationStatus::Unchanged)
+        );
+    }
+
+    #[tokio::test]
+    async fn marks_sources_missing_when_they_are_gone_before_recording() {
+        let tmp = tempdir().unwrap();
+        let store = SqliteDownloadStore::open(tmp.path().join("data")).unwrap();
+        let inspector = FilesystemCueInputInspector::new(false);
+        let audio_path = tmp.path().join("album.flac");
+        store
+            .upsert_tracked_download(&TrackedDownload::pending(
+                "download-1".into(),
+                "Album".into(),
+                "completed".into(),
+                tmp.path().to_string_lossy().into_owned(),
+                "importFailed".into(),
+            ))
+            .await
+            .unwrap();
+        store
+            .record_input_file(
+                "download-1",
+                None,
+                &audio_path,
+                InputFileKind::Audio,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let sources = fingerprint_source_files(
+            &store,
+            &inspector,
+            "download-1",
+            std::slice::from_ref(&audio_path),
+        )
+        .await
+        .unwrap();
+
+        assert!(sources.fingerprints.is_empty());
+        assert_eq!(
+            sources.missing,
+            vec![format!("{}: file is missing", audio_path.display())]
+        );
+        let download = store
+            .get_tracked_download("download-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            download.input_files[0]
+                .verification
+                .as_ref()
+                .map(|verification| verification.status),
+            Some(SourceVerificationStatus::Missing)
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use tracing::{error, info, warn, Instrument};
+
+use crate::adapters::audio_format;
+use crate::adapters::cli_output::{render_download_details, render_download_list};
+use crate::adapters::filesystem_cleanup::FilesystemTrackCleanup;
+use crate::adapters::filesystem_cue_input_inspector::FilesystemCueInputInspector;
+use crate::adapters::filesystem_cue_scanner::FilesystemCueScanner;
+use crate::adapters::filesystem_download_log::FilesystemDownloadLog;
+use crate::adapters::flac_tagger::FlacTrackTagger;
+use crate::adapters::gnudb_api::GnudbDiscReleaseLookup;
+use crate::adapters::lidarr_api::LidarrQueueSource;
+use crate::adapters::musicbrainz_api::FilesystemMusicBrainzDiscReleaseLookup;
+use crate::adapters::smtp_notifier::SmtpNotifier;
+use crate::adapters::sqlite_download_store::SqliteDownloadStore;
+use crate::adapters::web;
+use crate::adapters::web_auth::{LoginMethod, WebAuth, WebLogin};
+use crate::adapters::webhook_notifier::{WebhookFormat, WebhookNotifier};
+use crate::application::cleanup_processed_download::{cleanup_processed_download, CleanupReason};
+use crate::application::download_lock::with_download_lock;
+use crate::application::metrics::Metrics;
+use crate::application::notifications::Notifiers;
+use crate::application::ports::{DownloadStore, Notifier};
+use crate::application::process_tracked_download::process_tracked_download;
+use crate::application::select_musicbrainz_release::MusicBrainzReleaseSelector;
+use crate::application::service::{
+    download_span, MonitorService, ProcessingAdapters, QueueCheckRequests, Shutdown,
+};
+use crate::bootstrap::cue_splitter::ConfiguredCueSplitter;
+use crate::bootstrap::settings::{AuthMethod, Command, NotifierKind, Settings, SplitterBackend};
+use crate::domain::{
+    local_download_id, DownloadLifecycleState, SplitLayout, TrackedDownload, LOCAL_DOWNLOAD_STATE,
+};
+
+type Adapters = ProcessingAdapters<
+    FilesystemCueScanner,
+    FilesystemCueInputInspector,
+    ConfiguredCueSplitter,
+    LidarrQueueSource,
+    FilesystemDownloadLog,
+    FilesystemTrackCleanup,
+    FlacTrackTagger,
+>;
+
+pub async fn run_command(settings: Settings, command: Command) -> Result<()> {
+    match command {
+        Command::Run => run_daemon(settings).await,
+        Command::Split { dir } => split_directory(settings, &dir).await,
+        Command::Reprocess { download_id } => reprocess_download(settings, &download_id).await,
+        Command::Cleanup { download_id } => cleanup_download(settings, &download_id).await,
+        Command::List => {
+            let store = open_store(&settings)?;
+            let downloads = store.load_tracked_download_summaries().await?;
+            print!("{}", render_download_list(&downloads));
+            Ok(())
+        }
+        Command::Show { download_id } => {
+            let store = open_store(&settings)?;
+            let download = find_download(&store, &download_id).await?;
+            print!("{}", render_download_details(&download));
+            Ok(())
+        }
+    }
+}
+
+async fn run_daemon(settings: Settings) -> Result<()> {
+    let metrics = Metrics::default();
+    let adapters = processing_adapters(&settings, &metrics);
+    let download_store = open_store(&settings)?;
+    let web_store = download_store.clone();
+    let audio_formats = report_missing_audio_programs(&settings);
+    let queue_checks = QueueCheckRequests::default();
+    let shutdown = Shutdown::default();
+    let check_frequency_seconds = if settings.webhook.enabled {
+        settings.webhook.fallback_check_frequency_seconds
+    } else {
+        settings.check_frequency_seconds
+    };
+    let webhook = settings.webhook.enabled.then(|| web::LidarrWebhook {
+        username: settings.webhook.username.clone(),
+        password: settings.webhook.password.clone(),
+    });
+    let status_config = web::StatusConfig {
+        version: env!("CARGO_PKG_VERSION"),
+        data_dir: settings.data_dir.to_string_lossy().into_owned(),
+        staging_dir: settings
+            .staging_dir
+            .as_ref()
+            .map(|staging_dir| staging_dir.to_string_lossy().into_owned()),
+        check_frequency_seconds,
+        max_concurrent_downloads: settings.max_concurrent_downloads,
+        webhook_enabled: settings.webhook.enabled,
+        api_key_enabled: !settings.server.api_key.is_empty(),
+        login_method: login_method(settings.server.auth_method),
+        retry_max_attempts: settings.retry.max_attempts,
+        retry_initial_delay_seconds: settings.retry.initial_delay_seconds,
+        retry_max_delay_seconds: settings.retry.max_delay_seconds,
+        dry_run: settings.dry_run,
+        download_log_enabled: settings.logging.download_log_enabled,
+        lidarr_url: settings.lidarr.url.clone(),
+        manual_import_enabled: settings.lidarr.manual_import_enabled,
+        musicbrainz_enabled: settings.musicbrainz.disc_lookup_enabled,
+        musicbrainz_base_url: settings.musicbrainz.base_url.clone(),
+        musicbrainz_trust_disc_lookup: settings.musicbrainz.trust_disc_lookup,
+        musicbrainz_add_missing_release_group: settings
+            .musicbrainz
+            .add_missing_release_group_enabled,
+        gnudb_enabled: settings.gnudb.disc_lookup_enabled,
+        gnudb_server: settings.gnudb.server.clone(),
+        cue_strict: settings.cue.strict,
+        splitter_backend: settings.splitter.backend.as_str().to_owned(),
+        shnsplit_path: settings.shnsplit.path.to_string_lossy().into_owned(),
+        shnsplit_overwrite: settings.shnsplit.overwrite,
+        shnsplit_format: settings.shnsplit.format.clone(),
+        audio_formats,
+        ignore_rules: settings.ignore.rules(),
+    };
+    let service = MonitorService::new(
+        adapters.manual_import.clone(),
+        download_store,
+        adapters,
+        check_frequency_seconds,
+    )
+    .with_ignore_rules(settings.ignore.rules())
+    .with_retry_policy(settings.retry.policy())
+    .with_queue_check_requests(queue_checks.clone())
+    .with_max_concurrent_downloads(settings.max_concurrent_downloads)
+    .with_shutdown(shutdown.clone())
+    .with_metrics(metrics.clone());
+    let listener = tokio::net::TcpListener::bind(&settings.server.bind_address)
+        .await
+        .with_context(|| format!("bind {}", settings.server.bind_address))?;
+    let auth = WebAuth::new(
+        Some(settings.server.api_key.clone()),
+        login_method(settings.server.auth_method).map(|method| WebLogin {
+            method,
+            username: settings.server.username.clone(),
+            password: settings.server.password.clone(),
+        }),
+    )
+    .with_trusted_proxies(settings.server.trusted_proxies.clone());
+    let app = web::router(
+        web_store.clone(),
+        status_config,
+        webhook,
+        auth,
+        metrics,
+        queue_checks,
+    )
+    .into_make_service_with_connect_info::<SocketAddr>();
+
+    info!(
+        "Web UI listening on http://{}",
+        settings.server.bind_address
+    );
+
+    let monitor = tokio::spawn(async move {
+        if let Err(err) = service.run().await {
+            error!("Monitor exited: {err:#}");
+        }
+    });
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            match shutdown_signal().await {
+                Ok(()) => {
+                    info!("Shutting down, no new downloads will be started");
+                    shutdown.request();
+                }
+                Err(err) => error!("Listening for shutdown signals failed: {err:#}"),
+            }
+        }
+    });
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { shutdown.requested().await })
+        .await
+        .context("run web server")?;
+
+    let timeout = Duration::from_secs(settings.shutdown_timeout_seconds);
+    if tokio::time::timeout(timeout, monitor).await.is_err() {
+        warn!(
+            "In-flight downloads did not finish within {} s, they will be recovered on the next start",
+            settings.shutdown_timeout_seconds
+        );
+        web_store.release_download_locks().await?;
+    }
+    Ok(())
+}
+
+fn login_method(auth_method: AuthMethod) -> Option<LoginMethod> {
+    match auth_method {
+        AuthMethod::None => None,
+        AuthMethod::Basic => Some(LoginMethod::Basic),
+        AuthMethod::Forms => Some(LoginMethod::Forms),
+    }
+}
+
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .context("listen for SIGTERM")?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.context("listen for SIGINT"),
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await.context("listen for Ctrl-C")
+    }
+}
+
+async fn split_directory(mut settings: Settings, dir: &Path) -> Result<()> {
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("resolve {}", dir.display()))?;
+    if !dir.is_dir() {
+        return Err(anyhow!("not a directory: {}", dir.display()));
+    }
+    settings.lidarr.manual_import_enabled = false;
+    report_missing_audio_programs(&settings);
+    let store = open_store(&settings)?;
+    let adapters = processing_adapters(&settings, &Metrics::default());
+
+    let download_id = local_download_id(&dir);
+    let download = match store.get_tracked_download(&download_id).await? {
+        Some(download) => download,
+        None => {
+            let title = dir.file_name().map_or_else(
+                || dir.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            let download = TrackedDownload::pending(
+                download_id.clone(),
+                title,
+                "completed".to_owned(),
+                dir.display().to_string(),
+                LOCAL_DOWNLOAD_STATE.to_owned(),
+            );
+            store.upsert_tracked_download(&download).await?;
+            download
+        }
+    };
+
+    locked(&store, &download_id, async {
+        process_download(&store, &adapters, download).await?;
+        let download = find_download(&store, &download_id).await?;
+        if download.lifecycle_state == DownloadLifecycleState::AwaitingImport {
+            // Nothing imports local splits, so the tracks are the final result.
+            store.mark_download_completed(&download_id).await?;
+        }
+        Ok(())
+    })
+    .await?;
+    report_result(find_download(&store, &download_id).await?)
+}
+
+async fn reprocess_download(settings: Settings, download_id: &str) -> Result<()> {
+    let store = open_store(&settings)?;
+    let download = find_download(&store, download_id).await?;
+    if download.is_local() {
+        return split_directory(settings, Path::new(&download.output_path)).await;
+    }
+    report_missing_audio_programs(&settings);
+    let adapters = processing_adapters(&settings, &Metrics::default());
+    locked(
+        &store,
+        download_id,
+        process_download(&store, &adapters, download),
+    )
+    .await?;
+    report_result(find_download(&store, download_id).await?)
+}
+
+async fn cleanup_download(settings: Settings, download_id: &str) -> Result<()> {
+    let store = open_store(&settings)?;
+    let download = find_download(&store, download_id).await?;
+    if settings.dry_run {
+        print!("{}", render_download_details(&download));
+        println!(
+            "Dry run: would delete {} generated track(s)",
+            download.generated_track_count()
+        );
+        return Ok(());
+    }
+    let adapters = processing_adapters(&settings, &Metrics::default());
+    locked(
+        &store,
+        download_id,
+        cleanup_processed_download(
+            &store,
+            &adapters.track_cleanup,
+            &adapters.download_log,
+            &adapters.notifiers,
+            &download,
+            CleanupReason::Requested,
+        )
+        .instrument(download_span(&download)),
+    )
+    .await?;
+    report_result(find_download(&store, download_id).await?)
+}
+
+async fn process_download(
+    store: &SqliteDownloadStore,
+    adapters: &Adapters,
+    download: TrackedDownload,
+) -> Result<()> {
+    if let Err(err) = process_tracked_download(store, adapters, download.clone())
+        .instrument(download_span(&download))
+        .await
+    {
+        let message = err.to_string();
+        store
+            .mark_download_failed(&download.download_id, Some(&message))
+            .await?;
+        return Err(err).with_context(|| format!("process {}", download.title));
+    }
+    Ok(())
+}
+
+async fn locked<T>(
+    store: &SqliteDownloadStore,
+    download_id: &str,
+    work: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        result = with_download_lock(store, download_id, work) => {
+            result?.ok_or_else(|| anyhow!("download is already being processed: {download_id}"))
+        }
+        signal = shutdown_signal() => {
+            signal?;
+            // Only removes the lock if this run holds it. Partial output is
+            // removed by the daemon's recovery.
+            store.unlock_download(download_id).await?;
+            Err(anyhow!("interrupted while working on {download_id}"))
+        }
+    }
+}
+
+fn report_result(download: TrackedDownload) -> Result<()> {
+    print!("{}", render_download_details(&download));
+    if download.lifecycle_state == DownloadLifecycleState::Failed {
+        return Err(anyhow!("{} failed", download.title));
+    }
+    Ok(())
+}
+
+async fn find_download(store: &SqliteDownloadStore, download_id: &str) -> Result<TrackedDownload> {
+    store
+        .get_tracked_download(download_id)
+        .await?
+        .ok_or_else(|| anyhow!("download not found: {download_id}"))
+}
+
+fn open_store(settings: &Settings) -> Result<SqliteDownloadStore> {
+    SqliteDownloadStore::open(&settings.data_dir).context("initialize Splittarr database")
+}
+
+fn processing_adapters(settings: &Settings, metrics: &Metrics) -> Adapters {
+    let disc_release_lookup =
+        Arc::new(GnudbDiscReleaseLookup::new(&settings.gnudb).with_metrics(metrics.clone()));
+    let musicbrainz_lookup = Arc::new(
+        FilesystemMusicBrainzDiscReleaseLookup::new(&settings.musicbrainz)
+            .with_metrics(metrics.clone()),
+    );
+    let musicbrainz = MusicBrainzReleaseSelector::new(
+        musicbrainz_lookup.clone(),
+        settings.musicbrainz.trust_disc_lookup,
+    );
+    let manual_import = LidarrQueueSource::new(&settings.lidarr)
+        .with_musicbrainz_disc_release_lookup(musicbrainz_lookup)
+        .with_musicbrainz_trust_disc_lookup(settings.musicbrainz.trust_disc_lookup)
+        .with_musicbrainz_add_missing_release_group(
+            settings.musicbrainz.add_missing_release_group_enabled,
+        )
+        .with_disc_release_lookup(disc_release_lookup)
+        .with_dry_run(settings.dry_run)
+        .with_metrics(metrics.clone());
+    let mut cue_input_inspector =
+        FilesystemCueInputInspector::new(settings.verification.content_hash);
+    if settings.cue.repair {
+        cue_input_inspector =
+            cue_input_inspector.with_repair_dir(settings.data_dir.join("repaired-cues"));
+    }
+    let split_layout = SplitLayout::new(settings.staging_dir.clone());
+    ProcessingAdapters {
+        cue_scanner: FilesystemCueScanner::new(),
+        cue_input_inspector,
+        cue_splitter: ConfiguredCueSplitter::from_settings(settings).with_metrics(metrics.clone()),
+        manual_import,
+        download_log: FilesystemDownloadLog::new(
+            settings.logging.download_log_enabled && !settings.dry_run,
+        ),
+        track_cleanup: FilesystemTrackCleanup::new(split_layout.clone())
+            .with_metrics(metrics.clone()),
+        track_tagger: FlacTrackTagger,
+        split_layout,
+        musicbrainz,
+        notifiers: notifiers(settings),
+        dry_run: settings.dry_run,
+    }
+}
+
+fn notifiers(settings: &Settings) -> Notifiers {
+    if settings.dry_run {
+        return Notifiers::default();
+    }
+    settings
+        .notifications
+        .iter()
+        .fold(Notifiers::default(), |notifiers, notifier| {
+            let webhook = |format| Arc::new(WebhookNotifier::new(&notifier.url, format));
+            let target: Arc<dyn Notifier> = match notifier.kind {
+                NotifierKind::Webhook => webhook(WebhookFormat::Generic),
+                NotifierKind::Apprise => webhook(WebhookFormat::Apprise),
+                NotifierKind::Discord => webhook(WebhookFormat::Discord),
+                NotifierKind::Slack => webhook(WebhookFormat::Slack),
+                NotifierKind::Email => Arc::new(SmtpNotifier::new(notifier)),
+            };
+            notifiers.with_notifier(notifier.name.clone(), notifier.events.clone(), target)
+        })
+}
+
+fn report_missing_audio_programs(settings: &Settings) -> Vec<audio_format::AudioFormatCapability> {
+    let audio_formats = audio_format::capability_report();
+    for capability in &audio_formats {
+        let support = match settings.splitter.backend {
+            SplitterBackend::Shnsplit => &capability.shnsplit,
+            SplitterBackend::Native => &capability.native,
+        };
+        if let Some(program) = support.missing_program() {
+            warn!(
+                "{} sources cannot be split: {program} was not found in PATH",
+                capability.format.label()
+            );
+        }
+    }
+    audio_formats
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::adapters::native_splitter::NativeCueSplitter;
+use crate::adapters::shnsplit_splitter::ShnsplitCueSplitter;
+use crate::application::metrics::{Metrics, SplitResult};
+use crate::application::ports::CueSplitter;
+use crate::bootstrap::settings::{Settings, SplitterBackend};
+use crate::domain::{SplitOutcome, SplitStatus};
+
+#[derive(Debug, Clone)]
+pub struct ConfiguredCueSplitter {
+    backend: Backend,
+    metrics: Metrics,
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Shnsplit(ShnsplitCueSplitter),
+    Native(NativeCueSplitter),
+}
+
+impl ConfiguredCueSplitter {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            backend: Backend::from_settings(settings),
+            metrics: Metrics::default(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+}
+
+impl Backend {
+    fn from_settings(settings: &Settings) -> Self {
+        if settings.dry_run {
+            // Both backends name tracks from shnsplit.format, so the native plan fits either.
+            return Self::Native(
+                NativeCueSplitter::new(
+                    settings.cue.strict,
+                    settings.shnsplit.overwrite,
+                    settings.shnsplit.format.clone(),
+                    settings.output.codec,
+                    settings.cue.pregap,
+                )
+                .with_dry_run(true),
+            );
+        }
+        match settings.splitter.backend {
+            SplitterBackend::Shnsplit => Self::Shnsplit(ShnsplitCueSplitter::new(
+                settings.cue.strict,
+                settings.shnsplit.path.clone(),
+                settings.shnsplit.overwrite,
+                settings.shnsplit.format.clone(),
+                settings.output.clone(),
+                settings.cue.pregap,
+            )),
+            SplitterBackend::Native => Self::Native(NativeCueSplitter::new(
+                settings.cue.strict,
+                settings.shnsplit.overwrite,
+                settings.shnsplit.format.clone(),
+                settings.output.codec,
+                settings.cue.pregap,
+            )),
+        }
+    }
+}
+
+impl CueSplitter for ConfiguredCueSplitter {
+    async fn split_cue(&self, cue_path: &Path, output_dir: &Path) -> Result<SplitOutcome> {
+        self.metrics.record_split_attempt();
+        let outcome = match &self.backend {
+            Backend::Shnsplit(splitter) => splitter.split_cue(cue_path, output_dir).await,
+            Backend::Native(splitter) => splitter.split_cue(cue_path, output_dir).await,
+        };
+        self.metrics.record_split(match &outcome {
+            Ok(outcome) => match outcome.status {
+                SplitStatus::Split => SplitResult::Succeeded,
+                SplitStatus::Skipped => SplitResult::Skipped,
+                SplitStatus::Planned => SplitResult::Planned,
+            },
+            Err(_) => SplitResult::Failed,
+        });
+        outcome
+    }
+}
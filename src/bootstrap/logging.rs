@@ -0,0 +1,20 @@
+use std::io::IsTerminal;
+
+use anyhow::{anyhow, Result};
+use tracing_subscriber::fmt;
+
+use crate::bootstrap::settings::{LogFormat, LoggingSettings};
+
+/// Installs the process-wide subscriber. Logs go to stderr so the output of
+/// one-shot commands like `list` and `show` stays pipeable.
+pub fn init_logging(settings: &LoggingSettings) -> Result<()> {
+    let builder = fmt()
+        .with_env_filter(settings.env_filter()?)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    match settings.format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().flatten_event(true).try_init(),
+    }
+    .map_err(|err| anyhow!("install {} logger: {err}", settings.format.as_str()))
+}
@@ -1 +1,4 @@
+pub mod commands;
+pub mod cue_splitter;
+pub mod logging;
 pub mod settings;
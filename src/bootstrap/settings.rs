@@ -1,21 +1,56 @@
+use std::net::IpAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use config::{Config, ConfigError, Environment, File};
 use directories::ProjectDirs;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use thiserror::Error;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+use crate::application::ignore_list::validate_ignore_rule;
+use crate::application::retry_policy::RetryPolicy;
+use crate::domain::{IgnoreRule, IgnoreRuleKind, NotificationEvent, OutputCodec, PregapPolicy};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
 pub struct Cli {
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", global = true)]
     pub config: Option<PathBuf>,
+    /// Plan splits and imports without writing tracks or changing Lidarr
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Watch Lidarr's queue and serve the web UI (default)
+    Run,
+    /// Split the CUE sheets in a folder without involving Lidarr
+    Split {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+    /// Process a tracked download again and retrigger its Lidarr import
+    Reprocess { download_id: String },
+    /// Delete the generated tracks of a tracked download
+    Cleanup { download_id: String },
+    /// List tracked downloads
+    List,
+    /// Show a tracked download with its CUE sheets and tracks
+    Show { download_id: String },
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct CueSettings {
     pub strict: bool,
+    pub repair: bool,
+    #[serde(deserialize_with = "deserialize_pregap_policy")]
+    pub pregap: PregapPolicy,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -34,14 +69,119 @@ pub struct ShnsplitSettings {
     pub format: String,
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitterBackend {
+    Shnsplit,
+    Native,
+}
+
+impl SplitterBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Shnsplit => "shnsplit",
+            Self::Native => "native",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct SplitterSettings {
+    pub backend: SplitterBackend,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct OutputSettings {
+    #[serde(deserialize_with = "deserialize_output_codec")]
+    pub codec: OutputCodec,
+    pub compression_level: u8,
+    pub bitrate_kbps: u32,
+    pub encoder_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct VerificationSettings {
+    pub content_hash: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthMethod {
+    None,
+    Basic,
+    Forms,
+}
+
+impl AuthMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Basic => "basic",
+            Self::Forms => "forms",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct ServerSettings {
     pub bind_address: String,
+    pub api_key: String,
+    pub auth_method: AuthMethod,
+    pub username: String,
+    pub password: String,
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub username: String,
+    pub password: String,
+    pub fallback_check_frequency_seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct LoggingSettings {
     pub download_log_enabled: bool,
+    pub level: String,
+    pub format: LogFormat,
+    /// Per-module overrides such as `splittarr::adapters::lidarr_api=debug`.
+    pub filters: Vec<String>,
+}
+
+impl LoggingSettings {
+    pub fn env_filter(&self) -> Result<EnvFilter, SettingsError> {
+        let level = self
+            .level
+            .parse::<LevelFilter>()
+            .map_err(|err| SettingsError::InvalidLoggingLevel(format!("{}: {err}", self.level)))?;
+        let mut filter = EnvFilter::builder()
+            .with_default_directive(level.into())
+            .parse("")
+            .map_err(|err| SettingsError::InvalidLoggingFilter(err.to_string()))?;
+        for directive in &self.filters {
+            filter = filter.add_directive(directive.parse().map_err(|err| {
+                SettingsError::InvalidLoggingFilter(format!("{directive}: {err}"))
+            })?);
+        }
+        Ok(filter)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -59,17 +199,132 @@ pub struct MusicBrainzSettings {
     pub add_missing_release_group_enabled: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct RetrySettings {
+    pub max_attempts: u32,
+    pub initial_delay_seconds: u64,
+    pub max_delay_seconds: u64,
+}
+
+impl RetrySettings {
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts,
+            initial_delay: Duration::from_secs(self.initial_delay_seconds),
+            max_delay: Duration::from_secs(self.max_delay_seconds),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct IgnoreSettings {
+    pub download_ids: Vec<String>,
+    pub title_patterns: Vec<String>,
+    pub path_globs: Vec<String>,
+}
+
+impl IgnoreSettings {
+    pub fn rules(&self) -> Vec<IgnoreRule> {
+        let kinds = [
+            (IgnoreRuleKind::DownloadId, &self.download_ids),
+            (IgnoreRuleKind::TitleRegex, &self.title_patterns),
+            (IgnoreRuleKind::PathGlob, &self.path_globs),
+        ];
+        kinds
+            .into_iter()
+            .flat_map(|(kind, patterns)| {
+                patterns
+                    .iter()
+                    .map(move |pattern| IgnoreRule::configured(kind, pattern.clone()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifierKind {
+    Webhook,
+    Apprise,
+    Discord,
+    Slack,
+    Email,
+}
+
+impl NotifierKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Webhook => "webhook",
+            Self::Apprise => "apprise",
+            Self::Discord => "discord",
+            Self::Slack => "slack",
+            Self::Email => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    None,
+    #[default]
+    StartTls,
+    Tls,
+}
+
+/// One `[[notifications]]` entry. Which fields are used depends on `kind`:
+/// `url` for the HTTP notifiers and the `smtp_*`, `from` and `to` fields for
+/// email.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct NotifierSettings {
+    pub kind: NotifierKind,
+    #[serde(default)]
+    pub name: String,
+    #[serde(
+        default = "all_notification_events",
+        deserialize_with = "deserialize_notification_events"
+    )]
+    pub events: Vec<NotificationEvent>,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    #[serde(default)]
+    pub smtp_tls: SmtpTls,
+    #[serde(default)]
+    pub smtp_username: String,
+    #[serde(default)]
+    pub smtp_password: String,
+    #[serde(default)]
+    pub from: String,
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct Settings {
     pub data_dir: PathBuf,
+    pub staging_dir: Option<PathBuf>,
     pub check_frequency_seconds: u64,
+    pub dry_run: bool,
+    pub max_concurrent_downloads: usize,
+    pub shutdown_timeout_seconds: u64,
     pub server: ServerSettings,
+    pub webhook: WebhookSettings,
     pub logging: LoggingSettings,
     pub gnudb: GnudbSettings,
     pub musicbrainz: MusicBrainzSettings,
     pub cue: CueSettings,
     pub lidarr: LidarrSettings,
+    pub splitter: SplitterSettings,
+    pub output: OutputSettings,
+    pub verification: VerificationSettings,
     pub shnsplit: ShnsplitSettings,
+    pub retry: RetrySettings,
+    pub ignore: IgnoreSettings,
+    pub notifications: Vec<NotifierSettings>,
 }
 
 #[derive(Debug, Error)]
@@ -80,10 +335,51 @@ pub enum SettingsError {
     Config(#[from] ConfigError),
     #[error("gnudb.user_email must be a valid email when gnudb.disc_lookup_enabled is true")]
     MissingGnudbUserEmail,
+    #[error("webhook.password must be set when webhook.enabled is true")]
+    MissingWebhookPassword,
+    #[error("server.password must be set when server.auth_method is {0}")]
+    MissingServerPassword(&'static str),
     #[error("gnudb.server must be a hostname or unique code, not a URL or path: {0}")]
     InvalidGnudbServer(String),
     #[error("musicbrainz.base_url must be an HTTP(S) base URL: {0}")]
     InvalidMusicBrainzBaseUrl(String),
+    #[error("output.compression_level must be between 0 and 8: {0}")]
+    InvalidOutputCompressionLevel(u8),
+    #[error("output.bitrate_kbps must be between {min} and {max} for {codec}: {bitrate}")]
+    InvalidOutputBitrate {
+        codec: &'static str,
+        bitrate: u32,
+        min: u32,
+        max: u32,
+    },
+    #[error("output.encoder_args entries must be non-empty and contain no whitespace: {0:?}")]
+    InvalidOutputEncoderArg(String),
+    #[error("output.encoder_args is not supported for {0}")]
+    UnsupportedOutputEncoderArgs(String),
+    #[error("splitter.backend = \"native\" can only write flac or wav output, not {0}")]
+    UnsupportedNativeOutputCodec(&'static str),
+    #[error("cue.pregap = \"discard\" requires splitter.backend = \"native\"")]
+    UnsupportedShnsplitPregapPolicy,
+    #[error("staging_dir must be an absolute path: {0}")]
+    RelativeStagingDir(PathBuf),
+    #[error("max_concurrent_downloads must be at least 1")]
+    InvalidMaxConcurrentDownloads,
+    #[error("retry.max_attempts must be at least 1")]
+    InvalidRetryMaxAttempts,
+    #[error("retry.max_delay_seconds must not be lower than retry.initial_delay_seconds: {0}")]
+    InvalidRetryMaxDelay(u64),
+    #[error("ignore section is invalid: {0}")]
+    InvalidIgnoreRule(String),
+    #[error("notifications entry {0} needs an http(s) url")]
+    InvalidNotifierUrl(String),
+    #[error("notifications entry {0} needs smtp_host, a from address and at least one to address")]
+    IncompleteEmailNotifier(String),
+    #[error("notifications entry {0} must list at least one event")]
+    MissingNotifierEvents(String),
+    #[error("logging.level must be one of off, error, warn, info, debug or trace: {0}")]
+    InvalidLoggingLevel(String),
+    #[error("logging.filters entries must look like \"module=level\": {0}")]
+    InvalidLoggingFilter(String),
 }
 
 impl Settings {
@@ -105,8 +401,23 @@ impl Settings {
         let mut builder = Config::builder()
             .set_default("data_dir", default_data_dir.to_string_lossy().to_string())?
             .set_default("check_frequency_seconds", 60)?
+            .set_default("dry_run", false)?
+            .set_default("max_concurrent_downloads", 1)?
+            .set_default("shutdown_timeout_seconds", 60)?
             .set_default("server.bind_address", "127.0.0.1:9899")?
+            .set_default("server.api_key", "")?
+            .set_default("server.auth_method", "none")?
+            .set_default("server.username", "splittarr")?
+            .set_default("server.password", "")?
+            .set_default("server.trusted_proxies", Vec::<String>::new())?
+            .set_default("webhook.enabled", false)?
+            .set_default("webhook.username", "splittarr")?
+            .set_default("webhook.password", "")?
+            .set_default("webhook.fallback_check_frequency_seconds", 900)?
             .set_default("logging.download_log_enabled", true)?
+            .set_default("logging.level", "info")?
+            .set_default("logging.format", "text")?
+            .set_default("logging.filters", Vec::<String>::new())?
             .set_default("gnudb.disc_lookup_enabled", false)?
             .set_default("gnudb.server", "gnudb.gnudb.org")?
             .set_default("gnudb.user_email", "")?
@@ -115,12 +426,27 @@ impl Settings {
             .set_default("musicbrainz.trust_disc_lookup", false)?
             .set_default("musicbrainz.add_missing_release_group_enabled", false)?
             .set_default("cue.strict", false)?
+            .set_default("cue.repair", false)?
+            .set_default("cue.pregap", "append_previous")?
             .set_default("lidarr.queue_page_size", 100)?
             .set_default("lidarr.queue_max_pages", 100)?
             .set_default("lidarr.manual_import_enabled", true)?
+            .set_default("splitter.backend", "shnsplit")?
+            .set_default("output.codec", "flac")?
+            .set_default("output.compression_level", 8)?
+            .set_default("output.bitrate_kbps", 192)?
+            .set_default("output.encoder_args", Vec::<String>::new())?
+            .set_default("verification.content_hash", false)?
             .set_default("shnsplit.path", "shnsplit")?
             .set_default("shnsplit.overwrite", true)?
             .set_default("shnsplit.format", "%p - %a - %n - %t")?
+            .set_default("retry.max_attempts", 5)?
+            .set_default("retry.initial_delay_seconds", 300)?
+            .set_default("retry.max_delay_seconds", 21600)?
+            .set_default("ignore.download_ids", Vec::<String>::new())?
+            .set_default("ignore.title_patterns", Vec::<String>::new())?
+            .set_default("ignore.path_globs", Vec::<String>::new())?
+            .set_default("notifications", Vec::<String>::new())?
             .add_source(File::with_name("config.toml").required(false))
             .add_source(File::with_name("/config/config.toml").required(false));
 
@@ -148,11 +474,184 @@ impl Settings {
         if settings.gnudb.disc_lookup_enabled && !looks_like_email(&settings.gnudb.user_email) {
             return Err(SettingsError::MissingGnudbUserEmail);
         }
+        if settings.webhook.enabled && settings.webhook.password.is_empty() {
+            return Err(SettingsError::MissingWebhookPassword);
+        }
+        if settings.server.auth_method != AuthMethod::None && settings.server.password.is_empty() {
+            return Err(SettingsError::MissingServerPassword(
+                settings.server.auth_method.as_str(),
+            ));
+        }
+        validate_output(&settings.output, settings.splitter.backend)?;
+        if settings.cue.pregap == PregapPolicy::Discard
+            && settings.splitter.backend == SplitterBackend::Shnsplit
+        {
+            return Err(SettingsError::UnsupportedShnsplitPregapPolicy);
+        }
+        settings.staging_dir = settings
+            .staging_dir
+            .filter(|staging_dir| !staging_dir.as_os_str().is_empty());
+        if let Some(staging_dir) = &settings.staging_dir {
+            if !staging_dir.is_absolute() {
+                return Err(SettingsError::RelativeStagingDir(staging_dir.clone()));
+            }
+        }
+        if settings.max_concurrent_downloads == 0 {
+            return Err(SettingsError::InvalidMaxConcurrentDownloads);
+        }
+        if settings.retry.max_attempts == 0 {
+            return Err(SettingsError::InvalidRetryMaxAttempts);
+        }
+        if settings.retry.max_delay_seconds < settings.retry.initial_delay_seconds {
+            return Err(SettingsError::InvalidRetryMaxDelay(
+                settings.retry.max_delay_seconds,
+            ));
+        }
+        for rule in settings.ignore.rules() {
+            validate_ignore_rule(rule.kind, &rule.pattern)
+                .map_err(|err| SettingsError::InvalidIgnoreRule(err.to_string()))?;
+        }
+        settings.logging.env_filter()?;
+        for notifier in &mut settings.notifications {
+            validate_notifier(notifier)?;
+        }
 
         Ok(settings)
     }
 }
 
+fn deserialize_output_codec<'de, D>(deserializer: D) -> Result<OutputCodec, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    OutputCodec::parse(&value).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "unknown output codec {value:?}, expected flac, wav, alac, opus or mp3"
+        ))
+    })
+}
+
+fn deserialize_pregap_policy<'de, D>(deserializer: D) -> Result<PregapPolicy, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    PregapPolicy::parse(&value).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "unknown cue pregap policy {value:?}, expected discard, append_previous, prepend_next or track_zero"
+        ))
+    })
+}
+
+fn all_notification_events() -> Vec<NotificationEvent> {
+    NotificationEvent::ALL.to_vec()
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn deserialize_notification_events<'de, D>(
+    deserializer: D,
+) -> Result<Vec<NotificationEvent>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|value| {
+            NotificationEvent::parse(&value).ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "unknown notification event {value:?}, expected failed, import_skipped or completed"
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Checks one notifier and names it after its kind when no name is given.
+fn validate_notifier(notifier: &mut NotifierSettings) -> Result<(), SettingsError> {
+    notifier.name = notifier.name.trim().to_owned();
+    if notifier.name.is_empty() {
+        notifier.name = notifier.kind.as_str().to_owned();
+    }
+    if notifier.events.is_empty() {
+        return Err(SettingsError::MissingNotifierEvents(notifier.name.clone()));
+    }
+    match notifier.kind {
+        NotifierKind::Email => {
+            if notifier.smtp_host.trim().is_empty()
+                || !looks_like_email(&notifier.from)
+                || notifier.to.is_empty()
+                || !notifier.to.iter().all(|to| looks_like_email(to))
+            {
+                return Err(SettingsError::IncompleteEmailNotifier(
+                    notifier.name.clone(),
+                ));
+            }
+        }
+        NotifierKind::Webhook
+        | NotifierKind::Apprise
+        | NotifierKind::Discord
+        | NotifierKind::Slack => {
+            let url = notifier.url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(SettingsError::InvalidNotifierUrl(notifier.name.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_output(output: &OutputSettings, backend: SplitterBackend) -> Result<(), SettingsError> {
+    let codec = output.codec;
+    if backend == SplitterBackend::Native && !matches!(codec, OutputCodec::Flac | OutputCodec::Wav)
+    {
+        return Err(SettingsError::UnsupportedNativeOutputCodec(codec.as_str()));
+    }
+    if codec == OutputCodec::Flac && output.compression_level > 8 {
+        return Err(SettingsError::InvalidOutputCompressionLevel(
+            output.compression_level,
+        ));
+    }
+    let bitrate_range = match codec {
+        OutputCodec::Opus => Some((6, 510)),
+        OutputCodec::Mp3 => Some((8, 320)),
+        _ => None,
+    };
+    if let Some((min, max)) = bitrate_range {
+        if !(min..=max).contains(&output.bitrate_kbps) {
+            return Err(SettingsError::InvalidOutputBitrate {
+                codec: codec.as_str(),
+                bitrate: output.bitrate_kbps,
+                min,
+                max,
+            });
+        }
+    }
+    if !output.encoder_args.is_empty() {
+        if codec == OutputCodec::Wav {
+            return Err(SettingsError::UnsupportedOutputEncoderArgs(
+                "wav output".into(),
+            ));
+        }
+        if backend == SplitterBackend::Native {
+            return Err(SettingsError::UnsupportedOutputEncoderArgs(
+                "the native splitter".into(),
+            ));
+        }
+    }
+    if let Some(arg) = output
+        .encoder_args
+        .iter()
+        .find(|arg| arg.is_empty() || arg.chars().any(char::is_whitespace))
+    {
+        return Err(SettingsError::InvalidOutputEncoderArg(arg.clone()));
+    }
+    Ok(())
+}
+
 fn normalize_musicbrainz_base_url(value: &str) -> Result<String, SettingsError> {
     let value = value.trim().trim_end_matches('/');
     if !value.starts_with("http://") && !value.starts_with("https://") {
@@ -208,6 +707,36 @@ mod tests {
 
     static ENV_LOCK: Mutex<()> = Mutex::new(());
 
+    #[test]
+    fn cli_defaults_to_the_daemon_and_accepts_one_shot_commands() {
+        let cli = Cli::try_parse_from(["splittarr", "--config", "splittarr.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("splittarr.toml")));
+        assert_eq!(cli.command, None);
+
+        let cli =
+            Cli::try_parse_from(["splittarr", "split", "/music/album", "-c", "s.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("s.toml")));
+        assert_eq!(
+            cli.command,
+            Some(Command::Split {
+                dir: PathBuf::from("/music/album")
+            })
+        );
+
+        let cli = Cli::try_parse_from(["splittarr", "split", "/music/album", "--dry-run"]).unwrap();
+        assert!(cli.dry_run);
+
+        let cli = Cli::try_parse_from(["splittarr", "show", "download-1"]).unwrap();
+        assert!(!cli.dry_run);
+        assert_eq!(
+            cli.command,
+            Some(Command::Show {
+                download_id: "download-1".to_owned()
+            })
+        );
+        assert!(Cli::try_parse_from(["splittarr", "reprocess"]).is_err());
+    }
+
     #[test]
     fn download_log_is_enabled_by_default() {
         let _guard = ENV_LOCK.lock().unwrap();
@@ -228,6 +757,7 @@ api_key = "secret"
             Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None).unwrap();
 
         assert!(settings.logging.download_log_enabled);
+        assert_eq!(settings.staging_dir, None);
     }
 
     #[test]
@@ -241,6 +771,9 @@ api_key = "secret"
             r#"
 check_frequency_seconds = 5
 data_dir = "/tmp/splittarr-data"
+dry_run = true
+max_concurrent_downloads = 3
+shutdown_timeout_seconds = 120
 
 [lidarr]
 url = "http://lidarr"
@@ -268,6 +801,11 @@ add_missing_release_group_enabled = true
 
 [cue]
 strict = true
+repair = true
+pregap = "discard"
+
+[splitter]
+backend = "native"
 
 [shnsplit]
 path = "/usr/bin/shnsplit"
@@ -282,6 +820,9 @@ format = "%n - %t"
 
         assert_eq!(settings.check_frequency_seconds, 5);
         assert_eq!(settings.data_dir, PathBuf::from("/tmp/splittarr-data"));
+        assert!(settings.dry_run);
+        assert_eq!(settings.max_concurrent_downloads, 3);
+        assert_eq!(settings.shutdown_timeout_seconds, 120);
         assert_eq!(settings.server.bind_address, "127.0.0.1:9899");
         assert!(!settings.logging.download_log_enabled);
         assert!(settings.gnudb.disc_lookup_enabled);
@@ -292,10 +833,13 @@ format = "%n - %t"
         assert!(settings.musicbrainz.trust_disc_lookup);
         assert!(settings.musicbrainz.add_missing_release_group_enabled);
         assert!(settings.cue.strict);
+        assert!(settings.cue.repair);
+        assert_eq!(settings.cue.pregap, PregapPolicy::Discard);
         assert_eq!(settings.lidarr.url, "http://lidarr");
         assert_eq!(settings.lidarr.queue_page_size, 25);
         assert_eq!(settings.lidarr.queue_max_pages, 20);
         assert!(settings.lidarr.manual_import_enabled);
+        assert_eq!(settings.splitter.backend, SplitterBackend::Native);
         assert_eq!(settings.shnsplit.path, PathBuf::from("/usr/bin/shnsplit"));
         assert!(!settings.shnsplit.overwrite);
     }
@@ -319,6 +863,8 @@ api_key = "file-secret"
         .unwrap();
 
         std::env::set_var("SPLITTARR_CHECK_FREQUENCY_SECONDS", "9");
+        std::env::set_var("SPLITTARR_DRY_RUN", "true");
+        std::env::set_var("SPLITTARR_MAX_CONCURRENT_DOWNLOADS", "4");
         std::env::set_var("SPLITTARR_LIDARR__URL", "http://from-env");
         std::env::set_var("SPLITTARR_LIDARR__MANUAL_IMPORT_ENABLED", "true");
         std::env::set_var("SPLITTARR_LOGGING__DOWNLOAD_LOG_ENABLED", "false");
@@ -333,14 +879,20 @@ api_key = "file-secret"
             "true",
         );
         std::env::set_var("SPLITTARR_SERVER__BIND_ADDRESS", "0.0.0.0:1234");
+        std::env::set_var("SPLITTARR_SPLITTER__BACKEND", "native");
+        std::env::set_var("SPLITTARR_OUTPUT__CODEC", "wav");
 
         let settings =
             Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None).unwrap();
 
         std::env::remove_var("SPLITTARR_CHECK_FREQUENCY_SECONDS");
+        std::env::remove_var("SPLITTARR_DRY_RUN");
+        std::env::remove_var("SPLITTARR_MAX_CONCURRENT_DOWNLOADS");
         std::env::remove_var("SPLITTARR_LIDARR__URL");
         std::env::remove_var("SPLITTARR_LIDARR__MANUAL_IMPORT_ENABLED");
         std::env::remove_var("SPLITTARR_LOGGING__DOWNLOAD_LOG_ENABLED");
+        std::env::remove_var("SPLITTARR_LOGGING__LEVEL");
+        std::env::remove_var("SPLITTARR_LOGGING__FORMAT");
         std::env::remove_var("SPLITTARR_GNUDB__DISC_LOOKUP_ENABLED");
         std::env::remove_var("SPLITTARR_GNUDB__SERVER");
         std::env::remove_var("SPLITTARR_GNUDB__USER_EMAIL");
@@ -349,8 +901,12 @@ api_key = "file-secret"
         std::env::remove_var("SPLITTARR_MUSICBRAINZ__TRUST_DISC_LOOKUP");
         std::env::remove_var("SPLITTARR_MUSICBRAINZ__ADD_MISSING_RELEASE_GROUP_ENABLED");
         std::env::remove_var("SPLITTARR_SERVER__BIND_ADDRESS");
+        std::env::remove_var("SPLITTARR_SPLITTER__BACKEND");
+        std::env::remove_var("SPLITTARR_OUTPUT__CODEC");
 
         assert_eq!(settings.check_frequency_seconds, 9);
+        assert!(settings.dry_run);
+        assert_eq!(settings.max_concurrent_downloads, 4);
         assert_eq!(settings.lidarr.url, "http://from-env");
         assert_eq!(settings.lidarr.api_key, "file-secret");
         assert!(settings.lidarr.manual_import_enabled);
@@ -363,6 +919,8 @@ api_key = "file-secret"
         assert!(settings.musicbrainz.trust_disc_lookup);
         assert!(settings.musicbrainz.add_missing_release_group_enabled);
         assert_eq!(settings.server.bind_address, "0.0.0.0:1234");
+        assert_eq!(settings.splitter.backend, SplitterBackend::Native);
+        assert_eq!(settings.output.codec, OutputCodec::Wav);
     }
 
     #[test]
@@ -392,6 +950,175 @@ api_key = "secret"
         assert!(!settings.musicbrainz.trust_disc_lookup);
         assert!(!settings.musicbrainz.add_missing_release_group_enabled);
         assert!(settings.lidarr.manual_import_enabled);
+        assert_eq!(settings.splitter.backend, SplitterBackend::Shnsplit);
+        assert_eq!(settings.output.codec, OutputCodec::Flac);
+        assert_eq!(settings.output.compression_level, 8);
+        assert!(settings.output.encoder_args.is_empty());
+        assert!(!settings.verification.content_hash);
+        assert!(!settings.cue.repair);
+        assert_eq!(settings.cue.pregap, PregapPolicy::AppendPrevious);
+    }
+
+    #[test]
+    fn output_section_is_parsed_and_validated() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        fs::write(
+            &config_path,
+            r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+[output]
+codec = "opus"
+bitrate_kbps = 128
+encoder_args = ["--vbr"]
+"#,
+        )
+        .unwrap();
+
+        let settings =
+            Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None).unwrap();
+
+        assert_eq!(settings.output.codec, OutputCodec::Opus);
+        assert_eq!(settings.output.bitrate_kbps, 128);
+        assert_eq!(settings.output.encoder_args, vec!["--vbr".to_owned()]);
+    }
+
+    #[test]
+    fn invalid_output_settings_are_rejected() {
+        let output =
+            |codec, compression_level, bitrate_kbps, encoder_args: &[&str]| OutputSettings {
+                codec,
+                compression_level,
+                bitrate_kbps,
+                encoder_args: encoder_args.iter().map(|arg| (*arg).to_owned()).collect(),
+            };
+
+        assert!(matches!(
+            validate_output(
+                &output(OutputCodec::Flac, 9, 192, &[]),
+                SplitterBackend::Shnsplit
+            ),
+            Err(SettingsError::InvalidOutputCompressionLevel(9))
+        ));
+        assert!(matches!(
+            validate_output(
+                &output(OutputCodec::Mp3, 8, 512, &[]),
+                SplitterBackend::Shnsplit
+            ),
+            Err(SettingsError::InvalidOutputBitrate { bitrate: 512, .. })
+        ));
+        assert!(matches!(
+            validate_output(
+                &output(OutputCodec::Alac, 8, 192, &[]),
+                SplitterBackend::Native
+            ),
+            Err(SettingsError::UnsupportedNativeOutputCodec("alac"))
+        ));
+        assert!(matches!(
+            validate_output(
+                &output(OutputCodec::Wav, 8, 192, &["-x"]),
+                SplitterBackend::Shnsplit
+            ),
+            Err(SettingsError::UnsupportedOutputEncoderArgs(_))
+        ));
+        assert!(matches!(
+            validate_output(
+                &output(OutputCodec::Flac, 5, 192, &["--replay-gain extra"]),
+                SplitterBackend::Shnsplit
+            ),
+            Err(SettingsError::InvalidOutputEncoderArg(_))
+        ));
+        assert!(validate_output(
+            &output(OutputCodec::Wav, 8, 0, &[]),
+            SplitterBackend::Native
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn unknown_output_codec_is_rejected() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        fs::write(
+            &config_path,
+            r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+[output]
+codec = "aac"
+"#,
+        )
+        .unwrap();
+
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+
+        assert!(err.to_string().contains("unknown output codec"));
+    }
+
+    #[test]
+    fn pregap_policy_is_parsed_and_checked_against_the_backend() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let load = |pregap: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    "[lidarr]\nurl = \"http://lidarr\"\napi_key = \"secret\"\n\n[cue]\npregap = \"{pregap}\"\n"
+                ),
+            )
+            .unwrap();
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+        };
+
+        assert_eq!(
+            load("track_zero").unwrap().cue.pregap,
+            PregapPolicy::TrackZero
+        );
+        assert!(matches!(
+            load("discard"),
+            Err(SettingsError::UnsupportedShnsplitPregapPolicy)
+        ));
+        assert!(load("keep")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown cue pregap policy"));
+    }
+
+    #[test]
+    fn unknown_splitter_backend_is_rejected() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        fs::write(
+            &config_path,
+            r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+[splitter]
+backend = "ffmpeg"
+"#,
+        )
+        .unwrap();
+
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+
+        assert!(matches!(err, SettingsError::Config(_)));
     }
 
     #[test]
@@ -473,11 +1200,450 @@ disc_lookup_enabled = true
         assert!(matches!(err, SettingsError::MissingGnudbUserEmail));
     }
 
+    #[test]
+    fn webhook_is_disabled_by_default_and_requires_a_password() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        fs::write(
+            &config_path,
+            r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+"#,
+        )
+        .unwrap();
+
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert!(!settings.webhook.enabled);
+        assert_eq!(settings.webhook.username, "splittarr");
+        assert_eq!(settings.webhook.fallback_check_frequency_seconds, 900);
+
+        std::env::set_var("SPLITTARR_WEBHOOK__ENABLED", "true");
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::MissingWebhookPassword));
+
+        std::env::set_var("SPLITTARR_WEBHOOK__PASSWORD", "hunter2");
+        let settings =
+            Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None).unwrap();
+        clear_test_env();
+        assert!(settings.webhook.enabled);
+        assert_eq!(settings.webhook.password, "hunter2");
+    }
+
+    #[test]
+    fn web_auth_is_off_by_default_and_a_login_requires_a_password() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        fs::write(
+            &config_path,
+            r#"
+[server]
+trusted_proxies = ["10.0.0.2", "fd00::2"]
+
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+"#,
+        )
+        .unwrap();
+
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.server.api_key, "");
+        assert_eq!(settings.server.auth_method, AuthMethod::None);
+        assert_eq!(settings.server.username, "splittarr");
+        assert_eq!(
+            settings.server.trusted_proxies,
+            vec![
+                "10.0.0.2".parse::<std::net::IpAddr>().unwrap(),
+                "fd00::2".parse().unwrap()
+            ]
+        );
+
+        std::env::set_var("SPLITTARR_SERVER__API_KEY", "0123456789abcdef");
+        std::env::set_var("SPLITTARR_SERVER__AUTH_METHOD", "forms");
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::MissingServerPassword("forms")));
+
+        std::env::set_var("SPLITTARR_SERVER__PASSWORD", "hunter2");
+        let settings =
+            Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None).unwrap();
+        clear_test_env();
+        assert_eq!(settings.server.api_key, "0123456789abcdef");
+        assert_eq!(settings.server.auth_method, AuthMethod::Forms);
+        assert_eq!(settings.server.password, "hunter2");
+    }
+
+    #[test]
+    fn staging_dir_must_be_absolute() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |staging_dir: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"
+staging_dir = "{staging_dir}"
+
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("/srv/splittarr-staging");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(
+            settings.staging_dir,
+            Some(PathBuf::from("/srv/splittarr-staging"))
+        );
+
+        write_config("");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.staging_dir, None);
+
+        write_config("staging");
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::RelativeStagingDir(_)));
+    }
+
+    #[test]
+    fn ignore_section_is_parsed_and_validated() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |title_pattern: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+[ignore]
+download_ids = ["SABnzbd_nzo_1"]
+title_patterns = ["{title_pattern}"]
+path_globs = ["/downloads/podcasts/**"]
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("(?i)audiobook");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        let rules = settings.ignore.rules();
+        assert_eq!(
+            rules
+                .iter()
+                .map(|rule| (rule.kind, rule.pattern.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (IgnoreRuleKind::DownloadId, "SABnzbd_nzo_1"),
+                (IgnoreRuleKind::TitleRegex, "(?i)audiobook"),
+                (IgnoreRuleKind::PathGlob, "/downloads/podcasts/**"),
+            ]
+        );
+        assert!(rules.iter().all(|rule| rule.id.is_none()));
+
+        write_config("(unclosed");
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidIgnoreRule(_)));
+    }
+
+    #[test]
+    fn retry_policy_defaults_and_validation() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |retry: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+[retry]
+{retry}
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.retry.policy(), RetryPolicy::default());
+
+        std::env::set_var("SPLITTARR_RETRY__MAX_ATTEMPTS", "2");
+        write_config("initial_delay_seconds = 30\nmax_delay_seconds = 600");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        std::env::remove_var("SPLITTARR_RETRY__MAX_ATTEMPTS");
+        assert_eq!(
+            settings.retry.policy(),
+            RetryPolicy {
+                max_attempts: 2,
+                initial_delay: Duration::from_secs(30),
+                max_delay: Duration::from_secs(600),
+            }
+        );
+
+        write_config("max_attempts = 0");
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidRetryMaxAttempts));
+
+        write_config("initial_delay_seconds = 600\nmax_delay_seconds = 60");
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidRetryMaxDelay(60)));
+    }
+
+    #[test]
+    fn downloads_are_processed_one_at_a_time_by_default() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |extra: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"{extra}
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.max_concurrent_downloads, 1);
+        assert_eq!(settings.shutdown_timeout_seconds, 60);
+
+        write_config("max_concurrent_downloads = 0");
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidMaxConcurrentDownloads));
+    }
+
+    #[test]
+    fn logging_defaults_to_info_text_and_rejects_bad_filters() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |logging: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"
+[logging]
+{logging}
+
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.logging.level, "info");
+        assert_eq!(settings.logging.format, LogFormat::Text);
+        assert!(settings.logging.filters.is_empty());
+
+        write_config(
+            r#"level = "warn"
+format = "json"
+filters = ["splittarr::adapters::lidarr_api=debug"]"#,
+        );
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.logging.format, LogFormat::Json);
+        assert_eq!(
+            settings.logging.env_filter().unwrap().to_string(),
+            "splittarr::adapters::lidarr_api=debug,warn"
+        );
+
+        std::env::set_var("SPLITTARR_LOGGING__LEVEL", "debug");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        std::env::remove_var("SPLITTARR_LOGGING__LEVEL");
+        assert_eq!(settings.logging.level, "debug");
+
+        write_config(r#"level = "loud""#);
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidLoggingLevel(_)));
+
+        write_config(r#"filters = ["splittarr=loud"]"#);
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidLoggingFilter(_)));
+
+        write_config(r#"format = "xml""#);
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::Config(_)));
+    }
+
+    #[test]
+    fn notifications_default_names_and_events_and_reject_incomplete_entries() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_test_env();
+        let tmp = tempdir().unwrap();
+        let config_path = tmp.path().join("splittarr.toml");
+        let write_config = |notifications: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    r#"
+[lidarr]
+url = "http://lidarr"
+api_key = "secret"
+
+{notifications}
+"#
+                ),
+            )
+            .unwrap();
+        };
+
+        write_config("");
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert!(settings.notifications.is_empty());
+
+        write_config(
+            r#"[[notifications]]
+kind = "discord"
+url = "https://discord.com/api/webhooks/1/token"
+
+[[notifications]]
+kind = "email"
+name = "me"
+events = ["failed", "import_skipped"]
+smtp_host = "smtp.example.com"
+from = "splittarr@example.com"
+to = ["me@example.com"]"#,
+        );
+        let settings =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap();
+        assert_eq!(settings.notifications.len(), 2);
+        assert_eq!(settings.notifications[0].name, "discord");
+        assert_eq!(settings.notifications[0].events, NotificationEvent::ALL);
+        assert_eq!(settings.notifications[1].name, "me");
+        assert_eq!(
+            settings.notifications[1].events,
+            [NotificationEvent::Failed, NotificationEvent::ImportSkipped]
+        );
+        assert_eq!(settings.notifications[1].smtp_port, 587);
+        assert_eq!(settings.notifications[1].smtp_tls, SmtpTls::StartTls);
+
+        write_config(
+            r#"[[notifications]]
+kind = "slack"
+url = "hooks.slack.com/services/x""#,
+        );
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidNotifierUrl(name) if name == "slack"));
+
+        write_config(
+            r#"[[notifications]]
+kind = "email"
+smtp_host = "smtp.example.com"
+from = "splittarr@example.com""#,
+        );
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::IncompleteEmailNotifier(_)));
+
+        write_config(
+            r#"[[notifications]]
+kind = "webhook"
+url = "http://localhost:9000/hook"
+events = []"#,
+        );
+        let err =
+            Settings::load_with_paths(Some(config_path.clone()), tmp.path().join("default"), None)
+                .unwrap_err();
+        assert!(matches!(err, SettingsError::MissingNotifierEvents(_)));
+
+        write_config(
+            r#"[[notifications]]
+kind = "webhook"
+url = "http://localhost:9000/hook"
+events = ["started"]"#,
+        );
+        let err = Settings::load_with_paths(Some(config_path), tmp.path().join("default"), None)
+            .unwrap_err();
+        assert!(matches!(err, SettingsError::Config(_)));
+    }
+
     fn clear_test_env() {
         std::env::remove_var("SPLITTARR_CHECK_FREQUENCY_SECONDS");
+        std::env::remove_var("SPLITTARR_DRY_RUN");
+        std::env::remove_var("SPLITTARR_MAX_CONCURRENT_DOWNLOADS");
         std::env::remove_var("SPLITTARR_LIDARR__URL");
         std::env::remove_var("SPLITTARR_LIDARR__MANUAL_IMPORT_ENABLED");
         std::env::remove_var("SPLITTARR_LOGGING__DOWNLOAD_LOG_ENABLED");
+        std::env::remove_var("SPLITTARR_LOGGING__LEVEL");
+        std::env::remove_var("SPLITTARR_LOGGING__FORMAT");
         std::env::remove_var("SPLITTARR_GNUDB__DISC_LOOKUP_ENABLED");
         std::env::remove_var("SPLITTARR_GNUDB__SERVER");
         std::env::remove_var("SPLITTARR_GNUDB__USER_EMAIL");
@@ -486,5 +1652,17 @@ disc_lookup_enabled = true
         std::env::remove_var("SPLITTARR_MUSICBRAINZ__TRUST_DISC_LOOKUP");
         std::env::remove_var("SPLITTARR_MUSICBRAINZ__ADD_MISSING_RELEASE_GROUP_ENABLED");
         std::env::remove_var("SPLITTARR_SERVER__BIND_ADDRESS");
+        std::env::remove_var("SPLITTARR_SERVER__API_KEY");
+        std::env::remove_var("SPLITTARR_SERVER__AUTH_METHOD");
+        std::env::remove_var("SPLITTARR_SERVER__PASSWORD");
+        std::env::remove_var("SPLITTARR_SPLITTER__BACKEND");
+        std::env::remove_var("SPLITTARR_OUTPUT__CODEC");
+        std::env::remove_var("SPLITTARR_STAGING_DIR");
+        std::env::remove_var("SPLITTARR_VERIFICATION__CONTENT_HASH");
+        std::env::remove_var("SPLITTARR_CUE__REPAIR");
+        std::env::remove_var("SPLITTARR_CUE__PREGAP");
+        std::env::remove_var("SPLITTARR_RETRY__MAX_ATTEMPTS");
+        std::env::remove_var("SPLITTARR_WEBHOOK__ENABLED");
+        std::env::remove_var("SPLITTARR_WEBHOOK__PASSWORD");
     }
 }
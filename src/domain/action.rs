@@ -0,0 +1,48 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadAction {
+    Retry,
+    Reimport,
+    ForceCleanup,
+    Ignore,
+}
+
+impl DownloadAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::Reimport => "reimport",
+            Self::ForceCleanup => "force_cleanup",
+            Self::Ignore => "ignore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "retry" => Some(Self::Retry),
+            "reimport" => Some(Self::Reimport),
+            "force_cleanup" => Some(Self::ForceCleanup),
+            "ignore" => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadActionStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// A user-requested action on a download. The web UI records it as pending and
+/// the queue monitor runs it on its next cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadAuditEvent {
+    pub id: i64,
+    pub download_id: String,
+    pub action: DownloadAction,
+    pub status: DownloadActionStatus,
+    pub message: Option<String>,
+    pub requested_at: String,
+    pub finished_at: Option<String>,
+}
@@ -10,7 +10,11 @@ pub struct CueSheet {
     pub status: CueSheetStatus,
     pub message: Option<String>,
     pub updated_at: String,
+    pub encoding: Option<String>,
+    pub pregap_policy: Option<PregapPolicy>,
     pub tracks: Vec<GeneratedTrack>,
+    pub findings: Vec<CueFinding>,
+    pub repairs: Vec<CueRepair>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +23,102 @@ pub enum CueSheetStatus {
     Split,
     Skipped,
     Failed,
+    Planned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueFinding {
+    pub severity: CueFindingSeverity,
+    pub kind: CueFindingKind,
+    pub track: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueFindingSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueFindingKind {
+    Unparseable,
+    MissingFile,
+    EmptyFile,
+    UnreadableAudio,
+    MissingIndex,
+    IndexOrder,
+    ShortTrack,
+    PastEndOfAudio,
+}
+
+impl CueFinding {
+    pub fn is_error(&self) -> bool {
+        self.severity == CueFindingSeverity::Error
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.track {
+            Some(track) => format!("track {track}: {}", self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueRepair {
+    pub kind: CueRepairKind,
+    pub original: String,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueRepairKind {
+    FileCase,
+    FileExtension,
+    OnlyAudioFile,
+    Encoding,
+}
+
+impl CueRepair {
+    pub fn describe(&self) -> String {
+        let reason = match self.kind {
+            CueRepairKind::FileCase => "matched ignoring case",
+            CueRepairKind::FileExtension => "matched by file stem",
+            CueRepairKind::OnlyAudioFile => "only audio file in the folder",
+            CueRepairKind::Encoding => "transcoded",
+        };
+        format!("{} -> {} ({reason})", self.original, self.replacement)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PregapPolicy {
+    Discard,
+    AppendPrevious,
+    PrependNext,
+    TrackZero,
+}
+
+impl PregapPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Discard => "discard",
+            Self::AppendPrevious => "append_previous",
+            Self::PrependNext => "prepend_next",
+            Self::TrackZero => "track_zero",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "discard" => Some(Self::Discard),
+            "append_previous" => Some(Self::AppendPrevious),
+            "prepend_next" => Some(Self::PrependNext),
+            "track_zero" => Some(Self::TrackZero),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,11 +141,60 @@ pub struct InputFile {
     pub path: String,
     pub kind: InputFileKind,
     pub size_bytes: Option<i64>,
+    pub audio_format: Option<String>,
+    pub modified_ns: Option<i64>,
+    pub content_hash: Option<String>,
     pub captured_at: String,
+    pub verification: Option<SourceVerification>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputFileKind {
     Cue,
     Audio,
+    Artwork,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFingerprint {
+    pub size_bytes: i64,
+    pub modified_ns: Option<i64>,
+    pub content_hash: Option<String>,
+}
+
+impl FileFingerprint {
+    pub fn changes_since(&self, baseline: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.size_bytes != baseline.size_bytes {
+            changes.push(format!(
+                "size changed from {} to {} bytes",
+                baseline.size_bytes, self.size_bytes
+            ));
+        }
+        if let (Some(before), Some(after)) = (baseline.modified_ns, self.modified_ns) {
+            if before != after {
+                changes.push("modification time changed".to_owned());
+            }
+        }
+        if let (Some(before), Some(after)) = (&baseline.content_hash, &self.content_hash) {
+            if before != after {
+                changes.push("content hash changed".to_owned());
+            }
+        }
+        changes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceVerification {
+    pub status: SourceVerificationStatus,
+    pub message: Option<String>,
+    pub verified_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceVerificationStatus {
+    Unchanged,
+    Changed,
+    Missing,
 }
@@ -1,4 +1,21 @@
-use crate::domain::{CueSheet, InputFile};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::{CueSheet, DownloadAuditEvent, InputFile, NotificationDelivery};
+
+pub const LOCAL_DOWNLOAD_STATE: &str = "local";
+
+/// ID of a folder split with `splittarr split`. The path is hashed because
+/// download IDs appear as a single segment in web routes.
+pub fn local_download_id(dir: &Path) -> String {
+    let digest = Sha256::digest(dir.as_os_str().as_encoded_bytes());
+    let hex: String = digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("local:{hex}")
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TrackedDownload {
@@ -21,6 +38,11 @@ pub struct TrackedDownload {
     pub cue_sheets: Vec<CueSheet>,
     pub generated_track_count: usize,
     pub last_error: Option<String>,
+    pub import_plan: Option<String>,
+    pub audit_events: Vec<DownloadAuditEvent>,
+    pub notification_deliveries: Vec<NotificationDelivery>,
+    pub attempt_count: u32,
+    pub next_retry_at: Option<String>,
 }
 
 impl TrackedDownload {
@@ -51,6 +73,11 @@ impl TrackedDownload {
             cue_sheets: Vec::new(),
             generated_track_count: 0,
             last_error: None,
+            import_plan: None,
+            audit_events: Vec::new(),
+            notification_deliveries: Vec::new(),
+            attempt_count: 0,
+            next_retry_at: None,
         }
     }
 
@@ -65,6 +92,10 @@ impl TrackedDownload {
     pub fn has_generated_tracks(&self) -> bool {
         self.generated_track_count() > 0
     }
+
+    pub fn is_local(&self) -> bool {
+        self.tracked_download_state == LOCAL_DOWNLOAD_STATE
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -75,14 +106,20 @@ pub enum DownloadLifecycleState {
     CleaningUp,
     Completed,
     Failed,
+    Planned,
+    Ignored,
+    GaveUp,
 }
 
 impl DownloadLifecycleState {
     pub fn is_terminal(&self) -> bool {
-        matches!(self, Self::Completed)
+        matches!(self, Self::Completed | Self::Ignored | Self::GaveUp)
     }
 
     pub fn is_ready_for_processing(&self) -> bool {
-        matches!(self, Self::Detected | Self::Failed | Self::Processing)
+        matches!(
+            self,
+            Self::Detected | Self::Failed | Self::Processing | Self::Planned
+        )
     }
 }
@@ -0,0 +1,46 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreRuleKind {
+    DownloadId,
+    TitleRegex,
+    PathGlob,
+}
+
+impl IgnoreRuleKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DownloadId => "download_id",
+            Self::TitleRegex => "title_regex",
+            Self::PathGlob => "path_glob",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "download_id" => Some(Self::DownloadId),
+            "title_regex" => Some(Self::TitleRegex),
+            "path_glob" => Some(Self::PathGlob),
+            _ => None,
+        }
+    }
+}
+
+/// A download, title or output path Splittarr leaves alone. Rules from the
+/// config file have no `id` and cannot be removed from the web UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreRule {
+    pub id: Option<i64>,
+    pub kind: IgnoreRuleKind,
+    pub pattern: String,
+    pub created_at: Option<String>,
+}
+
+impl IgnoreRule {
+    pub fn configured(kind: IgnoreRuleKind, pattern: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            kind,
+            pattern: pattern.into(),
+            created_at: None,
+        }
+    }
+}
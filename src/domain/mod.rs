@@ -1,9 +1,27 @@
+pub mod action;
 pub mod cue;
 pub mod download;
+pub mod ignore;
+pub mod notification;
 pub mod processing;
 pub mod track;
 
-pub use cue::{CueSheet, CueSheetStatus, DiscoveredCueSheets, InputFile, InputFileKind};
-pub use download::{DownloadLifecycleState, TrackedDownload};
-pub use processing::{FailedImportCandidate, QueueSnapshot, SplitOutcome, SplitStatus};
-pub use track::{GeneratedTrack, RecordedTrack, TrackCleanupOutcome, TrackCleanupStatus};
+pub use action::{DownloadAction, DownloadActionStatus, DownloadAuditEvent};
+pub use cue::{
+    CueFinding, CueFindingKind, CueFindingSeverity, CueRepair, CueRepairKind, CueSheet,
+    CueSheetStatus, DiscoveredCueSheets, FileFingerprint, InputFile, InputFileKind, PregapPolicy,
+    SourceVerification, SourceVerificationStatus,
+};
+pub use download::{
+    local_download_id, DownloadLifecycleState, TrackedDownload, LOCAL_DOWNLOAD_STATE,
+};
+pub use ignore::{IgnoreRule, IgnoreRuleKind};
+pub use notification::{
+    Notification, NotificationDelivery, NotificationDeliveryStatus, NotificationEvent,
+};
+pub use processing::{
+    FailedImportCandidate, InProgressSplit, QueueSnapshot, SplitLayout, SplitOutcome, SplitStatus,
+};
+pub use track::{
+    GeneratedTrack, OutputCodec, RecordedTrack, TrackCleanupOutcome, TrackCleanupStatus, TrackTag,
+};
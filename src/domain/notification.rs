@@ -0,0 +1,73 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    Failed,
+    ImportSkipped,
+    Completed,
+}
+
+impl NotificationEvent {
+    pub const ALL: [Self; 3] = [Self::Failed, Self::ImportSkipped, Self::Completed];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Failed => "failed",
+            Self::ImportSkipped => "import_skipped",
+            Self::Completed => "completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "failed" => Some(Self::Failed),
+            "import_skipped" => Some(Self::ImportSkipped),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// Something that happened to a download that the user may want to hear about
+/// without opening the web UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub download_id: String,
+    pub title: String,
+    pub message: String,
+}
+
+impl Notification {
+    pub fn summary(&self) -> String {
+        let outcome = match self.event {
+            NotificationEvent::Failed => "failed",
+            NotificationEvent::ImportSkipped => "was not imported",
+            NotificationEvent::Completed => "completed",
+        };
+        format!("{} {outcome}", self.title)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationDeliveryStatus {
+    Delivered,
+    Failed,
+}
+
+impl NotificationDeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// The outcome of sending one notification through one configured notifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationDelivery {
+    pub notifier: String,
+    pub event: NotificationEvent,
+    pub status: NotificationDeliveryStatus,
+    pub message: Option<String>,
+    pub sent_at: String,
+}
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::domain::{OutputCodec, PregapPolicy};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FailedImportCandidate {
@@ -22,11 +24,94 @@ pub struct QueueSnapshot {
 pub struct SplitOutcome {
     pub status: SplitStatus,
     pub tracks: Vec<PathBuf>,
+    pub codec: OutputCodec,
     pub message: Option<String>,
+    pub pregap_policy: Option<PregapPolicy>,
+    pub hidden_track: Option<PathBuf>,
+}
+
+/// A split that was started but has not finished. `existing_files` lists what
+/// was in the output directory beforehand, so leftovers can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InProgressSplit {
+    pub download_id: String,
+    pub output_dir: String,
+    pub existing_files: Vec<String>,
+    pub started_at: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SplitStatus {
     Split,
     Skipped,
+    Planned,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitLayout {
+    staging_dir: Option<PathBuf>,
+}
+
+impl SplitLayout {
+    pub fn new(staging_dir: Option<PathBuf>) -> Self {
+        Self { staging_dir }
+    }
+
+    pub fn staging_root(&self, download_id: &str) -> Option<PathBuf> {
+        self.staging_dir
+            .as_ref()
+            .map(|staging_dir| staging_dir.join(path_component(download_id)))
+    }
+
+    pub fn output_dirs(&self, download_id: &str, cue_paths: &[PathBuf]) -> Vec<PathBuf> {
+        let Some(root) = self.staging_root(download_id) else {
+            return cue_paths
+                .iter()
+                .map(|cue_path| {
+                    cue_path
+                        .parent()
+                        .map_or_else(PathBuf::new, Path::to_path_buf)
+                })
+                .collect();
+        };
+
+        let mut used = HashSet::new();
+        cue_paths
+            .iter()
+            .map(|cue_path| {
+                let name = cue_path
+                    .file_stem()
+                    .map(|stem| path_component(&stem.to_string_lossy()))
+                    .unwrap_or_else(|| "cue".to_owned());
+                let mut candidate = name.clone();
+                let mut suffix = 2;
+                while !used.insert(candidate.clone()) {
+                    candidate = format!("{name} ({suffix})");
+                    suffix += 1;
+                }
+                root.join(candidate)
+            })
+            .collect()
+    }
+
+    pub fn import_folder(&self, download_id: &str, output_path: &str) -> String {
+        self.staging_root(download_id).map_or_else(
+            || output_path.to_owned(),
+            |root| root.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+fn path_component(value: &str) -> String {
+    let component = value
+        .chars()
+        .map(|character| match character {
+            '/' | '\\' | '\0' => '_',
+            character => character,
+        })
+        .collect::<String>();
+    match component.trim() {
+        "" | "." | ".." => "_".to_owned(),
+        _ => component,
+    }
 }
@@ -5,12 +5,62 @@ pub struct GeneratedTrack {
     pub download_id: String,
     pub path: String,
     pub size_bytes: Option<i64>,
+    pub codec: OutputCodec,
     pub cleanup_status: TrackCleanupStatus,
     pub cleanup_message: Option<String>,
     pub deleted_at: Option<String>,
+    pub tags: Vec<TrackTag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackTag {
+    pub name: String,
+    pub value: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputCodec {
+    Flac,
+    Wav,
+    Alac,
+    Opus,
+    Mp3,
+}
+
+impl OutputCodec {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flac => "flac",
+            Self::Wav => "wav",
+            Self::Alac => "alac",
+            Self::Opus => "opus",
+            Self::Mp3 => "mp3",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "flac" => Some(Self::Flac),
+            "wav" => Some(Self::Wav),
+            "alac" => Some(Self::Alac),
+            "opus" => Some(Self::Opus),
+            "mp3" => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Flac => "flac",
+            Self::Wav => "wav",
+            Self::Alac => "m4a",
+            Self::Opus => "opus",
+            Self::Mp3 => "mp3",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TrackCleanupStatus {
     Pending,
     Deleted,
@@ -22,6 +72,7 @@ pub enum TrackCleanupStatus {
 pub struct RecordedTrack {
     pub path: String,
     pub size_bytes: Option<i64>,
+    pub codec: OutputCodec,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]